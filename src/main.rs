@@ -4,8 +4,26 @@
 //! a feature-rich REPL environment with customizable themes, built-in commands, and
 //! system command execution capabilities.
 
+use std::path::PathBuf;
+
+use clap::Parser;
+
 mod shell;
 
+/// Command-line options.
+#[derive(Parser)]
+#[command(version, about = "PascheK Shell")]
+struct Cli {
+    /// Explorer root for the TUI; repeat to switch between several roots
+    /// (overrides `roots` in config/explorer.toml)
+    #[arg(long = "root", value_name = "PATH")]
+    roots: Vec<PathBuf>,
+
+    /// Script to run non-interactively instead of starting the REPL
+    #[arg(value_name = "SCRIPT")]
+    script: Option<PathBuf>,
+}
+
 /// Program entry point that initializes and starts the PascheK Shell REPL.
 /// 
 /// The REPL (Read-Eval-Print Loop) is responsible for:
@@ -15,5 +33,9 @@ mod shell;
 /// - Displaying command output
 /// - Maintaining the shell state
 fn main() {
-    shell::repl::start_repl();
+    let cli = Cli::parse();
+    if let Some(script) = cli.script {
+        std::process::exit(shell::script::run(&script));
+    }
+    std::process::exit(shell::repl::start_repl(cli.roots));
 }
\ No newline at end of file
@@ -0,0 +1,66 @@
+//! Command aliases shared by the REPL and the TUI shell.
+//!
+//! Aliases live in `aliases.toml` of the config directory (under `[aliases]`,
+//! `name = "command"`, see `config::paths`). The first word of a
+//! command line is replaced by its alias before dispatch; an alias may start with
+//! another alias, but a name is never expanded twice (`ls = "ls --color"` is fine).
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::shell::config::paths;
+
+#[derive(Default, Serialize, Deserialize)]
+struct AliasesFile {
+    #[serde(default)]
+    aliases: BTreeMap<String, String>,
+}
+
+/// Location of the aliases file (fixed on first call, so `cd` does not move it).
+pub fn aliases_path() -> &'static PathBuf {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| paths::config_file("aliases.toml"))
+}
+
+/// Aliases by name (sorted). A missing or invalid file yields no aliases.
+pub fn load() -> BTreeMap<String, String> {
+    fs::read_to_string(aliases_path())
+        .ok()
+        .and_then(|s| toml::from_str::<AliasesFile>(&s).ok())
+        .map(|f| f.aliases)
+        .unwrap_or_default()
+}
+
+/// Overwrite the aliases file with `aliases`.
+pub fn save(aliases: &BTreeMap<String, String>) -> io::Result<()> {
+    let path = aliases_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = AliasesFile { aliases: aliases.clone() };
+    let content = toml::to_string_pretty(&file).map_err(io::Error::other)?;
+    fs::write(path, content)
+}
+
+/// Whether `name` can be used as an alias name.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || "_-.".contains(c))
+}
+
+/// `line` with its first word replaced by its alias (repeatedly, each name once).
+pub fn expand(line: &str, aliases: &BTreeMap<String, String>) -> String {
+    let mut line = line.trim_start().to_string();
+    let mut seen = BTreeSet::new();
+    loop {
+        let word = line.split_whitespace().next().unwrap_or("");
+        let Some(value) = aliases.get(word).filter(|_| seen.insert(word.to_string())) else {
+            return line;
+        };
+        line = format!("{}{}", value, &line[word.len()..]);
+    }
+}
@@ -0,0 +1,50 @@
+//! Pinned directories shared by the `bm` built-in and the TUI explorer.
+//!
+//! The list lives in `~/.config/paschek/bookmarks` (see `config::paths`), one
+//! absolute path per line, in pin order.
+
+use crate::shell::config::paths;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Location of the bookmarks file.
+pub fn bookmarks_path() -> PathBuf {
+    paths::config_dir().join("bookmarks")
+}
+
+/// Pinned directories, in pin order (empty when the file does not exist).
+pub fn load() -> Vec<PathBuf> {
+    fs::read_to_string(bookmarks_path())
+        .map(|text| text.lines().filter(|l| !l.trim().is_empty()).map(PathBuf::from).collect())
+        .unwrap_or_default()
+}
+
+/// Overwrite the bookmarks file with `pins`.
+pub fn save(pins: &[PathBuf]) -> io::Result<()> {
+    let path = bookmarks_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let text: String = pins.iter().map(|p| format!("{}\n", p.display())).collect();
+    fs::write(path, text)
+}
+
+/// Pin `dir` (made absolute) if it is not pinned yet, unpin it otherwise.
+/// Returns true when the directory is now pinned.
+pub fn toggle(dir: &Path) -> io::Result<bool> {
+    let dir = dir.canonicalize()?;
+    let mut pins = load();
+    let pinned = match pins.iter().position(|p| *p == dir) {
+        Some(i) => {
+            pins.remove(i);
+            false
+        }
+        None => {
+            pins.push(dir);
+            true
+        }
+    };
+    save(&pins)?;
+    Ok(pinned)
+}
@@ -0,0 +1,108 @@
+// src/shell/commands/alias.rs
+use super::Command;
+use crate::shell::aliases;
+use crate::shell::engine::{Output, ShellEngine};
+
+pub struct AliasCommand;
+pub struct UnaliasCommand;
+
+/// Exécute `alias` : liste, affiche ou définit un alias.
+fn run(args: &[&str], out: &mut dyn Output) -> i32 {
+    let mut all = aliases::load();
+    if args.is_empty() {
+        if all.is_empty() {
+            out.line("Aucun alias (alias nom='commande')");
+        }
+        for (name, value) in &all {
+            out.line(&format!("alias {}='{}'", name, value));
+        }
+        return 0;
+    }
+    // `alias ll=ls -la` sans guillemets : on recolle la définition
+    let def = args.join(" ");
+    let Some((name, value)) = def.split_once('=') else {
+        return match all.get(def.as_str()) {
+            Some(value) => {
+                out.line(&format!("alias {}='{}'", def, value));
+                0
+            }
+            None => {
+                out.error(&format!("alias: {}: introuvable", def));
+                1
+            }
+        };
+    };
+    let value = value.trim();
+    if !aliases::is_valid_name(name) {
+        out.error(&format!("alias: nom invalide : {}", name));
+        return 1;
+    }
+    if value.is_empty() {
+        out.error("Usage: alias [nom[='commande']]");
+        return 2;
+    }
+    all.insert(name.to_string(), value.to_string());
+    match aliases::save(&all) {
+        Ok(()) => 0,
+        Err(e) => {
+            out.error(&format!("alias: {}", e));
+            1
+        }
+    }
+}
+
+/// Exécute `unalias` : 1 si un des noms est introuvable.
+fn run_unalias(args: &[&str], out: &mut dyn Output) -> i32 {
+    if args.is_empty() {
+        out.error("Usage: unalias <nom>...");
+        return 2;
+    }
+    let mut all = aliases::load();
+    let mut status = 0;
+    let mut removed = 0;
+    for name in args {
+        if all.remove(*name).is_some() {
+            removed += 1;
+        } else {
+            out.error(&format!("unalias: {}: introuvable", name));
+            status = 1;
+        }
+    }
+    if removed > 0 && let Err(e) = aliases::save(&all) {
+        out.error(&format!("unalias: {}", e));
+        status = 1;
+    }
+    status
+}
+
+impl Command for AliasCommand {
+    fn name(&self) -> &'static str {
+        "alias"
+    }
+    fn about(&self) -> &'static str {
+        "Définit ou liste les alias (config/aliases.toml)."
+    }
+    fn usage(&self) -> &'static str {
+        "alias [name[='command']]"
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        run(args, out)
+    }
+}
+
+impl Command for UnaliasCommand {
+    fn name(&self) -> &'static str {
+        "unalias"
+    }
+    fn about(&self) -> &'static str {
+        "Supprime des alias."
+    }
+    fn usage(&self) -> &'static str {
+        "unalias <name>..."
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        run_unalias(args, out)
+    }
+}
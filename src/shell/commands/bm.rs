@@ -0,0 +1,94 @@
+// src/shell/commands/bm.rs
+use super::Command;
+use crate::shell::bookmarks;
+use crate::shell::engine::{Output, ShellEngine};
+use std::env;
+use std::path::{Path, PathBuf};
+
+pub struct BmCommand;
+
+/// Retrouve un favori par numéro (1, 2, …) ou par chemin.
+fn find(pins: &[PathBuf], key: &str) -> Option<usize> {
+    match key.parse::<usize>() {
+        Ok(n) => (n >= 1 && n <= pins.len()).then(|| n - 1),
+        Err(_) => {
+            let path = Path::new(key).canonicalize().ok()?;
+            pins.iter().position(|p| *p == path)
+        }
+    }
+}
+
+/// Exécute `bm` : liste, ajoute ou retire un favori, ou s'y déplace.
+fn run(args: &[&str], out: &mut dyn Output) -> i32 {
+    let mut pins = bookmarks::load();
+    let result = match args {
+        [] | ["ls"] => {
+            if pins.is_empty() {
+                out.line("Aucun favori (bm add [chemin])");
+            }
+            for (i, p) in pins.iter().enumerate() {
+                out.line(&format!("{:>2}  {}", i + 1, p.display()));
+            }
+            Ok(())
+        }
+        ["add", rest @ ..] => {
+            let dir = rest.first().map(PathBuf::from).unwrap_or_else(|| env::current_dir().unwrap_or_default());
+            match dir.canonicalize() {
+                Ok(dir) if !dir.is_dir() => Err(format!("bm: {}: pas un dossier", dir.display())),
+                Ok(dir) if pins.contains(&dir) => {
+                    out.line(&format!("Déjà épinglé : {}", dir.display()));
+                    Ok(())
+                }
+                Ok(dir) => {
+                    pins.push(dir.clone());
+                    bookmarks::save(&pins)
+                        .map(|()| out.line(&format!("📌 {}  {}", pins.len(), dir.display())))
+                        .map_err(|e| format!("bm: {}", e))
+                }
+                Err(e) => Err(format!("bm: {}: {}", dir.display(), e)),
+            }
+        }
+        ["rm", key] => match find(&pins, key) {
+            Some(i) => {
+                let removed = pins.remove(i);
+                bookmarks::save(&pins)
+                    .map(|()| out.line(&format!("Retiré : {}", removed.display())))
+                    .map_err(|e| format!("bm: {}", e))
+            }
+            None => Err(format!("bm: favori introuvable : {}", key)),
+        },
+        [key] => match find(&pins, key) {
+            Some(i) => env::set_current_dir(&pins[i])
+                .map(|()| out.line(&format!("(bm) -> {}", pins[i].display())))
+                .map_err(|e| format!("bm: {}: {}", pins[i].display(), e)),
+            None => Err(format!("bm: favori introuvable : {}", key)),
+        },
+        _ => {
+            out.error("Usage: bm [ls | add [chemin] | rm <n|chemin> | <n>]");
+            return 2;
+        }
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            out.error(&e);
+            1
+        }
+    }
+}
+
+impl Command for BmCommand {
+    fn name(&self) -> &'static str {
+        "bm"
+    }
+    fn about(&self) -> &'static str {
+        "Dossiers favoris (épinglés aussi dans l’explorateur du TUI)."
+    }
+    fn usage(&self) -> &'static str {
+        "bm [ls | add [path] | rm <n|path> | <n>]"
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        run(args, out)
+    }
+}
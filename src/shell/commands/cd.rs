@@ -1,28 +1,100 @@
 // src/shell/commands/cd.rs
 use super::Command;
-use crate::shell::commands::CommandRegistry;
+use crate::shell::engine::{Output, ShellEngine};
+use crate::shell::config::expand_home;
+use crate::shell::variables;
 use std::env;
+use std::path::{Path, PathBuf};
 
 pub struct CdCommand;
 
+/// Dossier visé par `path` : relatif au dossier courant, ou trouvé dans un dossier
+/// de `CDPATH` (le booléen indique alors qu'il faut afficher le chemin obtenu).
+/// `~` est déjà développé par le tokenizer : un `'~'` entre guillemets reste littéral.
+fn resolve(path: &str) -> (PathBuf, bool) {
+    let target = PathBuf::from(path);
+    // `./x`, `..\x` (Windows), `/x`, `C:\x` : pas de recherche dans CDPATH
+    let explicit = target.has_root() || target.starts_with(".") || target.starts_with("..");
+    if !explicit && let Some(cdpath) = env::var_os("CDPATH") {
+        // Séparés par `:` (`;` sous Windows)
+        for dir in env::split_paths(&cdpath) {
+            // Entrée vide : le dossier courant
+            let empty = dir.as_os_str().is_empty();
+            let candidate = if empty { target.clone() } else { expand_home(&dir.to_string_lossy()).join(&target) };
+            if candidate.is_dir() {
+                return (candidate, !empty);
+            }
+        }
+    }
+    (target, false)
+}
+
+/// Exécute `cd`.
+///
+/// - `cd` : dossier personnel
+/// - `cd -` : dossier précédent (`OLDPWD`), affiché
+/// - `cd nom` : cherché aussi dans les dossiers de `CDPATH` (séparés par `:`, `;` sous Windows)
+fn run(args: &[&str], out: &mut dyn Output) -> i32 {
+    let (target, show) = match args {
+        [] => match dirs::home_dir() {
+            Some(home) => (home, false),
+            None => {
+                out.error("cd: HOME not set");
+                return 1;
+            }
+        },
+        ["-"] => match env::var("OLDPWD") {
+            Ok(old) if !old.is_empty() => (PathBuf::from(old), true),
+            _ => {
+                out.error("cd: OLDPWD not set");
+                return 1;
+            }
+        },
+        [path] => resolve(path),
+        _ => {
+            out.error("usage: cd [path | -]");
+            return 2;
+        }
+    };
+    match change_to(&target) {
+        Ok(current) => {
+            if show {
+                out.line(&current.display().to_string());
+            }
+            0
+        }
+        Err(e) => {
+            out.error(&e);
+            1
+        }
+    }
+}
+
+/// Se place dans `target` en tenant `OLDPWD` et `PWD` à jour ; renvoie le nouveau
+/// dossier courant, ou le message d'erreur.
+pub fn change_to(target: &Path) -> Result<PathBuf, String> {
+    let previous = env::current_dir().ok();
+    env::set_current_dir(target).map_err(|e| format!("❌ Impossible de se déplacer: {}: {e}", target.display()))?;
+    let current = env::current_dir().unwrap_or_else(|_| target.to_path_buf());
+    if let Some(previous) = previous {
+        variables::set("OLDPWD", &previous.to_string_lossy());
+    }
+    variables::set("PWD", &current.to_string_lossy());
+    Ok(current)
+}
+
 impl Command for CdCommand {
     fn name(&self) -> &'static str {
         "cd"
     }
     fn about(&self) -> &'static str {
-        "Change le répertoire courant."
+        "Change le répertoire courant (sans argument : dossier personnel, `-` : précédent)."
     }
     fn usage(&self) -> &'static str {
-        "cd <path>"
+        "cd [path | -]"
     }
 
-    fn execute(&self, args: &[&str], _registry: &CommandRegistry) {
-        if args.is_empty() {
-            eprintln!("Usage: cd <path>");
-            return;
-        }
-        if let Err(e) = env::set_current_dir(args[0]) {
-            eprintln!("❌ Impossible de se déplacer: {e}");
-        }
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        run(args, out)
     }
 }
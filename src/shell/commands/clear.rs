@@ -1,6 +1,6 @@
 // src/shell/commands/clear.rs
 use super::Command;
-use crate::shell::commands::CommandRegistry;
+use crate::shell::engine::{Output, ShellEngine};
 
 pub struct ClearCommand;
 
@@ -18,7 +18,8 @@ impl Command for ClearCommand {
         &["cls"]
     }
 
-    fn execute(&self, _args: &[&str], _registry: &CommandRegistry) {
-        print!("\x1B[2J\x1B[1;1H");
+    fn execute(&self, _args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        out.clear();
+        0
     }
 }
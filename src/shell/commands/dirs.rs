@@ -0,0 +1,143 @@
+// src/shell/commands/dirs.rs
+use super::Command;
+use crate::shell::commands::cd;
+use crate::shell::engine::{Output, ShellEngine};
+use crate::shell::dirstack;
+use std::env;
+use std::path::{Path, PathBuf};
+
+pub struct PushdCommand;
+pub struct PopdCommand;
+pub struct DirsCommand;
+
+/// Chemin avec `~` pour le dossier personnel.
+fn tilde(path: &Path) -> String {
+    match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+        Some(rest) if rest.as_os_str().is_empty() => String::from("~"),
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Affiche le dossier courant puis la pile, sur une ligne (ou numérotés, un par ligne,
+/// avec `verbose`).
+fn listing(verbose: bool, out: &mut dyn Output) {
+    let mut all: Vec<PathBuf> = env::current_dir().into_iter().collect();
+    all.extend(dirstack::entries());
+    if verbose {
+        for (i, dir) in all.iter().enumerate() {
+            out.line(&format!("{:>2}  {}", i, tilde(dir)));
+        }
+    } else {
+        out.line(&all.iter().map(|dir| tilde(dir)).collect::<Vec<_>>().join(" "));
+    }
+}
+
+/// Exécute `pushd` : empile le dossier courant et va dans `dir` ; sans argument,
+/// échange le dossier courant et le haut de la pile.
+fn run_pushd(args: &[&str], out: &mut dyn Output) -> i32 {
+    let Ok(current) = env::current_dir() else {
+        out.error("pushd: current directory unavailable");
+        return 1;
+    };
+    let moved = match args {
+        [] => match dirstack::swap_top(current.clone()) {
+            Some(top) => cd::change_to(&top).inspect_err(|_| {
+                dirstack::swap_top(top.clone());
+            }),
+            None => Err(String::from("pushd: no other directory")),
+        },
+        [dir] => cd::change_to(Path::new(dir)).inspect(|_| dirstack::push(current)),
+        _ => {
+            out.error("usage: pushd [dir]");
+            return 2;
+        }
+    };
+    if let Err(e) = moved {
+        out.error(&e);
+        return 1;
+    }
+    listing(false, out);
+    0
+}
+
+/// Exécute `popd` : retourne dans le dossier le plus récent de la pile.
+fn run_popd(args: &[&str], out: &mut dyn Output) -> i32 {
+    if !args.is_empty() {
+        out.error("usage: popd");
+        return 2;
+    }
+    let Some(dir) = dirstack::pop() else {
+        out.error("popd: directory stack empty");
+        return 1;
+    };
+    if let Err(e) = cd::change_to(&dir) {
+        dirstack::push(dir);
+        out.error(&e);
+        return 1;
+    }
+    listing(false, out);
+    0
+}
+
+/// Exécute `dirs` : affiche la pile (`-v` numérotée), ou la vide (`-c`).
+fn run_dirs(args: &[&str], out: &mut dyn Output) -> i32 {
+    match args {
+        [] => listing(false, out),
+        ["-v"] => listing(true, out),
+        ["-c"] => dirstack::clear(),
+        _ => {
+            out.error("usage: dirs [-v | -c]");
+            return 2;
+        }
+    }
+    0
+}
+
+impl Command for PushdCommand {
+    fn name(&self) -> &'static str {
+        "pushd"
+    }
+    fn about(&self) -> &'static str {
+        "Empile le dossier courant et change de dossier."
+    }
+    fn usage(&self) -> &'static str {
+        "pushd [dir]"
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        run_pushd(args, out)
+    }
+}
+
+impl Command for PopdCommand {
+    fn name(&self) -> &'static str {
+        "popd"
+    }
+    fn about(&self) -> &'static str {
+        "Retourne dans le dernier dossier empilé."
+    }
+    fn usage(&self) -> &'static str {
+        "popd"
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        run_popd(args, out)
+    }
+}
+
+impl Command for DirsCommand {
+    fn name(&self) -> &'static str {
+        "dirs"
+    }
+    fn about(&self) -> &'static str {
+        "Affiche la pile de dossiers (-v numérotée, -c pour la vider)."
+    }
+    fn usage(&self) -> &'static str {
+        "dirs [-v | -c]"
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        run_dirs(args, out)
+    }
+}
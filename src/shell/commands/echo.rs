@@ -0,0 +1,106 @@
+// src/shell/commands/echo.rs
+use super::Command;
+use crate::shell::engine::{Output, ShellEngine};
+
+pub struct EchoCommand;
+
+/// Options en tête des arguments (`-n`, `-e`, `-E`, groupées comme `-ne`) : sans saut
+/// de ligne final, avec les séquences `\` interprétées. Le premier mot qui n'en est
+/// pas une (`-x`, `--`) est affiché tel quel, comme dans bash.
+fn options<'a>(args: &'a [&'a str]) -> (bool, bool, &'a [&'a str]) {
+    let (mut newline, mut escapes) = (true, false);
+    let mut rest = args;
+    while let Some((first, tail)) = rest.split_first() {
+        let Some(flags) = first.strip_prefix('-').filter(|f| !f.is_empty() && f.chars().all(|c| "neE".contains(c))) else {
+            break;
+        };
+        for c in flags.chars() {
+            match c {
+                'n' => newline = false,
+                'e' => escapes = true,
+                _ => escapes = false,
+            }
+        }
+        rest = tail;
+    }
+    (newline, escapes, rest)
+}
+
+/// Interprète les séquences de `echo -e` ; `false` quand `\c` coupe la suite (et le
+/// saut de ligne final).
+fn unescape(text: &str, result: &mut String) -> bool {
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('a') => result.push('\x07'),
+            Some('b') => result.push('\x08'),
+            Some('e') => result.push('\x1b'),
+            Some('f') => result.push('\x0c'),
+            Some('v') => result.push('\x0b'),
+            Some('\\') => result.push('\\'),
+            Some('c') => return false,
+            // \0nnn (octal) et \xHH (hexadécimal)
+            Some(base @ ('0' | 'x')) => {
+                let (radix, max) = if base == '0' { (8, 3) } else { (16, 2) };
+                let mut digits = String::new();
+                while digits.len() < max {
+                    match chars.next_if(|d| d.is_digit(radix)) {
+                        Some(d) => digits.push(d),
+                        None => break,
+                    }
+                }
+                match u32::from_str_radix(&digits, radix).ok().and_then(char::from_u32) {
+                    Some(byte) => result.push(byte),
+                    None if base == '0' => result.push('\0'),
+                    None => result.push_str("\\x"),
+                }
+            }
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    true
+}
+
+impl Command for EchoCommand {
+    fn name(&self) -> &'static str {
+        "echo"
+    }
+    fn about(&self) -> &'static str {
+        "Affiche ses arguments (-n : sans saut de ligne, -e : séquences \\n, \\t…)."
+    }
+    fn usage(&self) -> &'static str {
+        "echo [-neE] [text]..."
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        let (mut newline, escapes, words) = options(args);
+        let mut text = String::new();
+        for (i, word) in words.iter().enumerate() {
+            if i > 0 {
+                text.push(' ');
+            }
+            if !escapes {
+                text.push_str(word);
+            } else if !unescape(word, &mut text) {
+                newline = false;
+                break;
+            }
+        }
+        if newline {
+            text.push('\n');
+        }
+        out.write(&text);
+        0
+    }
+}
@@ -0,0 +1,34 @@
+// src/shell/commands/exit.rs
+use super::Command;
+use crate::shell::engine::{Output, ShellEngine};
+
+pub struct ExitCommand;
+
+impl Command for ExitCommand {
+    fn name(&self) -> &'static str {
+        "exit"
+    }
+    fn about(&self) -> &'static str {
+        "Quitte le shell (ou le script) avec un code de sortie."
+    }
+    fn usage(&self) -> &'static str {
+        "exit [code]"
+    }
+
+    fn execute(&self, args: &[&str], shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        // Sans argument : le code de la dernière commande
+        let code = match args {
+            [] => shell.last_status(),
+            [code] => code.parse().unwrap_or_else(|_| {
+                out.error(&format!("exit: {}: numeric argument required", code));
+                2
+            }),
+            _ => {
+                out.error("exit: too many arguments");
+                return 1;
+            }
+        };
+        shell.request_exit(code);
+        code
+    }
+}
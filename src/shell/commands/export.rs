@@ -0,0 +1,51 @@
+// src/shell/commands/export.rs
+use super::Command;
+use crate::shell::engine::{Output, ShellEngine};
+use crate::shell::variables;
+
+pub struct ExportCommand;
+
+/// Exécute `export` : liste les variables, ou les définit (1 si une affectation est invalide).
+fn run(args: &[&str], out: &mut dyn Output) -> i32 {
+    if args.is_empty() {
+        let vars = variables::exported();
+        if vars.is_empty() {
+            out.line("Aucune variable exportée (export NOM=valeur)");
+        }
+        for (name, value) in vars {
+            out.line(&format!("{}={}", name, value));
+        }
+        return 0;
+    }
+    let mut status = 0;
+    for arg in args {
+        let Some((name, value)) = arg.split_once('=') else {
+            out.error(&format!("Usage: export NOM=valeur (reçu : {})", arg));
+            status = 1;
+            continue;
+        };
+        if !variables::is_valid_name(name) {
+            out.error(&format!("export: nom invalide : {}", name));
+            status = 1;
+            continue;
+        }
+        variables::export(name, value);
+    }
+    status
+}
+
+impl Command for ExportCommand {
+    fn name(&self) -> &'static str {
+        "export"
+    }
+    fn about(&self) -> &'static str {
+        "Définit des variables d’environnement, ou liste celles du shell."
+    }
+    fn usage(&self) -> &'static str {
+        "export [NAME=value]..."
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        run(args, out)
+    }
+}
@@ -0,0 +1,56 @@
+// src/shell/commands/functions.rs
+use super::Command;
+use crate::shell::engine::{Output, ShellEngine};
+
+pub struct FunctionsCommand;
+
+impl Command for FunctionsCommand {
+    fn name(&self) -> &'static str {
+        "functions"
+    }
+    fn about(&self) -> &'static str {
+        "Liste, affiche ou supprime les fonctions (fn nom { … })."
+    }
+    fn usage(&self) -> &'static str {
+        "functions [name] | functions -d <name>..."
+    }
+
+    fn execute(&self, args: &[&str], shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        match args {
+            [] => {
+                let functions = shell.functions();
+                if functions.is_empty() {
+                    out.line("Aucune fonction (fn nom { commandes })");
+                }
+                for function in functions.values() {
+                    out.line(&function.definition());
+                }
+                0
+            }
+            ["-d", names @ ..] if !names.is_empty() => {
+                let mut status = 0;
+                for name in names {
+                    if !shell.remove_function(name) {
+                        out.error(&format!("functions: {}: introuvable", name));
+                        status = 1;
+                    }
+                }
+                status
+            }
+            [name] if !name.starts_with('-') => match shell.functions().get(*name) {
+                Some(function) => {
+                    out.line(&function.definition());
+                    0
+                }
+                None => {
+                    out.error(&format!("functions: {}: introuvable", name));
+                    1
+                }
+            },
+            _ => {
+                out.error(&format!("Usage: {}", self.usage()));
+                2
+            }
+        }
+    }
+}
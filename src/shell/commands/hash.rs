@@ -0,0 +1,49 @@
+// src/shell/commands/hash.rs
+use super::Command;
+use crate::shell::engine::{Output, ShellEngine};
+use crate::shell::pathcache;
+
+pub struct HashCommand;
+
+impl Command for HashCommand {
+    fn name(&self) -> &'static str {
+        "hash"
+    }
+    fn about(&self) -> &'static str {
+        "Index des programmes du PATH (-r pour le reconstruire)."
+    }
+    fn usage(&self) -> &'static str {
+        "hash [-r] [name...]"
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        match args {
+            [] => {
+                out.line(&format!("{} programmes dans le PATH", pathcache::executables().len()));
+                0
+            }
+            ["-r"] => {
+                pathcache::clear();
+                0
+            }
+            names if !names.iter().any(|n| n.starts_with('-')) => {
+                let executables = pathcache::executables();
+                let mut status = 0;
+                for name in names {
+                    match executables.get(*name) {
+                        Some(path) => out.line(&format!("{}\t{}", name, path.display())),
+                        None => {
+                            out.error(&format!("hash: {}: introuvable", name));
+                            status = 1;
+                        }
+                    }
+                }
+                status
+            }
+            _ => {
+                out.error(&format!("Usage: {}", self.usage()));
+                2
+            }
+        }
+    }
+}
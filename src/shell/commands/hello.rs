@@ -1,6 +1,6 @@
 // src/shell/commands/hello.rs
 use super::Command;
-use crate::shell::commands::CommandRegistry;
+use crate::shell::engine::{Output, ShellEngine};
 
 pub struct HelloCommand;
 
@@ -15,7 +15,8 @@ impl Command for HelloCommand {
         "hello"
     }
 
-    fn execute(&self, _args: &[&str], _registry: &CommandRegistry) {
-        println!("Hello from PascheK Shell 🦀");
+    fn execute(&self, _args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        out.line("Hello from PascheK Shell 🦀");
+        0
     }
 }
@@ -1,6 +1,6 @@
 // src/shell/commands/help.rs
 use super::Command;
-use crate::shell::commands::CommandRegistry;
+use crate::shell::engine::{Output, ShellEngine};
 
 pub struct HelpCommand;
 
@@ -18,30 +18,33 @@ impl Command for HelpCommand {
         &["h"]
     }
 
-    fn execute(&self, args: &[&str], registry: &CommandRegistry) {
-        if let Some(cmd_name) = args.get(0).copied() {
+    fn execute(&self, args: &[&str], shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        if let Some(cmd_name) = args.first().copied() {
             // détail pour une commande précise
-            if let Some(md) = registry
+            if let Some(md) = shell
+                .registry()
                 .list_metadata()
                 .into_iter()
                 .find(|(n, _, _)| n == cmd_name)
             {
-                println!("{} — {}", md.0, md.1);
-                println!("Usage: {}", md.2);
-                return;
+                out.line(&format!("{} — {}", md.0, md.1));
+                out.line(&format!("Usage: {}", md.2));
+                return 0;
             }
-            println!("Commande inconnue: {cmd_name}");
-            if let Some(s) = registry.suggest(cmd_name) {
-                println!("Vouliez-vous dire: {} ?", s);
+            out.error(&format!("Commande inconnue: {cmd_name}"));
+            if let Some(s) = shell.registry().suggest(cmd_name) {
+                out.error(&format!("Vouliez-vous dire: {} ?", s));
             }
-            return;
+            return 1;
         }
 
         // sinon, liste des commandes
-        println!("Commandes disponibles:");
-        for (name, about, usage) in registry.list_metadata() {
-            println!("  - {:<12} {:<40}  (usage: {})", name, about, usage);
+        out.line("Commandes disponibles:");
+        for (name, about, usage) in shell.registry().list_metadata() {
+            out.line(&format!("  - {:<12} {:<40}  (usage: {})", name, about, usage));
         }
-        println!("\nAstuce: `help <commande>` pour le détail.");
+        out.line("");
+        out.line("Astuce: `help <commande>` pour le détail.");
+        0
     }
 }
@@ -0,0 +1,53 @@
+// src/shell/commands/history.rs
+use super::Command;
+use crate::shell::engine::{Output, ShellEngine};
+use crate::shell::history::HistoryStore;
+
+pub struct HistoryCommand;
+
+/// Exécute `history` sur `store`.
+///
+/// - `history` : toutes les commandes, numérotées pour `!N`
+/// - `history N` : les N dernières
+/// - `history <texte>` : celles qui contiennent le texte
+/// - `history -c` : vide l'historique
+fn run(args: &[&str], store: &mut HistoryStore, out: &mut dyn Output) -> i32 {
+    if args == ["-c"] {
+        return match store.clear() {
+            Ok(()) => 0,
+            Err(e) => {
+                out.error(&format!("history: {}", e));
+                1
+            }
+        };
+    }
+    let numbered = store.entries().iter().enumerate().map(|(i, cmd)| (i + 1, cmd));
+    let shown: Vec<(usize, &String)> = match args {
+        [] => numbered.collect(),
+        [n] if let Ok(n) = n.parse::<usize>() => numbered.skip(store.len().saturating_sub(n)).collect(),
+        words => {
+            let pattern = words.join(" ");
+            numbered.filter(|(_, cmd)| cmd.contains(&pattern)).collect()
+        }
+    };
+    for (n, cmd) in shown {
+        out.line(&format!("{:>5}  {}", n, cmd.replace('\n', " ")));
+    }
+    0
+}
+
+impl Command for HistoryCommand {
+    fn name(&self) -> &'static str {
+        "history"
+    }
+    fn about(&self) -> &'static str {
+        "Historique des commandes (relancer avec !N ou !!)."
+    }
+    fn usage(&self) -> &'static str {
+        "history [N | text] | history -c"
+    }
+
+    fn execute(&self, args: &[&str], shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        run(args, &mut shell.history(), out)
+    }
+}
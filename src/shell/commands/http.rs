@@ -0,0 +1,99 @@
+// src/shell/commands/http.rs
+use super::Command;
+use crate::shell::engine::{Output, ShellEngine};
+use crate::shell::http::{self, HttpRequest, METHODS};
+
+pub struct HttpCommand;
+
+impl HttpCommand {
+    /// Analyse `[METHOD] <url> [-H K:V]... [-d body] [-i]` ; retourne la requête et l’option -i.
+    fn parse(args: &[&str]) -> Result<(HttpRequest, bool), String> {
+        let mut req = HttpRequest { method: String::from("GET"), ..Default::default() };
+        let mut include_headers = false;
+        let mut it = args.iter();
+        while let Some(&arg) = it.next() {
+            match arg {
+                "-H" => {
+                    let h = it.next().ok_or("-H attend un en-tête K:V")?;
+                    req.headers.push(http::parse_header(h).ok_or(format!("En-tête invalide: {h}"))?);
+                }
+                "-d" => req.body = it.next().ok_or("-d attend un corps")?.to_string(),
+                "-i" => include_headers = true,
+                m if req.url.is_empty() && METHODS.contains(&m.to_uppercase().as_str()) => {
+                    req.method = m.to_uppercase();
+                }
+                url if req.url.is_empty() => req.url = url.to_string(),
+                other => return Err(format!("Argument inattendu: {other}")),
+            }
+        }
+        if req.url.is_empty() {
+            return Err(String::from("URL manquante"));
+        }
+        Ok((req, include_headers))
+    }
+
+    /// Envoie `req` et affiche la réponse ; 1 si la requête n’aboutit pas.
+    fn run(req: &HttpRequest, include_headers: bool, out: &mut dyn Output) -> i32 {
+        match http::send(req) {
+            Ok(resp) => {
+                out.line(&format!("{} {} ({} ms)", resp.status, resp.status_text, resp.elapsed.as_millis()));
+                if include_headers {
+                    for (k, v) in &resp.headers {
+                        out.line(&format!("{k}: {v}"));
+                    }
+                    out.line("");
+                }
+                for line in resp.pretty_body().lines() {
+                    out.line(line);
+                }
+                0
+            }
+            Err(e) => {
+                out.error(&format!("❌ http: {e}"));
+                1
+            }
+        }
+    }
+}
+
+impl Command for HttpCommand {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+    fn about(&self) -> &'static str {
+        "Envoie une requête HTTP (ou une requête enregistrée)."
+    }
+    fn usage(&self) -> &'static str {
+        "http [METHOD] <url> [-H K:V]... [-d body] [-i] | http list | http run <name>"
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        match args {
+            ["list"] => {
+                let saved = http::load_collection();
+                if saved.is_empty() {
+                    out.line(&format!("Aucune requête enregistrée ({}).", http::collection_path().display()));
+                }
+                for (name, req) in saved {
+                    out.line(&format!("{name:<20} {} {}", req.method, req.url));
+                }
+                0
+            }
+            ["run", name] => match http::load_collection().get(*name) {
+                Some(req) => Self::run(req, false, out),
+                None => {
+                    out.error(&format!("❌ Requête inconnue: {name}"));
+                    1
+                }
+            },
+            _ => match Self::parse(args) {
+                Ok((req, include_headers)) => Self::run(&req, include_headers, out),
+                Err(e) => {
+                    out.error(&format!("❌ {e}"));
+                    out.error(&format!("Usage: {}", self.usage()));
+                    2
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// src/shell/commands/jobs.rs
+use super::Command;
+use crate::shell::engine::{Output, ShellEngine};
+use crate::shell::jobs;
+
+pub struct JobsCommand;
+pub struct FgCommand;
+pub struct BgCommand;
+
+impl Command for JobsCommand {
+    fn name(&self) -> &'static str {
+        "jobs"
+    }
+    fn about(&self) -> &'static str {
+        "Liste les jobs lancés en arrière-plan (cmd &)."
+    }
+
+    fn execute(&self, _args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        let lines = jobs::list();
+        if lines.is_empty() {
+            out.line("Aucun job (cmd & pour lancer en arrière-plan)");
+        }
+        for line in lines {
+            out.line(&line);
+        }
+        0
+    }
+}
+
+impl Command for FgCommand {
+    fn name(&self) -> &'static str {
+        "fg"
+    }
+    fn about(&self) -> &'static str {
+        "Ramène un job au premier plan et attend sa fin."
+    }
+    fn usage(&self) -> &'static str {
+        "fg [%n]"
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        match jobs::fg(args.first().copied()) {
+            Ok(line) => {
+                if !line.is_empty() {
+                    out.line(&line);
+                }
+                0
+            }
+            Err(e) => {
+                out.error(&format!("fg: {e}"));
+                1
+            }
+        }
+    }
+}
+
+impl Command for BgCommand {
+    fn name(&self) -> &'static str {
+        "bg"
+    }
+    fn about(&self) -> &'static str {
+        "Relance un job stoppé en arrière-plan."
+    }
+    fn usage(&self) -> &'static str {
+        "bg [%n]"
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        match jobs::bg(args.first().copied()) {
+            Ok(line) => {
+                out.line(&line);
+                0
+            }
+            Err(e) => {
+                out.error(&format!("bg: {e}"));
+                1
+            }
+        }
+    }
+}
@@ -0,0 +1,231 @@
+// src/shell/commands/ls.rs
+use super::Command;
+use crate::shell::engine::{Output, ShellEngine};
+use chrono::{DateTime, Local};
+use owo_colors::OwoColorize;
+use std::fs::{self, Metadata};
+use std::path::Path;
+
+pub struct LsCommand;
+
+/// Options de `ls`.
+#[derive(Default)]
+struct Options {
+    /// `-a` : fichiers cachés (`.x`)
+    all: bool,
+    /// `-l` : une ligne détaillée par fichier
+    long: bool,
+    /// `-1` : un nom par ligne
+    one: bool,
+}
+
+/// Une entrée à afficher : nom montré, métadonnées (sans suivre les liens) et cible
+/// d'un lien symbolique.
+struct Entry {
+    name: String,
+    meta: Metadata,
+    target: Option<String>,
+}
+
+impl Entry {
+    fn read(path: &Path, name: String) -> std::io::Result<Self> {
+        let meta = fs::symlink_metadata(path)?;
+        let target = meta.file_type().is_symlink().then(|| fs::read_link(path).map(|t| t.display().to_string()).unwrap_or_default());
+        Ok(Self { name, meta, target })
+    }
+
+    /// Le nom, coloré comme GNU ls (dossiers, liens, exécutables).
+    fn painted(&self, colors: bool) -> String {
+        if !colors {
+            return self.name.clone();
+        }
+        let kind = self.meta.file_type();
+        if kind.is_symlink() {
+            self.name.cyan().bold().to_string()
+        } else if kind.is_dir() {
+            self.name.blue().bold().to_string()
+        } else if is_executable(&self.meta) {
+            self.name.green().bold().to_string()
+        } else {
+            self.name.clone()
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.is_file() && meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &Metadata) -> bool {
+    false
+}
+
+/// Type et droits façon `drwxr-xr-x`.
+#[cfg(unix)]
+fn mode(meta: &Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt;
+    let kind = match meta.file_type() {
+        t if t.is_symlink() => 'l',
+        t if t.is_dir() => 'd',
+        _ => '-',
+    };
+    let bits = meta.permissions().mode();
+    let rights: String = (0..9).map(|i| if bits & (0o400 >> i) != 0 { ['r', 'w', 'x'][i % 3] } else { '-' }).collect();
+    format!("{kind}{rights}")
+}
+
+#[cfg(not(unix))]
+fn mode(meta: &Metadata) -> String {
+    let kind = if meta.is_dir() { 'd' } else { '-' };
+    let write = if meta.permissions().readonly() { '-' } else { 'w' };
+    format!("{kind}r{write}-------")
+}
+
+/// Lit les options ; `Err` avec l'option inconnue.
+fn parse(args: &[&str]) -> Result<(Options, Vec<String>), char> {
+    let mut options = Options::default();
+    let mut paths = Vec::new();
+    for arg in args {
+        match arg.strip_prefix('-').filter(|flags| !flags.is_empty()) {
+            Some(flags) => {
+                for c in flags.chars() {
+                    match c {
+                        'a' => options.all = true,
+                        'l' => options.long = true,
+                        '1' => options.one = true,
+                        other => return Err(other),
+                    }
+                }
+            }
+            None => paths.push(arg.to_string()),
+        }
+    }
+    Ok((options, paths))
+}
+
+/// Contenu du dossier `dir`, trié par nom (sans tenir compte de la casse).
+fn list_dir(dir: &Path, options: &Options) -> std::io::Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    if options.all {
+        for name in [".", ".."] {
+            entries.push(Entry::read(&dir.join(name), name.to_string())?);
+        }
+    }
+    for entry in fs::read_dir(dir)?.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !options.all && name.starts_with('.') {
+            continue;
+        }
+        if let Ok(entry) = Entry::read(&entry.path(), name) {
+            entries.push(entry);
+        }
+    }
+    entries.sort_by_cached_key(|e| e.name.to_lowercase());
+    Ok(entries)
+}
+
+/// Affiche `entries` : détaillées (`-l`), une par ligne (`-1`, ou hors terminal), ou en
+/// colonnes à la largeur du terminal.
+fn print(entries: &[Entry], options: &Options, out: &mut dyn Output) {
+    let colors = out.colors();
+    if options.long {
+        let width = entries.iter().map(|e| e.meta.len().to_string().len()).max().unwrap_or(0);
+        for entry in entries {
+            let modified = entry.meta.modified().map(|t| DateTime::<Local>::from(t).format("%b %e %H:%M").to_string()).unwrap_or_default();
+            let mut line = format!("{} {:>width$} {} {}", mode(&entry.meta), entry.meta.len(), modified, entry.painted(colors));
+            if let Some(target) = &entry.target {
+                line.push_str(&format!(" -> {}", target));
+            }
+            out.line(&line);
+        }
+        return;
+    }
+    if options.one || !colors {
+        for entry in entries {
+            out.line(&entry.painted(colors));
+        }
+        return;
+    }
+    // Colonnes : largeur du nom le plus long + 2, remplies de haut en bas
+    let terminal = crossterm::terminal::size().map(|(w, _)| w as usize).unwrap_or(80);
+    let cell = entries.iter().map(|e| e.name.chars().count()).max().unwrap_or(0) + 2;
+    let columns = (terminal / cell).max(1);
+    let rows = entries.len().div_ceil(columns);
+    for row in 0..rows {
+        let mut line = String::new();
+        for entry in entries.iter().skip(row).step_by(rows) {
+            let pad = cell - entry.name.chars().count();
+            line.push_str(&entry.painted(colors));
+            line.push_str(&" ".repeat(pad));
+        }
+        out.line(line.trim_end());
+    }
+}
+
+impl Command for LsCommand {
+    fn name(&self) -> &'static str {
+        "ls"
+    }
+    fn about(&self) -> &'static str {
+        "Liste le contenu des dossiers (-a : cachés, -l : détails, -1 : un par ligne)."
+    }
+    fn usage(&self) -> &'static str {
+        "ls [-al1] [path]..."
+    }
+
+    fn defers_to_program(&self, args: &[&str]) -> bool {
+        // `-lh`, `-t`, `--color=auto`… : le `ls` du système
+        parse(args).is_err()
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        let (options, mut paths) = match parse(args) {
+            Ok(parsed) => parsed,
+            Err(flag) => {
+                out.error(&format!("ls: option inconnue -- '{}'", flag));
+                out.error(&format!("Usage: {}", self.usage()));
+                return 2;
+            }
+        };
+        if paths.is_empty() {
+            paths.push(String::from("."));
+        }
+
+        // Comme ls : les fichiers nommés d'abord, puis chaque dossier avec son titre
+        let mut status = 0;
+        let (mut files, mut dirs) = (Vec::new(), Vec::new());
+        for path in &paths {
+            match Entry::read(Path::new(path), path.clone()) {
+                Ok(_) if fs::metadata(path).is_ok_and(|m| m.is_dir()) => dirs.push(path),
+                Ok(entry) => files.push(entry),
+                Err(e) => {
+                    out.error(&format!("ls: {}: {}", path, e));
+                    status = 1;
+                }
+            }
+        }
+        files.sort_by_cached_key(|e| e.name.to_lowercase());
+        print(&files, &options, out);
+
+        let titled = paths.len() > 1;
+        for (i, dir) in dirs.iter().enumerate() {
+            if titled {
+                if i > 0 || !files.is_empty() {
+                    out.line("");
+                }
+                out.line(&format!("{}:", dir));
+            }
+            match list_dir(Path::new(dir), &options) {
+                Ok(entries) => print(&entries, &options, out),
+                Err(e) => {
+                    out.error(&format!("ls: {}: {}", dir, e));
+                    status = 1;
+                }
+            }
+        }
+        status
+    }
+}
@@ -1,22 +1,44 @@
 // src/shell/commands/mod.rs
+use crate::shell::aliases;
+use crate::shell::engine::{Output, ShellEngine};
+use crate::shell::pathcache;
+use crate::shell::plugins::Plugin;
 use std::collections::HashMap;
+use std::sync::Arc;
 
+pub mod alias;
+pub mod bm;
 pub mod cd;
 pub mod clear;
+pub mod dirs;
+pub mod echo;
+pub mod exit;
+pub mod export;
+pub mod functions;
+pub mod hash;
 pub mod hello;
 pub mod help;
+pub mod history;
+pub mod http;
+pub mod jobs;
+pub mod ls;
+pub mod open;
+pub mod plugin;
+pub mod pwd;
+pub mod source;
 pub mod theme;
+pub mod tui;
 
 /// Contrat minimal d’une commande interne.
 pub trait Command: Send + Sync {
     /// Nom canonique (clé d’invocation), ex: "help".
-    fn name(&self) -> &'static str;
+    fn name(&self) -> &str;
 
     /// Brève description (pour liste/overview).
-    fn about(&self) -> &'static str;
+    fn about(&self) -> &str;
 
     /// Syntaxe d’utilisation (ex: "help [command]").
-    fn usage(&self) -> &'static str {
+    fn usage(&self) -> &str {
         self.name()
     }
 
@@ -25,17 +47,27 @@ pub trait Command: Send + Sync {
         &[]
     }
 
-    /// Point d’entrée : exécute la commande.
-    /// `registry` est passé pour les commandes qui veulent introspecter (ex: help).
-    fn execute(&self, args: &[&str], registry: &CommandRegistry);
+    /// Arguments mieux servis par le programme du même nom dans le PATH (options que
+    /// la version interne ne connaît pas, ex: `ls -lh`) ; sans ce programme, la
+    /// commande interne s'exécute quand même et signale l'erreur.
+    fn defers_to_program(&self, _args: &[&str]) -> bool {
+        false
+    }
+
+    /// Point d’entrée : exécute la commande en écrivant dans `out` (erreurs via
+    /// `out.error`) et renvoie son code de sortie (0 succès, 1 échec, 2 mauvais usage).
+    /// `shell` est passé pour les commandes qui ont besoin du moteur (help, source, history).
+    fn execute(&self, args: &[&str], shell: &ShellEngine, out: &mut dyn Output) -> i32;
 }
 
 /// Registre central des commandes internes.
 pub struct CommandRegistry {
     /// commandes par nom canonique
-    commands: HashMap<String, Box<dyn Command>>,
+    commands: HashMap<String, Arc<dyn Command>>,
     /// alias -> nom canonique
     alias_map: HashMap<String, String>,
+    /// commandes des manifestes de plugins (remplacées par `set_plugins`)
+    plugins: Vec<Arc<Plugin>>,
 }
 
 impl CommandRegistry {
@@ -44,34 +76,39 @@ impl CommandRegistry {
         let mut registry = Self {
             commands: HashMap::new(),
             alias_map: HashMap::new(),
+            plugins: Vec::new(),
         };
 
         // Enregistre ici toutes les commandes "simples"
         registry.register(hello::HelloCommand);
         registry.register(clear::ClearCommand);
         registry.register(cd::CdCommand);
+        registry.register(pwd::PwdCommand);
+        registry.register(echo::EchoCommand);
+        registry.register(ls::LsCommand);
+        registry.register(dirs::PushdCommand);
+        registry.register(dirs::PopdCommand);
+        registry.register(dirs::DirsCommand);
+        registry.register(bm::BmCommand);
+        registry.register(http::HttpCommand);
+        registry.register(open::OpenCommand);
+        registry.register(alias::AliasCommand);
+        registry.register(alias::UnaliasCommand);
+        registry.register(export::ExportCommand);
+        registry.register(functions::FunctionsCommand);
+        registry.register(hash::HashCommand);
+        registry.register(jobs::JobsCommand);
+        registry.register(jobs::FgCommand);
+        registry.register(jobs::BgCommand);
+        registry.register(source::SourceCommand);
+        registry.register(exit::ExitCommand);
+        registry.register(tui::TuiCommand);
+        registry.register(history::HistoryCommand);
         // `help` utilise le registry en lecture, mais on lui passe `&registry` à l'exécution
         registry.register(help::HelpCommand);
-        // `theme` nécessitera l’accès au Prompt => voir new_with_prompt dans ton code si besoin
-
-        registry
-    }
-
-    /// Si tu as besoin d’injecter un Prompt (Arc<Mutex<Prompt>>) pour certaines commandes,
-    /// ajoute ici leur enregistrement (ex: ThemeCommand { prompt }).
-    pub fn new_with_prompt(
-        prompt: std::sync::Arc<std::sync::Mutex<crate::shell::prompt::Prompt>>,
-    ) -> Self {
-        let mut registry = Self {
-            commands: HashMap::new(),
-            alias_map: HashMap::new(),
-        };
-
-        registry.register(hello::HelloCommand);
-        registry.register(clear::ClearCommand);
-        registry.register(cd::CdCommand);
-        registry.register(help::HelpCommand);
-        registry.register(theme::ThemeCommand { prompt });
+        // `theme` agit sur le Prompt du moteur (`shell.prompt()`)
+        registry.register(theme::ThemeCommand);
+        registry.register(plugin::PluginCommand);
 
         registry
     }
@@ -81,31 +118,59 @@ impl CommandRegistry {
         let name = cmd.name().to_string();
         let aliases = cmd.aliases();
 
-        self.commands.insert(name.clone(), Box::new(cmd));
+        self.commands.insert(name.clone(), Arc::new(cmd));
         for &al in aliases {
             self.alias_map.insert(al.to_string(), name.clone());
         }
     }
 
-    /// Résout un nom (ou alias) vers la commande interne.
-    fn resolve(&self, name_or_alias: &str) -> Option<&Box<dyn Command>> {
-        if let Some(c) = self.commands.get(name_or_alias) {
-            return Some(c);
+    /// Remplace les commandes des plugins par `plugins` ; un plugin dont le nom ou un
+    /// alias est déjà pris (commande interne, plugin précédent) est écarté avec un message.
+    pub fn set_plugins(&mut self, plugins: Vec<Plugin>) -> Vec<String> {
+        for old in std::mem::take(&mut self.plugins) {
+            self.commands.remove(old.name());
+            for al in old.alias_names() {
+                self.alias_map.remove(al);
+            }
         }
-        if let Some(real) = self.alias_map.get(name_or_alias) {
-            return self.commands.get(real);
+        let mut errors = Vec::new();
+        for plugin in plugins {
+            let names = std::iter::once(plugin.name()).chain(plugin.alias_names().iter().map(String::as_str));
+            if let Some(taken) = names.into_iter().find(|n| self.resolves(n)) {
+                errors.push(format!("plugin {}: `{}` is already a command", plugin.manifest().display(), taken));
+                continue;
+            }
+            let plugin = Arc::new(plugin);
+            let name = plugin.name().to_string();
+            for al in plugin.alias_names() {
+                self.alias_map.insert(al.clone(), name.clone());
+            }
+            self.commands.insert(name, plugin.clone());
+            self.plugins.push(plugin);
         }
-        None
+        errors
     }
 
-    /// Exécute si c’est une commande interne, sinon retourne false pour laisser la main au système.
-    pub fn execute(&self, cmd: &str, args: &[&str]) -> bool {
-        if let Some(c) = self.resolve(cmd) {
-            c.execute(args, self);
-            true
-        } else {
-            false
-        }
+    /// Commandes chargées depuis les manifestes de plugins.
+    pub fn plugins(&self) -> &[Arc<Plugin>] {
+        &self.plugins
+    }
+
+    /// Résout un nom (ou alias) vers la commande interne.
+    fn resolve(&self, name_or_alias: &str) -> Option<&Arc<dyn Command>> {
+        let name = self.alias_map.get(name_or_alias).map(String::as_str).unwrap_or(name_or_alias);
+        self.commands.get(name_or_alias).or_else(|| self.commands.get(name))
+    }
+
+    /// Vrai si `name_or_alias` désigne une commande interne.
+    pub fn resolves(&self, name_or_alias: &str) -> bool {
+        self.resolve(name_or_alias).is_some()
+    }
+
+    /// Commande interne désignée par `name_or_alias`, à exécuter sans garder le
+    /// registre emprunté (`plugin reload` le modifie).
+    pub fn get(&self, name_or_alias: &str) -> Option<Arc<dyn Command>> {
+        self.resolve(name_or_alias).cloned()
     }
 
     /// Liste (triée) des noms *canoniques* (pour autocomplétion & affichage).
@@ -131,29 +196,59 @@ impl CommandRegistry {
 
     /// Proposition simple si commande inconnue (distance d’édition minimale).
     pub fn suggest(&self, unknown: &str) -> Option<String> {
-        let mut best: Option<(usize, String)> = None;
-        for name in self.commands.keys() {
-            let d = levenshtein(unknown, name);
-            if best.as_ref().map(|(bd, _)| d < *bd).unwrap_or(true) {
-                best = Some((d, name.clone()));
-            }
+        closest(unknown, self.commands.keys())
+    }
+
+    /// Commande introuvable : jusqu’à 3 noms proches parmi les commandes internes, les
+    /// alias et les programmes du PATH, du plus proche au plus éloigné.
+    pub fn suggest_command(&self, unknown: &str) -> Vec<String> {
+        let executables = pathcache::executables();
+        let aliases = aliases::load();
+        let mut found: Vec<(usize, &String)> = self
+            .commands
+            .keys()
+            .chain(aliases.keys())
+            .chain(executables.keys())
+            .map(|name| (levenshtein(unknown, name), name))
+            .filter(|(d, _)| *d <= 2)
+            .collect();
+        found.sort();
+        found.dedup_by(|a, b| a.1 == b.1);
+        found.into_iter().take(3).map(|(_, name)| name.clone()).collect()
+    }
+}
+
+/// Nom le plus proche de `unknown` (à 2 modifications au plus), le premier en cas d’égalité.
+fn closest<'a>(unknown: &str, names: impl Iterator<Item = &'a String>) -> Option<String> {
+    let mut best: Option<(usize, &String)> = None;
+    for name in names {
+        let d = levenshtein(unknown, name);
+        if best.as_ref().map(|(bd, _)| d < *bd).unwrap_or(true) {
+            best = Some((d, name));
         }
-        best.and_then(|(d, s)| if d <= 2 { Some(s) } else { None })
     }
+    best.and_then(|(d, s)| if d <= 2 { Some(s.clone()) } else { None })
 }
 
-/// Levenshtein minimaliste (pour une proposition "Did you mean ...?")
+/// Levenshtein minimaliste (pour une proposition "Did you mean ...?") ; deux lettres
+/// voisines inversées (`gts` / `gst`) comptent pour une seule modification.
 fn levenshtein(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut before: Vec<usize> = Vec::new();
     let mut prev: Vec<usize> = (0..=b.len()).collect();
     let mut curr = vec![0; b.len() + 1];
 
-    for (i, ca) in a.chars().enumerate() {
+    for (i, &ca) in a.iter().enumerate() {
         curr[0] = i + 1;
-        for (j, cb) in b.chars().enumerate() {
+        for (j, &cb) in b.iter().enumerate() {
             let cost = if ca == cb { 0 } else { 1 };
             curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+            if i > 0 && j > 0 && ca == b[j - 1] && a[i - 1] == cb {
+                curr[j + 1] = curr[j + 1].min(before[j - 1] + 1);
+            }
         }
-        std::mem::swap(&mut prev, &mut curr);
+        before = std::mem::replace(&mut prev, std::mem::take(&mut curr));
+        curr = vec![0; b.len() + 1];
     }
     prev[b.len()]
 }
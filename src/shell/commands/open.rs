@@ -0,0 +1,59 @@
+// src/shell/commands/open.rs
+use super::Command;
+use crate::shell::engine::{Output, ShellEngine};
+use std::io;
+use std::path::Path;
+use std::process::{Command as Process, Stdio};
+
+pub struct OpenCommand;
+
+/// Programme du bureau qui ouvre un fichier avec l’application par défaut.
+pub fn system_opener() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    }
+}
+
+/// Ouvre `path` avec l’application par défaut, sans attendre sa fermeture.
+pub fn open_default(path: &Path) -> io::Result<()> {
+    let mut child = Process::new(system_opener())
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // Récupère le processus à sa sortie (pas de zombie)
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+impl Command for OpenCommand {
+    fn name(&self) -> &'static str {
+        "open"
+    }
+    fn about(&self) -> &'static str {
+        "Ouvre des fichiers avec l’application par défaut."
+    }
+    fn usage(&self) -> &'static str {
+        "open <path>..."
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        if args.is_empty() {
+            out.error("Usage: open <path>...");
+            return 2;
+        }
+        let mut status = 0;
+        for path in args {
+            if let Err(e) = open_default(Path::new(path)) {
+                out.error(&format!("❌ Impossible d’ouvrir {path}: {e}"));
+                status = 1;
+            }
+        }
+        status
+    }
+}
@@ -0,0 +1,45 @@
+// src/shell/commands/plugin.rs
+use super::Command;
+use crate::shell::engine::{Output, ShellEngine};
+use crate::shell::plugins;
+
+pub struct PluginCommand;
+
+impl Command for PluginCommand {
+    fn name(&self) -> &'static str {
+        "plugin"
+    }
+    fn about(&self) -> &'static str {
+        "Commandes externes déclarées dans plugins/ (list, reload)."
+    }
+    fn usage(&self) -> &'static str {
+        "plugin list | plugin reload"
+    }
+
+    fn execute(&self, args: &[&str], shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        match args {
+            [] | ["list"] => {
+                let registry = shell.registry();
+                if registry.plugins().is_empty() {
+                    out.line(&format!("Aucun plugin ({}/*.toml)", plugins::plugins_dir().display()));
+                }
+                for plugin in registry.plugins() {
+                    out.line(&format!("{:<12} {:<40} {}", plugin.name(), plugin.about(), plugin.manifest().display()));
+                }
+                0
+            }
+            ["reload"] => {
+                let errors = shell.reload_plugins();
+                for e in &errors {
+                    out.error(&format!("⚠️ {}", e));
+                }
+                out.line(&format!("🔌 Plugins: {}", shell.registry().plugins().len()));
+                if errors.is_empty() { 0 } else { 1 }
+            }
+            _ => {
+                out.error(&format!("Usage: {}", self.usage()));
+                2
+            }
+        }
+    }
+}
@@ -0,0 +1,35 @@
+// src/shell/commands/pwd.rs
+use super::Command;
+use crate::shell::engine::{Output, ShellEngine};
+use std::env;
+
+pub struct PwdCommand;
+
+impl Command for PwdCommand {
+    fn name(&self) -> &'static str {
+        "pwd"
+    }
+    fn about(&self) -> &'static str {
+        "Affiche le répertoire courant."
+    }
+    fn usage(&self) -> &'static str {
+        "pwd"
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        if !args.is_empty() {
+            out.error(&format!("Usage: {}", self.usage()));
+            return 2;
+        }
+        match env::current_dir() {
+            Ok(dir) => {
+                out.line(&dir.display().to_string());
+                0
+            }
+            Err(e) => {
+                out.error(&format!("pwd: {}", e));
+                1
+            }
+        }
+    }
+}
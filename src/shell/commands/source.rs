@@ -0,0 +1,32 @@
+// src/shell/commands/source.rs
+use super::Command;
+use crate::shell::engine::{Output, ShellEngine};
+use crate::shell::script;
+use std::path::Path;
+
+pub struct SourceCommand;
+
+impl Command for SourceCommand {
+    fn name(&self) -> &'static str {
+        "source"
+    }
+    fn about(&self) -> &'static str {
+        "Exécute les commandes d’un fichier dans le shell courant."
+    }
+    fn usage(&self) -> &'static str {
+        "source <file>"
+    }
+    fn aliases(&self) -> &'static [&'static str] {
+        &["."]
+    }
+
+    fn execute(&self, args: &[&str], shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        match args {
+            [path] => script::run_file(Path::new(path), shell, out),
+            _ => {
+                out.error(&format!("Usage: {}", self.usage()));
+                2
+            }
+        }
+    }
+}
@@ -1,11 +1,17 @@
 // src/shell/commands/theme.rs
 use super::Command;
-use crate::shell::commands::CommandRegistry;
-use crate::shell::prompt::Prompt;
-use std::sync::{Arc, Mutex};
+use crate::shell::engine::{Output, ShellEngine};
+use crate::shell::prompt::theme;
 
-pub struct ThemeCommand {
-    pub prompt: Arc<Mutex<Prompt>>,
+pub struct ThemeCommand;
+
+/// Thèmes disponibles, l'actif marqué d'une `*`.
+fn list() -> Vec<String> {
+    let active = theme::active_name();
+    theme::names()
+        .into_iter()
+        .map(|name| format!("{} {}", if name == active { '*' } else { ' ' }, name))
+        .collect()
 }
 
 impl Command for ThemeCommand {
@@ -13,18 +19,45 @@ impl Command for ThemeCommand {
         "theme"
     }
     fn about(&self) -> &'static str {
-        "Gestion du thème (reload)."
+        "Gestion du thème (list, set <nom>, reload, use <fichier>)."
     }
     fn usage(&self) -> &'static str {
-        "theme reload"
+        "theme list | theme set <name> | theme reload | theme use <file>"
     }
 
-    fn execute(&self, args: &[&str], _registry: &CommandRegistry) {
-        if args.first().copied() == Some("reload") {
-            let mut p = self.prompt.lock().unwrap();
-            p.reload();
-        } else {
-            println!("Usage: theme reload");
+    fn execute(&self, args: &[&str], shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        match args {
+            [] | ["list"] => {
+                for line in list() {
+                    out.line(&line);
+                }
+            }
+            ["set", name] => {
+                let mut p = shell.prompt().lock().unwrap();
+                match p.set_named(name) {
+                    Ok(()) => out.line(&format!("🎨 Theme: {}", name)),
+                    Err(e) => {
+                        out.error(&format!("⚠️ Could not load theme: {}", e));
+                        return 1;
+                    }
+                }
+            }
+            ["reload"] => {
+                let mut p = shell.prompt().lock().unwrap();
+                p.reload();
+            }
+            ["use", path] => {
+                let mut p = shell.prompt().lock().unwrap();
+                if !p.use_file(path) {
+                    out.error(&format!("⚠️ Could not load theme: {} (missing or invalid config).", path));
+                    return 1;
+                }
+            }
+            _ => {
+                out.error(&format!("Usage: {}", self.usage()));
+                return 2;
+            }
         }
+        0
     }
 }
@@ -0,0 +1,30 @@
+// src/shell/commands/tui.rs
+use super::Command;
+use crate::shell::engine::{Output, ShellEngine};
+
+pub struct TuiCommand;
+
+impl Command for TuiCommand {
+    fn name(&self) -> &'static str {
+        "tui"
+    }
+    fn about(&self) -> &'static str {
+        "Ouvre l’interface plein écran (retour au REPL avec :repl ou :q)."
+    }
+    fn usage(&self) -> &'static str {
+        "tui"
+    }
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ui"]
+    }
+
+    fn execute(&self, args: &[&str], shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        if !args.is_empty() {
+            out.error(&format!("Usage: {}", self.usage()));
+            return 2;
+        }
+        // Le REPL ouvre le TUI une fois la ligne terminée
+        shell.request_tui();
+        0
+    }
+}
@@ -1,12 +1,41 @@
-use std::{fs, path::Path};
+pub mod paths;
+
+use std::{collections::HashMap, fs, path::{Path, PathBuf}, sync::OnceLock};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 pub struct ThemeConfig {
+    /// Prompt template (`{shell} {symbol} {path}`…), see `prompt::builder`
+    pub format: Option<String>,
     pub shell: ColorSection,
     pub path: ColorSection,
     pub time: ColorSection,
     pub symbol: ColorSection,
+    /// TUI look (`[tui]`), optional
+    #[serde(default)]
+    pub tui: TuiThemeConfig,
+}
+
+/// `[tui]` section of `config/theme.toml`
+#[derive(Debug, Default, Deserialize)]
+pub struct TuiThemeConfig {
+    #[serde(default)]
+    pub icons: IconConfig,
+}
+
+/// Explorer icons (`[tui.icons]`); every key is optional.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct IconConfig {
+    /// `emoji`, `nerd` (Nerd Font glyphs) or `ascii`
+    pub style: Option<String>,
+    pub directory: Option<String>,
+    pub file: Option<String>,
+    pub symlink: Option<String>,
+    /// Directories holding a `.git`
+    pub git_repo: Option<String>,
+    /// Icon per file extension (without the dot), over the built-in set
+    #[serde(default)]
+    pub extensions: HashMap<String, String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -15,12 +44,305 @@ pub struct ColorSection {
 }
 
 impl ThemeConfig {
-    pub fn load_from_file(path: &str) -> Option<Self> {
-        if Path::new(path).exists() {
+    pub fn load_from_file(path: impl AsRef<Path>) -> Option<Self> {
+        let path = path.as_ref();
+        if path.exists() {
             let content = fs::read_to_string(path).ok()?;
             toml::from_str::<ThemeConfig>(&content).ok()
         } else {
             None
         }
     }
-}
\ No newline at end of file
+}
+
+/// Shell behavior (`config.toml`); every key is optional.
+#[derive(Debug, Default, Deserialize)]
+pub struct ShellConfig {
+    /// Commands kept in the history (1000 when unset)
+    pub history_size: Option<usize>,
+    /// Screen the TUI opens on: `home` (default), `shell`, `workspace`, `dashboard`,
+    /// `git`, `containers` or `http`
+    pub default_screen: Option<String>,
+    /// Indentation width of the editor when `editor.toml` sets none (4 when unset)
+    pub tab_width: Option<usize>,
+    /// Show dotfiles in the explorer at startup (off when unset)
+    pub show_hidden: Option<bool>,
+    /// Ask before leaving the REPL or the TUI (off when unset)
+    pub confirm_exit: Option<bool>,
+    /// `error`, `info` (default) or `debug`
+    pub log_level: Option<String>,
+    /// Expand `*`, `?`, `[...]` and a leading `~` in command lines (on when unset)
+    pub glob: Option<bool>,
+}
+
+/// How much the shell logs (`log_level`), from the quietest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    #[default]
+    Info,
+    Debug,
+}
+
+impl ShellConfig {
+    pub fn load_from_file(path: impl AsRef<Path>) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        toml::from_str::<ShellConfig>(&content).ok()
+    }
+
+    /// Settings of `config.toml`, read once (defaults when the file is missing or invalid).
+    pub fn get() -> &'static ShellConfig {
+        static CONFIG: OnceLock<ShellConfig> = OnceLock::new();
+        CONFIG.get_or_init(|| Self::load_from_file(paths::config_file("config.toml")).unwrap_or_default())
+    }
+
+    pub fn history_size(&self) -> usize {
+        self.history_size.filter(|n| *n > 0).unwrap_or(crate::shell::history::HISTORY_CAPACITY)
+    }
+
+    pub fn tab_width(&self) -> usize {
+        self.tab_width.filter(|w| *w > 0).unwrap_or(4)
+    }
+
+    pub fn show_hidden(&self) -> bool {
+        self.show_hidden.unwrap_or(false)
+    }
+
+    pub fn confirm_exit(&self) -> bool {
+        self.confirm_exit.unwrap_or(false)
+    }
+
+    pub fn glob(&self) -> bool {
+        self.glob.unwrap_or(true)
+    }
+
+    pub fn log_level(&self) -> LogLevel {
+        match self.log_level.as_deref().map(str::to_lowercase).as_deref() {
+            Some("error") => LogLevel::Error,
+            Some("debug") => LogLevel::Debug,
+            _ => LogLevel::Info,
+        }
+    }
+}
+
+/// Explorer preferences (`config/explorer.toml`); every key is optional.
+#[derive(Debug, Default, Deserialize)]
+pub struct ExplorerConfig {
+    /// `name`, `size`, `modified` or `extension`
+    pub sort: Option<String>,
+    pub descending: Option<bool>,
+    /// Hide entries matched by `.gitignore` and the git excludes
+    pub hide_gitignored: Option<bool>,
+    /// Make the explorer follow `cd` in the Shell screen
+    pub follow_cwd: Option<bool>,
+    /// Explorer roots (`~` is expanded); the first one is opened at startup
+    #[serde(default)]
+    pub roots: Vec<String>,
+    /// Programs offered by the "Open with" menu
+    #[serde(default)]
+    pub open_with: Vec<OpenWithProgram>,
+}
+
+/// One `[[open_with]]` entry: `command` gets the file path in place of `{}` (appended otherwise).
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenWithProgram {
+    pub name: String,
+    pub command: String,
+    /// Extensions the program is offered for; empty means every file
+    #[serde(default)]
+    pub extensions: Vec<String>,
+}
+
+impl ExplorerConfig {
+    pub fn load_from_file(path: impl AsRef<Path>) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        toml::from_str::<ExplorerConfig>(&content).ok()
+    }
+}
+
+/// Editor preferences (`config/editor.toml`); every key is optional.
+#[derive(Debug, Default, Deserialize)]
+pub struct EditorConfig {
+    /// Columns of one indentation level (4 when unset)
+    pub tab_width: Option<usize>,
+    /// Indent with spaces (the default) rather than tab characters
+    pub expand_tabs: Option<bool>,
+    /// Overrides per file type: extension without the dot, or a whole file name (`Makefile`)
+    #[serde(default)]
+    pub filetypes: HashMap<String, FiletypeConfig>,
+    /// Strip the spaces and tabs ending lines when saving (off when unset)
+    pub trim_trailing_whitespace: Option<bool>,
+    /// End the file with a line break when saving (off when unset)
+    pub final_newline: Option<bool>,
+    /// Keep the undo history of saved files across sessions (off when unset)
+    pub persistent_undo: Option<bool>,
+    /// Close brackets and quotes as they are opened (on when unset)
+    pub auto_pairs: Option<bool>,
+    /// Lines kept visible above and below the cursor when scrolling (0 when unset)
+    pub scrolloff: Option<usize>,
+    /// Snippets expanded with Tab after their trigger (`[[snippets]]` entries)
+    #[serde(default)]
+    pub snippets: Vec<SnippetConfig>,
+}
+
+/// One `[[snippets]]` entry: the body replaces the trigger typed before the cursor.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct SnippetConfig {
+    pub trigger: String,
+    /// Text inserted, with `${1:placeholder}` / `$1` tab stops and `$0` for the
+    /// final cursor position
+    pub body: String,
+    /// File types (extensions or file names) the snippet is offered in; all when empty
+    #[serde(default)]
+    pub filetypes: Vec<String>,
+}
+
+/// One `[filetypes.<type>]` entry; unset keys fall back to the defaults.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct FiletypeConfig {
+    pub tab_width: Option<usize>,
+    pub expand_tabs: Option<bool>,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub final_newline: Option<bool>,
+    /// Comment markers: a line prefix (`"#"`), or an opening and a closing marker
+    /// separated by a space (`"<!-- -->"`)
+    pub comment: Option<String>,
+}
+
+/// Comment syntax of a file type, used to comment lines out (Alt+c, `gc`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommentSyntax {
+    pub start: String,
+    /// Closing marker of block-style comments (`-->`, `*/`)
+    pub end: Option<String>,
+}
+
+impl CommentSyntax {
+    /// Parse a `comment` value: the opening marker, then an optional closing one.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.split_whitespace();
+        let start = parts.next()?.to_string();
+        Some(CommentSyntax { start, end: parts.next().map(str::to_string) })
+    }
+}
+
+/// Comment markers of well-known file types (extension or file name), used when
+/// `config/editor.toml` sets none.
+const COMMENTS: &[(&[&str], &str)] = &[
+    (
+        &["rs", "c", "h", "cc", "cpp", "hpp", "cs", "java", "kt", "kts", "scala", "swift", "go", "js", "jsx", "mjs", "cjs", "ts", "tsx", "dart", "php", "jsonc", "proto", "zig"],
+        "//",
+    ),
+    (
+        &["sh", "bash", "zsh", "fish", "py", "rb", "pl", "r", "toml", "yaml", "yml", "conf", "cfg", "nix", "ps1", "cmake", "tf", "Makefile", "Dockerfile", "gitignore", "env"],
+        "#",
+    ),
+    (&["lua", "sql", "hs", "elm", "ada"], "--"),
+    (&["ini", "asm", "lisp", "clj", "el"], ";"),
+    (&["tex", "sty", "erl"], "%"),
+    (&["vim"], "\""),
+    (&["html", "htm", "xml", "svg", "md", "markdown", "vue"], "<!-- -->"),
+    (&["css", "scss", "less"], "/* */"),
+];
+
+/// Indentation of a buffer, resolved from the editor configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Indent {
+    pub width: usize,
+    pub expand_tabs: bool,
+}
+
+impl Default for Indent {
+    fn default() -> Self {
+        Indent { width: ShellConfig::get().tab_width(), expand_tabs: true }
+    }
+}
+
+/// Clean-up applied to a buffer when it is saved, resolved from the editor configuration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SaveCleanup {
+    pub trim_trailing_whitespace: bool,
+    pub final_newline: bool,
+}
+
+impl Indent {
+    /// Text of one indentation level.
+    pub fn unit(&self) -> String {
+        if self.expand_tabs { " ".repeat(self.width) } else { String::from("\t") }
+    }
+}
+
+impl EditorConfig {
+    pub fn load_from_file(path: impl AsRef<Path>) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        toml::from_str::<EditorConfig>(&content).ok()
+    }
+
+    /// Settings of a file's type: its file name's entry, else its extension's.
+    fn filetype(&self, path: Option<&Path>) -> Option<&FiletypeConfig> {
+        let name = path.and_then(|p| p.file_name()).map(|n| n.to_string_lossy().to_string());
+        let ext = path.and_then(|p| p.extension()).map(|e| e.to_string_lossy().to_lowercase());
+        name.iter().chain(ext.iter()).find_map(|key| self.filetypes.get(key))
+    }
+
+    /// Indentation for a file: its file type's settings, then the defaults.
+    pub fn indent_for(&self, path: Option<&Path>) -> Indent {
+        let filetype = self.filetype(path);
+        let default = Indent::default();
+        Indent {
+            width: filetype
+                .and_then(|f| f.tab_width)
+                .or(self.tab_width)
+                .filter(|w| *w > 0)
+                .unwrap_or(default.width),
+            expand_tabs: filetype.and_then(|f| f.expand_tabs).or(self.expand_tabs).unwrap_or(default.expand_tabs),
+        }
+    }
+
+    /// Comment syntax for a file: its file type's `comment`, then the built-in table.
+    pub fn comment_for(&self, path: Option<&Path>) -> Option<CommentSyntax> {
+        if let Some(comment) = self.filetype(path).and_then(|f| f.comment.as_deref()) {
+            return CommentSyntax::parse(comment);
+        }
+        let name = path.and_then(|p| p.file_name()).map(|n| n.to_string_lossy().to_string())?;
+        let ext = path.and_then(|p| p.extension()).map(|e| e.to_string_lossy().to_lowercase());
+        let dotfile = name.strip_prefix('.');
+        COMMENTS
+            .iter()
+            .find(|(types, _)| types.iter().any(|t| *t == name || Some(*t) == ext.as_deref() || Some(*t) == dotfile))
+            .and_then(|(_, comment)| CommentSyntax::parse(comment))
+    }
+
+    /// Snippets offered in a file: those of its file type and those without one.
+    pub fn snippets_for(&self, path: Option<&Path>) -> Vec<SnippetConfig> {
+        let name = path.and_then(|p| p.file_name()).map(|n| n.to_string_lossy().to_string());
+        let ext = path.and_then(|p| p.extension()).map(|e| e.to_string_lossy().to_lowercase());
+        self.snippets
+            .iter()
+            .filter(|s| s.filetypes.is_empty() || s.filetypes.iter().any(|t| Some(t) == name.as_ref() || Some(t) == ext.as_ref()))
+            .cloned()
+            .collect()
+    }
+
+    /// Clean-up on save for a file: its file type's settings, then the defaults.
+    pub fn cleanup_for(&self, path: Option<&Path>) -> SaveCleanup {
+        let filetype = self.filetype(path);
+        SaveCleanup {
+            trim_trailing_whitespace: filetype
+                .and_then(|f| f.trim_trailing_whitespace)
+                .or(self.trim_trailing_whitespace)
+                .unwrap_or(false),
+            final_newline: filetype.and_then(|f| f.final_newline).or(self.final_newline).unwrap_or(false),
+        }
+    }
+}
+
+/// Replace a leading `~` with the home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), home::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with(std::path::is_separator) => {
+            home.join(rest.trim_start_matches(std::path::is_separator))
+        }
+        _ => PathBuf::from(path),
+    }
+}
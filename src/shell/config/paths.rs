@@ -0,0 +1,62 @@
+//! Locations of the configuration and state files.
+//!
+//! User files live in `$XDG_CONFIG_HOME/paschek/` (`~/.config/paschek/` when the
+//! variable is unset) and per-machine state in `$XDG_STATE_HOME/paschek/`
+//! (`~/.local/state/paschek/`). A config file missing from the user directory is
+//! looked up in the bundled `config/` directory (the defaults shipped with the
+//! sources), under the current directory or next to the executable, so a checkout
+//! keeps working without installing anything.
+
+use std::env;
+use std::path::PathBuf;
+
+/// `$<var>` when it holds an absolute path, else `~/<fallback>`.
+fn xdg_dir(var: &str, fallback: &str) -> PathBuf {
+    env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| dirs::home_dir().map(|home| home.join(fallback)))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("paschek")
+}
+
+/// User configuration directory (`~/.config/paschek/`).
+pub fn config_dir() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// State directory (`~/.local/state/paschek/`): history, swap files, undo…
+pub fn state_dir() -> PathBuf {
+    xdg_dir("XDG_STATE_HOME", ".local/state")
+}
+
+/// Bundled `config/` directories: under the current directory, then next to the
+/// executable or a few levels up (`target/debug/` in a checkout).
+fn bundled_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from("config")];
+    if let Some(exe_dir) = env::current_exe().ok().and_then(|exe| exe.parent().map(PathBuf::from)) {
+        dirs.extend(exe_dir.ancestors().take(3).map(|dir| dir.join("config")).filter(|dir| dir.is_dir()));
+    }
+    dirs
+}
+
+/// Config file `name` (`theme.toml`, `themes/dark.toml`…): the user's copy when it
+/// exists, else a bundled one, else the user path (where a new file should be written).
+pub fn config_file(name: &str) -> PathBuf {
+    let user = config_dir().join(name);
+    if user.exists() {
+        return user;
+    }
+    bundled_dirs()
+        .into_iter()
+        .map(|dir| dir.join(name))
+        .find(|path| path.exists())
+        .unwrap_or(user)
+}
+
+/// Directories that may hold config files under `name`, user directory first.
+pub fn config_dirs(name: &str) -> Vec<PathBuf> {
+    let mut dirs = vec![config_dir().join(name)];
+    dirs.extend(bundled_dirs().into_iter().map(|dir| dir.join(name)));
+    dirs
+}
@@ -0,0 +1,41 @@
+//! Directory stack of `pushd`, `popd` and `dirs`.
+//!
+//! The stack holds the directories left by `pushd`, most recent last; the current
+//! directory is always its implicit top. It is shared by the REPL and the TUI shell
+//! for the whole session.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+static STACK: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Push `dir` on the stack.
+pub fn push(dir: PathBuf) {
+    if let Ok(mut stack) = STACK.lock() {
+        stack.push(dir);
+    }
+}
+
+/// Remove and return the most recent directory.
+pub fn pop() -> Option<PathBuf> {
+    STACK.lock().ok()?.pop()
+}
+
+/// Replace the most recent directory with `dir`; returns the one it replaced.
+pub fn swap_top(dir: PathBuf) -> Option<PathBuf> {
+    let mut stack = STACK.lock().ok()?;
+    let top = stack.last_mut()?;
+    Some(std::mem::replace(top, dir))
+}
+
+/// Directories of the stack, most recent first.
+pub fn entries() -> Vec<PathBuf> {
+    STACK.lock().map(|stack| stack.iter().rev().cloned().collect()).unwrap_or_default()
+}
+
+/// Empty the stack.
+pub fn clear() {
+    if let Ok(mut stack) = STACK.lock() {
+        stack.clear();
+    }
+}
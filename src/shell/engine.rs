@@ -0,0 +1,507 @@
+//! Shell core shared by the REPL, scripts and the TUI shell.
+//!
+//! [`ShellEngine`] holds what a command line needs: the built-in registry (with the
+//! commands of [`crate::shell::plugins`]), the functions defined during the session
+//! ([`crate::shell::functions`]), the prompt, the working directory and the history
+//! (the environment is the process one, set with `export`). It expands a line (`!N`,
+//! aliases, variables), parses it and runs built-ins and functions itself; an
+//! external program goes to the frontend: a single one comes back as [`External`],
+//! the ones of compound lines (`a; b`, `if`, `for`, functions) go to
+//! [`Output::run_external`]. The REPL and scripts run them with [`executor::run`]
+//! (foreground with redirections, or a job with `&`); the TUI starts them in its own
+//! job manager or a PTY.
+//!
+//! Everything a command prints goes through [`Output`]: [`Stdout`] for the REPL and
+//! scripts, the `TerminalPane` for the TUI.
+
+use crate::shell::aliases;
+use crate::shell::commands::CommandRegistry;
+use crate::shell::config::{LogLevel, ShellConfig};
+use crate::shell::executor::{self, Redirections};
+use crate::shell::functions::Function;
+use crate::shell::history::{self, HistoryStore};
+use crate::shell::parser;
+use crate::shell::pathcache;
+use crate::shell::plugins;
+use crate::shell::prompt::Prompt;
+use crate::shell::signals;
+use crate::shell::syntax::{self, Node};
+use crate::shell::variables;
+use std::cell::{Cell, Ref, RefCell, RefMut};
+use std::collections::BTreeMap;
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Where the output of a command line goes.
+pub trait Output {
+    /// One line of normal output.
+    fn line(&mut self, line: &str);
+
+    /// One line of error output (same place as [`Output::line`] by default).
+    fn error(&mut self, line: &str) {
+        self.line(line);
+    }
+
+    /// Raw text, possibly without a final line break (`echo -n`); split into lines
+    /// by default.
+    fn write(&mut self, text: &str) {
+        for line in text.lines() {
+            self.line(line);
+        }
+    }
+
+    /// Whether ANSI colors are shown (a terminal, not the TUI pane or a file).
+    fn colors(&self) -> bool {
+        false
+    }
+
+    /// Clear the screen (`clear`).
+    fn clear(&mut self) {}
+
+    /// Run an external program of the line to its end (a `&` job is only started);
+    /// returns its exit status.
+    fn run_external(&mut self, external: &External, registry: &CommandRegistry) -> i32;
+}
+
+/// Output of the REPL and of scripts: the process stdout / stderr.
+pub struct Stdout;
+
+impl Output for Stdout {
+    fn line(&mut self, line: &str) {
+        println!("{line}");
+    }
+
+    fn error(&mut self, line: &str) {
+        eprintln!("{line}");
+    }
+
+    fn write(&mut self, text: &str) {
+        print!("{text}");
+        let _ = io::stdout().flush();
+    }
+
+    fn colors(&self) -> bool {
+        io::stdout().is_terminal()
+    }
+
+    fn clear(&mut self) {
+        print!("\x1B[2J\x1B[1;1H");
+    }
+
+    fn run_external(&mut self, external: &External, registry: &CommandRegistry) -> i32 {
+        executor::run(external, registry, self)
+    }
+}
+
+/// External program of a command line, left to the frontend.
+pub struct External {
+    /// The line after expansion, without its trailing `&` (label of the job).
+    pub line: String,
+    /// Program and arguments.
+    pub words: Vec<String>,
+    /// `< f`, `> f`, `>> f`, `2> f`
+    pub redirections: Redirections,
+    /// Ends with `&`.
+    pub background: bool,
+}
+
+/// What [`ShellEngine::dispatch`] did with a line.
+pub enum Dispatch {
+    /// Handled (built-in, empty line or error): exit status.
+    Done(i32),
+    /// External program to run.
+    External(External),
+    /// `exit` (or a failure under `set -e`) stopped the commands: exit status.
+    Exit(i32),
+}
+
+/// How a run of parsed commands ended.
+pub enum Flow {
+    /// Ran to the end: status of the last command.
+    Done(i32),
+    /// Stopped by `exit [n]`, a failure under `set -e` or Ctrl+C: exit status.
+    Exit(i32),
+}
+
+/// Script whose commands run, for the messages of the failing ones.
+#[derive(Clone, Copy)]
+pub struct Script<'a> {
+    pub path: &'a Path,
+    /// Report every failing command (`~/.paschekrc`), not only the one stopping a
+    /// `set -e` script.
+    pub report: bool,
+}
+
+/// `path:line: ` of a command of `script`, nothing outside scripts.
+fn location(script: Option<Script<'_>>, line: usize) -> String {
+    script.map(|s| format!("{}:{}: ", s.path.display(), line)).unwrap_or_default()
+}
+
+/// Shell state shared by the frontends.
+pub struct ShellEngine {
+    registry: RefCell<CommandRegistry>,
+    prompt: Arc<Mutex<Prompt>>,
+    /// Directory last reported by [`ShellEngine::sync_cwd`]
+    cwd: PathBuf,
+    history: RefCell<HistoryStore>,
+    functions: RefCell<BTreeMap<String, Function>>,
+    /// Function calls in progress (bounded, against endless recursion)
+    depth: Cell<usize>,
+    /// `set -e`: stop at the first failing command
+    errexit: Cell<bool>,
+    /// Status of the last command (`exit` without argument)
+    last_status: Cell<i32>,
+    /// Code given to `exit`, until the frontend leaves
+    exit: Cell<Option<i32>>,
+    /// `tui` was run: the REPL opens the TUI after the line
+    tui: Cell<bool>,
+}
+
+/// Nested function calls allowed.
+const MAX_DEPTH: usize = 100;
+
+impl ShellEngine {
+    /// Engine with every built-in; `theme` changes `prompt`. Plugins are only
+    /// registered by [`ShellEngine::reload_plugins`].
+    pub fn new(prompt: Arc<Mutex<Prompt>>) -> Self {
+        Self {
+            registry: RefCell::new(CommandRegistry::new()),
+            prompt,
+            cwd: env::current_dir().unwrap_or_default(),
+            history: RefCell::new(HistoryStore::open()),
+            functions: RefCell::new(BTreeMap::new()),
+            depth: Cell::new(0),
+            errexit: Cell::new(false),
+            last_status: Cell::new(0),
+            exit: Cell::new(None),
+            tui: Cell::new(false),
+        }
+    }
+
+    /// Built-in commands.
+    pub fn registry(&self) -> Ref<'_, CommandRegistry> {
+        self.registry.borrow()
+    }
+
+    /// Re-read the plugin manifests and register their commands in place of the
+    /// previous ones; returns one message per plugin left out.
+    pub fn reload_plugins(&self) -> Vec<String> {
+        let (found, mut errors) = plugins::discover();
+        errors.extend(self.registry.borrow_mut().set_plugins(found));
+        errors
+    }
+
+    /// Functions defined during the session, by name.
+    pub fn functions(&self) -> Ref<'_, BTreeMap<String, Function>> {
+        self.functions.borrow()
+    }
+
+    /// Forget the function `name`; false when there is none.
+    pub fn remove_function(&self, name: &str) -> bool {
+        self.functions.borrow_mut().remove(name).is_some()
+    }
+
+    /// Status of the last command that ran.
+    pub fn last_status(&self) -> i32 {
+        self.last_status.get()
+    }
+
+    /// Ask the frontend to leave with `code` (`exit`): scripts stop at once, the REPL
+    /// and the TUI quit after the command line.
+    pub fn request_exit(&self, code: i32) {
+        self.exit.set(Some(code));
+    }
+
+    /// Code of a pending `exit`.
+    pub fn exit_requested(&self) -> Option<i32> {
+        self.exit.get()
+    }
+
+    /// Drop a pending `exit` (the user did not confirm it); returns its code.
+    pub fn take_exit(&self) -> Option<i32> {
+        self.exit.take()
+    }
+
+    /// Ask the REPL to open the TUI once the command line is done (`tui`).
+    pub fn request_tui(&self) {
+        self.tui.set(true);
+    }
+
+    /// Whether `tui` was run since the last call.
+    pub fn take_tui(&self) -> bool {
+        self.tui.take()
+    }
+
+    /// Prompt of the REPL (theme, last status).
+    pub fn prompt(&self) -> &Arc<Mutex<Prompt>> {
+        &self.prompt
+    }
+
+    /// The new working directory if a command (`cd`, `pushd`…) changed it since the
+    /// last call.
+    pub fn sync_cwd(&mut self) -> Option<PathBuf> {
+        let now = env::current_dir().ok().filter(|dir| *dir != self.cwd)?;
+        self.cwd = now.clone();
+        Some(now)
+    }
+
+    /// The shared history, re-read from disk (the other frontend may have written to it).
+    pub fn history(&self) -> RefMut<'_, HistoryStore> {
+        let mut store = self.history.borrow_mut();
+        store.reload();
+        store
+    }
+
+    /// Expand and parse `input`, then run it unless it is a single external program.
+    pub fn dispatch(&self, input: &str, out: &mut dyn Output) -> Dispatch {
+        // `!!`, `!N` : la ligne développée est affichée avant d'être lancée
+        let expanded = if input.contains('!') { history::expand_events(input, self.history().entries()) } else { Ok(None) };
+        let input = match expanded {
+            Ok(Some(expanded)) => {
+                out.line(&expanded);
+                expanded
+            }
+            Ok(None) => input.to_string(),
+            Err(e) => {
+                out.error(&format!("❌ {}", e));
+                return Dispatch::Done(1);
+            }
+        };
+        let nodes = match syntax::parse(&input) {
+            Ok(nodes) => nodes,
+            Err(e) => {
+                out.error(&format!("❌ Syntax error: {}", e));
+                return Dispatch::Done(2);
+            }
+        };
+        // Une commande simple reste au frontend s'il s'agit d'un programme externe (PTY, job du TUI)
+        if let [Node::Command { text, .. }] = nodes.as_slice() {
+            return self.dispatch_command(text, out);
+        }
+        match self.run(&nodes, None, out) {
+            Flow::Done(status) => Dispatch::Done(status),
+            Flow::Exit(status) => Dispatch::Exit(status),
+        }
+    }
+
+    /// Expand and parse one command line, then run it if it is a built-in or a function.
+    fn dispatch_command(&self, input: &str, out: &mut dyn Output) -> Dispatch {
+        let input = aliases::expand(input, &aliases::load());
+        let parsed = parser::tokenize(&input).and_then(|mut tokens| {
+            let background = parser::take_background(&mut tokens)?;
+            executor::parse_redirections(tokens).map(|(words, redir)| (words, redir, background))
+        });
+        let (words, redirections, background) = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                out.error(&format!("❌ Syntax error: {}", e));
+                return Dispatch::Done(2);
+            }
+        };
+        let Some(cmd) = words.first() else {
+            return Dispatch::Done(0);
+        };
+        if ShellConfig::get().log_level() == LogLevel::Debug {
+            out.error(&format!("+ {}", words.join(" ")));
+        }
+
+        // Commandes internes (et plugins)
+        // `ls -lh` : options que le built-in ne connaît pas, le programme du PATH s'en charge
+        let args: Vec<&str> = words[1..].iter().map(String::as_str).collect();
+        let builtin = self.registry.borrow().get(cmd).filter(|command| {
+            !(command.defers_to_program(&args) && pathcache::executables().contains_key(cmd.as_str()))
+        });
+        if let Some(command) = builtin {
+            if background {
+                out.error(&format!("❌ `&` is not supported for built-in command: {}", cmd));
+                return Dispatch::Done(2);
+            }
+            // `echo hi > f`, `ls 2> err` : la sortie de la commande va dans les fichiers
+            let mut out = match executor::RedirectedOutput::open(&redirections, out) {
+                Ok(out) => out,
+                Err(e) => {
+                    out.error(&format!("❌ {}: {}", cmd, e));
+                    return Dispatch::Done(if redirections.reads_input() { 2 } else { 1 });
+                }
+            };
+            let status = command.execute(&args, self, &mut out);
+            // `exit` (directement ou via `source`) : le reste de la ligne ou du script est abandonné
+            return match self.exit_requested() {
+                Some(code) => Dispatch::Exit(code),
+                None => Dispatch::Done(status),
+            };
+        }
+
+        let function = self.functions.borrow().get(cmd).cloned();
+        if let Some(function) = function {
+            if !redirections.is_empty() || background {
+                out.error(&format!("❌ Redirections and `&` are not supported for function: {}", cmd));
+                return Dispatch::Done(2);
+            }
+            return match self.call(&function, words, out) {
+                Flow::Done(status) => Dispatch::Done(status),
+                Flow::Exit(status) => Dispatch::Exit(status),
+            };
+        }
+
+        let line = if background { input.trim_end().trim_end_matches('&').trim_end() } else { input.trim() };
+        Dispatch::External(External { line: line.to_string(), words, redirections, background })
+    }
+
+    /// Run parsed commands; external programs go to `out`. With `script`, failing
+    /// commands are reported with their line (all of them when `report` is set, else
+    /// the one stopping a `set -e` script).
+    pub fn run(&self, nodes: &[Node], script: Option<Script<'_>>, out: &mut dyn Output) -> Flow {
+        let mut status = 0;
+        for node in nodes {
+            let flow = match node {
+                Node::Command { text, line } => self.run_command(text, *line, script, out),
+                Node::If { branches, otherwise } => self.run_if(branches, otherwise, script, out),
+                Node::For { var, words, body } => self.run_for(var, words, body, script, out),
+                Node::While { cond, body } => self.run_while(cond, body, script, out),
+                Node::Function(function) => Flow::Done(self.define(function.clone(), out)),
+            };
+            status = match flow {
+                // Commande interrompue (Ctrl+C) : le reste est abandonné
+                Flow::Done(130) => return Flow::Exit(130),
+                Flow::Done(status) => status,
+                exit => return exit,
+            };
+        }
+        Flow::Done(status)
+    }
+
+    /// Run a script: `set -e` applies until its end only.
+    pub fn run_script(&self, nodes: &[Node], script: Script<'_>, out: &mut dyn Output) -> i32 {
+        let errexit = self.errexit.replace(false);
+        let flow = self.run(nodes, Some(script), out);
+        self.errexit.set(errexit);
+        match flow {
+            Flow::Done(status) | Flow::Exit(status) => status,
+        }
+    }
+
+    /// Condition of `if` / `while`: its failure is expected, not reported nor fatal.
+    fn check(&self, cond: &[Node], out: &mut dyn Output) -> Flow {
+        let errexit = self.errexit.replace(false);
+        let flow = self.run(cond, None, out);
+        self.errexit.set(errexit);
+        flow
+    }
+
+    /// One command line; `set -e` / `set +e` are handled here.
+    fn run_command(&self, text: &str, line: usize, script: Option<Script<'_>>, out: &mut dyn Output) -> Flow {
+        if text.split_whitespace().next() == Some("set") {
+            let words = parser::tokenize(text).and_then(parser::words).unwrap_or_default();
+            match words.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+                ["set", "-e"] => {
+                    self.errexit.set(true);
+                    return Flow::Done(0);
+                }
+                ["set", "+e"] => {
+                    self.errexit.set(false);
+                    return Flow::Done(0);
+                }
+                _ => {}
+            }
+        }
+        let status = match self.dispatch_command(text, out) {
+            Dispatch::Done(status) => status,
+            Dispatch::Exit(status) => return Flow::Exit(status),
+            Dispatch::External(external) => out.run_external(&external, &self.registry.borrow()),
+        };
+        self.last_status.set(status);
+        let errexit = self.errexit.get();
+        if status != 0 && (errexit || script.is_some_and(|s| s.report)) {
+            out.error(&format!("❌ {}`{}` failed (exit {})", location(script, line), text, status));
+        }
+        if status != 0 && errexit { Flow::Exit(status) } else { Flow::Done(status) }
+    }
+
+    fn run_if(&self, branches: &[(Vec<Node>, Vec<Node>)], otherwise: &[Node], script: Option<Script<'_>>, out: &mut dyn Output) -> Flow {
+        for (cond, body) in branches {
+            match self.check(cond, out) {
+                Flow::Done(0) => return self.run(body, script, out),
+                Flow::Done(_) => {}
+                exit => return exit,
+            }
+        }
+        self.run(otherwise, script, out)
+    }
+
+    fn run_for(&self, var: &str, words: &str, body: &[Node], script: Option<Script<'_>>, out: &mut dyn Output) -> Flow {
+        let words = match parser::tokenize(words).and_then(parser::words) {
+            Ok(words) => words,
+            Err(e) => {
+                out.error(&format!("❌ Syntax error: {}", e));
+                return Flow::Done(2);
+            }
+        };
+        let mut status = 0;
+        for word in words {
+            variables::set(var, &word);
+            match self.run(body, script, out) {
+                Flow::Done(s) => status = s,
+                exit => return exit,
+            }
+        }
+        Flow::Done(status)
+    }
+
+    fn run_while(&self, cond: &[Node], body: &[Node], script: Option<Script<'_>>, out: &mut dyn Output) -> Flow {
+        let mut status = 0;
+        loop {
+            if signals::take_interrupt() {
+                return Flow::Exit(130);
+            }
+            match self.check(cond, out) {
+                Flow::Done(0) => {}
+                Flow::Done(_) => return Flow::Done(status),
+                exit => return exit,
+            }
+            match self.run(body, script, out) {
+                Flow::Done(s) => status = s,
+                exit => return exit,
+            }
+        }
+    }
+
+    /// Store a function definition; returns the status of the definition.
+    fn define(&self, function: Function, out: &mut dyn Output) -> i32 {
+        if self.registry.borrow().resolves(&function.name) {
+            out.error(&format!("❌ `{}` is a built-in command and cannot be redefined", function.name));
+            return 1;
+        }
+        self.functions.borrow_mut().insert(function.name.clone(), function);
+        0
+    }
+
+    /// Run the body of `function` with `words` (name, then arguments) as positional
+    /// parameters.
+    fn call(&self, function: &Function, words: Vec<String>, out: &mut dyn Output) -> Flow {
+        if self.depth.get() >= MAX_DEPTH {
+            out.error(&format!("❌ {}: maximum function nesting level exceeded ({})", function.name, MAX_DEPTH));
+            return Flow::Done(1);
+        }
+        self.depth.set(self.depth.get() + 1);
+        variables::push_positional(words);
+        let flow = self.run(&function.body, None, out);
+        variables::pop_positional();
+        self.depth.set(self.depth.get() - 1);
+        flow
+    }
+
+    /// Run a command line the REPL way; returns its exit status (the built-in's or the
+    /// program's, 2 for a syntax error, 127 when the command does not exist).
+    pub fn execute(&self, input: &str, out: &mut dyn Output) -> i32 {
+        let status = match self.dispatch(input, out) {
+            Dispatch::Done(status) | Dispatch::Exit(status) => status,
+            Dispatch::External(external) => out.run_external(&external, &self.registry.borrow()),
+        };
+        self.last_status.set(status);
+        self.prompt.lock().unwrap().set_status(status);
+        status
+    }
+}
@@ -1,36 +1,306 @@
 // src/shell/executor.rs
 use crate::shell::commands::CommandRegistry;
-use std::process::Command as SysCommand;
+use crate::shell::engine::{External, Output};
+use crate::shell::jobs;
+use crate::shell::parser::{Redirect, Token};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::process::{Command as SysCommand, ExitStatus, Stdio};
 
-pub fn execute_command(input: &str, registry: &CommandRegistry) {
-    let parts: Vec<&str> = input.split_whitespace().collect();
-    if parts.is_empty() {
-        return;
+/// Files named by the redirection operators of a command line.
+#[derive(Default)]
+pub struct Redirections {
+    /// `< file`
+    stdin: Option<String>,
+    /// `> file` / `>> file` (true = append)
+    stdout: Option<(String, bool)>,
+    /// `2> file`
+    stderr: Option<String>,
+}
+
+impl Redirections {
+    /// No redirection on the line.
+    pub fn is_empty(&self) -> bool {
+        self.stdin.is_none() && self.stdout.is_none() && self.stderr.is_none()
+    }
+
+    /// `< file` on the line.
+    pub fn reads_input(&self) -> bool {
+        self.stdin.is_some()
+    }
+}
+
+/// Split the redirections (`> f`, `>> f`, `< f`, `2> f`) off the tokens of a command line.
+pub fn parse_redirections(tokens: Vec<Token>) -> Result<(Vec<String>, Redirections), String> {
+    let mut words = Vec::new();
+    let mut redir = Redirections::default();
+    let mut iter = tokens.into_iter();
+    while let Some(token) = iter.next() {
+        let op = match token {
+            Token::Word(w) => {
+                words.push(w);
+                continue;
+            }
+            Token::Redirect(op) => op,
+            Token::Background => return Err(String::from("unexpected `&`")),
+        };
+        let target = match iter.next() {
+            Some(Token::Word(w)) => w,
+            Some(Token::Redirect(next)) => return Err(format!("unexpected `{}` after `{}`", next.symbol(), op.symbol())),
+            Some(Token::Background) | None => return Err(format!("missing file after `{}`", op.symbol())),
+        };
+        match op {
+            Redirect::Append => redir.stdout = Some((target, true)),
+            Redirect::Stdout => redir.stdout = Some((target, false)),
+            Redirect::Stderr => redir.stderr = Some(target),
+            Redirect::Stdin => redir.stdin = Some(target),
+        }
     }
+    Ok((words, redir))
+}
 
-    let cmd = parts[0];
-    let args = &parts[1..];
+fn open_err(path: &str, e: io::Error) -> String {
+    format!("{}: {}", path, e)
+}
 
-    // Essai commandes internes
-    if registry.execute(cmd, args) {
-        return;
+/// Files of `> f` / `>> f` and `2> f`, opened (truncated or appended).
+fn open_outputs(redir: &Redirections) -> Result<(Option<File>, Option<File>), String> {
+    let stdout = match &redir.stdout {
+        Some((path, append)) => Some(
+            OpenOptions::new()
+                .create(true)
+                .write(true)
+                .append(*append)
+                .truncate(!append)
+                .open(path)
+                .map_err(|e| open_err(path, e))?,
+        ),
+        None => None,
+    };
+    let stderr = match &redir.stderr {
+        Some(path) => Some(File::create(path).map_err(|e| open_err(path, e))?),
+        None => None,
+    };
+    Ok((stdout, stderr))
+}
+
+/// Open the redirection files and plug them into `command`.
+pub fn apply_redirections(command: &mut SysCommand, redir: &Redirections) -> Result<(), String> {
+    if let Some(path) = &redir.stdin {
+        let file = File::open(path).map_err(|e| open_err(path, e))?;
+        command.stdin(Stdio::from(file));
+    }
+    let (stdout, stderr) = open_outputs(redir)?;
+    if let Some(file) = stdout {
+        command.stdout(Stdio::from(file));
     }
+    if let Some(file) = stderr {
+        command.stderr(Stdio::from(file));
+    }
+    Ok(())
+}
+
+/// Output of a built-in with `>`, `>>` or `2>`: the redirected streams go to their
+/// file, the others to the frontend's output.
+pub struct RedirectedOutput<'a> {
+    stdout: Option<File>,
+    stderr: Option<File>,
+    out: &'a mut dyn Output,
+}
 
-    // Sinon, essai système
-    match SysCommand::new(cmd).args(args).output() {
-        Ok(out) => {
-            if !out.stdout.is_empty() {
-                print!("{}", String::from_utf8_lossy(&out.stdout));
+impl<'a> RedirectedOutput<'a> {
+    /// Open the files of `redir` in front of `out`; built-ins read no input, so `<`
+    /// is refused.
+    pub fn open(redir: &Redirections, out: &'a mut dyn Output) -> Result<Self, String> {
+        if redir.reads_input() {
+            return Err(String::from("`<` is not supported for built-in commands"));
+        }
+        let (stdout, stderr) = open_outputs(redir)?;
+        Ok(Self { stdout, stderr, out })
+    }
+}
+
+impl Output for RedirectedOutput<'_> {
+    fn line(&mut self, line: &str) {
+        match &mut self.stdout {
+            Some(file) => {
+                let _ = writeln!(file, "{line}");
+            }
+            None => self.out.line(line),
+        }
+    }
+
+    fn error(&mut self, line: &str) {
+        match &mut self.stderr {
+            Some(file) => {
+                let _ = writeln!(file, "{line}");
+            }
+            None => self.out.error(line),
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        match &mut self.stdout {
+            Some(file) => {
+                let _ = file.write_all(text.as_bytes());
+            }
+            None => self.out.write(text),
+        }
+    }
+
+    fn colors(&self) -> bool {
+        self.stdout.is_none() && self.out.colors()
+    }
+
+    fn clear(&mut self) {
+        self.out.clear();
+    }
+
+    fn run_external(&mut self, external: &External, registry: &CommandRegistry) -> i32 {
+        // `source f > out` : le programme attend ici pour que sa sortie aille au fichier
+        if self.stdout.is_some() || self.stderr.is_some() {
+            run(external, registry, self)
+        } else {
+            self.out.run_external(external, registry)
+        }
+    }
+}
+
+/// `cmd.exe` commands without a program of their own on Windows.
+#[cfg(windows)]
+const CMD_BUILTINS: [&str; 20] = [
+    "assoc", "copy", "date", "del", "dir", "erase", "ftype", "md", "mkdir", "mklink", "move", "path", "rd", "ren",
+    "rename", "rmdir", "start", "time", "type", "ver",
+];
+
+/// Process starting `program` with `args`. On Windows, the `cmd.exe` commands
+/// (`dir`, `copy`…) run through `cmd /C`, `.ps1` scripts and PowerShell cmdlets
+/// (`Get-ChildItem`) through PowerShell; elsewhere the program is started directly.
+#[cfg(not(windows))]
+pub fn command<S: AsRef<str>>(program: &str, args: &[S]) -> SysCommand {
+    let mut command = SysCommand::new(program);
+    command.args(args.iter().map(AsRef::as_ref));
+    command
+}
+
+#[cfg(windows)]
+pub fn command<S: AsRef<str>>(program: &str, args: &[S]) -> SysCommand {
+    use std::os::windows::process::CommandExt;
+    let args: Vec<&str> = args.iter().map(AsRef::as_ref).collect();
+    let lower = program.to_ascii_lowercase();
+    if CMD_BUILTINS.contains(&lower.as_str()) {
+        // `cmd` relit la ligne lui-même : guillemets doublés, pas l'échappement de Rust
+        let line: Vec<String> = std::iter::once(program).chain(args.iter().copied()).map(quote_cmd).collect();
+        let mut command = SysCommand::new("cmd");
+        command.arg("/C").raw_arg(line.join(" "));
+        return command;
+    }
+    if lower.ends_with(".ps1") {
+        let mut command = powershell();
+        command.args(["-ExecutionPolicy", "Bypass", "-File", program]).args(&args);
+        return command;
+    }
+    if is_cmdlet(program) && !crate::shell::pathcache::executables().contains_key(program) {
+        let line: Vec<String> = std::iter::once(program.to_string()).chain(args.iter().map(|a| quote_ps(a))).collect();
+        let mut command = powershell();
+        command.arg("-Command").arg(line.join(" "));
+        return command;
+    }
+    let mut command = SysCommand::new(program);
+    command.args(&args);
+    command
+}
+
+#[cfg(windows)]
+fn powershell() -> SysCommand {
+    let mut command = SysCommand::new("powershell");
+    command.arg("-NoProfile");
+    command
+}
+
+/// `Verb-Noun`, the form of PowerShell cmdlets.
+#[cfg(windows)]
+fn is_cmdlet(name: &str) -> bool {
+    let starts_upper = |s: &str| s.starts_with(|c: char| c.is_ascii_uppercase());
+    name.split_once('-').is_some_and(|(verb, noun)| {
+        starts_upper(verb) && starts_upper(noun) && verb.chars().chain(noun.chars()).all(|c| c.is_ascii_alphanumeric())
+    })
+}
+
+/// Argument for a `cmd /C` line.
+#[cfg(windows)]
+fn quote_cmd(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains(|c: char| c.is_whitespace() || "\"&|<>^".contains(c)) {
+        return arg.to_string();
+    }
+    format!("\"{}\"", arg.replace('"', "\"\""))
+}
+
+/// Argument for a PowerShell `-Command` line (single quotes, taken literally).
+#[cfg(windows)]
+fn quote_ps(arg: &str) -> String {
+    if !arg.is_empty() && arg.chars().all(|c| c.is_alphanumeric() || "-_./\\:".contains(c)) {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', "''"))
+}
+
+/// Exit status of a finished program: 128 + the signal number when a signal killed
+/// it, so only Ctrl+C (SIGINT, 130) stops the rest of a script.
+fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
+
+/// Run the external program of a command line: in the foreground (its output goes
+/// to `out`), or as a job with `&`. Returns its exit status (127 when it does not exist).
+pub fn run(external: &External, registry: &CommandRegistry, out: &mut dyn Output) -> i32 {
+    let cmd = &external.words[0];
+    let redir = &external.redirections;
+    let mut command = command(cmd, &external.words[1..]);
+    if let Err(e) = apply_redirections(&mut command, redir) {
+        out.error(&format!("❌ {}", e));
+        return 1;
+    }
+    if external.background {
+        // Pas d'entrée clavier pour un job en arrière-plan, sauf redirection
+        if redir.stdin.is_none() {
+            command.stdin(Stdio::null());
+        }
+        return match jobs::spawn(&mut command, &external.line) {
+            Ok((id, pid)) => {
+                out.line(&format!("[{}] {}", id, pid));
+                0
+            }
+            Err(_) => {
+                out.error(&format!("❌ Command not found: {}", cmd));
+                127
+            }
+        };
+    }
+    match command.output() {
+        Ok(output) => {
+            for line in String::from_utf8_lossy(&output.stdout).lines() {
+                out.line(line);
             }
-            if !out.stderr.is_empty() {
-                eprint!("{}", String::from_utf8_lossy(&out.stderr));
+            for line in String::from_utf8_lossy(&output.stderr).lines() {
+                out.error(line);
             }
+            exit_code(output.status)
         }
         Err(_) => {
-            eprintln!("❌ Command not found: {}", cmd);
-            if let Some(s) = registry.suggest(cmd) {
-                eprintln!("   Did you mean: {} ?", s);
+            out.error(&format!("❌ Command not found: {}", cmd));
+            let close = registry.suggest_command(cmd);
+            if !close.is_empty() {
+                out.error(&format!("   Did you mean: {} ?", close.join(", ")));
             }
+            127
         }
     }
 }
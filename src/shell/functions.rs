@@ -0,0 +1,39 @@
+//! User-defined functions of the shell language.
+//!
+//! A function is defined with `fn greet { echo hi $1; echo bye }` or the POSIX-like
+//! `greet() { echo hi $1 }` (parsed by [`crate::shell::syntax`]), at the prompt or
+//! in a script / `~/.paschekrc` where the body may also span several lines:
+//!
+//! ```text
+//! fn greet {
+//!     echo hi $1
+//! }
+//! ```
+//!
+//! Functions live in the [`crate::shell::engine::ShellEngine`] for the session and
+//! are called like commands: the body runs through the engine with `$1`…`$9`, `$#`
+//! and `$@` set to the call's arguments (see [`crate::shell::variables`]); the status
+//! is the last command's. A built-in cannot be redefined.
+
+use crate::shell::syntax::{self, Node};
+
+/// A function defined in the shell.
+#[derive(Clone, Debug)]
+pub struct Function {
+    pub name: String,
+    /// Commands of the body, in order.
+    pub body: Vec<Node>,
+}
+
+impl Function {
+    /// Definition on one line, as it can be typed again.
+    pub fn definition(&self) -> String {
+        format!("fn {} {{ {} }}", self.name, syntax::render(&self.body))
+    }
+}
+
+/// Whether `name` can name a function.
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_') && chars.all(|c| c.is_alphanumeric() || "_-".contains(c))
+}
@@ -0,0 +1,213 @@
+//! Thin wrapper around the `git` CLI.
+//!
+//! Used by the TUI Git screen and the explorer's status badges. Every helper
+//! runs `git -C <dir> ...` and returns an `io::Result`, turning a non-zero exit
+//! status into an error carrying git's stderr.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+/// One entry of `git status --porcelain`.
+#[derive(Clone)]
+pub struct GitFileStatus {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Index (staged) status column, e.g. 'M', 'A', 'D', ' ' or '?'
+    pub index: char,
+    /// Worktree (unstaged) status column
+    pub worktree: char,
+}
+
+impl GitFileStatus {
+    /// True if the entry has changes in the index.
+    pub fn is_staged(&self) -> bool {
+        self.index != ' ' && self.index != '?'
+    }
+
+    /// Single-character badge summarizing the status (for the explorer).
+    pub fn badge(&self) -> char {
+        match (self.index, self.worktree) {
+            ('?', _) => '?',
+            (_, 'M') | ('M', _) => 'M',
+            ('A', _) => 'A',
+            (_, 'D') | ('D', _) => 'D',
+            ('R', _) => 'R',
+            ('U', _) | (_, 'U') => 'U',
+            _ => '•',
+        }
+    }
+}
+
+fn run(dir: &Path, args: &[&str]) -> std::io::Result<Output> {
+    let out = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if out.status.success() {
+        Ok(out)
+    } else {
+        Err(std::io::Error::other(
+            String::from_utf8_lossy(&out.stderr).trim().to_string(),
+        ))
+    }
+}
+
+/// Root of the repository containing `dir`, if any.
+pub fn repo_root(dir: &Path) -> Option<PathBuf> {
+    let out = run(dir, &["rev-parse", "--show-toplevel"]).ok()?;
+    let s = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    if s.is_empty() { None } else { Some(PathBuf::from(s)) }
+}
+
+/// Name of the current branch (or short commit hash when detached).
+pub fn current_branch(dir: &Path) -> Option<String> {
+    let out = run(dir, &["rev-parse", "--abbrev-ref", "HEAD"]).ok()?;
+    let s = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    if s == "HEAD" {
+        let out = run(dir, &["rev-parse", "--short", "HEAD"]).ok()?;
+        return Some(String::from_utf8_lossy(&out.stdout).trim().to_string());
+    }
+    if s.is_empty() { None } else { Some(s) }
+}
+
+/// Working tree status of the repository containing `dir` (paths relative to its root).
+pub fn status(dir: &Path) -> std::io::Result<Vec<GitFileStatus>> {
+    let out = run(dir, &["status", "--porcelain=v1", "-z", "--untracked-files=all"])?;
+    let raw = String::from_utf8_lossy(&out.stdout);
+    let mut entries = Vec::new();
+    let mut fields = raw.split('\0');
+    while let Some(field) = fields.next() {
+        if field.len() < 4 {
+            continue;
+        }
+        let mut chars = field.chars();
+        let index = chars.next().unwrap_or(' ');
+        let worktree = chars.next().unwrap_or(' ');
+        let path = field[3..].to_string();
+        // Renames/copies are followed by the original path as a separate field
+        if index == 'R' || index == 'C' {
+            fields.next();
+        }
+        entries.push(GitFileStatus { path, index, worktree });
+    }
+    Ok(entries)
+}
+
+/// Stage a path (relative to the repository root).
+pub fn stage(root: &Path, path: &str) -> std::io::Result<()> {
+    run(root, &["add", "--", path]).map(|_| ())
+}
+
+/// Unstage a path, keeping the worktree changes.
+pub fn unstage(root: &Path, path: &str) -> std::io::Result<()> {
+    run(root, &["reset", "-q", "HEAD", "--", path])
+        .or_else(|_| run(root, &["rm", "--cached", "-q", "--", path]))
+        .map(|_| ())
+}
+
+/// Create a commit with the given message; returns git's summary line.
+pub fn commit(root: &Path, message: &str) -> std::io::Result<String> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["commit", "-F", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(message.as_bytes())?;
+    }
+    let out = child.wait_with_output()?;
+    if out.status.success() {
+        Ok(String::from_utf8_lossy(&out.stdout).lines().next().unwrap_or("").to_string())
+    } else {
+        let mut msg = String::from_utf8_lossy(&out.stderr).trim().to_string();
+        if msg.is_empty() {
+            msg = String::from_utf8_lossy(&out.stdout).trim().to_string();
+        }
+        Err(std::io::Error::other(msg))
+    }
+}
+
+/// Diff of a single path: staged changes (`--cached`) or worktree changes.
+/// Untracked files are shown as a full addition.
+pub fn diff(root: &Path, entry: &GitFileStatus, staged: bool) -> std::io::Result<Vec<String>> {
+    let out = if entry.index == '?' {
+        // `--no-index` exits with 1 when files differ: not an error here
+        Command::new("git")
+            .arg("-C")
+            .arg(root)
+            .args(["diff", "--no-color", "--no-index", "--", "/dev/null", &entry.path])
+            .output()?
+    } else if staged {
+        run(root, &["diff", "--no-color", "--cached", "--", &entry.path])?
+    } else {
+        run(root, &["diff", "--no-color", "--", &entry.path])?
+    };
+    Ok(String::from_utf8_lossy(&out.stdout).lines().map(|l| l.to_string()).collect())
+}
+
+/// Repository root of `file` and its path relative to that root (with `/` separators).
+pub fn locate(file: &Path) -> Option<(PathBuf, String)> {
+    let abs = file.canonicalize().ok()?;
+    let root = repo_root(abs.parent()?)?;
+    let root = root.canonicalize().unwrap_or(root);
+    let rel = abs.strip_prefix(&root).ok()?.to_string_lossy().replace('\\', "/");
+    Some((root, rel))
+}
+
+/// Raw content of `path` (relative to the repository root) at HEAD.
+pub fn head_blob(root: &Path, path: &str) -> std::io::Result<Vec<u8>> {
+    Ok(run(root, &["show", &format!("HEAD:{}", path)])?.stdout)
+}
+
+/// Content of `path` (relative to the repository root) at HEAD.
+pub fn show_head(root: &Path, path: &str) -> std::io::Result<String> {
+    Ok(String::from_utf8_lossy(&head_blob(root, path)?).into_owned())
+}
+
+/// Last commit of one line, from `git blame`.
+#[derive(Clone)]
+pub struct BlameLine {
+    pub author: String,
+    /// Author time (Unix seconds)
+    pub time: i64,
+    pub summary: String,
+    /// False for lines changed since the last commit
+    pub committed: bool,
+}
+
+/// Blame of `path` as if its content were `contents`: one entry per line.
+pub fn blame(root: &Path, path: &str, contents: &[u8]) -> std::io::Result<Vec<BlameLine>> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["blame", "--line-porcelain", "--contents", "-", "--", path])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // git lit tout le contenu avant d'écrire quoi que ce soit
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(contents)?;
+    }
+    let out = child.wait_with_output()?;
+    if !out.status.success() {
+        return Err(std::io::Error::other(String::from_utf8_lossy(&out.stderr).trim().to_string()));
+    }
+    let mut lines = Vec::new();
+    let mut current = BlameLine { author: String::new(), time: 0, summary: String::new(), committed: false };
+    for line in String::from_utf8_lossy(&out.stdout).lines() {
+        if line.starts_with('\t') {
+            lines.push(current.clone());
+        } else if let Some(author) = line.strip_prefix("author ") {
+            current.author = author.to_string();
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            current.time = time.parse().unwrap_or(0);
+        } else if let Some(summary) = line.strip_prefix("summary ") {
+            current.summary = summary.to_string();
+        } else if let Some(hash) = line.split(' ').next().filter(|h| h.len() >= 40 && h.chars().all(|c| c.is_ascii_hexdigit())) {
+            current.committed = hash.chars().any(|c| c != '0');
+        }
+    }
+    Ok(lines)
+}
@@ -0,0 +1,262 @@
+//! Persistent command history shared by the REPL and the TUI shell.
+//!
+//! Both frontends read and append to the same file (`history` in the state
+//! directory, or `~/.paschek_history` when that older file exists),
+//! so a command typed in one mode is available in the other:
+//! - The REPL hands the path to Reedline's `FileBackedHistory`
+//! - The TUI `TerminalPane` uses [`HistoryStore`] directly
+//!
+//! The on-disk format matches Reedline's: one entry per line, with embedded
+//! newlines escaped as `<\n>`.
+//!
+//! [`expand_events`] replaces `!!`, `!N` and `!-N` with earlier entries before a
+//! line is run (the `history` built-in shows the numbers). [`ReverseSearch`] is the
+//! Ctrl+R search of both frontends: they only translate their keys and draw its label.
+
+use crate::shell::config::{paths, ShellConfig};
+use dirs::home_dir;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Number of entries kept when `config.toml` sets no `history_size`.
+pub const HISTORY_CAPACITY: usize = 1000;
+
+/// Maximum number of entries kept in memory and on disk (`history_size`).
+pub fn capacity() -> usize {
+    ShellConfig::get().history_size()
+}
+
+/// Escape sequence used by Reedline for newlines inside an entry.
+const NEWLINE_ESCAPE: &str = "<\\n>";
+
+/// Location of the shared history file (`~/.local/state/paschek/history`).
+pub fn history_path() -> PathBuf {
+    let path = paths::state_dir().join("history");
+    let legacy = home_dir().map(|home| home.join(".paschek_history"));
+    match legacy {
+        // Historique d'avant le dossier d'état : on continue de s'en servir
+        Some(legacy) if !path.exists() && legacy.exists() => legacy,
+        _ => path,
+    }
+}
+
+/// File-backed history store (oldest entry first).
+pub struct HistoryStore {
+    path: PathBuf,
+    entries: Vec<String>,
+}
+
+impl HistoryStore {
+    /// Open the shared history file, loading existing entries if any.
+    pub fn open() -> Self {
+        let mut store = Self {
+            path: history_path(),
+            entries: Vec::new(),
+        };
+        store.reload();
+        store
+    }
+
+    /// Re-read entries from disk (picks up commands saved by the other frontend).
+    pub fn reload(&mut self) {
+        self.entries = fs::read_to_string(&self.path)
+            .map(|content| {
+                content
+                    .lines()
+                    .filter(|l| !l.trim().is_empty())
+                    .map(|l| l.replace(NEWLINE_ESCAPE, "\n"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if self.entries.len() > capacity() {
+            let overflow = self.entries.len() - capacity();
+            self.entries.drain(0..overflow);
+        }
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return true if the history is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// All entries, oldest first.
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Forget every entry, in memory and on disk.
+    pub fn clear(&mut self) -> std::io::Result<()> {
+        self.entries.clear();
+        if self.path.exists() {
+            fs::write(&self.path, "")?;
+        }
+        Ok(())
+    }
+
+    /// Entry at `idx` (0 = oldest), if any.
+    pub fn get(&self, idx: usize) -> Option<&str> {
+        self.entries.get(idx).map(|s| s.as_str())
+    }
+
+    /// Append an entry unless it is empty or equal to the last one, and persist it.
+    pub fn push(&mut self, line: &str) -> std::io::Result<()> {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || self.entries.last().map(|s| s.as_str()) == Some(trimmed) {
+            return Ok(());
+        }
+        self.entries.push(trimmed.to_string());
+        if self.entries.len() > capacity() {
+            self.entries.remove(0);
+        }
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut f = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(f, "{}", trimmed.replace('\n', NEWLINE_ESCAPE))
+    }
+}
+
+/// Replace the history references of `line` (`!!` for the previous command, `!N`
+/// for entry N, `!-N` for the Nth previous one) from `entries`, outside single
+/// quotes. Returns `Ok(None)` when there is nothing to replace, an error for an
+/// unknown entry.
+pub fn expand_events(line: &str, mut entries: &[String]) -> Result<Option<String>, String> {
+    if !line.contains('!') {
+        return Ok(None);
+    }
+    // La ligne en cours est déjà dans l'historique (REPL, TUI) : l'ignorer
+    if entries.last().is_some_and(|last| last == line.trim()) {
+        entries = &entries[..entries.len() - 1];
+    }
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::new();
+    let mut replaced = false;
+    let mut quoted = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        if c == '\'' {
+            quoted = !quoted;
+        }
+        if c != '!' || quoted {
+            out.push(c);
+            continue;
+        }
+        let (index, len) = if chars.get(i) == Some(&'!') {
+            (entries.len().checked_sub(1), 1)
+        } else {
+            let negative = chars.get(i) == Some(&'-');
+            let start = if negative { i + 1 } else { i };
+            let digits: String = chars[start.min(chars.len())..].iter().take_while(|c| c.is_ascii_digit()).collect();
+            let Ok(n) = digits.parse::<usize>() else {
+                out.push(c);
+                continue;
+            };
+            let index = if negative { entries.len().checked_sub(n) } else { n.checked_sub(1) };
+            (index, start - i + digits.len())
+        };
+        let reference: String = chars[i - 1..i + len].iter().collect();
+        let entry = index.and_then(|idx| entries.get(idx)).ok_or_else(|| format!("{}: event not found", reference))?;
+        out.push_str(entry);
+        replaced = true;
+        i += len;
+    }
+    Ok(replaced.then_some(out))
+}
+
+/// Key of a reverse incremental search, as read by the REPL or the TUI.
+pub enum SearchKey {
+    Char(char),
+    Backspace,
+    /// Ctrl+R again: next older match
+    Older,
+    /// Enter: run the match
+    Accept,
+    /// Esc: back to the line being edited
+    Cancel,
+}
+
+/// What the frontend does after a search key.
+pub enum SearchStep {
+    Continue,
+    Run(String),
+    Cancel,
+}
+
+/// Reverse incremental search (Ctrl+R): each typed char narrows the match, the
+/// newest entry containing the query.
+#[derive(Default)]
+pub struct ReverseSearch {
+    query: String,
+    /// Index of the current match in the entries
+    found: Option<usize>,
+    /// The query matches nothing (the previous match stays shown)
+    failed: bool,
+}
+
+impl ReverseSearch {
+    /// Apply `key`, searching `entries` (oldest first).
+    pub fn key(&mut self, key: SearchKey, entries: &[String]) -> SearchStep {
+        match key {
+            SearchKey::Char(c) => {
+                self.query.push(c);
+                // La correspondance actuelle reste valable si elle contient encore la requête
+                let end = self.found.map_or(entries.len(), |i| i + 1);
+                self.find(entries, end, None);
+            }
+            SearchKey::Backspace => {
+                self.query.pop();
+                self.found = None;
+                self.find(entries, entries.len(), None);
+            }
+            SearchKey::Older => {
+                let current = self.matched(entries).map(str::to_string);
+                let end = self.found.unwrap_or(entries.len());
+                self.find(entries, end, current.as_deref());
+            }
+            SearchKey::Accept => {
+                return match self.matched(entries) {
+                    Some(line) if !self.query.is_empty() => SearchStep::Run(line.to_string()),
+                    _ => SearchStep::Cancel,
+                };
+            }
+            SearchKey::Cancel => return SearchStep::Cancel,
+        }
+        SearchStep::Continue
+    }
+
+    /// Newest entry before `end` holding the query (other than `skip`).
+    fn find(&mut self, entries: &[String], end: usize, skip: Option<&str>) {
+        if self.query.is_empty() {
+            self.failed = false;
+            return;
+        }
+        let hit = entries[..end.min(entries.len())]
+            .iter()
+            .rposition(|e| e.contains(&self.query) && Some(e.as_str()) != skip);
+        self.failed = hit.is_none();
+        if hit.is_some() {
+            self.found = hit;
+        }
+    }
+
+    /// Current match, if any.
+    pub fn matched<'a>(&self, entries: &'a [String]) -> Option<&'a str> {
+        self.found.and_then(|i| entries.get(i)).map(String::as_str)
+    }
+
+    /// Line shown while searching: `(reverse-i-search)`query': match`.
+    pub fn label(&self, entries: &[String]) -> String {
+        let failed = if self.failed { "failed " } else { "" };
+        let matched = self.matched(entries).unwrap_or_default().replace('\n', " ");
+        format!("({}reverse-i-search)`{}': {}", failed, self.query, matched)
+    }
+}
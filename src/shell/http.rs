@@ -0,0 +1,124 @@
+//! Minimal HTTP client shared by the `http` built-in and the TUI HTTP screen.
+//!
+//! Requests are described by [`HttpRequest`] (method, URL, headers, body) and sent
+//! with `ureq`. Non-2xx statuses are returned as regular responses, only transport
+//! failures are errors. Saved requests ("collections") are stored as TOML in
+//! `~/.paschek_http.toml`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Methods offered by the request builder.
+pub const METHODS: [&str; 7] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+/// A request as edited in the TUI or parsed from `http` arguments.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct HttpRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub body: String,
+}
+
+/// A received response.
+#[derive(Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub status_text: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    pub elapsed: Duration,
+}
+
+impl HttpResponse {
+    /// Body pretty-printed if it is JSON, unchanged otherwise.
+    pub fn pretty_body(&self) -> String {
+        serde_json::from_str::<serde_json::Value>(&self.body)
+            .ok()
+            .and_then(|v| serde_json::to_string_pretty(&v).ok())
+            .unwrap_or_else(|| self.body.clone())
+    }
+}
+
+/// Parse a `Key: Value` header line.
+pub fn parse_header(line: &str) -> Option<(String, String)> {
+    let (k, v) = line.split_once(':')?;
+    let k = k.trim();
+    if k.is_empty() { None } else { Some((k.to_string(), v.trim().to_string())) }
+}
+
+/// Send a request and wait for the full response.
+pub fn send(req: &HttpRequest) -> Result<HttpResponse, String> {
+    let method = if req.method.is_empty() { "GET" } else { req.method.as_str() };
+    let mut url = req.url.trim().to_string();
+    if !url.contains("://") {
+        url = format!("http://{}", url);
+    }
+    let mut request = ureq::request(method, &url).timeout(Duration::from_secs(30));
+    for (k, v) in &req.headers {
+        request = request.set(k, v);
+    }
+
+    let started = Instant::now();
+    let result = if req.body.is_empty() { request.call() } else { request.send_string(&req.body) };
+    let response = match result {
+        Ok(r) => r,
+        // Les statuts 4xx/5xx restent des réponses à afficher
+        Err(ureq::Error::Status(_, r)) => r,
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let status = response.status();
+    let status_text = response.status_text().to_string();
+    let headers = response
+        .headers_names()
+        .into_iter()
+        .filter_map(|name| response.header(&name).map(|v| (name.clone(), v.to_string())))
+        .collect();
+    let mut bytes = Vec::new();
+    response.into_reader().read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(HttpResponse {
+        status,
+        status_text,
+        headers,
+        body: String::from_utf8_lossy(&bytes).into_owned(),
+        elapsed: started.elapsed(),
+    })
+}
+
+/// Location of the saved request collection.
+pub fn collection_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".paschek_http.toml")
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CollectionFile {
+    #[serde(default)]
+    requests: BTreeMap<String, HttpRequest>,
+}
+
+/// Saved requests by name (sorted). A missing or invalid file yields an empty collection.
+pub fn load_collection() -> BTreeMap<String, HttpRequest> {
+    fs::read_to_string(collection_path())
+        .ok()
+        .and_then(|s| toml::from_str::<CollectionFile>(&s).ok())
+        .map(|c| c.requests)
+        .unwrap_or_default()
+}
+
+/// Save (or overwrite) a named request in the collection.
+pub fn save_request(name: &str, req: &HttpRequest) -> std::io::Result<()> {
+    let mut file = CollectionFile { requests: load_collection() };
+    file.requests.insert(name.to_string(), req.clone());
+    let content = toml::to_string_pretty(&file).map_err(std::io::Error::other)?;
+    fs::write(collection_path(), content)
+}
@@ -0,0 +1,204 @@
+//! Background jobs of the REPL.
+//!
+//! A command ending with `&` is spawned without waiting for it: its output goes
+//! straight to the terminal and it is tracked here by job number and PID. `jobs`
+//! lists the table, `fg` waits for a job (resuming it when stopped) and `bg`
+//! resumes a stopped job in the background. Finished jobs are reported before the
+//! next prompt, which shows the number of jobs still alive.
+//!
+//! The TUI keeps its own jobs (`shell::tui::jobs`), whose output is captured.
+
+use std::io;
+use std::process::{Child, Command};
+use std::sync::Mutex;
+
+#[cfg(unix)]
+use crate::shell::signals;
+
+/// State of a job.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Running,
+    Stopped,
+    Done(i32),
+}
+
+impl JobStatus {
+    fn label(self) -> String {
+        match self {
+            JobStatus::Running => String::from("Running"),
+            JobStatus::Stopped => String::from("Stopped"),
+            JobStatus::Done(0) => String::from("Done"),
+            JobStatus::Done(code) => format!("Exit {}", code),
+        }
+    }
+}
+
+struct Job {
+    id: usize,
+    command: String,
+    child: Child,
+    status: JobStatus,
+}
+
+impl Job {
+    fn line(&self) -> String {
+        format!("[{}] {:<8} {:<10} {}", self.id, self.child.id(), self.status.label(), self.command)
+    }
+}
+
+struct JobTable {
+    jobs: Vec<Job>,
+}
+
+static TABLE: Mutex<JobTable> = Mutex::new(JobTable { jobs: Vec::new() });
+
+fn table() -> std::sync::MutexGuard<'static, JobTable> {
+    TABLE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// New status of `child` if it changed (waits for a change when `block`).
+#[cfg(unix)]
+fn wait(child: &mut Child, block: bool) -> Option<JobStatus> {
+    let flags = libc::WUNTRACED | if block { 0 } else { libc::WNOHANG };
+    let mut status = 0;
+    loop {
+        // SAFETY: `waitpid` sur un enfant de ce processus avec un entier local
+        let r = unsafe { libc::waitpid(child.id() as libc::pid_t, &mut status, flags) };
+        if r == 0 {
+            return None;
+        }
+        if r < 0 {
+            if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                // Ctrl+C pendant `fg` : le job a son propre groupe, on lui relaie
+                if signals::take_interrupt() {
+                    // SAFETY: simple envoi de signal à un PID dont on est le parent
+                    unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGINT) };
+                }
+                continue;
+            }
+            // Déjà récupéré ailleurs : on le considère terminé
+            return Some(JobStatus::Done(-1));
+        }
+        return Some(if libc::WIFSTOPPED(status) {
+            JobStatus::Stopped
+        } else if libc::WIFEXITED(status) {
+            JobStatus::Done(libc::WEXITSTATUS(status))
+        } else {
+            JobStatus::Done(128 + libc::WTERMSIG(status))
+        });
+    }
+}
+
+#[cfg(not(unix))]
+fn wait(child: &mut Child, block: bool) -> Option<JobStatus> {
+    let status = if block { child.wait().ok() } else { child.try_wait().ok().flatten() };
+    status.map(|s| JobStatus::Done(s.code().unwrap_or(-1)))
+}
+
+/// Send SIGCONT to a stopped job.
+#[cfg(unix)]
+fn resume(child: &Child) -> io::Result<()> {
+    // SAFETY: simple envoi de signal à un PID dont on est le parent
+    match unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGCONT) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+#[cfg(not(unix))]
+fn resume(_child: &Child) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "job control is not supported on this platform"))
+}
+
+/// Spawn `command` as a background job; returns its job number and PID.
+pub fn spawn(command: &mut Command, line: &str) -> io::Result<(usize, u32)> {
+    // Groupe de processus à part : Ctrl+C au terminal ne l'atteint pas
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(command, 0);
+    let child = command.spawn()?;
+    let pid = child.id();
+    let mut table = table();
+    let id = table.jobs.iter().map(|j| j.id).max().unwrap_or(0) + 1;
+    table.jobs.push(Job { id, command: line.to_string(), child, status: JobStatus::Running });
+    Ok((id, pid))
+}
+
+/// Refresh the statuses; finished jobs are removed and reported (one line each).
+pub fn reap() -> Vec<String> {
+    let mut table = table();
+    for job in table.jobs.iter_mut().filter(|j| !matches!(j.status, JobStatus::Done(_))) {
+        if let Some(status) = wait(&mut job.child, false) {
+            job.status = status;
+        }
+    }
+    let mut done = Vec::new();
+    table.jobs.retain(|j| {
+        let finished = matches!(j.status, JobStatus::Done(_));
+        if finished {
+            done.push(j.line());
+        }
+        !finished
+    });
+    done
+}
+
+/// Number of jobs still alive (running or stopped).
+pub fn count() -> usize {
+    table().jobs.len()
+}
+
+/// One line per job, oldest first.
+pub fn list() -> Vec<String> {
+    let mut lines = reap();
+    lines.extend(table().jobs.iter().map(Job::line));
+    lines
+}
+
+/// Job `spec` (`%2` or `2`), or the most recent job accepted by `filter`.
+fn find(jobs: &[Job], spec: Option<&str>, filter: impl Fn(&Job) -> bool) -> Result<usize, String> {
+    match spec {
+        Some(spec) => {
+            let id: usize = spec.trim_start_matches('%').parse().map_err(|_| format!("{}: invalid job number", spec))?;
+            jobs.iter().position(|j| j.id == id).ok_or_else(|| format!("{}: no such job", spec))
+        }
+        None => jobs.iter().rposition(filter).ok_or_else(|| String::from("no current job")),
+    }
+}
+
+/// Bring a job to the foreground and wait until it ends or stops.
+pub fn fg(spec: Option<&str>) -> Result<String, String> {
+    // Le verrou est relâché pendant l'attente : on retire le job de la table
+    let mut job = {
+        let mut table = table();
+        let i = find(&table.jobs, spec, |_| true)?;
+        table.jobs.remove(i)
+    };
+    println!("{}", job.command);
+    if job.status == JobStatus::Stopped {
+        resume(&job.child).map_err(|e| e.to_string())?;
+        job.status = JobStatus::Running;
+    }
+    job.status = wait(&mut job.child, true).unwrap_or(JobStatus::Done(-1));
+    if job.status == JobStatus::Stopped {
+        let line = job.line();
+        let mut table = table();
+        let at = table.jobs.partition_point(|j| j.id < job.id);
+        table.jobs.insert(at, job);
+        return Ok(line);
+    }
+    Ok(String::new())
+}
+
+/// Resume a stopped job in the background.
+pub fn bg(spec: Option<&str>) -> Result<String, String> {
+    let mut table = table();
+    let i = find(&table.jobs, spec, |j| j.status == JobStatus::Stopped)?;
+    let job = &mut table.jobs[i];
+    if job.status != JobStatus::Stopped {
+        return Err(format!("job {} already in background", job.id));
+    }
+    resume(&job.child).map_err(|e| e.to_string())?;
+    job.status = JobStatus::Running;
+    Ok(format!("[{}] {} &", job.id, job.command))
+}
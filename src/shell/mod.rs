@@ -3,21 +3,55 @@
 //! This module serves as the primary namespace for all shell-related functionality:
 //! 
 //! - [`repl`]: The Read-Eval-Print Loop that drives the shell's interaction cycle
-//! - [`executor`]: Command execution engine for both built-in and system commands
+//! - [`engine`]: Shell core shared by the REPL, scripts and the TUI (expansion, built-ins, output sinks)
+//! - [`executor`]: Runs external programs (redirections, background jobs)
+//! - [`parser`]: Command-line tokenizer (quotes, escapes, variables, redirections)
+//! - [`syntax`]: Parser of the shell language (`if`, `for`, `while`, functions) into commands
+//! - [`script`]: Script files (`paschek-cli script.psh`, `source`), with `set -e`
 //! - [`commands`]: Registry and implementations of built-in shell commands
+//! - [`plugins`]: External commands declared by manifests in `plugins/`, registered as built-ins
 //! - [`prompt`]: Customizable prompt rendering and theming system
-//! - [`config`]: Shell configuration management and persistence
+//! - [`config`]: Shell configuration management and persistence (`config::paths`: where files live)
+//! - [`pathcache`]: Index of the executables on `PATH` (completion, suggestions, `hash`)
+//! - [`jobs`]: Background jobs of the REPL (`cmd &`, `jobs`, `fg`, `bg`)
+//! - [`signals`]: Ctrl+C stops the foreground command, not the shell
+//! - [`history`]: Persistent command history shared by the REPL and the TUI
+//! - [`dirstack`]: Directory stack of `pushd`, `popd` and `dirs`
+//! - [`bookmarks`]: Pinned directories shared by the `bm` built-in and the TUI explorer
+//! - [`functions`]: Functions defined in the shell language (`fn name { … }`)
+//! - [`aliases`]: Command aliases (`aliases.toml`) expanded before dispatch
+//! - [`variables`]: `$VAR` expansion and the variables set with `export`
+//! - [`git`]: Thin wrapper around the `git` CLI (status, staging, diffs)
+//! - [`http`]: HTTP client and saved requests, shared by `http` and the TUI
+//! - [`vfs`]: Read-only virtual filesystems (archives) browsed by the TUI explorer
 //!
 //! The architecture follows a clear separation of concerns:
 //! 1. The REPL orchestrates the interaction loop
-//! 2. Commands are dispatched through the executor
+//! 2. Commands are dispatched through the engine, which both frontends call
 //! 3. Built-in commands are registered in the command registry
 //! 4. The prompt system handles visual presentation
 //! 5. Configuration manages persistent settings
 
 pub mod repl;
+pub mod engine;
 pub mod executor;
+pub mod parser;
+pub mod syntax;
+pub mod script;
 pub mod commands;
+pub mod plugins;
 pub mod prompt;
 pub mod config;
+pub mod history;
+pub mod jobs;
+pub mod pathcache;
+pub mod signals;
+pub mod dirstack;
+pub mod bookmarks;
+pub mod aliases;
+pub mod functions;
+pub mod variables;
+pub mod git;
+pub mod http;
+pub mod vfs;
 pub mod tui;
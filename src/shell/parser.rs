@@ -0,0 +1,403 @@
+//! Tokenizer for command lines, shared by the REPL executor and the TUI shell.
+//!
+//! Words are separated by unquoted whitespace. Inside single quotes everything is
+//! literal; inside double quotes `$VAR` is still expanded and `\` only escapes `"`,
+//! `\` and `$`; outside quotes `\` escapes any character. Unquoted `>`, `>>`, `<`
+//! and `2>` (at the start of a word) are redirection operators, and an unquoted `&`
+//! asks for a background job. A word made only of
+//! an unset variable disappears, while `""` stays an empty argument.
+//!
+//! A leading unquoted `~` (alone or before `/`) becomes the home directory, and a
+//! word holding unquoted `*`, `?` or `[...]` is replaced by the matching paths,
+//! sorted (dotfiles only when the pattern starts with `.`); a pattern matching
+//! nothing stays as typed. `glob = false` in `config.toml` turns both off. Text from
+//! quotes, escapes and variables is never a pattern.
+
+use std::path::Path;
+
+use crate::shell::config::ShellConfig;
+use crate::shell::variables;
+
+/// Redirection operators.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Redirect {
+    /// `<`
+    Stdin,
+    /// `>`
+    Stdout,
+    /// `>>`
+    Append,
+    /// `2>`
+    Stderr,
+}
+
+impl Redirect {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Redirect::Stdin => "<",
+            Redirect::Stdout => ">",
+            Redirect::Append => ">>",
+            Redirect::Stderr => "2>",
+        }
+    }
+}
+
+/// Token of a command line.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Token {
+    Word(String),
+    Redirect(Redirect),
+    /// `&`
+    Background,
+}
+
+/// Word being read by [`tokenize`].
+#[derive(Default)]
+struct Word {
+    text: String,
+    /// Same word as a glob pattern, quoted characters escaped
+    pattern: String,
+    /// True as soon as a literal character or quotes were seen: `""` is a word
+    started: bool,
+    /// Holds an unquoted `*`, `?` or `[`
+    glob: bool,
+}
+
+impl Word {
+    /// Character from quotes, an escape or a variable: never a pattern.
+    fn literal(&mut self, c: char) {
+        self.text.push(c);
+        if matches!(c, '*' | '?' | '[' | ']') {
+            self.pattern.extend(['[', c, ']']);
+        } else {
+            self.pattern.push(c);
+        }
+    }
+
+    fn literal_str(&mut self, s: &str) {
+        s.chars().for_each(|c| self.literal(c));
+    }
+
+    /// Unquoted character.
+    fn plain(&mut self, c: char) {
+        self.started = true;
+        self.glob |= matches!(c, '*' | '?' | '[');
+        self.text.push(c);
+        self.pattern.push(c);
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.started && self.text.is_empty()
+    }
+}
+
+/// Split `line` into words (quotes removed, variables, `~` and patterns expanded) and
+/// redirections.
+pub fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    scan(line, ShellConfig::get().glob())
+}
+
+/// Check that `line` tokenizes (quotes closed, `&` at the end, a file after each
+/// redirection) without expanding `~` or patterns; used to reject a script before
+/// it runs.
+pub fn check(line: &str) -> Result<(), String> {
+    let mut tokens = scan(line, false)?;
+    take_background(&mut tokens)?;
+    crate::shell::executor::parse_redirections(tokens).map(|_| ())
+}
+
+/// [`tokenize`], with `~` and patterns expanded only when `expand` is set.
+fn scan(line: &str, expand: bool) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut tokens = Vec::new();
+    let mut word = Word::default();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match c {
+            c if c.is_whitespace() => flush(&mut word, &mut tokens, expand),
+            '\'' => {
+                word.started = true;
+                let Some(len) = chars[i..].iter().position(|&c| c == '\'') else {
+                    return Err(String::from("unterminated single quote"));
+                };
+                chars[i..i + len].iter().for_each(|&c| word.literal(c));
+                i += len + 1;
+            }
+            '"' => {
+                word.started = true;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(String::from("unterminated double quote")),
+                        Some('"') => {
+                            i += 1;
+                            break;
+                        }
+                        Some('\\') if matches!(chars.get(i + 1), Some('"' | '\\' | '$')) => {
+                            word.literal(chars[i + 1]);
+                            i += 2;
+                        }
+                        Some('$') => i = expand_at(&chars, i + 1, &mut word),
+                        Some(&c) => {
+                            word.literal(c);
+                            i += 1;
+                        }
+                    }
+                }
+            }
+            '\\' => {
+                word.started = true;
+                word.literal(chars.get(i).copied().unwrap_or('\\'));
+                i += 1;
+            }
+            '$' => i = expand_at(&chars, i, &mut word),
+            '~' if expand && word.is_empty() && chars.get(i).is_none_or(|&c| std::path::is_separator(c) || c.is_whitespace()) => {
+                match dirs::home_dir() {
+                    Some(home) => {
+                        word.started = true;
+                        word.literal_str(&home.to_string_lossy());
+                    }
+                    None => word.plain('~'),
+                }
+            }
+            '>' | '<' => {
+                flush(&mut word, &mut tokens, expand);
+                let op = match (c, chars.get(i)) {
+                    ('>', Some('>')) => {
+                        i += 1;
+                        Redirect::Append
+                    }
+                    ('>', _) => Redirect::Stdout,
+                    _ => Redirect::Stdin,
+                };
+                tokens.push(Token::Redirect(op));
+            }
+            '&' => {
+                flush(&mut word, &mut tokens, expand);
+                tokens.push(Token::Background);
+            }
+            '2' if word.is_empty() && chars.get(i) == Some(&'>') => {
+                i += 1;
+                tokens.push(Token::Redirect(Redirect::Stderr));
+            }
+            c => word.plain(c),
+        }
+    }
+    flush(&mut word, &mut tokens, expand);
+    Ok(tokens)
+}
+
+/// End the current word, if any; a pattern becomes the paths it matches.
+fn flush(word: &mut Word, tokens: &mut Vec<Token>, expand: bool) {
+    let word = std::mem::take(word);
+    if word.is_empty() {
+        return;
+    }
+    if expand && word.glob {
+        let matches: Vec<String> = glob::glob(&word.pattern)
+            .map(|paths| {
+                paths
+                    .filter_map(Result::ok)
+                    .filter(|path| dots_asked(&word.pattern, path))
+                    .map(|p| match p.to_string_lossy() {
+                        // `./*.txt` garde son `./` (utile pour un nom commençant par `-`)
+                        p if word.pattern.starts_with("./") && !p.starts_with("./") => format!("./{p}"),
+                        p => p.into_owned(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !matches.is_empty() {
+            tokens.extend(matches.into_iter().map(Token::Word));
+            return;
+        }
+    }
+    tokens.push(Token::Word(word.text));
+}
+
+/// Whether every hidden name of `path` matches a part of `pattern` that starts with a
+/// dot (`require_literal_leading_dot` of `glob` also hides them from `.*`); `.` and
+/// `..` are only kept where the pattern spells them.
+fn dots_asked(pattern: &str, path: &Path) -> bool {
+    // Comparaison depuis la fin : `glob` peut retirer le `./` de tête
+    let path = path.to_string_lossy();
+    pattern.rsplit(std::path::is_separator).zip(path.rsplit(std::path::is_separator)).all(|(pat, name)| match name {
+        "." | ".." => pat == name,
+        name => !name.starts_with('.') || pat.starts_with('.'),
+    })
+}
+
+/// Expand the variable named after a `$` (`at` is just past it) into `word`;
+/// returns the index following the reference.
+fn expand_at(chars: &[char], at: usize, word: &mut Word) -> usize {
+    match variables::lookup(chars, at) {
+        Some((value, next)) => {
+            word.literal_str(&value);
+            next
+        }
+        None => {
+            word.literal('$');
+            at
+        }
+    }
+}
+
+/// Remove a trailing `&` from `tokens`; true when there was one. A `&` anywhere
+/// else is an error.
+pub fn take_background(tokens: &mut Vec<Token>) -> Result<bool, String> {
+    let background = tokens.last() == Some(&Token::Background);
+    if background {
+        tokens.pop();
+    }
+    if tokens.contains(&Token::Background) {
+        return Err(String::from("`&` is only allowed at the end of a command"));
+    }
+    Ok(background)
+}
+
+/// Words of `tokens`, rejecting redirections (for commands that cannot redirect).
+pub fn words(tokens: Vec<Token>) -> Result<Vec<String>, String> {
+    tokens
+        .into_iter()
+        .map(|t| match t {
+            Token::Word(w) => Ok(w),
+            Token::Redirect(op) => Err(format!("redirection `{}` not supported here", op.symbol())),
+            Token::Background => Err(String::from("unexpected `&`")),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn word(text: &str) -> Token {
+        Token::Word(text.to_string())
+    }
+
+    /// Words of `line`, globbing on.
+    fn words_of(line: &str) -> Vec<String> {
+        words(scan(line, true).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn splits_on_unquoted_whitespace() {
+        assert_eq!(words_of("  echo   a\tb  "), ["echo", "a", "b"]);
+        assert!(scan("", true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn single_quotes_are_literal() {
+        assert_eq!(words_of(r#"echo 'a  b' '$HOME' '\n' 'x"y'"#), ["echo", "a  b", "$HOME", r"\n", r#"x"y"#]);
+    }
+
+    #[test]
+    fn double_quotes_keep_spaces_and_expand_variables() {
+        variables::set("PASCHEK_TEST_DQ", "v a l");
+        assert_eq!(words_of(r#"echo "$PASCHEK_TEST_DQ!" "a \"b\" \\ \$x \n""#), ["echo", "v a l!", r#"a "b" \ $x \n"#]);
+    }
+
+    #[test]
+    fn backslash_escapes_outside_quotes() {
+        assert_eq!(words_of(r"echo a\ b \$x \> \&"), ["echo", "a b", "$x", ">", "&"]);
+    }
+
+    #[test]
+    fn unterminated_quotes_are_errors() {
+        assert_eq!(scan("echo 'abc", true), Err(String::from("unterminated single quote")));
+        assert_eq!(scan("echo \"abc", true), Err(String::from("unterminated double quote")));
+    }
+
+    #[test]
+    fn variables_expand_and_unset_ones_disappear() {
+        variables::set("PASCHEK_TEST_VAR", "hello");
+        assert_eq!(words_of("echo $PASCHEK_TEST_VAR ${PASCHEK_TEST_VAR}x $PASCHEK_TEST_UNSET"), ["echo", "hello", "hellox"]);
+        // `""` reste un argument vide
+        assert_eq!(words_of(r#"echo "" "$PASCHEK_TEST_UNSET""#), ["echo", "", ""]);
+    }
+
+    #[test]
+    fn expanded_values_are_not_split_or_globbed() {
+        variables::set("PASCHEK_TEST_GLOB", "* b");
+        assert_eq!(words_of("echo $PASCHEK_TEST_GLOB"), ["echo", "* b"]);
+    }
+
+    #[test]
+    fn leading_tilde_is_the_home_directory() {
+        let home = dirs::home_dir().unwrap().to_string_lossy().into_owned();
+        assert_eq!(words_of("cd ~"), ["cd", home.as_str()]);
+        assert_eq!(words_of("cd ~/src"), ["cd", format!("{home}/src").as_str()]);
+        assert_eq!(words_of("echo a~ '~' \\~ ~x"), ["echo", "a~", "~", "~", "~x"]);
+        // Sans expansion (`glob = false`) : tel quel
+        assert_eq!(words(scan("cd ~", false).unwrap()).unwrap(), ["cd", "~"]);
+    }
+
+    #[test]
+    fn redirections() {
+        assert_eq!(
+            scan("cmd <in >out 2>err", true).unwrap(),
+            [
+                word("cmd"),
+                Token::Redirect(Redirect::Stdin),
+                word("in"),
+                Token::Redirect(Redirect::Stdout),
+                word("out"),
+                Token::Redirect(Redirect::Stderr),
+                word("err"),
+            ]
+        );
+        assert_eq!(scan("a >> log", true).unwrap(), [word("a"), Token::Redirect(Redirect::Append), word("log")]);
+        // `2>` seulement en début de mot, opérateurs entre guillemets littéraux
+        assert_eq!(scan("echo a2>f", true).unwrap(), [word("echo"), word("a2"), Token::Redirect(Redirect::Stdout), word("f")]);
+        assert_eq!(words_of("echo '>' \"2>\""), ["echo", ">", "2>"]);
+    }
+
+    #[test]
+    fn words_rejects_redirections() {
+        assert_eq!(words(scan("a > b", true).unwrap()), Err(String::from("redirection `>` not supported here")));
+    }
+
+    #[test]
+    fn trailing_ampersand_is_background() {
+        let mut tokens = scan("sleep 1 &", true).unwrap();
+        assert_eq!(take_background(&mut tokens), Ok(true));
+        assert_eq!(tokens, [word("sleep"), word("1")]);
+
+        let mut tokens = scan("sleep 1", true).unwrap();
+        assert_eq!(take_background(&mut tokens), Ok(false));
+
+        let mut tokens = scan("a & b", true).unwrap();
+        assert!(take_background(&mut tokens).is_err());
+    }
+
+    #[test]
+    fn check_reports_errors_without_expanding() {
+        assert_eq!(check("echo ok > f &"), Ok(()));
+        assert_eq!(check("echo 'x"), Err(String::from("unterminated single quote")));
+        assert_eq!(check("echo a & b"), Err(String::from("`&` is only allowed at the end of a command")));
+        assert_eq!(check("echo >"), Err(String::from("missing file after `>`")));
+    }
+
+    #[test]
+    fn globs_match_sorted_paths_unless_quoted_or_disabled() {
+        let dir = std::env::temp_dir().join(format!("paschek-parser-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["b.txt", "a.txt", ".hidden.txt", "c.log"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        let d = dir.to_string_lossy();
+
+        assert_eq!(words_of(&format!("ls {d}/*.txt")), ["ls".to_string(), format!("{d}/a.txt"), format!("{d}/b.txt")]);
+        assert_eq!(words_of(&format!("ls {d}/.*.txt")), ["ls".to_string(), format!("{d}/.hidden.txt")]);
+        assert_eq!(words_of(&format!("ls {d}/?.log")), ["ls".to_string(), format!("{d}/c.log")]);
+        // Aucun résultat : le motif reste tel quel
+        assert_eq!(words_of(&format!("ls {d}/*.md")), ["ls".to_string(), format!("{d}/*.md")]);
+        // Entre guillemets, échappé ou globbing désactivé : littéral
+        assert_eq!(words_of(&format!("ls '{d}/*.txt' {d}/\\*.txt")), ["ls".to_string(), format!("{d}/*.txt"), format!("{d}/*.txt")]);
+        assert_eq!(words(scan(&format!("ls {d}/*.txt"), false).unwrap()).unwrap(), ["ls".to_string(), format!("{d}/*.txt")]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,90 @@
+//! Index of the executables found on `PATH`, for Tab completion and suggestions.
+//!
+//! The index is built on first use and rebuilt lazily when `PATH` changes (`export
+//! PATH=…`) or one of its directories is modified (a program installed or removed);
+//! `hash -r` drops it. A name present in several directories resolves to the first
+//! one, like the lookup of `std::process::Command`. On Windows a program is named
+//! without the `PATHEXT` extension it is found with (`git` for `git.exe`).
+
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Executables by name, with the file each one runs.
+pub type Executables = BTreeMap<String, PathBuf>;
+
+struct Index {
+    /// `PATH` the index was built from
+    path: Option<OsString>,
+    /// Modification time of each directory when it was read
+    stamps: Vec<Option<SystemTime>>,
+    executables: Arc<Executables>,
+}
+
+static INDEX: Mutex<Option<Index>> = Mutex::new(None);
+
+/// Directories of `PATH`, in lookup order.
+fn dirs(path: Option<&OsString>) -> Vec<PathBuf> {
+    path.map(|p| env::split_paths(p).filter(|d| !d.as_os_str().is_empty()).collect()).unwrap_or_default()
+}
+
+fn stamp(dir: &Path) -> Option<SystemTime> {
+    fs::metadata(dir).and_then(|m| m.modified()).ok()
+}
+
+/// Name the file `name` of a `PATH` directory is run by, if it is a program.
+#[cfg(unix)]
+fn program_name(name: String, meta: &fs::Metadata) -> Option<String> {
+    use std::os::unix::fs::PermissionsExt;
+    (meta.is_file() && meta.permissions().mode() & 0o111 != 0).then_some(name)
+}
+
+#[cfg(not(unix))]
+fn program_name(name: String, meta: &fs::Metadata) -> Option<String> {
+    let (stem, ext) = name.rsplit_once('.')?;
+    let pathext = env::var("PATHEXT").unwrap_or_else(|_| String::from(".COM;.EXE;.BAT;.CMD"));
+    let runnable = pathext.split(';').any(|e| e.strip_prefix('.').is_some_and(|e| e.eq_ignore_ascii_case(ext)));
+    (meta.is_file() && runnable && !stem.is_empty()).then(|| stem.to_string())
+}
+
+fn build(path: Option<OsString>) -> Index {
+    let dirs = dirs(path.as_ref());
+    let mut executables = Executables::new();
+    for dir in &dirs {
+        let Ok(entries) = fs::read_dir(dir) else { continue };
+        for entry in entries.flatten() {
+            let Ok(name) = entry.file_name().into_string() else { continue };
+            // `metadata` suit les liens symboliques (/usr/bin/python -> python3.x)
+            let Some(name) = fs::metadata(entry.path()).ok().and_then(|m| program_name(name, &m)) else { continue };
+            executables.entry(name).or_insert_with(|| entry.path());
+        }
+    }
+    Index { stamps: dirs.iter().map(|d| stamp(d)).collect(), path, executables: Arc::new(executables) }
+}
+
+/// The executables of the current `PATH`, rebuilt first if it is stale.
+pub fn executables() -> Arc<Executables> {
+    let path = env::var_os("PATH");
+    let mut index = INDEX.lock().unwrap_or_else(|e| e.into_inner());
+    let fresh = index
+        .as_ref()
+        .is_some_and(|i| i.path == path && dirs(path.as_ref()).iter().map(|d| stamp(d)).eq(i.stamps.iter().copied()));
+    if !fresh {
+        *index = Some(build(path));
+    }
+    index.as_ref().map(|i| i.executables.clone()).unwrap_or_default()
+}
+
+/// Forget the index (`hash -r`); the next lookup reads `PATH` again.
+pub fn clear() {
+    *INDEX.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Executables whose name starts with `prefix`, sorted.
+pub fn complete(prefix: &str) -> Vec<String> {
+    executables().range(prefix.to_string()..).map(|(name, _)| name).take_while(|name| name.starts_with(prefix)).cloned().collect()
+}
@@ -0,0 +1,184 @@
+//! External commands declared by the user, registered as built-ins.
+//!
+//! Each `*.toml` file of `plugins/` in the config directory
+//! (`~/.config/paschek/plugins/`) describes one command:
+//!
+//! ```toml
+//! name = "weather"
+//! about = "Météo du jour"
+//! usage = "weather [ville]"
+//! exec = "weather.sh"      # relative to plugins/, or a program of the PATH
+//! args = ["--short"]       # optional, passed before the user's arguments
+//! aliases = ["meteo"]      # optional
+//! ```
+//!
+//! The program runs to completion with the arguments of the command line, no
+//! standard input and `PASCHEK_PLUGIN` set to its name; its output goes through the
+//! engine like a built-in's (REPL or TUI pane) and its exit code is the status.
+//!
+//! `wasm = "weather.wasm"` (relative to plugins/) replaces `exec` for a WebAssembly
+//! module run in the shell's sandbox (see [`wasm`]). Manifests are read (and modules
+//! compiled) when the shell starts and again by `plugin reload`.
+
+mod wasm;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command as Process, Stdio};
+
+use serde::Deserialize;
+
+use crate::shell::commands::Command;
+use crate::shell::config::paths;
+use crate::shell::engine::{Output, ShellEngine};
+use wasm::WasmModule;
+
+/// Contents of a plugin manifest.
+#[derive(Deserialize)]
+struct Manifest {
+    name: String,
+    #[serde(default)]
+    about: String,
+    usage: Option<String>,
+    exec: Option<String>,
+    wasm: Option<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+}
+
+/// What runs a plugin command.
+enum Runner {
+    /// External program (`exec`)
+    Program(PathBuf),
+    /// WebAssembly module (`wasm`)
+    Wasm(WasmModule),
+}
+
+/// A command provided by a plugin manifest.
+pub struct Plugin {
+    name: String,
+    about: String,
+    usage: String,
+    runner: Runner,
+    args: Vec<String>,
+    aliases: Vec<String>,
+    manifest: PathBuf,
+}
+
+/// Directory scanned for manifests.
+pub fn plugins_dir() -> PathBuf {
+    paths::config_dir().join("plugins")
+}
+
+/// Whether `name` can name a plugin command or alias.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+impl Plugin {
+    /// Read the manifest at `path`.
+    fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let m: Manifest = toml::from_str(&text).map_err(|e| e.message().to_string())?;
+        if let Some(bad) = std::iter::once(&m.name).chain(&m.aliases).find(|n| !is_valid_name(n)) {
+            return Err(format!("invalid command name `{}`", bad));
+        }
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let runner = match (m.exec.as_deref().map(str::trim), m.wasm.as_deref().map(str::trim)) {
+            (Some(exec), None) if !exec.is_empty() => {
+                // `exec` relatif au dossier du manifeste s'il y existe, sinon cherché dans le PATH
+                let local = dir.join(exec);
+                Runner::Program(if local.exists() { local } else { PathBuf::from(exec) })
+            }
+            (None, Some(module)) if !module.is_empty() => Runner::Wasm(WasmModule::load(&dir.join(module))?),
+            (Some(_), Some(_)) => return Err(String::from("`exec` and `wasm` cannot be used together")),
+            _ => return Err(String::from("missing `exec` or `wasm`")),
+        };
+        Ok(Self {
+            usage: m.usage.unwrap_or_else(|| m.name.clone()),
+            name: m.name,
+            about: m.about,
+            runner,
+            args: m.args,
+            aliases: m.aliases,
+            manifest: path.to_path_buf(),
+        })
+    }
+
+    /// Other names of the command.
+    pub fn alias_names(&self) -> &[String] {
+        &self.aliases
+    }
+
+    /// Manifest the plugin was read from.
+    pub fn manifest(&self) -> &Path {
+        &self.manifest
+    }
+}
+
+/// Read every manifest of the plugins directory, sorted by file name; returns the
+/// plugins and one message per manifest that could not be used.
+pub fn discover() -> (Vec<Plugin>, Vec<String>) {
+    let Ok(entries) = fs::read_dir(plugins_dir()) else {
+        return (Vec::new(), Vec::new());
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    files.sort();
+    let (mut plugins, mut errors) = (Vec::new(), Vec::new());
+    for path in files {
+        match Plugin::load(&path) {
+            Ok(plugin) => plugins.push(plugin),
+            Err(e) => errors.push(format!("plugin {}: {}", path.display(), e)),
+        }
+    }
+    (plugins, errors)
+}
+
+impl Command for Plugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn about(&self) -> &str {
+        &self.about
+    }
+    fn usage(&self) -> &str {
+        &self.usage
+    }
+
+    fn execute(&self, args: &[&str], _shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+        let program = match &self.runner {
+            Runner::Program(program) => program,
+            Runner::Wasm(module) => {
+                let args = self.args.iter().cloned().chain(args.iter().map(|a| a.to_string())).collect();
+                return module.run(&self.name, args, out);
+            }
+        };
+        let output = Process::new(program)
+            .args(&self.args)
+            .args(args)
+            .env("PASCHEK_PLUGIN", &self.name)
+            .stdin(Stdio::null())
+            .output();
+        match output {
+            Ok(output) => {
+                for line in String::from_utf8_lossy(&output.stdout).lines() {
+                    out.line(line);
+                }
+                for line in String::from_utf8_lossy(&output.stderr).lines() {
+                    out.error(line);
+                }
+                // Pas de code : tué par un signal (Ctrl+C…)
+                output.status.code().unwrap_or(130)
+            }
+            Err(e) => {
+                out.error(&format!("❌ plugin {}: {}: {}", self.name, program.display(), e));
+                127
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! WASM runtime of the plugins declared with `wasm = "module.wasm"`.
+//!
+//! The module has no access to the system (no WASI): it exports its `memory` and a
+//! `run() -> i32` function (the exit status) and talks to the shell through the
+//! functions it imports from `paschek`:
+//!
+//! | Import                                   | Effect                                                    |
+//! |------------------------------------------|-----------------------------------------------------------|
+//! | `arg_count() -> i32`                     | Number of arguments (the manifest's `args` come first)    |
+//! | `arg(index, ptr, cap: i32) -> i32`       | Copy argument `index` (UTF-8, up to `cap` bytes) to `ptr`; its length, -1 if out of range |
+//! | `cwd(ptr, cap: i32) -> i32`              | Same for the working directory                            |
+//! | `print(ptr, len: i32)`                   | Write text to the output (lines are split on `\n`)        |
+//! | `eprint(ptr, len: i32)`                  | Same for the error output                                 |
+//!
+//! A run is limited in instructions, memory and output (text passed to `print` is
+//! bounds-checked and capped), so a broken module cannot hang or exhaust the shell.
+
+use std::path::Path;
+use std::sync::OnceLock;
+
+use wasmtime::{Caller, Config, Engine, Extern, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::shell::engine::Output;
+
+/// Instructions a run may execute (a few seconds of work).
+const FUEL: u64 = 5_000_000_000;
+/// Linear memory a module may use.
+const MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+/// Bytes one `print` / `eprint` call may pass.
+const TEXT_LIMIT: usize = 1024 * 1024;
+/// Output a run may produce, lines (both streams) and bytes.
+const OUTPUT_LINES: usize = 10_000;
+const OUTPUT_BYTES: usize = 4 * 1024 * 1024;
+
+/// Engine shared by every module (compilation settings).
+fn engine() -> &'static Engine {
+    static ENGINE: OnceLock<Engine> = OnceLock::new();
+    ENGINE.get_or_init(|| {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        Engine::new(&config).expect("WASM engine configuration")
+    })
+}
+
+/// A compiled plugin module.
+pub struct WasmModule {
+    module: Module,
+}
+
+/// Where a piece of text written by the module goes.
+#[derive(Clone, Copy)]
+enum Stream {
+    Out,
+    Err,
+}
+
+/// State seen by the host functions during a run.
+struct Host {
+    args: Vec<String>,
+    cwd: String,
+    /// Complete lines, in the order they were written
+    lines: Vec<(Stream, String)>,
+    /// Text after the last `\n` of each stream
+    pending: [String; 2],
+    /// Bytes written so far
+    written: usize,
+    limits: StoreLimits,
+}
+
+impl Host {
+    /// Add `text` to `stream`; fails (stopping the module) past the output limits.
+    fn write(&mut self, stream: Stream, text: &str) -> wasmtime::Result<()> {
+        self.written += text.len();
+        if self.written > OUTPUT_BYTES || self.lines.len() + text.matches('\n').count() > OUTPUT_LINES {
+            return Err(wasmtime::Error::msg("output limit exceeded"));
+        }
+        let pending = &mut self.pending[stream as usize];
+        pending.push_str(text);
+        while let Some(end) = pending.find('\n') {
+            let line = pending[..end].trim_end_matches('\r').to_string();
+            pending.drain(..=end);
+            self.lines.push((stream, line));
+        }
+        Ok(())
+    }
+
+    /// Send the output of the run to `out`.
+    fn flush(mut self, out: &mut dyn Output) {
+        for stream in [Stream::Out, Stream::Err] {
+            let rest = std::mem::take(&mut self.pending[stream as usize]);
+            if !rest.is_empty() {
+                self.lines.push((stream, rest));
+            }
+        }
+        for (stream, line) in self.lines {
+            match stream {
+                Stream::Out => out.line(&line),
+                Stream::Err => out.error(&line),
+            }
+        }
+    }
+}
+
+/// Exported memory of the calling module.
+fn memory(caller: &mut Caller<'_, Host>) -> wasmtime::Result<Memory> {
+    match caller.get_export("memory") {
+        Some(Extern::Memory(memory)) => Ok(memory),
+        _ => Err(wasmtime::Error::msg("module does not export `memory`")),
+    }
+}
+
+/// Copy `text` (at most `cap` bytes) to `ptr` in the module's memory; returns its length.
+fn copy_out(caller: &mut Caller<'_, Host>, text: &str, ptr: i32, cap: i32) -> wasmtime::Result<i32> {
+    let len = text.len().min(cap.max(0) as usize);
+    memory(caller)?.write(&mut *caller, ptr as u32 as usize, &text.as_bytes()[..len])?;
+    Ok(text.len() as i32)
+}
+
+/// Text of `len` bytes at `ptr` in the module's memory; the range is checked before
+/// anything is allocated.
+fn read_text(caller: &mut Caller<'_, Host>, ptr: i32, len: i32) -> wasmtime::Result<String> {
+    let memory = memory(caller)?;
+    let (start, len) = (ptr as u32 as usize, len.max(0) as usize);
+    if len > TEXT_LIMIT {
+        return Err(wasmtime::Error::msg(format!("text of {} bytes (limit {})", len, TEXT_LIMIT)));
+    }
+    let bytes = start
+        .checked_add(len)
+        .and_then(|end| memory.data(&*caller).get(start..end))
+        .ok_or_else(|| wasmtime::Error::msg("text out of the module's memory"))?;
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Host API imported by the modules.
+fn linker() -> wasmtime::Result<Linker<Host>> {
+    let mut linker = Linker::new(engine());
+    linker.func_wrap("paschek", "arg_count", |caller: Caller<'_, Host>| caller.data().args.len() as i32)?;
+    linker.func_wrap("paschek", "arg", |mut caller: Caller<'_, Host>, index: i32, ptr: i32, cap: i32| {
+        let Some(arg) = usize::try_from(index).ok().and_then(|i| caller.data().args.get(i)).cloned() else {
+            return Ok(-1);
+        };
+        copy_out(&mut caller, &arg, ptr, cap)
+    })?;
+    linker.func_wrap("paschek", "cwd", |mut caller: Caller<'_, Host>, ptr: i32, cap: i32| {
+        let cwd = caller.data().cwd.clone();
+        copy_out(&mut caller, &cwd, ptr, cap)
+    })?;
+    linker.func_wrap("paschek", "print", |mut caller: Caller<'_, Host>, ptr: i32, len: i32| {
+        let text = read_text(&mut caller, ptr, len)?;
+        caller.data_mut().write(Stream::Out, &text)
+    })?;
+    linker.func_wrap("paschek", "eprint", |mut caller: Caller<'_, Host>, ptr: i32, len: i32| {
+        let text = read_text(&mut caller, ptr, len)?;
+        caller.data_mut().write(Stream::Err, &text)
+    })?;
+    Ok(linker)
+}
+
+impl WasmModule {
+    /// Compile the module at `path` and check that it has a `run` entry point.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let module = Module::from_file(engine(), path).map_err(|e| format!("{}: {}", path.display(), e.root_cause()))?;
+        if module.get_export("run").and_then(|e| e.func().cloned()).is_none() {
+            return Err(format!("{}: no exported `run` function", path.display()));
+        }
+        Ok(Self { module })
+    }
+
+    /// Run the module with `args`; returns the status returned by `run`, or 1 when
+    /// the module fails (trap, limits, bad imports).
+    pub fn run(&self, name: &str, args: Vec<String>, out: &mut dyn Output) -> i32 {
+        let cwd = std::env::current_dir().map(|d| d.display().to_string()).unwrap_or_default();
+        let host = Host {
+            args,
+            cwd,
+            lines: Vec::new(),
+            pending: [String::new(), String::new()],
+            written: 0,
+            limits: StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build(),
+        };
+        let mut store = Store::new(engine(), host);
+        store.limiter(|host| &mut host.limits);
+        let result = store.set_fuel(FUEL).and_then(|()| {
+            let instance = linker()?.instantiate(&mut store, &self.module)?;
+            instance.get_typed_func::<(), i32>(&mut store, "run")?.call(&mut store, ())
+        });
+        store.into_data().flush(out);
+        match result {
+            Ok(status) => status,
+            Err(e) => {
+                // Le message racine (trap, import manquant…) suffit
+                out.error(&format!("❌ plugin {}: {}", name, e.root_cause()));
+                1
+            }
+        }
+    }
+}
@@ -1,35 +1,37 @@
 //! Prompt builder for PascheK Shell
 //!
-//! This module is responsible for constructing the shell's prompt string,
-//! which includes:
-//! - Shell name with customizable color
-//! - Current directory name
-//! - Current time
-//! - Decorative symbol
+//! This module is responsible for constructing the shell's prompt string from
+//! the `format` template of the theme (`config/theme.toml`). Placeholders:
+//! - `{shell}`: shell name, `{symbol}`: decorative bullet
+//! - `{path}`: current directory name, `{cwd}`: full current directory (`~` for home)
+//! - `{user}`, `{host}`: user and machine names
+//! - `{git}`: current git branch (empty outside a repository)
+//! - `{time}`: current time, `{status}`: exit code of the last command (empty when 0)
+//! - `{jobs}`: number of background jobs (`[2]`, empty when there are none)
 //!
-//! The prompt is built using the following segments:
+//! An empty segment also drops the space that follows it, and `{{` / `}}` write
+//! literal braces. The default template gives:
 //! ```text
-//! [Shell Name]> • [Current Dir] [Time]
+//! [Shell Name]> • [Current Dir] [Time] [Jobs]
 //! ```
 //!
 //! Each segment's color is controlled by the active theme.
 
 use chrono::Local;
+use owo_colors::{AnsiColors, OwoColorize};
 use std::env;
+use crate::shell::git;
+use crate::shell::jobs;
 use crate::shell::prompt::theme::Theme;
-use owo_colors::OwoColorize;
+
+/// Template used when the theme sets no `format`.
+pub const DEFAULT_FORMAT: &str = "{shell} {symbol} {path} {time} {jobs} ";
 
 /// Builds a formatted prompt string for display in the terminal
 ///
 /// # Arguments
-/// * `theme` - Reference to the current Theme for color information
-///
-/// # Format
-/// The prompt is constructed with the following segments:
-/// 1. Shell name ("PascheK>") in shell_color
-/// 2. Bullet point ("•") in symbol_color
-/// 3. Current directory name in path_color
-/// 4. Current time (HH:MM:SS) in time_color
+/// * `theme` - Reference to the current Theme for colors and the format template
+/// * `status` - Exit code of the last command, for `{status}`
 ///
 /// # Example Output
 /// ```text
@@ -38,30 +40,84 @@ use owo_colors::OwoColorize;
 ///
 /// # Returns
 /// A String containing the fully formatted prompt with ANSI color codes
-pub fn build_prompt(theme: &Theme) -> String {
-    // Get the current working directory name
-    // Falls back to "~" if the directory name can't be determined
-    let cwd = env::current_dir()
-        .ok()  // Handle potential errors from current_dir()
-        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
-        .unwrap_or_else(|| "~".into());
-
-    // Format current local time as HH:MM:SS
-    let time = Local::now().format("%H:%M:%S").to_string();
+pub fn build_prompt(theme: &Theme, status: i32) -> String {
+    let chars: Vec<char> = theme.format.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        if (c == '{' || c == '}') && chars.get(i) == Some(&c) {
+            out.push(c);
+            i += 1;
+            continue;
+        }
+        let end = chars[i..].iter().position(|&c| c == '}').map(|n| i + n);
+        let Some(end) = end.filter(|_| c == '{') else {
+            out.push(c);
+            continue;
+        };
+        let name: String = chars[i..end].iter().collect();
+        match segment(theme, &name, status) {
+            Some(text) if text.is_empty() => {
+                // Segment vide : on saute aussi l'espace qui le suit
+                i = end + 1;
+                if chars.get(i) == Some(&' ') {
+                    i += 1;
+                }
+            }
+            Some(text) => {
+                out.push_str(&text);
+                i = end + 1;
+            }
+            // Nom inconnu : gardé tel quel
+            None => out.push(c),
+        }
+    }
+    out
+}
 
-    // Build the prompt with themed color segments:
-    // 1. Shell name with theme's shell color
-    // 2. Bullet separator with theme's symbol color
-    // 3. Directory name with theme's path color
-    // 4. Time with theme's time color
-    // Note: Extra space at the end ensures proper cursor positioning
-    format!(
-        "{} {} {} {} ",
-        theme.apply_shell("PascheK>"),
-        theme.apply_symbol("•"),
-        theme.apply_path(&cwd),
-        theme.apply_time(&time),
-    )
+/// Colored text of the placeholder `name`; `None` when the name is unknown.
+fn segment(theme: &Theme, name: &str, status: i32) -> Option<String> {
+    let text = match name {
+        "shell" => theme.apply_shell("PascheK>"),
+        "symbol" => theme.apply_symbol("•"),
+        // Falls back to "~" if the directory name can't be determined
+        "path" => theme.apply_path(
+            &env::current_dir()
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or_else(|| "~".into()),
+        ),
+        "cwd" => {
+            let cwd = env::current_dir().unwrap_or_default();
+            let shown = match dirs::home_dir().and_then(|home| cwd.strip_prefix(home).ok().map(|rest| rest.to_path_buf())) {
+                Some(rest) if rest.as_os_str().is_empty() => String::from("~"),
+                Some(rest) => format!("~/{}", rest.display()),
+                None => cwd.display().to_string(),
+            };
+            theme.apply_path(&shown)
+        }
+        "user" => theme.apply_shell(&env::var("USER").or_else(|_| env::var("USERNAME")).unwrap_or_default()),
+        "host" => theme.apply_shell(&sysinfo::System::host_name().unwrap_or_default()),
+        "git" => env::current_dir()
+            .ok()
+            .and_then(|dir| git::current_branch(&dir))
+            .map(|branch| theme.apply_symbol(&branch))
+            .unwrap_or_default(),
+        // Format current local time as HH:MM:SS
+        "time" => theme.apply_time(&Local::now().format("%H:%M:%S").to_string()),
+        "status" => match status {
+            0 => String::new(),
+            code => code.to_string().color(AnsiColors::Red).to_string(),
+        },
+        "jobs" => match jobs::count() {
+            0 => String::new(),
+            n => theme.apply_symbol(&format!("[{}]", n)),
+        },
+        _ => return None,
+    };
+    Some(text)
 }
 
 
@@ -9,7 +9,7 @@
 pub mod builder;
 pub mod theme;
 
-use crate::shell::config::ThemeConfig;
+use crate::shell::config::{expand_home, ThemeConfig};
 use crate::shell::prompt::builder::build_prompt;
 
 // Réexport public pour éviter d’avoir à importer `theme::Theme` partout.
@@ -18,23 +18,60 @@ pub use self::theme::Theme;
 /// Représente l'invite (prompt) courante du shell, pilotée par un `Theme`.
 pub struct Prompt {
     theme: Theme,
+    /// Fichier du thème courant (`theme.toml`, ou celui de `theme set` / `theme use`)
+    path: String,
+    /// Code de sortie de la dernière commande (`{status}`)
+    status: i32,
+    /// Nom du thème mémorisé au dernier chargement
+    active: String,
 }
 
 impl Prompt {
     /// Crée une nouvelle instance de `Prompt`.
     ///
-    /// Tente de charger la configuration depuis `config/theme.toml`; en cas d’échec,
-    /// utilise `Theme::default()`.
+    /// Charge le thème actif (`theme set`, `theme.toml` par défaut); en cas
+    /// d’échec, utilise `Theme::default()`.
     pub fn new() -> Self {
-        let theme = ThemeConfig::load_from_file("config/theme.toml")
-            .map(|cfg| Theme::from_config(&cfg))
-            .unwrap_or_else(Theme::default);
-        Self { theme }
+        let (path, theme) = theme::load_active();
+        Self { theme, path: path.to_string_lossy().into_owned(), status: 0, active: theme::active_name() }
     }
 
-    /// Recharge le thème depuis `config/theme.toml`.
+    /// Passe au thème nommé `name` et le mémorise pour les prochaines sessions.
+    pub fn set_named(&mut self, name: &str) -> Result<(), String> {
+        let path = theme::named_path(name);
+        let cfg = ThemeConfig::load_from_file(&path)
+            .ok_or_else(|| format!("{} (missing or invalid config)", path.display()))?;
+        theme::save_active(name).map_err(|e| e.to_string())?;
+        self.theme = Theme::from_config(&cfg);
+        self.path = path.to_string_lossy().into_owned();
+        self.active = name.to_string();
+        Ok(())
+    }
+
+    /// Recharge le thème actif s’il a été changé ailleurs (`theme set` dans le TUI).
+    pub fn sync_active(&mut self) {
+        if theme::active_name() != self.active {
+            *self = Self { status: self.status, ..Self::new() };
+        }
+    }
+
+    /// Passe au thème du fichier `path` (`~` accepté) ; `theme reload` le relira ensuite.
+    /// Retourne false si le fichier est absent ou invalide.
+    pub fn use_file(&mut self, path: &str) -> bool {
+        let path = expand_home(path).to_string_lossy().into_owned();
+        match ThemeConfig::load_from_file(&path) {
+            Some(cfg) => {
+                self.theme = Theme::from_config(&cfg);
+                self.path = path;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Recharge le thème depuis son fichier (`theme.toml` par défaut).
     pub fn reload(&mut self) {
-        if let Some(cfg) = ThemeConfig::load_from_file("config/theme.toml") {
+        if let Some(cfg) = ThemeConfig::load_from_file(&self.path) {
             self.theme = Theme::from_config(&cfg);
             println!("🔄 Theme reloaded successfully!");
         } else {
@@ -42,9 +79,14 @@ impl Prompt {
         }
     }
 
+    /// Mémorise le code de sortie de la dernière commande.
+    pub fn set_status(&mut self, status: i32) {
+        self.status = status;
+    }
+
     /// Construit et retourne la chaîne du prompt en fonction du thème courant.
     pub fn render(&self) -> String {
-        build_prompt(&self.theme)
+        build_prompt(&self.theme, self.status)
     }
 
     /// (Optionnel) Accès en lecture au thème courant.
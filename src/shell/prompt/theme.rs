@@ -4,15 +4,19 @@
 //! - Color definitions for different prompt segments
 //! - Theme loading from TOML configuration
 //! - Color parsing from string names
+//! - The prompt layout (`format` template, see `builder`)
 //!
 //! # Supported Colors
-//! All ANSI colors are supported through the `owo-colors` crate:
+//! Colors are rendered through the `owo-colors` crate (prompt) and `ratatui` (TUI):
 //! - Standard colors: black, red, green, yellow, blue, magenta, cyan, white
 //! - Bright variants: brightred, brightgreen, etc.
+//! - 256-color palette indices: `"208"`
+//! - True colors: `"#ff8800"`
 //!
 //! # Configuration
 //! Themes are configured via TOML files with sections for each prompt segment:
 //! ```toml
+//! format = "{shell} {symbol} {path} {time} {jobs} "
 //! [shell]
 //! color = "brightgreen"
 //! [path]
@@ -22,9 +26,20 @@
 //! [symbol]
 //! color = "brightmagenta"
 //! ```
+//!
+//! # Named Themes
+//! Extra themes live in `themes/<name>.toml` of the config directory (see
+//! `config::paths`); `default` is `theme.toml`. The active name (`theme set <name>`)
+//! is saved in the `theme` file of the config directory.
 
-use owo_colors::AnsiColors;
-use crate::shell::config::ThemeConfig;
+use owo_colors::{AnsiColors, DynColors, XtermColors};
+use ratatui::style::Color;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use crate::shell::config::{paths, ThemeConfig};
+use crate::shell::prompt::builder::DEFAULT_FORMAT;
 
 /// Theme configuration for the shell prompt
 ///
@@ -34,20 +49,21 @@ use crate::shell::config::ThemeConfig;
 /// - Timestamp
 /// - Prompt symbol
 ///
-/// Colors are stored as ANSI color values for efficient rendering.
+/// Colors are stored as `DynColors` (named ANSI, 256-color index or RGB).
 /// The theme can be created from default values or loaded from a
 /// configuration file.
 #[derive(Clone)]
 pub struct Theme {
     /// Color for the shell name segment
-    pub shell_color: AnsiColors,
+    pub shell_color: DynColors,
     /// Color for the current path segment
-    pub path_color: AnsiColors,
+    pub path_color: DynColors,
     /// Color for the timestamp segment
-    pub time_color: AnsiColors,
+    pub time_color: DynColors,
     /// Color for the prompt symbol
-    pub symbol_color: AnsiColors,
-
+    pub symbol_color: DynColors,
+    /// Prompt template with `{placeholder}` segments
+    pub format: String,
 }
 
 impl Theme {
@@ -63,10 +79,11 @@ impl Theme {
     /// A new Theme instance with default colors
     pub fn default() -> Self {
         Self {
-            shell_color: AnsiColors::BrightGreen,
-            path_color: AnsiColors::BrightBlue,
-            time_color: AnsiColors::BrightYellow,
-            symbol_color: AnsiColors::BrightMagenta,
+            shell_color: DynColors::Ansi(AnsiColors::BrightGreen),
+            path_color: DynColors::Ansi(AnsiColors::BrightBlue),
+            time_color: DynColors::Ansi(AnsiColors::BrightYellow),
+            symbol_color: DynColors::Ansi(AnsiColors::BrightMagenta),
+            format: DEFAULT_FORMAT.to_string(),
         }
     }
 
@@ -76,8 +93,8 @@ impl Theme {
     /// * `cfg` - Reference to a ThemeConfig containing color settings
     ///
     /// # Color Parsing
-    /// Colors are parsed from strings in the configuration file.
-    /// If a color name is invalid, it falls back to a default color.
+    /// Colors are parsed from strings in the configuration file (see `parse_color`).
+    /// If a color is invalid, it falls back to a default color.
     ///
     /// # Returns
     /// A new Theme instance with colors from the configuration
@@ -87,11 +104,25 @@ impl Theme {
             path_color: Self::parse_color(&cfg.path.color),
             time_color: Self::parse_color(&cfg.time.color),
             symbol_color: Self::parse_color(&cfg.symbol.color),
+            format: cfg.format.clone().unwrap_or_else(|| DEFAULT_FORMAT.to_string()),
         }
     }
 
-    fn parse_color(name: &str) -> AnsiColors {
-        match name.to_lowercase().as_str() {
+    /// Parses a color name, a 256-color index (`"208"`) or a `#RRGGBB` hex value.
+    /// Unknown values give white.
+    pub fn parse_color(name: &str) -> DynColors {
+        let name = name.trim().to_lowercase();
+        if let Some(hex) = name.strip_prefix('#') {
+            let channel = |i: usize| hex.get(i..i + 2).and_then(|h| u8::from_str_radix(h, 16).ok());
+            if hex.len() == 6 && let (Some(r), Some(g), Some(b)) = (channel(0), channel(2), channel(4)) {
+                return DynColors::Rgb(r, g, b);
+            }
+            return DynColors::Ansi(AnsiColors::White);
+        }
+        if let Ok(index) = name.parse::<u8>() {
+            return DynColors::Xterm(XtermColors::from(index));
+        }
+        let ansi = match name.replace(['_', '-', ' '], "").as_str() {
             "black" => AnsiColors::Black,
             "red" => AnsiColors::Red,
             "green" => AnsiColors::Green,
@@ -100,16 +131,115 @@ impl Theme {
             "magenta" => AnsiColors::Magenta,
             "cyan" => AnsiColors::Cyan,
             "white" => AnsiColors::White,
+            "brightblack" | "gray" | "grey" => AnsiColors::BrightBlack,
+            "brightred" => AnsiColors::BrightRed,
             "brightgreen" => AnsiColors::BrightGreen,
-            "brightblue" => AnsiColors::BrightBlue,
             "brightyellow" => AnsiColors::BrightYellow,
+            "brightblue" => AnsiColors::BrightBlue,
             "brightmagenta" => AnsiColors::BrightMagenta,
             "brightcyan" => AnsiColors::BrightCyan,
+            "brightwhite" => AnsiColors::BrightWhite,
             _ => AnsiColors::White,
-        }
+        };
+        DynColors::Ansi(ansi)
+    }
+}
+
+/// Same color for ratatui widgets (the TUI).
+pub fn tui_color(color: DynColors) -> Color {
+    match color {
+        DynColors::Ansi(ansi) => match ansi {
+            AnsiColors::Black => Color::Black,
+            AnsiColors::Red => Color::Red,
+            AnsiColors::Green => Color::Green,
+            AnsiColors::Yellow => Color::Yellow,
+            AnsiColors::Blue => Color::Blue,
+            AnsiColors::Magenta => Color::Magenta,
+            AnsiColors::Cyan => Color::Cyan,
+            AnsiColors::White => Color::Gray,
+            AnsiColors::Default => Color::Reset,
+            AnsiColors::BrightBlack => Color::DarkGray,
+            AnsiColors::BrightRed => Color::LightRed,
+            AnsiColors::BrightGreen => Color::LightGreen,
+            AnsiColors::BrightYellow => Color::LightYellow,
+            AnsiColors::BrightBlue => Color::LightBlue,
+            AnsiColors::BrightMagenta => Color::LightMagenta,
+            AnsiColors::BrightCyan => Color::LightCyan,
+            AnsiColors::BrightWhite => Color::White,
+        },
+        DynColors::Xterm(x) => Color::Indexed(u8::from(x)),
+        DynColors::Rgb(r, g, b) => Color::Rgb(r, g, b),
+        DynColors::Css(_) => Color::Reset,
+    }
+}
+/// Name of the theme in `config/theme.toml`.
+pub const DEFAULT_NAME: &str = "default";
+
+/// File of the theme `name`.
+pub fn named_path(name: &str) -> PathBuf {
+    if name == DEFAULT_NAME {
+        paths::config_file("theme.toml")
+    } else {
+        paths::config_file(&format!("themes/{}.toml", name))
     }
+}
+
+/// Names of the available themes, `default` first then the files of the `themes/`
+/// directories.
+pub fn names() -> Vec<String> {
+    let mut names: Vec<String> = paths::config_dirs("themes")
+        .into_iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+        .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .filter(|name| name != DEFAULT_NAME)
+        .collect();
+    names.sort();
+    names.dedup();
+    names.insert(0, DEFAULT_NAME.to_string());
+    names
+}
+
+/// File remembering the active theme name.
+fn active_file() -> PathBuf {
+    paths::config_dir().join("theme")
+}
+
+/// Saved theme name (`default` when none was chosen).
+pub fn active_name() -> String {
+    fs::read_to_string(active_file())
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_NAME.to_string())
+}
 
-    pub fn to_ansi_color(&self) -> AnsiColors {
-        self.shell_color
+/// Remember `name` as the active theme.
+pub fn save_active(name: &str) -> io::Result<()> {
+    let path = active_file();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
     }
-}
\ No newline at end of file
+    fs::write(path, format!("{}\n", name))
+}
+
+/// Active theme with its file; falls back to `theme.toml`, then to the
+/// built-in colors, when the saved theme can't be loaded.
+pub fn load_active() -> (PathBuf, Theme) {
+    let mut path = named_path(&active_name());
+    let mut cfg = ThemeConfig::load_from_file(&path);
+    if cfg.is_none() {
+        path = named_path(DEFAULT_NAME);
+        cfg = ThemeConfig::load_from_file(&path);
+    }
+    let theme = cfg.as_ref().map(Theme::from_config).unwrap_or_else(Theme::default);
+    (path, theme)
+}
+
+/// Changes whenever another theme is chosen or the active theme file is edited.
+pub fn active_stamp() -> (String, Option<SystemTime>) {
+    let name = active_name();
+    let modified = fs::metadata(named_path(&name)).and_then(|m| m.modified()).ok();
+    (name, modified)
+}
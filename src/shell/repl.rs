@@ -1,44 +1,126 @@
-use crate::shell::{commands::CommandRegistry, executor::execute_command, prompt::Prompt};
-use dirs::home_dir;
+use crate::shell::{
+    aliases,
+    config::ShellConfig,
+    engine::{ShellEngine, Stdout},
+    history::{self, history_path, HistoryStore, ReverseSearch, SearchKey, SearchStep},
+    jobs,
+    pathcache,
+    script,
+    signals,
+    prompt::Prompt,
+};
+use crossterm::{event, terminal};
 use reedline::{
-    DefaultCompleter, DefaultPrompt, DefaultPromptSegment, FileBackedHistory, Reedline, Signal,
+    default_emacs_keybindings, ColumnarMenu, Completer, DefaultPrompt, DefaultPromptSegment, EditCommand, Emacs,
+    FileBackedHistory, HistoryItem, KeyCode, KeyModifiers, MenuBuilder, Reedline, ReedlineEvent, ReedlineMenu,
+    Signal, Span, Suggestion,
 };
+use std::io::{self, Write};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
-pub fn start_repl() {
-    let prompt = Arc::new(Mutex::new(Prompt::new()));
-    let registry = CommandRegistry::new_with_prompt(prompt.clone());
+/// Line returned by Reedline for Ctrl+R (cannot be typed).
+const REVERSE_SEARCH: &str = "\u{0}reverse-search";
+
+/// Menu of the Tab completions.
+const COMPLETION_MENU: &str = "completion_menu";
+
+/// Tab completion of the command name (first word): built-ins, then the programs of
+/// the PATH (see [`pathcache`]).
+struct CommandCompleter {
+    builtins: Vec<String>,
+}
+
+impl Completer for CommandCompleter {
+    fn complete(&mut self, line: &str, pos: usize) -> Vec<Suggestion> {
+        let before = &line[..pos];
+        let start = before.len() - before.trim_start().len();
+        let prefix = &before[start..];
+        // Au moins 1 caractère, et seulement pour le premier mot
+        if prefix.is_empty() || prefix.contains(char::is_whitespace) {
+            return Vec::new();
+        }
+        let builtins = self.builtins.iter().filter(|n| n.starts_with(prefix)).cloned();
+        let programs = pathcache::complete(prefix).into_iter().filter(|n| !self.builtins.contains(n));
+        builtins
+            .chain(programs)
+            .map(|value| Suggestion { value, span: Span::new(start, pos), append_whitespace: true, ..Default::default() })
+            .collect()
+    }
+}
 
-    // Historique
-    let history_path = home_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join(".paschek_history");
+/// Runs the REPL; `roots` (from `--root`) are handed to the TUI opened by `tui`.
+/// Returns the exit code of the shell (`exit [code]`, or the last status on Ctrl+D).
+pub fn start_repl(roots: Vec<PathBuf>) -> i32 {
+    let mut shell = ShellEngine::new(Arc::new(Mutex::new(Prompt::new())));
+    // Ctrl+C interrompt la commande au premier plan, pas le shell
+    signals::install();
+    // Fixe l'emplacement de aliases.toml avant le premier `cd`
+    aliases::aliases_path();
 
-    // Récupère la liste des commandes internes (ex: ["help","cd","clear","theme","hello"])
-    let command_names: Vec<String> = registry.list_names();
+    // Historique (fichier partagé avec le shell du TUI)
+    let history_path = history_path();
 
-    // (Optionnel) Petit debug pour vérifier qu’on a bien des mots à compléter
-    eprintln!("(debug) completions: {:?}", command_names);
+    // Plugins (plugins/*.toml) : enregistrés comme des commandes internes
+    for e in shell.reload_plugins() {
+        eprintln!("⚠️ {e}");
+    }
 
-    // Seuil à 1 caractère (au lieu de 2) pour voir des suggestions dès la 1ère lettre
-    let completer = reedline::DefaultCompleter::new_with_wordlen(command_names, 1);
+    // Tab : commandes internes (ex: ["help","cd","clear","theme","hello"]) puis programmes du PATH
+    let completer = CommandCompleter { builtins: shell.registry().list_names() };
+    // Index du PATH construit en arrière-plan : la première complétion est immédiate
+    std::thread::spawn(pathcache::executables);
 
     // Historique Reedline
-    let mut file_history = FileBackedHistory::with_file(1000, history_path.clone()).unwrap();
+    let file_history = FileBackedHistory::with_file(history::capacity(), history_path).unwrap();
+    // Raccourcis Emacs par défaut de Reedline (←/→, Home/End, Ctrl+A/E/W/U, ↑/↓),
+    // auxquels on ajoute Ctrl+R et Tab
+    let mut keybindings = default_emacs_keybindings();
+    // Ctrl+R : notre recherche (la même que dans le TUI), hors de Reedline
+    keybindings.add_binding(
+        KeyModifiers::CONTROL,
+        KeyCode::Char('r'),
+        ReedlineEvent::ExecuteHostCommand(REVERSE_SEARCH.to_string()),
+    );
+    keybindings.add_binding(
+        KeyModifiers::NONE,
+        KeyCode::Tab,
+        ReedlineEvent::UntilFound(vec![ReedlineEvent::Menu(COMPLETION_MENU.to_string()), ReedlineEvent::MenuNext]),
+    );
+    let edit_mode = Box::new(Emacs::new(keybindings));
     // Initialisation de l’éditeur
     let mut line_editor = Reedline::create()
         .with_history(Box::new(file_history))
-        .with_completer(Box::new(completer));
+        .with_completer(Box::new(completer))
+        .with_menu(ReedlineMenu::EngineCompleter(Box::new(ColumnarMenu::default().with_name(COMPLETION_MENU))))
+        .with_edit_mode(edit_mode);
 
-    println!("🦀 Welcome to PascheK Shell");
-    println!("Type 'help' for a list of commands.\n");
+    // ~/.paschekrc : alias, variables, thème… (les erreurs n'empêchent pas le démarrage)
+    script::run_rc(&shell);
+    if let Some(code) = shell.take_exit() {
+        return code;
+    }
+
+    // Accueil : PASCHEK_GREETING le remplace (vide = aucun message)
+    match std::env::var("PASCHEK_GREETING") {
+        Ok(greeting) if greeting.is_empty() => {}
+        Ok(greeting) => println!("{greeting}\n"),
+        Err(_) => {
+            println!("🦀 Welcome to PascheK Shell");
+            println!("Type 'help' for a list of commands.\n");
+        }
+    }
 
     loop {
+        // Jobs d'arrière-plan terminés depuis la dernière commande
+        for line in jobs::reap() {
+            println!("{line}");
+        }
+
         // Prompt dynamique coloré
-        let prompt_text = prompt.lock().unwrap().render();
+        let prompt_text = shell.prompt().lock().unwrap().render();
         let custom_prompt = DefaultPrompt::new(
-            DefaultPromptSegment::Basic(prompt_text.into()),
+            DefaultPromptSegment::Basic(prompt_text),
             DefaultPromptSegment::Empty,
         );
 
@@ -47,27 +129,63 @@ pub fn start_repl() {
 
         match sig {
             Ok(Signal::Success(cmd)) => {
+                let cmd = if cmd == REVERSE_SEARCH {
+                    let _ = line_editor.sync_history();
+                    let Some(found) = reverse_search(HistoryStore::open().entries()) else {
+                        // Échap : retour à la ligne en cours d'édition
+                        continue;
+                    };
+                    line_editor.run_edit_commands(&[EditCommand::Clear]);
+                    let _ = line_editor.history_mut().save(HistoryItem::from_command_line(&found));
+                    found
+                } else {
+                    cmd
+                };
                 let trimmed = cmd.trim();
                 if trimmed.is_empty() {
                     continue;
                 }
-                if trimmed == "ui" {
-                    if let Err(e) = crate::shell::tui::start_tui() {
-                        println!("TUI error: {e}");
-                    }
-                    // On revient au REPL quand le TUI se ferme
-                    continue;
+                // Historique écrit avant la commande (`history`, `!N`) puis relu
+                // après (`history -c`)
+                if let Err(e) = line_editor.sync_history() {
+                    eprintln!("⚠️ History sync failed: {e}");
                 }
-                if trimmed == "exit" {
+                shell.execute(trimmed, &mut Stdout);
+                let _ = line_editor.sync_history();
+                // Ctrl+C déjà transmis à la commande
+                signals::take_interrupt();
+                if let Some(code) = shell.take_exit() {
+                    if !confirm_exit() {
+                        continue;
+                    }
                     println!("👋 Goodbye!");
-                    break;
+                    return code;
+                }
+                if shell.take_tui() {
+                    if let Err(e) = crate::shell::tui::start_tui(&roots, &mut shell) {
+                        println!("TUI error: {e}");
+                    }
+                    // On revient au REPL quand le TUI se ferme (:repl, :q) : récupérer
+                    // les commandes tapées dans le shell du TUI (le dossier courant
+                    // est celui du processus, déjà partagé)
+                    if let Err(e) = line_editor.sync_history() {
+                        eprintln!("⚠️ History sync failed: {e}");
+                    }
+                    // … et le thème choisi avec `theme set`
+                    shell.prompt().lock().unwrap().sync_active();
+                    // `exit` tapé dans le TUI (et confirmé là-bas) quitte aussi le REPL
+                    if let Some(code) = shell.take_exit() {
+                        println!("👋 Goodbye!");
+                        return code;
+                    }
                 }
-
-                execute_command(trimmed, &registry);
             }
             Ok(Signal::CtrlD) => {
                 println!();
-                break;
+                if !confirm_exit() {
+                    continue;
+                }
+                return shell.last_status();
             }
             Ok(Signal::CtrlC) => {
                 println!("^C");
@@ -75,8 +193,66 @@ pub fn start_repl() {
             }
             Err(e) => {
                 eprintln!("❌ Input error: {}", e);
-                break;
+                return 1;
             }
         }
     }
 }
+
+/// Asks before leaving when `confirm_exit` is set in `config.toml`.
+fn confirm_exit() -> bool {
+    if !ShellConfig::get().confirm_exit() {
+        return true;
+    }
+    print!("Quit PascheK Shell? [y/N] ");
+    let _ = io::stdout().flush();
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer).is_ok_and(|_| answer.trim().eq_ignore_ascii_case("y"))
+}
+
+/// Ctrl+R: reverse incremental search in `entries`, drawn under the input line.
+/// Returns the command to run (Enter), or None (Esc).
+fn reverse_search(entries: &[String]) -> Option<String> {
+    use event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+    terminal::enable_raw_mode().ok()?;
+    let mut out = io::stdout();
+    let mut search = ReverseSearch::default();
+    let _ = write!(out, "\r\n");
+    let step = loop {
+        let width = terminal::size().map(|(w, _)| w as usize).unwrap_or(80);
+        let label: String = search.label(entries).chars().take(width.saturating_sub(1)).collect();
+        let _ = write!(out, "\r\x1b[K{}", label);
+        let _ = out.flush();
+        let Ok(Event::Key(key)) = event::read() else { continue };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let key = match key.code {
+            KeyCode::Char('r') if ctrl => SearchKey::Older,
+            KeyCode::Char('c' | 'g') if ctrl => SearchKey::Cancel,
+            KeyCode::Char(c) if !ctrl => SearchKey::Char(c),
+            KeyCode::Backspace => SearchKey::Backspace,
+            KeyCode::Enter => SearchKey::Accept,
+            KeyCode::Esc => SearchKey::Cancel,
+            _ => continue,
+        };
+        match search.key(key, entries) {
+            SearchStep::Continue => {}
+            step => break step,
+        }
+    };
+    let _ = terminal::disable_raw_mode();
+    match step {
+        SearchStep::Run(line) => {
+            let _ = writeln!(out);
+            Some(line)
+        }
+        _ => {
+            // Effacer la ligne de recherche et remonter sur la saisie
+            let _ = write!(out, "\r\x1b[K\x1b[1A");
+            let _ = out.flush();
+            None
+        }
+    }
+}
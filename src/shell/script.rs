@@ -0,0 +1,77 @@
+//! Script files, run with `paschek-cli script.psh` or the `source` built-in.
+//!
+//! The file is parsed as a whole ([`crate::shell::syntax`]: `if`, `for`, `while`,
+//! functions over several lines), then each command goes through the [`ShellEngine`]
+//! like a line typed at the prompt (aliases, variables, redirections, `&`). Blank
+//! lines and comments (a shebang included) are skipped. `set -e` stops the script at
+//! the first command that fails (outside `if` / `while` conditions), `set +e` goes
+//! back to ignoring failures, and `exit [n]` ends the script with status `n` (the
+//! last command's by default). A syntax error (an unexpected `fi`, an unterminated
+//! quote, a redirection without a file) is reported with its line before anything runs.
+//!
+//! `~/.paschekrc` is run the same way when the REPL starts, except that each failing
+//! line is reported with its line number and the file goes on (aliases, `export`,
+//! `theme use <file>`, and `PASCHEK_GREETING` to replace the welcome banner).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::shell::aliases;
+use crate::shell::engine::{Output, Script, ShellEngine, Stdout};
+use crate::shell::prompt::Prompt;
+use crate::shell::syntax;
+
+/// Run the commands of `path`; returns the status of the script (the failing command
+/// under `set -e`, the `exit` argument, or the last command).
+pub fn run_file(path: &Path, shell: &ShellEngine, out: &mut dyn Output) -> i32 {
+    run_lines(path, shell, out, false)
+}
+
+/// Location of the startup file.
+pub fn rc_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".paschekrc"))
+}
+
+/// Run `~/.paschekrc` if it exists, reporting the lines that fail.
+pub fn run_rc(shell: &ShellEngine) {
+    if let Some(path) = rc_path().filter(|p| p.is_file()) {
+        run_lines(&path, shell, &mut Stdout, true);
+    }
+}
+
+/// Run the commands of `path`; with `report`, every failing command is reported
+/// with its line number (not only the one stopping a `set -e` script).
+fn run_lines(path: &Path, shell: &ShellEngine, out: &mut dyn Output, report: bool) -> i32 {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            out.error(&format!("❌ {}: {}", path.display(), e));
+            return 1;
+        }
+    };
+    // Le fichier entier est analysé avant de lancer quoi que ce soit
+    match syntax::parse(&text) {
+        Ok(nodes) => shell.run_script(&nodes, Script { path, report }, out),
+        Err(e) => {
+            out.error(&format!("❌ {}:{}: syntax error: {}", path.display(), e.line, e));
+            2
+        }
+    }
+}
+
+/// Non-interactive mode: run `path` with a fresh engine and return its status.
+pub fn run(path: &Path) -> i32 {
+    // Même emplacement de config/aliases.toml que le REPL, même si le script fait `cd`
+    aliases::aliases_path();
+    let shell = ShellEngine::new(Arc::new(Mutex::new(Prompt::new())));
+    for e in shell.reload_plugins() {
+        eprintln!("⚠️ {e}");
+    }
+    let status = run_file(path, &shell, &mut Stdout);
+    // Pas de REPL pour ouvrir le TUI
+    if shell.take_tui() {
+        eprintln!("⚠️ tui: only available in the interactive shell");
+    }
+    status
+}
@@ -0,0 +1,39 @@
+//! Ctrl+C handling for the REPL.
+//!
+//! At the prompt reedline reads Ctrl+C as a key (the terminal is in raw mode). While
+//! a foreground command runs, the terminal sends SIGINT to the whole process group:
+//! the shell catches it with a handler that only records it, so the command stops
+//! and the shell goes back to its prompt. Handlers are reset by `exec`, so commands
+//! keep the default behaviour. Background jobs run in their own process group and
+//! are not affected; `fg` relays the signal to the job it waits for.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn on_sigint(_: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Install the SIGINT handler of the shell.
+#[cfg(unix)]
+pub fn install() {
+    // SAFETY: le gestionnaire ne fait qu'écrire un booléen atomique
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = on_sigint as *const () as libc::sighandler_t;
+        libc::sigemptyset(&mut action.sa_mask);
+        // Pas de SA_RESTART : `waitpid` est interrompu et `fg` peut relayer le signal
+        action.sa_flags = 0;
+        libc::sigaction(libc::SIGINT, &action, std::ptr::null_mut());
+    }
+}
+
+#[cfg(not(unix))]
+pub fn install() {}
+
+/// Whether Ctrl+C was pressed since the last call.
+pub fn take_interrupt() -> bool {
+    INTERRUPTED.swap(false, Ordering::SeqCst)
+}
@@ -0,0 +1,440 @@
+//! Parser of the shell language: command lines, conditionals, loops and functions.
+//!
+//! Source text (a line typed at the prompt, a script, a function body) is cut into
+//! statements at unquoted `;` and line breaks, `#` starting a comment. Statements
+//! whose first word is a keyword build compound commands:
+//!
+//! ```text
+//! if cmd; then …; elif cmd; then …; else …; fi
+//! for name in words…; do …; done
+//! while cmd; do …; done
+//! fn name { … }        name() { … }
+//! ```
+//!
+//! Conditions are command lists whose last exit status decides (0 is true). The other
+//! statements stay [`Node::Command`] text, checked with [`parser::check`] (closed
+//! quotes, `&` and redirections) so a broken command fails the whole parse: aliases, variables and redirections are
+//! expanded by the engine when the command runs, so `$1` in a function body or `$x`
+//! in a loop see the current values.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::shell::functions::{self, Function};
+use crate::shell::parser;
+
+/// A piece of a parsed program.
+#[derive(Clone, Debug)]
+pub enum Node {
+    /// Simple command line (unexpanded) and the line it starts on.
+    Command { text: String, line: usize },
+    /// `if` / `elif` branches (condition, body), then the `else` body.
+    If { branches: Vec<(Vec<Node>, Vec<Node>)>, otherwise: Vec<Node> },
+    /// `for var in words; do body; done`; `words` is expanded when the loop starts.
+    For { var: String, words: String, body: Vec<Node> },
+    /// `while cond; do body; done`
+    While { cond: Vec<Node>, body: Vec<Node> },
+    /// Function definition.
+    Function(Function),
+}
+
+/// Why a text could not be parsed, and where.
+#[derive(Debug)]
+pub struct SyntaxError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Words that end a command list.
+const TERMINATORS: [&str; 7] = ["then", "elif", "else", "fi", "do", "done", "}"];
+
+/// Statement of the source: text without separators, first line.
+struct Statement {
+    text: String,
+    line: usize,
+}
+
+impl Statement {
+    /// First word and the text after it.
+    fn keyword(&self) -> (&str, &str) {
+        let text = self.text.trim_start();
+        let end = text.find(char::is_whitespace).unwrap_or(text.len());
+        (&text[..end], text[end..].trim_start())
+    }
+}
+
+/// Parse `text` into the commands it holds.
+pub fn parse(text: &str) -> Result<Vec<Node>, SyntaxError> {
+    let mut parser = Parser { statements: split(text), line: 1 };
+    let (nodes, end) = parser.list(&[])?;
+    match end {
+        Some(word) => Err(parser.error(format!("unexpected `{}`", word))),
+        None => Ok(nodes),
+    }
+}
+
+/// Cut `text` into statements at unquoted `;` and line breaks, without comments.
+fn split(text: &str) -> VecDeque<Statement> {
+    let mut statements = VecDeque::new();
+    let mut current = String::new();
+    let (mut line, mut start) = (1, 1);
+    let mut quote = None;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+        }
+        match (c, quote) {
+            ('\\', q) if q != Some('\'') => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    line += usize::from(next == '\n');
+                    current.push(next);
+                }
+            }
+            ('\'' | '"', None) => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (c, Some(q)) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            ('#', None) if current.is_empty() || current.ends_with(char::is_whitespace) => {
+                // Commentaire jusqu'à la fin de la ligne
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            (';' | '\n', None) => {
+                push(&mut statements, &mut current, start);
+                start = line;
+            }
+            _ => {
+                if current.trim().is_empty() {
+                    start = line;
+                }
+                current.push(c);
+            }
+        }
+    }
+    push(&mut statements, &mut current, start);
+    statements
+}
+
+fn push(statements: &mut VecDeque<Statement>, current: &mut String, line: usize) {
+    let text = std::mem::take(current);
+    if !text.trim().is_empty() {
+        statements.push_back(Statement { text: text.trim().to_string(), line });
+    }
+}
+
+struct Parser {
+    statements: VecDeque<Statement>,
+    /// Line of the last statement read (for errors)
+    line: usize,
+}
+
+impl Parser {
+    fn error(&self, message: String) -> SyntaxError {
+        SyntaxError { line: self.line, message }
+    }
+
+    fn next(&mut self) -> Option<Statement> {
+        let statement = self.statements.pop_front()?;
+        self.line = statement.line;
+        Some(statement)
+    }
+
+    /// Put the text following a keyword back as the next statement (`then echo a`).
+    fn push_back(&mut self, text: &str) {
+        if !text.is_empty() {
+            self.statements.push_front(Statement { text: text.to_string(), line: self.line });
+        }
+    }
+
+    /// Commands up to one of the `ends` keywords, which is returned (`None` at the end
+    /// of the text). Inside a function body (`ends` holds `}`), a command may end with
+    /// the closing brace: `fn f { echo a }`.
+    fn list(&mut self, ends: &[&str]) -> Result<(Vec<Node>, Option<String>), SyntaxError> {
+        let mut nodes = Vec::new();
+        while let Some(statement) = self.next() {
+            let (word, rest) = statement.keyword();
+            if TERMINATORS.contains(&word) {
+                if !ends.contains(&word) {
+                    return Err(self.error(format!("unexpected `{}`", word)));
+                }
+                // `then echo a`, `fi }` : la suite est relue comme instruction suivante
+                let (word, rest) = (word.to_string(), rest.to_string());
+                self.push_back(&rest);
+                return Ok((nodes, Some(word)));
+            }
+            if ends.contains(&"}")
+                && let Some(command) = statement.text.strip_suffix('}').filter(|c| c.ends_with(char::is_whitespace))
+            {
+                self.push_back("}");
+                self.push_back(command.trim_end());
+                continue;
+            }
+            let node = match word {
+                "if" => self.if_clause(rest.to_string())?,
+                "for" => self.for_clause(rest.to_string())?,
+                "while" => self.while_clause(rest.to_string())?,
+                _ => match function_header(&statement.text) {
+                    Some((name, rest)) => self.function(name, rest)?,
+                    None => {
+                        // Guillemets non fermés, `&` mal placé… : signalés avant l'exécution
+                        parser::check(&statement.text).map_err(|e| self.error(e))?;
+                        Node::Command { text: statement.text, line: statement.line }
+                    }
+                },
+            };
+            nodes.push(node);
+        }
+        match ends {
+            [] => Ok((nodes, None)),
+            _ => Err(self.error(format!("unexpected end of input (expected `{}`)", ends.join("` or `")))),
+        }
+    }
+
+    /// Commands up to `end`, or an error naming the construct.
+    fn block(&mut self, end: &str) -> Result<Vec<Node>, SyntaxError> {
+        Ok(self.list(&[end])?.0)
+    }
+
+    fn if_clause(&mut self, cond: String) -> Result<Node, SyntaxError> {
+        let mut branches = Vec::new();
+        let mut cond_text = cond;
+        loop {
+            self.push_back(&cond_text);
+            let cond = self.block("then")?;
+            if cond.is_empty() {
+                return Err(self.error(String::from("`if` without a condition")));
+            }
+            let (body, end) = self.list(&["elif", "else", "fi"])?;
+            branches.push((cond, body));
+            match end.as_deref() {
+                Some("elif") => {
+                    // La condition suivante a déjà été remise en tête par `list`
+                    cond_text = String::new();
+                }
+                Some("else") => {
+                    let otherwise = self.block("fi")?;
+                    return Ok(Node::If { branches, otherwise });
+                }
+                _ => return Ok(Node::If { branches, otherwise: Vec::new() }),
+            }
+        }
+    }
+
+    fn for_clause(&mut self, header: String) -> Result<Node, SyntaxError> {
+        let (var, rest) = header.split_once(char::is_whitespace).unwrap_or((&header, ""));
+        if !crate::shell::variables::is_valid_name(var) {
+            return Err(self.error(format!("invalid loop variable `{}`", var)));
+        }
+        let rest = rest.trim_start();
+        let words = match rest.strip_prefix("in") {
+            Some(words) if words.is_empty() || words.starts_with(char::is_whitespace) => words.trim(),
+            _ => return Err(self.error(String::from("expected `for name in words`"))),
+        };
+        let body = self.do_block()?;
+        Ok(Node::For { var: var.to_string(), words: words.to_string(), body })
+    }
+
+    fn while_clause(&mut self, cond: String) -> Result<Node, SyntaxError> {
+        self.push_back(&cond);
+        let cond = self.block("do")?;
+        if cond.is_empty() {
+            return Err(self.error(String::from("`while` without a condition")));
+        }
+        let body = self.list(&["done"])?.0;
+        Ok(Node::While { cond, body })
+    }
+
+    /// `do …; done` after a `for` header.
+    fn do_block(&mut self) -> Result<Vec<Node>, SyntaxError> {
+        match self.next() {
+            Some(statement) if statement.keyword().0 == "do" => {
+                let rest = statement.keyword().1.to_string();
+                self.push_back(&rest);
+                self.block("done")
+            }
+            Some(_) => Err(self.error(String::from("expected `do`"))),
+            None => Err(self.error(String::from("unexpected end of input (expected `do`)"))),
+        }
+    }
+
+    fn function(&mut self, name: String, rest: String) -> Result<Node, SyntaxError> {
+        if !functions::is_valid_name(&name) {
+            return Err(self.error(format!("invalid function name `{}`", name)));
+        }
+        let Some(body) = rest.trim_start().strip_prefix('{') else {
+            return Err(self.error(format!("{}: expected `{{ commands }}`", name)));
+        };
+        // `fn f { }` : accolade fermante sur la même ligne que l'ouvrante
+        if body.trim() == "}" {
+            return Err(self.error(format!("{}: empty function body", name)));
+        }
+        self.push_back(body.trim());
+        let body = self.block("}")?;
+        if body.is_empty() {
+            return Err(self.error(format!("{}: empty function body", name)));
+        }
+        Ok(Node::Function(Function { name, body }))
+    }
+}
+
+/// Name and text after it when `text` starts a definition (`fn name` or `name()`).
+fn function_header(text: &str) -> Option<(String, String)> {
+    if let Some(rest) = text.strip_prefix("fn ") {
+        let rest = rest.trim_start();
+        let end = rest.find(|c: char| c.is_whitespace() || c == '{').unwrap_or(rest.len());
+        return Some((rest[..end].to_string(), rest[end..].to_string()));
+    }
+    let (name, rest) = text.split_once("()")?;
+    let name = name.trim_end();
+    functions::is_valid_name(name).then(|| (name.to_string(), rest.to_string()))
+}
+
+/// `nodes` on one line, as they can be typed again.
+pub fn render(nodes: &[Node]) -> String {
+    nodes.iter().map(render_node).collect::<Vec<_>>().join("; ")
+}
+
+fn render_node(node: &Node) -> String {
+    match node {
+        Node::Command { text, .. } => text.clone(),
+        Node::If { branches, otherwise } => {
+            let mut text = String::new();
+            for (i, (cond, body)) in branches.iter().enumerate() {
+                let keyword = if i == 0 { "if" } else { "; elif" };
+                text.push_str(&format!("{} {}; then {}", keyword, render(cond), render(body)));
+            }
+            if !otherwise.is_empty() {
+                text.push_str(&format!("; else {}", render(otherwise)));
+            }
+            text + "; fi"
+        }
+        Node::For { var, words, body } => format!("for {} in {}; do {}; done", var, words, render(body)),
+        Node::While { cond, body } => format!("while {}; do {}; done", render(cond), render(body)),
+        Node::Function(function) => function.definition(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(text: &str) -> (usize, String) {
+        let e = parse(text).unwrap_err();
+        (e.line, e.message)
+    }
+
+    #[test]
+    fn commands_split_on_semicolons_and_lines() {
+        let nodes = parse("echo a; echo 'b;c'\n\n  echo d # comment\n# only a comment\necho e#f").unwrap();
+        let commands: Vec<(String, usize)> = nodes
+            .iter()
+            .map(|n| match n {
+                Node::Command { text, line } => (text.clone(), *line),
+                other => panic!("not a command: {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            commands,
+            [
+                ("echo a".to_string(), 1),
+                ("echo 'b;c'".to_string(), 1),
+                ("echo d".to_string(), 3),
+                ("echo e#f".to_string(), 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn if_elif_else() {
+        let nodes = parse("if test -f x; then echo f; elif false; then echo n; else echo e; fi").unwrap();
+        let [Node::If { branches, otherwise }] = nodes.as_slice() else { panic!("{:?}", nodes) };
+        assert_eq!(branches.len(), 2);
+        assert_eq!(otherwise.len(), 1);
+        assert_eq!(render(&nodes), "if test -f x; then echo f; elif false; then echo n; else echo e; fi");
+    }
+
+    #[test]
+    fn if_over_several_lines() {
+        let nodes = parse("if true\nthen\n  echo a\n  echo b\nfi\necho after").unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(render(&nodes), "if true; then echo a; echo b; fi; echo after");
+    }
+
+    #[test]
+    fn for_and_while() {
+        let nodes = parse("for x in a \"b c\" $y; do echo $x; done").unwrap();
+        let [Node::For { var, words, body }] = nodes.as_slice() else { panic!("{:?}", nodes) };
+        assert_eq!((var.as_str(), words.as_str(), body.len()), ("x", "a \"b c\" $y", 1));
+
+        let nodes = parse("while false\ndo\n  echo loop\ndone").unwrap();
+        assert!(matches!(nodes.as_slice(), [Node::While { cond, body }] if cond.len() == 1 && body.len() == 1));
+        assert_eq!(render(&nodes), "while false; do echo loop; done");
+    }
+
+    #[test]
+    fn nested_constructs() {
+        let text = "for x in 1 2; do\n  if true; then\n    while false; do echo w; done\n  fi\ndone";
+        assert_eq!(render(&parse(text).unwrap()), "for x in 1 2; do if true; then while false; do echo w; done; fi; done");
+    }
+
+    #[test]
+    fn function_definitions() {
+        let nodes = parse("fn greet { echo hi $1; echo bye }").unwrap();
+        let [Node::Function(f)] = nodes.as_slice() else { panic!("{:?}", nodes) };
+        assert_eq!(f.name, "greet");
+        assert_eq!(f.definition(), "fn greet { echo hi $1; echo bye }");
+
+        let nodes = parse("greet() {\n  if true; then echo a; fi }\ngreet").unwrap();
+        let [Node::Function(f), Node::Command { text, line: 3 }] = nodes.as_slice() else { panic!("{:?}", nodes) };
+        assert_eq!((f.name.as_str(), text.as_str()), ("greet", "greet"));
+        assert_eq!(f.definition(), "fn greet { if true; then echo a; fi }");
+
+        let nodes = parse("fn multi {\n  echo a\n}").unwrap();
+        assert!(matches!(nodes.as_slice(), [Node::Function(f)] if f.body.len() == 1));
+    }
+
+    #[test]
+    fn missing_terminators() {
+        assert_eq!(error("if true; then echo a"), (1, String::from("unexpected end of input (expected `elif` or `else` or `fi`)")));
+        assert_eq!(error("if true\necho a"), (2, String::from("unexpected end of input (expected `then`)")));
+        assert_eq!(error("for x in a; do echo $x"), (1, String::from("unexpected end of input (expected `done`)")));
+        assert_eq!(error("while true; do\necho a\n"), (2, String::from("unexpected end of input (expected `done`)")));
+        assert_eq!(error("fn f {\necho a"), (2, String::from("unexpected end of input (expected `}`)")));
+    }
+
+    #[test]
+    fn stray_keywords() {
+        assert_eq!(error("echo a\n}"), (2, String::from("unexpected `}`")));
+        assert_eq!(error("fi"), (1, String::from("unexpected `fi`")));
+        assert_eq!(error("if true; then echo a; done"), (1, String::from("unexpected `done`")));
+        assert_eq!(error("for x in a; echo $x; done"), (1, String::from("expected `do`")));
+    }
+
+    #[test]
+    fn invalid_headers() {
+        assert_eq!(error("if; then echo a; fi"), (1, String::from("`if` without a condition")));
+        assert_eq!(error("while; do echo a; done"), (1, String::from("`while` without a condition")));
+        assert_eq!(error("for 1x in a; do echo; done"), (1, String::from("invalid loop variable `1x`")));
+        assert_eq!(error("for x a; do echo; done"), (1, String::from("expected `for name in words`")));
+        assert_eq!(error("fn f { }"), (1, String::from("f: empty function body")));
+        assert_eq!(error("fn f echo"), (1, String::from("f: expected `{ commands }`")));
+        assert_eq!(error("fn 9x { echo }"), (1, String::from("invalid function name `9x`")));
+    }
+
+    #[test]
+    fn broken_commands_fail_the_parse() {
+        assert_eq!(error("echo ok\nif true; then\n  echo \"x\nfi"), (3, String::from("unterminated double quote")));
+        assert_eq!(error("echo a >"), (1, String::from("missing file after `>`")));
+    }
+}
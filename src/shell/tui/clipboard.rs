@@ -0,0 +1,63 @@
+//! System clipboard shared by the editor and the terminal pane.
+//!
+//! Copies go to the OS clipboard through `arboard`. Over SSH, or when no clipboard
+//! is reachable (no display server), the text is sent as an OSC 52 escape sequence
+//! instead: the local terminal emulator then puts it on the user's clipboard (inside
+//! tmux the sequence is wrapped for passthrough). OSC 52 cannot be read back, so
+//! `paste` only sees the OS clipboard; callers keep their own register as a fallback.
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+
+use arboard::Clipboard;
+use base64::Engine;
+
+thread_local! {
+    // Gardé ouvert : sous X11 le contenu copié disparaît avec le Clipboard qui le sert
+    static CLIPBOARD: RefCell<Option<Clipboard>> = const { RefCell::new(None) };
+}
+
+/// Whether the shell runs in an SSH session (the OS clipboard is on the remote side).
+fn over_ssh() -> bool {
+    std::env::var_os("SSH_CONNECTION").is_some() || std::env::var_os("SSH_TTY").is_some()
+}
+
+/// Run `f` on the (lazily opened) OS clipboard.
+fn with_clipboard<T>(f: impl FnOnce(&mut Clipboard) -> Result<T, arboard::Error>) -> Result<T, arboard::Error> {
+    CLIPBOARD.with(|cell| {
+        let mut slot = cell.borrow_mut();
+        if slot.is_none() {
+            *slot = Some(Clipboard::new()?);
+        }
+        f(slot.as_mut().expect("clipboard just opened"))
+    })
+}
+
+/// Put `text` on the system clipboard (OS clipboard, or OSC 52 over SSH).
+pub fn copy(text: &str) -> io::Result<()> {
+    if !over_ssh() && with_clipboard(|c| c.set_text(text)).is_ok() {
+        return Ok(());
+    }
+    osc52(text)
+}
+
+/// Text of the OS clipboard; None over SSH or when it holds no text.
+pub fn paste() -> Option<String> {
+    if over_ssh() {
+        return None;
+    }
+    with_clipboard(|c| c.get_text()).ok()
+}
+
+/// Ask the terminal emulator to set its clipboard (OSC 52).
+fn osc52(text: &str) -> io::Result<()> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let sequence = if std::env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;\x1b\x1b]52;c;{}\x07\x1b\\", encoded)
+    } else {
+        format!("\x1b]52;c;{}\x07", encoded)
+    };
+    let mut out = io::stdout();
+    out.write_all(sequence.as_bytes())?;
+    out.flush()
+}
@@ -1,14 +1,26 @@
 //! TUI command handler for ":"-prefixed commands in the Shell screen.
 //!
 //! Supported commands:
-//! - :q, :quit        → exit the TUI
+//! - :q, :quit        → exit the TUI (after confirming unsaved editor tabs)
+//! - :repl            → back to the REPL line mode, without the `confirm_exit` question
 //! - :l, :logs        → toggle the logs side panel (sticky)
 //! - :h, :help        → toggle the ephemeral help overlay
 //! - :clear           → clear logs
+//! - :jobs            → open the jobs overlay
+//! - :git             → open the Git screen for the explorer's directory
+//! - :docker, :containers → open the container panel
+//! - :http            → open the HTTP client screen
+//! - :tasks           → open the task runner for the explorer's project
+//! - :grep [regex]    → search file contents under the explorer root
 //!
 // src/shell/tui/command_mode.rs
-use crate::shell::tui::state::{TuiState, Overlay};
+use crate::shell::tui::state::{TuiState, Overlay, Screen};
+use crate::shell::tui::components::containers::ContainerView;
+use crate::shell::tui::components::git::GitView;
+use crate::shell::tui::components::http::HttpView;
 use crate::shell::tui::components::logs::LogPanel;
+use crate::shell::tui::components::search::SearchView;
+use crate::shell::tui::components::tasks::TaskView;
 
 /// Small helper object that mutates TuiState and LogPanel based on a parsed command.
 pub struct TuiCommandHandler<'a> {
@@ -23,7 +35,11 @@ impl<'a> TuiCommandHandler<'a> {
         match cmd {
             "q" | "quit" => {
                 self.logs.add("👋 Quit requested.");
-                self.state.running = false;
+                super::request_quit(self.state);
+            }
+            "repl" => {
+                self.logs.add("↩ Back to the REPL.");
+                super::leave_to_repl(self.state);
             }
             "l" | "logs" => {
                 self.state.show_logs = !self.state.show_logs; // ✅ sticky toggle
@@ -38,6 +54,36 @@ impl<'a> TuiCommandHandler<'a> {
                 self.state.overlay_input = None;
                 self.logs.add("🛈 Help toggled.");
             }
+            "jobs" => {
+                self.state.overlay = Overlay::Jobs;
+                self.state.jobs_selected = 0;
+                self.state.jobs_show_output = false;
+            }
+            "git" => {
+                let dir = self.state.explorer.cwd.clone();
+                GitView::refresh(&mut self.state.git, &dir);
+                self.state.screen = Screen::Git;
+            }
+            "docker" | "containers" => {
+                ContainerView::refresh(&mut self.state.containers);
+                self.state.screen = Screen::Containers;
+            }
+            "http" => {
+                HttpView::reload_collection(&mut self.state.http);
+                self.state.screen = Screen::Http;
+            }
+            "tasks" => {
+                let (cwd, root) = (self.state.explorer.cwd.clone(), self.state.explorer.root.clone());
+                TaskView::refresh(&mut self.state.tasks, &cwd, &root);
+                self.state.screen = Screen::Tasks;
+            }
+            c if c == "grep" || c.starts_with("grep ") => {
+                let root = self.state.explorer.root.clone();
+                let from = self.state.screen;
+                let query = Some(c["grep".len()..].trim()).filter(|q| !q.is_empty());
+                SearchView::open(&mut self.state.search, &root, query, from);
+                self.state.screen = Screen::Search;
+            }
             "clear" => {
                 self.logs.clear();
                 self.logs.add("🧹 Logs cleared.");
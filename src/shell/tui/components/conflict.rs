@@ -0,0 +1,37 @@
+//! External change conflict, shown when another program modifies a file whose tab
+//! has unsaved changes: [r] reloads the file (dropping the changes), [k] keeps the
+//! buffer (the next save overwrites the file), [d] compares the file with the buffer.
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::shell::tui::state::EditorState;
+
+/// Stateless renderer for the external change conflict.
+pub struct ConflictView;
+
+impl ConflictView {
+    /// Render the changed file and the choices.
+    pub fn render(f: &mut Frame, area: Rect, ed: &EditorState) {
+        f.render_widget(Clear, area);
+        let name = ed.path.as_ref().map(|p| p.display().to_string()).unwrap_or_default();
+        let lines = vec![
+            Line::from(Span::styled(name, Style::default().add_modifier(Modifier::BOLD))),
+            Line::from(""),
+            Line::from("Le fichier a été modifié sur le disque, mais l’onglet contient des modifications non enregistrées."),
+            Line::from(""),
+            Line::from(Span::styled(
+                "[r] Recharger (abandonner les modifications)  [k] Garder le buffer  [d] Différences",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ];
+        let widget = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title("Modifié sur le disque"));
+        f.render_widget(widget, area);
+    }
+}
@@ -0,0 +1,224 @@
+//! Container panel: lists containers and images through the docker (or podman) CLI,
+//! with start/stop actions and followed logs streamed into a scrollable pane.
+//!
+//! Logs are followed with `<runtime> logs -f` spawned as a background job, so the
+//! stream also shows up in `:jobs`; it is replaced when another container is followed
+//! and stopped when leaving the panel.
+use std::process::Command;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::shell::executor::Redirections;
+use crate::shell::tui::jobs::JobManager;
+use crate::shell::tui::state::{ContainerInfo, ContainerState, ContainerTab, ImageInfo};
+
+/// Stateless container view and actions operating on ContainerState.
+pub struct ContainerView;
+
+/// First available container CLI (docker, then podman).
+fn detect_runtime() -> Option<String> {
+    ["docker", "podman"]
+        .iter()
+        .find(|bin| Command::new(bin).arg("--version").output().map(|o| o.status.success()).unwrap_or(false))
+        .map(|s| s.to_string())
+}
+
+/// Run `<runtime> args...` and return stdout lines, or stderr as the error.
+fn run_lines(runtime: &str, args: &[&str]) -> Result<Vec<String>, String> {
+    let out = Command::new(runtime).args(args).output().map_err(|e| e.to_string())?;
+    if out.status.success() {
+        Ok(String::from_utf8_lossy(&out.stdout).lines().map(|l| l.to_string()).collect())
+    } else {
+        Err(String::from_utf8_lossy(&out.stderr).trim().to_string())
+    }
+}
+
+impl ContainerView {
+    /// Detect the runtime (first time) and reload containers and images.
+    pub fn refresh(state: &mut ContainerState) {
+        if state.runtime.is_none() {
+            state.runtime = detect_runtime();
+        }
+        let Some(rt) = state.runtime.clone() else {
+            state.message_line = Some(String::from("Ni docker ni podman trouvés dans le PATH"));
+            return;
+        };
+        state.message_line = None;
+
+        match run_lines(&rt, &["ps", "-a", "--format", "{{.ID}}\t{{.Names}}\t{{.Image}}\t{{.Status}}"]) {
+            Ok(lines) => {
+                state.containers = lines
+                    .iter()
+                    .filter_map(|l| {
+                        let mut f = l.split('\t');
+                        Some(ContainerInfo {
+                            id: f.next()?.to_string(),
+                            name: f.next()?.to_string(),
+                            image: f.next()?.to_string(),
+                            status: f.next().unwrap_or("").to_string(),
+                        })
+                    })
+                    .collect();
+            }
+            Err(e) => state.message_line = Some(format!("{} ps: {}", rt, e)),
+        }
+        match run_lines(&rt, &["images", "--format", "{{.Repository}}:{{.Tag}}\t{{.ID}}\t{{.Size}}"]) {
+            Ok(lines) => {
+                state.images = lines
+                    .iter()
+                    .filter_map(|l| {
+                        let mut f = l.split('\t');
+                        Some(ImageInfo {
+                            name: f.next()?.to_string(),
+                            id: f.next()?.to_string(),
+                            size: f.next().unwrap_or("").to_string(),
+                        })
+                    })
+                    .collect();
+            }
+            Err(e) => state.message_line = Some(format!("{} images: {}", rt, e)),
+        }
+        let len = Self::len(state);
+        if state.selected >= len {
+            state.selected = len.saturating_sub(1);
+        }
+    }
+
+    fn len(state: &ContainerState) -> usize {
+        match state.tab {
+            ContainerTab::Containers => state.containers.len(),
+            ContainerTab::Images => state.images.len(),
+        }
+    }
+
+    pub fn move_up(state: &mut ContainerState) {
+        state.selected = state.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(state: &mut ContainerState) {
+        if state.selected + 1 < Self::len(state) {
+            state.selected += 1;
+        }
+    }
+
+    /// Switch between the containers and images lists.
+    pub fn toggle_tab(state: &mut ContainerState) {
+        state.tab = match state.tab {
+            ContainerTab::Containers => ContainerTab::Images,
+            ContainerTab::Images => ContainerTab::Containers,
+        };
+        state.selected = 0;
+    }
+
+    /// Run `<runtime> start|stop <id>` on the selected container.
+    pub fn container_action(state: &mut ContainerState, action: &str) {
+        let (Some(rt), Some(c)) = (state.runtime.clone(), state.containers.get(state.selected).cloned()) else {
+            return;
+        };
+        if state.tab != ContainerTab::Containers {
+            return;
+        }
+        let result = run_lines(&rt, &[action, &c.id]);
+        Self::refresh(state);
+        state.message_line = Some(match result {
+            Ok(_) => format!("{} {} ✓", action, c.name),
+            Err(e) => format!("{} {}: {}", action, c.name, e),
+        });
+    }
+
+    /// Follow the logs of the selected container (replaces any previous stream).
+    pub fn follow_logs(state: &mut ContainerState, jobs: &mut JobManager) {
+        let (Some(rt), Some(c)) = (state.runtime.clone(), state.containers.get(state.selected).cloned()) else {
+            return;
+        };
+        if state.tab != ContainerTab::Containers {
+            return;
+        }
+        Self::stop_logs(state, jobs);
+        let line = format!("{} logs -f {}", rt, c.name);
+        match jobs.spawn(&line, &rt, &["logs", "-f", "--tail", "500", &c.id], &Redirections::default(), true) {
+            Ok(id) => {
+                state.logs_job = Some(id);
+                state.logs_title = c.name;
+                state.logs_scroll = 0;
+            }
+            Err(e) => state.message_line = Some(format!("{}: {}", line, e)),
+        }
+    }
+
+    /// Stop the current logs stream, if any.
+    pub fn stop_logs(state: &mut ContainerState, jobs: &mut JobManager) {
+        if let Some(id) = state.logs_job.take() {
+            let _ = jobs.kill(id);
+        }
+    }
+
+    /// Render the list (containers or images) and the logs pane.
+    pub fn render(f: &mut Frame, area: Rect, state: &ContainerState, jobs: &JobManager) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(area);
+
+        let row_style = |i: usize| {
+            if i == state.selected { Style::default().fg(Color::Yellow) } else { Style::default() }
+        };
+        let (items, label): (Vec<ListItem>, &str) = match state.tab {
+            ContainerTab::Containers => (
+                state
+                    .containers
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| {
+                        let running = c.status.starts_with("Up");
+                        let mark = if running { "●" } else { "○" };
+                        let label = format!("{} {:<24} {:<30} {}", mark, c.name, c.image, c.status);
+                        let style = if i == state.selected || !running { row_style(i) } else { Style::default().fg(Color::Green) };
+                        ListItem::new(label).style(style)
+                    })
+                    .collect(),
+                "Conteneurs",
+            ),
+            ContainerTab::Images => (
+                state
+                    .images
+                    .iter()
+                    .enumerate()
+                    .map(|(i, im)| ListItem::new(format!("{:<40} {:<14} {}", im.name, im.id, im.size)).style(row_style(i)))
+                    .collect(),
+                "Images",
+            ),
+        };
+        let runtime = state.runtime.clone().unwrap_or_else(|| String::from("—"));
+        let mut title = format!("{} ({}) — [Tab] Conteneurs/Images", label, runtime);
+        if let Some(msg) = &state.message_line {
+            title = format!("{} — {}", title, msg);
+        }
+        f.render_widget(List::new(items).block(Block::default().borders(Borders::ALL).title(title)), rows[0]);
+
+        let output = state
+            .logs_job
+            .and_then(|id| jobs.get(id))
+            .map(|j| j.output())
+            .unwrap_or_default();
+        let height = rows[1].height.saturating_sub(2) as usize;
+        let end = output.len().saturating_sub(state.logs_scroll);
+        let start = end.saturating_sub(height);
+        let lines: Vec<Line> = output[start..end].iter().map(|l| Line::from(l.clone())).collect();
+        let logs_title = if state.logs_job.is_some() {
+            format!("Logs — {} (suivi)", state.logs_title)
+        } else {
+            String::from("Logs — [l] pour suivre le conteneur sélectionné")
+        };
+        f.render_widget(
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(logs_title)),
+            rows[1],
+        );
+    }
+}
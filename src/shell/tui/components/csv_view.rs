@@ -0,0 +1,216 @@
+//! CSV/TSV table viewer: aligned columns, scrolling, column sorting and a filter row.
+//!
+//! The file is parsed once when opened; sorting and filtering only rebuild the
+//! list of visible row indices (`CsvState::view`).
+use std::cmp::Ordering;
+use std::path::Path;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::shell::tui::state::{CsvState, Screen};
+
+/// Maximum displayed width of a column.
+const MAX_COL_WIDTH: usize = 40;
+
+/// Stateless CSV view and actions operating on CsvState.
+pub struct CsvView;
+
+/// True for `.csv` / `.tsv` files.
+pub fn is_csv(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| matches!(e.to_ascii_lowercase().as_str(), "csv" | "tsv"))
+        .unwrap_or(false)
+}
+
+/// Compare two cells numerically when both are numbers, textually otherwise.
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+impl CsvView {
+    /// Parse `path` (tab-separated for `.tsv`) and show all rows.
+    pub fn open(state: &mut CsvState, path: &Path, from: Screen) {
+        *state = CsvState { return_to: from, ..Default::default() };
+        state.path = Some(path.to_path_buf());
+        let tsv = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("tsv"));
+        let reader = csv::ReaderBuilder::new()
+            .delimiter(if tsv { b'\t' } else { b',' })
+            .flexible(true)
+            .from_path(path);
+        let mut reader = match reader {
+            Ok(r) => r,
+            Err(e) => {
+                state.message_line = Some(format!("Lecture impossible: {}", e));
+                return;
+            }
+        };
+        state.headers = reader.headers().map(|h| h.iter().map(|s| s.to_string()).collect()).unwrap_or_default();
+        for record in reader.records() {
+            match record {
+                Ok(r) => state.rows.push(r.iter().map(|s| s.to_string()).collect()),
+                Err(e) => {
+                    state.message_line = Some(format!("Ligne ignorée: {}", e));
+                }
+            }
+        }
+        Self::rebuild_view(state);
+    }
+
+    /// Recompute visible rows from the filter and the sort column.
+    pub fn rebuild_view(state: &mut CsvState) {
+        let needle = state.filter.to_lowercase();
+        let mut view: Vec<usize> = (0..state.rows.len())
+            .filter(|&i| needle.is_empty() || state.rows[i].iter().any(|c| c.to_lowercase().contains(&needle)))
+            .collect();
+        if let Some((col, ascending)) = state.sort {
+            let rows = &state.rows;
+            view.sort_by(|&a, &b| {
+                let x = rows[a].get(col).map(String::as_str).unwrap_or("");
+                let y = rows[b].get(col).map(String::as_str).unwrap_or("");
+                let o = compare_cells(x, y);
+                if ascending { o } else { o.reverse() }
+            });
+        }
+        state.view = view;
+        if state.selected_row >= state.view.len() {
+            state.selected_row = state.view.len().saturating_sub(1);
+        }
+    }
+
+    /// Cycle the sort of the selected column: ascending → descending → none.
+    pub fn toggle_sort(state: &mut CsvState) {
+        let col = state.selected_col;
+        state.sort = match state.sort {
+            Some((c, true)) if c == col => Some((col, false)),
+            Some((c, false)) if c == col => None,
+            _ => Some((col, true)),
+        };
+        Self::rebuild_view(state);
+    }
+
+    fn column_count(state: &CsvState) -> usize {
+        state.rows.iter().map(|r| r.len()).chain(std::iter::once(state.headers.len())).max().unwrap_or(0)
+    }
+
+    pub fn move_row(state: &mut CsvState, delta: isize) {
+        let next = state.selected_row as isize + delta;
+        state.selected_row = next.clamp(0, state.view.len().saturating_sub(1) as isize) as usize;
+    }
+
+    /// Select the previous/next column, scrolling horizontally to keep it visible.
+    pub fn move_col(state: &mut CsvState, right: bool) {
+        let n = Self::column_count(state);
+        if right {
+            if state.selected_col + 1 < n {
+                state.selected_col += 1;
+            }
+        } else {
+            state.selected_col = state.selected_col.saturating_sub(1);
+        }
+        if state.selected_col < state.col_offset {
+            state.col_offset = state.selected_col;
+        }
+    }
+
+    /// Render the filter row and the table.
+    pub fn render(f: &mut Frame, area: Rect, state: &mut CsvState) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(3)])
+            .split(area);
+
+        let filter_style = if state.filter_editing { Style::default().fg(Color::Yellow) } else { Style::default() };
+        let filter = Paragraph::new(Line::from(state.filter.clone()))
+            .block(Block::default().borders(Borders::ALL).border_style(filter_style).title("Filtre [/]"));
+        f.render_widget(filter, rows[0]);
+        if state.filter_editing {
+            f.set_cursor_position(Position { x: rows[0].x + 1 + state.filter.chars().count() as u16, y: rows[0].y + 1 });
+        }
+
+        // Largeurs : en-tête + cellules, bornées ; on fait défiler pour garder la colonne sélectionnée visible
+        let n = Self::column_count(state);
+        let width_of = |c: usize| {
+            state
+                .rows
+                .iter()
+                .map(|r| r.get(c).map(|v| v.chars().count()).unwrap_or(0))
+                .chain(std::iter::once(state.headers.get(c).map(|h| h.chars().count() + 2).unwrap_or(0)))
+                .max()
+                .unwrap_or(1)
+                .clamp(1, MAX_COL_WIDTH)
+        };
+        let avail = rows[1].width.saturating_sub(2) as usize;
+        loop {
+            let used: usize = (state.col_offset..=state.selected_col.min(n.saturating_sub(1))).map(|c| width_of(c) + 2).sum();
+            if used <= avail || state.col_offset >= state.selected_col {
+                break;
+            }
+            state.col_offset += 1;
+        }
+        let visible: Vec<usize> = (state.col_offset..n).collect();
+        let widths: Vec<Constraint> = visible.iter().map(|&c| Constraint::Length(width_of(c) as u16)).collect();
+
+        let header = Row::new(visible.iter().map(|&c| {
+            let mut name = state.headers.get(c).cloned().unwrap_or_default();
+            match state.sort {
+                Some((sc, true)) if sc == c => name.push_str(" ▲"),
+                Some((sc, false)) if sc == c => name.push_str(" ▼"),
+                _ => {}
+            }
+            let style = if c == state.selected_col {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+            };
+            Cell::from(name).style(style)
+        }));
+
+        let height = rows[1].height.saturating_sub(3) as usize;
+        let start = (state.selected_row + 1).saturating_sub(height);
+        let body: Vec<Row> = state
+            .view
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(height)
+            .map(|(i, &r)| {
+                let cells = visible.iter().map(|&c| {
+                    let v = state.rows[r].get(c).cloned().unwrap_or_default();
+                    if i == state.selected_row && c == state.selected_col {
+                        Cell::from(v).style(Style::default().fg(Color::Black).bg(Color::Yellow))
+                    } else {
+                        Cell::from(v)
+                    }
+                });
+                let style = if i == state.selected_row { Style::default().bg(Color::DarkGray) } else { Style::default() };
+                Row::new(cells).style(style)
+            })
+            .collect();
+
+        let name = state
+            .path
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut title = format!("{} — {}/{} lignes", name, state.view.len(), state.rows.len());
+        if let Some(msg) = &state.message_line {
+            title = format!("{} — {}", title, msg);
+        }
+        let table = Table::new(body, widths)
+            .header(header)
+            .column_spacing(2)
+            .block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(table, rows[1]);
+    }
+}
@@ -0,0 +1,210 @@
+//! System dashboard: live CPU, memory, disk and network sparklines,
+//! plus battery and temperature readings where the platform exposes them.
+//!
+//! Samples are taken on the TUI tick (throttled to sysinfo's minimum CPU
+//! refresh interval) and kept in fixed-size ring buffers for the graphs.
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Sparkline},
+    Frame,
+};
+use sysinfo::{Components, Disks, Networks, System, MINIMUM_CPU_UPDATE_INTERVAL};
+
+/// Number of samples kept for each graph.
+const HISTORY_LEN: usize = 120;
+
+/// Live system metrics with their recent history.
+pub struct Dashboard {
+    system: System,
+    networks: Networks,
+    disks: Disks,
+    components: Components,
+    last_sample: Option<Instant>,
+    battery: Option<u8>,
+    cpu: VecDeque<u64>,
+    mem: VecDeque<u64>,
+    disk_io: VecDeque<u64>,
+    net_rx: VecDeque<u64>,
+    net_tx: VecDeque<u64>,
+}
+
+/// Push a sample into a bounded ring buffer.
+fn push_sample(buf: &mut VecDeque<u64>, v: u64) {
+    if buf.len() == HISTORY_LEN {
+        buf.pop_front();
+    }
+    buf.push_back(v);
+}
+
+/// Human-readable byte count (B, KiB, MiB, GiB).
+pub fn human_bytes(n: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut v = n as f64;
+    let mut unit = 0;
+    while v >= 1024.0 && unit + 1 < UNITS.len() {
+        v /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 { format!("{} B", n) } else { format!("{:.1} {}", v, UNITS[unit]) }
+}
+
+/// Battery charge in percent (Linux sysfs), if a battery is present.
+fn battery_percent() -> Option<u8> {
+    let dir = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    for e in dir.flatten() {
+        if e.file_name().to_string_lossy().starts_with("BAT") {
+            let raw = std::fs::read_to_string(e.path().join("capacity")).ok()?;
+            return raw.trim().parse().ok();
+        }
+    }
+    None
+}
+
+impl Dashboard {
+    /// Create the dashboard with empty histories; the first sample is taken on the next tick.
+    pub fn new() -> Self {
+        Self {
+            system: System::new(),
+            networks: Networks::new_with_refreshed_list(),
+            disks: Disks::new_with_refreshed_list(),
+            components: Components::new_with_refreshed_list(),
+            last_sample: None,
+            battery: None,
+            cpu: VecDeque::with_capacity(HISTORY_LEN),
+            mem: VecDeque::with_capacity(HISTORY_LEN),
+            disk_io: VecDeque::with_capacity(HISTORY_LEN),
+            net_rx: VecDeque::with_capacity(HISTORY_LEN),
+            net_tx: VecDeque::with_capacity(HISTORY_LEN),
+        }
+    }
+
+    /// Take a new sample if enough time has elapsed since the previous one.
+    pub fn tick(&mut self) {
+        if self.last_sample.is_some_and(|t| t.elapsed() < MINIMUM_CPU_UPDATE_INTERVAL) {
+            return;
+        }
+        // Les compteurs disque/réseau sont cumulés depuis le dernier refresh → ramener à la seconde
+        let secs = self.last_sample.map(|t| t.elapsed().as_secs_f64()).unwrap_or(1.0).max(0.001);
+        let per_sec = |bytes: u64| (bytes as f64 / secs) as u64;
+        self.last_sample = Some(Instant::now());
+
+        self.system.refresh_cpu_usage();
+        self.system.refresh_memory();
+        self.networks.refresh(true);
+        self.disks.refresh(true);
+        self.components.refresh(true);
+        self.battery = battery_percent();
+
+        push_sample(&mut self.cpu, self.system.global_cpu_usage().round() as u64);
+        let total = self.system.total_memory().max(1);
+        push_sample(&mut self.mem, self.system.used_memory() * 100 / total);
+        let io: u64 = self
+            .disks
+            .list()
+            .iter()
+            .map(|d| {
+                let u = d.usage();
+                u.read_bytes + u.written_bytes
+            })
+            .sum();
+        push_sample(&mut self.disk_io, per_sec(io));
+        let (rx, tx) = self
+            .networks
+            .list()
+            .values()
+            .fold((0, 0), |(rx, tx), n| (rx + n.received(), tx + n.transmitted()));
+        push_sample(&mut self.net_rx, per_sec(rx));
+        push_sample(&mut self.net_tx, per_sec(tx));
+    }
+
+    /// Render the four graphs and the sensors line.
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(45),
+                Constraint::Percentage(45),
+                Constraint::Min(3),
+            ])
+            .split(area);
+        let top = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(rows[0]);
+        let mid = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(rows[1]);
+        let net = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(mid[1]);
+
+        let last = |b: &VecDeque<u64>| b.back().copied().unwrap_or(0);
+
+        let cpu_title = format!("CPU {}%  ({} cœurs)", last(&self.cpu), self.system.cpus().len());
+        Self::sparkline(f, top[0], cpu_title, &self.cpu, Some(100), Color::Green);
+
+        let mem_title = format!(
+            "Mémoire {}%  ({} / {})",
+            last(&self.mem),
+            human_bytes(self.system.used_memory()),
+            human_bytes(self.system.total_memory())
+        );
+        Self::sparkline(f, top[1], mem_title, &self.mem, Some(100), Color::Magenta);
+
+        let (used, total) = self
+            .disks
+            .list()
+            .iter()
+            .fold((0, 0), |(u, t), d| (u + d.total_space() - d.available_space(), t + d.total_space()));
+        let disk_title = format!(
+            "Disque I/O {}/s  (utilisé {} / {})",
+            human_bytes(last(&self.disk_io)),
+            human_bytes(used),
+            human_bytes(total)
+        );
+        Self::sparkline(f, mid[0], disk_title, &self.disk_io, None, Color::Yellow);
+
+        let rx_title = format!("Réseau ↓ {}/s", human_bytes(last(&self.net_rx)));
+        Self::sparkline(f, net[0], rx_title, &self.net_rx, None, Color::Cyan);
+        let tx_title = format!("Réseau ↑ {}/s", human_bytes(last(&self.net_tx)));
+        Self::sparkline(f, net[1], tx_title, &self.net_tx, None, Color::Blue);
+
+        // Capteurs : batterie + températures (si disponibles)
+        let mut sensors: Vec<String> = Vec::new();
+        if let Some(b) = self.battery {
+            sensors.push(format!("🔋 {}%", b));
+        }
+        for c in self.components.list().iter().take(4) {
+            if let Some(t) = c.temperature() {
+                sensors.push(format!("{} {:.0}°C", c.label(), t));
+            }
+        }
+        if sensors.is_empty() {
+            sensors.push(String::from("Batterie / température : non disponibles"));
+        }
+        let p = Paragraph::new(Line::from(sensors.join("   ")))
+            .block(Block::default().borders(Borders::ALL).title("Capteurs"));
+        f.render_widget(p, rows[2]);
+    }
+
+    fn sparkline(f: &mut Frame, area: Rect, title: String, data: &VecDeque<u64>, max: Option<u64>, color: Color) {
+        // N'affiche que les derniers points qui tiennent dans la largeur
+        let width = area.width.saturating_sub(2) as usize;
+        let points: Vec<u64> = data.iter().skip(data.len().saturating_sub(width)).copied().collect();
+        let mut spark = Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .data(&points)
+            .style(Style::default().fg(color));
+        if let Some(m) = max {
+            spark = spark.max(m);
+        }
+        f.render_widget(spark, area);
+    }
+}
@@ -0,0 +1,205 @@
+//! Diff viewer: unified or side-by-side comparison with intra-line highlighting.
+//!
+//! Sources:
+//! - two files picked in the explorer (`D` on each)
+//! - the current editor buffer against its version at git HEAD
+//!
+//! Hunks (3 lines of context) are computed once with `similar` when the view opens.
+use std::path::Path;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use similar::{ChangeTag, TextDiff};
+
+use crate::shell::git;
+use crate::shell::tui::state::{DiffKind, DiffLine, DiffState, EditorState, Screen};
+
+/// Stateless diff view and actions operating on DiffState.
+pub struct DiffView;
+
+fn line_style(kind: DiffKind, emphasized: bool) -> Style {
+    match (kind, emphasized) {
+        (DiffKind::Equal, _) => Style::default(),
+        (DiffKind::Delete, false) => Style::default().fg(Color::Red),
+        (DiffKind::Delete, true) => Style::default().fg(Color::White).bg(Color::Red).add_modifier(Modifier::BOLD),
+        (DiffKind::Insert, false) => Style::default().fg(Color::Green),
+        (DiffKind::Insert, true) => Style::default().fg(Color::Black).bg(Color::Green).add_modifier(Modifier::BOLD),
+    }
+}
+
+/// Gutter (line number) + content spans of a diff line.
+fn spans_for(line: &DiffLine, no: Option<usize>, marker: &str) -> Vec<Span<'static>> {
+    let gutter = no.map(|n| format!("{:>5} ", n)).unwrap_or_else(|| String::from("      "));
+    let mut spans = vec![
+        Span::styled(gutter, Style::default().fg(Color::DarkGray)),
+        Span::styled(marker.to_string(), line_style(line.kind, false)),
+    ];
+    spans.extend(line.segments.iter().map(|(emph, text)| Span::styled(text.clone(), line_style(line.kind, *emph))));
+    spans
+}
+
+impl DiffView {
+    /// Compare two texts and show the result.
+    pub fn open_texts(state: &mut DiffState, left_name: String, old: &str, right_name: String, new: &str, from: Screen) {
+        let side_by_side = state.side_by_side;
+        *state = DiffState { left_name, right_name, side_by_side, return_to: from, ..Default::default() };
+        let diff = TextDiff::from_lines(old, new);
+        for group in diff.grouped_ops(3) {
+            let (Some(first), Some(last)) = (group.first(), group.last()) else { continue; };
+            let (o, n) = (first.old_range().start, first.new_range().start);
+            let (o_len, n_len) = (last.old_range().end - o, last.new_range().end - n);
+            let header = format!("@@ -{},{} +{},{} @@", o + 1, o_len, n + 1, n_len);
+            let mut lines = Vec::new();
+            for op in &group {
+                for change in diff.iter_inline_changes(op) {
+                    let kind = match change.tag() {
+                        ChangeTag::Equal => DiffKind::Equal,
+                        ChangeTag::Delete => DiffKind::Delete,
+                        ChangeTag::Insert => DiffKind::Insert,
+                    };
+                    let segments = change
+                        .iter_strings_lossy()
+                        .map(|(emph, s)| (emph, s.trim_end_matches(['\n', '\r']).to_string()))
+                        .collect();
+                    lines.push(DiffLine {
+                        kind,
+                        old_no: change.old_index().map(|i| i + 1),
+                        new_no: change.new_index().map(|i| i + 1),
+                        segments,
+                    });
+                }
+            }
+            state.hunks.push((header, lines));
+        }
+        if state.hunks.is_empty() {
+            state.message_line = Some(String::from("Aucune différence"));
+        }
+    }
+
+    /// Compare two files (read lossily as text).
+    pub fn open_files(state: &mut DiffState, left: &Path, right: &Path, from: Screen) {
+        let read = |p: &Path| std::fs::read(p).map(|b| String::from_utf8_lossy(&b).into_owned());
+        let name = |p: &Path| p.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        match (read(left), read(right)) {
+            (Ok(a), Ok(b)) => Self::open_texts(state, name(left), &a, name(right), &b, from),
+            (Err(e), _) | (_, Err(e)) => {
+                *state = DiffState { return_to: from, ..Default::default() };
+                state.message_line = Some(format!("Lecture impossible: {}", e));
+            }
+        }
+    }
+
+    /// Compare the editor buffer with the file's version at git HEAD.
+    pub fn open_buffer_vs_head(state: &mut DiffState, ed: &EditorState, from: Screen) {
+        if let Err(e) = Self::try_buffer_vs_head(state, ed, from) {
+            *state = DiffState { return_to: from, side_by_side: state.side_by_side, ..Default::default() };
+            state.message_line = Some(e);
+        }
+    }
+
+    fn try_buffer_vs_head(state: &mut DiffState, ed: &EditorState, from: Screen) -> Result<(), String> {
+        let path = ed.path.as_ref().ok_or("Buffer sans fichier")?;
+        path.canonicalize().map_err(|e| e.to_string())?;
+        let (root, rel) = git::locate(path).ok_or("Pas de dépôt git")?;
+        let head = git::show_head(&root, &rel).map_err(|e| e.to_string())?;
+        Self::open_texts(state, format!("HEAD:{}", rel), &head, format!("{} (buffer)", rel), &ed.buffer.to_string(), from);
+        Ok(())
+    }
+
+    /// Unified rendering: hunk headers then -/+ lines.
+    fn unified_lines(state: &DiffState) -> Vec<Line<'static>> {
+        let mut out = Vec::new();
+        for (header, lines) in &state.hunks {
+            out.push(Line::from(Span::styled(header.clone(), Style::default().fg(Color::Cyan))));
+            for l in lines {
+                let (no, marker) = match l.kind {
+                    DiffKind::Equal => (l.new_no, " "),
+                    DiffKind::Delete => (l.old_no, "-"),
+                    DiffKind::Insert => (l.new_no, "+"),
+                };
+                out.push(Line::from(spans_for(l, no, marker)));
+            }
+        }
+        out
+    }
+
+    /// Side-by-side rendering: deletions paired with the insertions that follow them.
+    fn split_lines(state: &DiffState) -> (Vec<Line<'static>>, Vec<Line<'static>>) {
+        let (mut left, mut right) = (Vec::new(), Vec::new());
+        for (header, lines) in &state.hunks {
+            let sep = || Line::from(Span::styled(header.clone(), Style::default().fg(Color::Cyan)));
+            left.push(sep());
+            right.push(sep());
+            let mut i = 0;
+            while i < lines.len() {
+                if lines[i].kind == DiffKind::Equal {
+                    left.push(Line::from(spans_for(&lines[i], lines[i].old_no, " ")));
+                    right.push(Line::from(spans_for(&lines[i], lines[i].new_no, " ")));
+                    i += 1;
+                    continue;
+                }
+                let dels: Vec<&DiffLine> = lines[i..].iter().take_while(|l| l.kind == DiffKind::Delete).collect();
+                i += dels.len();
+                let ins: Vec<&DiffLine> = lines[i..].iter().take_while(|l| l.kind == DiffKind::Insert).collect();
+                i += ins.len();
+                for k in 0..dels.len().max(ins.len()) {
+                    left.push(dels.get(k).map(|l| Line::from(spans_for(l, l.old_no, "-"))).unwrap_or_default());
+                    right.push(ins.get(k).map(|l| Line::from(spans_for(l, l.new_no, "+"))).unwrap_or_default());
+                }
+            }
+        }
+        (left, right)
+    }
+
+    /// Number of rendered rows in the current mode (for scrolling bounds).
+    pub fn row_count(state: &DiffState) -> usize {
+        if state.side_by_side {
+            Self::split_lines(state).0.len()
+        } else {
+            Self::unified_lines(state).len()
+        }
+    }
+
+    pub fn scroll_by(state: &mut DiffState, delta: isize) {
+        let max = Self::row_count(state).saturating_sub(1) as isize;
+        state.scroll = (state.scroll as isize + delta).clamp(0, max.max(0)) as usize;
+    }
+
+    pub fn render(f: &mut Frame, area: Rect, state: &DiffState) {
+        let (adds, dels) = state.hunks.iter().flat_map(|(_, l)| l).fold((0, 0), |(a, d), l| match l.kind {
+            DiffKind::Insert => (a + 1, d),
+            DiffKind::Delete => (a, d + 1),
+            DiffKind::Equal => (a, d),
+        });
+        let mut title = format!("Diff — {} ↔ {}  (+{} −{})", state.left_name, state.right_name, adds, dels);
+        if let Some(msg) = &state.message_line {
+            title = format!("{} — {}", title, msg);
+        }
+        let scroll = (state.scroll as u16, 0);
+        if state.side_by_side {
+            let cols = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(area);
+            let (left, right) = Self::split_lines(state);
+            f.render_widget(
+                Paragraph::new(left).scroll(scroll).block(Block::default().borders(Borders::ALL).title(title)),
+                cols[0],
+            );
+            f.render_widget(
+                Paragraph::new(right).scroll(scroll).block(Block::default().borders(Borders::ALL).title(state.right_name.clone())),
+                cols[1],
+            );
+        } else {
+            f.render_widget(
+                Paragraph::new(Self::unified_lines(state)).scroll(scroll).block(Block::default().borders(Borders::ALL).title(title)),
+                area,
+            );
+        }
+    }
+}
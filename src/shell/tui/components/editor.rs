@@ -1,30 +1,210 @@
 //! Simple text editor component used in the Workspace and Editor screens.
 //!
 //! Features:
-//! - Open and save files within a confined root path
+//! - Open and save files within a confined root path, or through a virtual
+//!   filesystem (archive members read-only, remote files over SFTP)
 //! - Rope-backed buffer for efficient editing
 //! - Line numbers gutter and a basic status bar
 //! - Minimal modes: Normal, Insert, Command (':' prompt)
-use crate::shell::tui::state::{EditorMode, EditorState};
+//! - Visual modes (`v` character-wise, `V` line-wise): the selection is highlighted,
+//!   `y` yanks and `d` / `x` deletes it into the shared register (and the system
+//!   clipboard), `p` / `P` paste it after / before the cursor; deleting and pasting
+//!   are each one undo step
+//! - Block selection (Ctrl+V, Alt+B in the workspace): a rectangle of columns; `I` /
+//!   `A` type before / after it on every line at once, `y` / `d` copy or cut it, and
+//!   a copied block is pasted column-wise on the following lines
+//! - Registers: `"a`–`"z` before a yank, delete or paste use a named register (`"A`
+//!   appends to it) instead of the unnamed one mirrored on the system clipboard;
+//!   `"0`–`"9` paste from the history of the last yanks (Alt+" in the workspace)
+//! - Marks: `m a` marks the cursor position, `'a` jumps to its line and `` `a `` to
+//!   the exact position (Alt+m / Alt+' in the workspace), `''` back to where the jump
+//!   started; uppercase marks reach other files. Marks follow line edits and are
+//!   saved across sessions (see `tui::marks`)
+//! - Line operations, each one undo step: `dd` / `yy` cut / copy the line (Alt+d /
+//!   Alt+y in the workspace), `J` (Alt+j) joins the next line, Alt+↑/↓ move the line
+//!   or the selected lines
+//! - Indentation from `config/editor.toml` (per file type `tab_width` / `expand_tabs`):
+//!   Enter keeps the indentation of the line, Tab indents (spaces up to the next tab
+//!   stop, or a tab character), Shift+Tab and `>` / `<` on a selection shift lines by
+//!   one level; tab characters are displayed `tab_width` columns wide
+//! - Scrolling follows the height of the pane and keeps `scrolloff` lines (config/
+//!   editor.toml) around the cursor; PageUp / PageDown move by a page, Ctrl+U /
+//!   Ctrl+D in Normal mode (Alt+PageUp / Alt+PageDown in the workspace) by half a page
+//! - Soft wrap per buffer (`:set wrap` / `:set nowrap`, Alt+z in the workspace): long
+//!   lines continue on the following screen rows, the cursor is placed on the row
+//!   and column of its buffer column; ↑/↓ still move by buffer line
+//! - Large files (from `LARGE_FILE` bytes) are read in the background, chunk by chunk,
+//!   with a progress gauge; from `READ_ONLY_FILE` bytes they open read-only
+//!   (`:set noreadonly` / Alt+r to allow edits). Only the visible lines are rendered
+//! - Encodings and line endings are kept: UTF-8 (with or without BOM), UTF-16 and
+//!   Latin-1 files, LF or CRLF, are edited as LF text and written back as they were
+//!   read; the status bar shows both
+//! - Files changed on disk by another program are reloaded when their buffer is
+//!   clean; a modified buffer gets a prompt (reload, keep, or diff with the file)
+//! - Modified buffers are copied to swap files every 15 s (see `tui::swap`); opening
+//!   a file with a newer swap copy offers to recover it
+//! - Word motions on Unicode word boundaries: `w` / `b` / `e` in Normal mode,
+//!   Ctrl+←/→ and Ctrl+Backspace / Ctrl+Delete (word deletion) while typing
+//! - Regex search (Ctrl+F) with case-insensitive / whole-word toggles: matches are
+//!   highlighted as the pattern is typed, `n` / `N` (F3 / Shift+F3) go to the next /
+//!   previous one and the status line shows "occurrence 3/17"
+//! - The bracket under the cursor and its match are highlighted, `%` (Alt+% in the
+//!   workspace) jumps between them; with `auto_pairs` typing an opening bracket or
+//!   quote inserts the closing one, and typing the closing one steps over it
+//! - `:set list` (Alt+i) shows tabs as `→`, non-breaking spaces as `⍽` and trailing
+//!   spaces as `·`; saving can strip trailing whitespace and add a final line break
+//!   (`trim_trailing_whitespace` / `final_newline`, per file type)
+//! - Indentation-based folding: `za` / `zc` / `zo` toggle, close and open the region
+//!   under the cursor (the lines indented deeper than its first line), `zM` / `zR`
+//!   fold and open everything (Alt+f / Alt+F in the workspace). A folded region shows
+//!   as its first line and a line count; editing inside it opens it
+//! - Vertical split (Alt+s, `:vsplit`): two tabs side by side, or the same file at
+//!   two positions, each pane keeping its cursor and scroll; Alt+o switches panes
+//!   and `:only` closes the split
+//! - Git gutter: lines added, modified or deleted since HEAD are marked next to the
+//!   line numbers; `:set blame` (Alt+b) shows the last commit of the cursor line
+//!   (see `tui::gutter`)
+//! - Go to symbol (Ctrl+K) lists the definitions of the buffer or of the workspace;
+//!   go to definition (F12, `gd` in Normal mode) jumps to the one named by the word
+//!   under the cursor, in this file, another open tab or the workspace index
+//! - Snippets (`[[snippets]]` in config/editor.toml): a trigger then Tab expands
+//!   the body; Tab / Shift+Tab walk its placeholders (see `tui::snippets`)
+//! - Comment toggling (Alt+c, `gc`): the cursor line or the selected lines get the
+//!   comment markers of their file type (`//`, `#`, `<!-- -->`…), or lose them when
+//!   they all have them; `comment` in config/editor.toml overrides the built-in table
+//! - Jump list: searches, goto-line, `%`, symbols, marks and opened files record
+//!   where the cursor was; Ctrl+O / Tab (Ctrl+I) in Normal mode walk back and
+//!   forward across tabs (Alt+, / Alt+. in the workspace)
+//! - Undo steps record the changed text, not copies of the buffer; typed characters
+//!   are grouped per word (a pause or a cursor move also ends a step). With
+//!   `persistent_undo` the history survives closing the file (see `tui::undo`)
+use crate::shell::tui::components::dashboard::human_bytes;
+use crate::shell::tui::encoding::{self, Decoder, TextEncoding};
+use crate::shell::tui::{gutter, marks, snippets, swap, undo};
+use crate::shell::tui::state::{BlockInsert, EditorMode, EditorRegister, EditorState, FileExplorerState, LineChange, LoadEvent, LoadState, RemoteFile, SnippetSession, TypingRun, VfsMount};
 use anyhow::{Result, bail};
+use regex::{Regex, RegexBuilder};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Position, Rect},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Clear, Gauge, Paragraph},
 };
 use std::fs;
-use std::io::Write;
-use std::path::Path;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::sync::mpsc::{self, Sender, TryRecvError};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use unicode_segmentation::UnicodeSegmentation;
+
+const NOT_TEXT: &str = "Fichier binaire : ouvrez-le dans la vue hexadécimale";
+
+/// Read `path` into a rope chunk by chunk, reporting the bytes read; the encoding
+/// is detected on the first chunk, and a file that turns out not to be UTF-8 further
+/// on is read again as Latin-1 (the choice of `from_bytes` on the whole file). Stops
+/// when the receiver is gone (load cancelled).
+fn stream_file(path: &Path, tx: &Sender<LoadEvent>) -> Result<(ropey::Rope, TextEncoding, encoding::LineEnding), String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut builder = ropey::RopeBuilder::new();
+    let mut chunk = vec![0u8; LOAD_CHUNK];
+    let mut decoder: Option<(TextEncoding, Decoder)> = None;
+    let mut done = 0u64;
+    loop {
+        let n = file.read(&mut chunk).map_err(|e| e.to_string())?;
+        let (detected, dec) = decoder.get_or_insert_with(|| {
+            let detected = TextEncoding::detect(&chunk[..n]);
+            (detected, Decoder::new(detected))
+        });
+        let detected = *detected;
+        match dec.decode(&chunk[..n], n == 0) {
+            Ok(text) => builder.append(&text),
+            // Début en ASCII, octets Latin-1 plus loin : tout relire en Latin-1
+            Err(_) if detected == TextEncoding::Utf8 => {
+                file.seek(SeekFrom::Start(0)).map_err(|e| e.to_string())?;
+                builder = ropey::RopeBuilder::new();
+                decoder = Some((TextEncoding::Latin1, Decoder::new(TextEncoding::Latin1)));
+                done = 0;
+                continue;
+            }
+            Err(e) => return Err(e),
+        }
+        if n == 0 {
+            break;
+        }
+        done += n as u64;
+        if tx.send(LoadEvent::Progress(done)).is_err() {
+            return Err(String::from("Ouverture annulée"));
+        }
+    }
+    let (detected, dec) = decoder.expect("set by the first read");
+    Ok((builder.finish(), detected, dec.line_ending()))
+}
 
 /// Ensure that a path resides under a given root (using canonical paths).
-fn within_root(root: &Path, path: &Path) -> bool {
+pub fn within_root(root: &Path, path: &Path) -> bool {
     let r = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
     let p = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
     p.starts_with(&r)
 }
 
+/// Files from this size on are read in the background with a progress gauge.
+pub const LARGE_FILE: u64 = 16 * 1024 * 1024;
+/// Files from this size on open read-only.
+pub const READ_ONLY_FILE: u64 = 128 * 1024 * 1024;
+/// Bytes read between two progress updates
+const LOAD_CHUNK: usize = 1024 * 1024;
+/// Bracket pairs matched by `%` and highlighted under the cursor.
+const BRACKETS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+/// Most chars scanned looking for a matching bracket.
+const BRACKET_SCAN: usize = 100_000;
+
+/// Char index of the bracket matching the one at `idx`, if it is a bracket.
+fn bracket_match(buffer: &ropey::Rope, idx: usize) -> Option<usize> {
+    let c = buffer.get_char(idx)?;
+    if let Some((open, close)) = BRACKETS.iter().find(|(open, _)| *open == c) {
+        let mut depth = 0usize;
+        for (i, ch) in buffer.chars_at(idx).enumerate().take(BRACKET_SCAN) {
+            if ch == *open {
+                depth += 1;
+            } else if ch == *close {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx + i);
+                }
+            }
+        }
+    } else if let Some((open, close)) = BRACKETS.iter().find(|(_, close)| *close == c) {
+        let mut depth = 0usize;
+        let mut chars = buffer.chars_at(idx + 1);
+        for i in 0..BRACKET_SCAN.min(idx + 1) {
+            match chars.prev() {
+                Some(ch) if ch == *close => depth += 1,
+                Some(ch) if ch == *open => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(idx - i);
+                    }
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+    }
+    None
+}
+
+/// Pause after which the next typed character starts a new undo step.
+const UNDO_PAUSE: Duration = Duration::from_secs(1);
+/// Delay between two checks of the open files for changes made by other programs.
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Modification time of a local file.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
 /// Stateless view providing open/save and render helpers for EditorState.
 pub struct EditorView;
 
@@ -37,33 +217,237 @@ impl EditorView {
             bail!("Refusé: chemin en dehors de la racine autorisée");
         }
 
-        let content = std::fs::read_to_string(p)?;
-        let mut ed = EditorState::new_empty();
-        ed.path = Some(p.to_path_buf());
-        ed.buffer = ropey::Rope::from_str(&content);
-        ed.cursor_row = 0;
-        ed.cursor_col = 0;
-        ed.scroll_row = 0;
-        ed.dirty = false;
+        let bytes = std::fs::read(p)?;
+        let mut ed = Self::from_bytes(p, bytes)?;
+        ed.disk_mtime = modified(p);
+        Ok(ed)
+    }
+
+    /// Open `path` (a path under `mount.source`) through the mounted filesystem;
+    /// the buffer is saved back through it.
+    pub fn open_vfs(mount: &VfsMount, path: &Path) -> Result<EditorState> {
+        let inner = path.strip_prefix(&mount.source)?.to_path_buf();
+        let bytes = mount.fs.read(&inner)?;
+        let mut ed = Self::from_bytes(path, bytes)?;
+        ed.remote = Some(RemoteFile { fs: mount.fs.clone(), path: inner });
         Ok(ed)
     }
 
-    /// Save current buffer to disk. Returns an error if no associated path or write fails.
+    /// Open a path returned by the explorer: through its mounted archive or remote
+    /// host when it lies inside, from the disk otherwise.
+    pub fn open_entry(path: PathBuf, explorer: &FileExplorerState) -> Result<EditorState> {
+        match explorer.vfs.as_ref().filter(|m| path.starts_with(&m.source)) {
+            Some(mount) => Self::open_vfs(mount, &path),
+            None => Self::open_path(path, &explorer.root),
+        }
+    }
+
+    fn from_bytes(path: &Path, bytes: Vec<u8>) -> Result<EditorState> {
+        if encoding::looks_binary(&bytes[..bytes.len().min(8192)]) {
+            bail!("{}", NOT_TEXT);
+        }
+        let detected = TextEncoding::detect(&bytes);
+        let mut decoder = Decoder::new(detected);
+        let content = decoder.decode(&bytes, true).map_err(anyhow::Error::msg)?;
+        let mut ed = Self::from_rope(path, ropey::Rope::from_str(&content));
+        ed.encoding = detected;
+        ed.line_ending = decoder.line_ending();
+        Ok(ed)
+    }
+
+    fn from_rope(path: &Path, buffer: ropey::Rope) -> EditorState {
+        let mut ed = EditorState::new_empty();
+        ed.path = Some(path.to_path_buf());
+        ed.buffer = buffer;
+        ed
+    }
+
+    /// Size of `path` when it is large enough to be read in the background.
+    pub fn large_file_size(path: &Path) -> Option<u64> {
+        fs::metadata(path).ok().map(|m| m.len()).filter(|len| *len >= LARGE_FILE)
+    }
+
+    /// Start reading a large file in the background; `poll_load` hands over the buffer.
+    pub fn start_load(load: &mut LoadState, path: &Path, total: u64) {
+        let (tx, rx) = mpsc::channel();
+        let target = path.to_path_buf();
+        std::thread::spawn(move || {
+            let result = stream_file(&target, &tx);
+            let _ = tx.send(LoadEvent::Finished(result));
+        });
+        *load = LoadState { path: path.to_path_buf(), running: Some(rx), done: 0, total };
+    }
+
+    /// Collect progress; once the file is read, returns its editor state (read-only
+    /// from `READ_ONLY_FILE` bytes) or the error.
+    pub fn poll_load(load: &mut LoadState) -> Option<Result<EditorState, String>> {
+        let rx = load.running.as_ref()?;
+        loop {
+            match rx.try_recv() {
+                Ok(LoadEvent::Progress(done)) => load.done = done,
+                Ok(LoadEvent::Finished(result)) => {
+                    load.running = None;
+                    return Some(result.map(|(rope, encoding, line_ending)| {
+                        let mut ed = Self::from_rope(&load.path, rope);
+                        ed.read_only = load.total >= READ_ONLY_FILE;
+                        ed.encoding = encoding;
+                        ed.line_ending = line_ending;
+                        ed.disk_mtime = modified(&load.path);
+                        ed
+                    }));
+                }
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => {
+                    load.running = None;
+                    return Some(Err(String::from("Lecture interrompue")));
+                }
+            }
+        }
+    }
+
+    /// Progress gauge of a background load.
+    pub fn render_load(f: &mut Frame, area: Rect, load: &LoadState) {
+        f.render_widget(Clear, area);
+        let ratio = if load.total == 0 { 0.0 } else { (load.done as f64 / load.total as f64).min(1.0) };
+        let name = load.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let gauge = Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title(format!("Ouverture de {}  [Esc] Annuler", name)))
+            .gauge_style(Style::default().fg(Color::Cyan))
+            .ratio(ratio)
+            .label(format!("{} / {}", human_bytes(load.done), human_bytes(load.total)));
+        f.render_widget(gauge, area);
+    }
+
+    /// Start an edit in a new undo step; false on a read-only buffer.
+    fn begin_edit(ed: &mut EditorState) -> bool {
+        if ed.read_only {
+            return false;
+        }
+        ed.push_undo();
+        true
+    }
+
+    /// `begin_edit` for one typed character (`deleting`: one Backspace): it joins the
+    /// undo step of the previous keys unless the cursor moved, the user paused for
+    /// `UNDO_PAUSE`, or a whitespace ends a word.
+    fn begin_typing(ed: &mut EditorState, deleting: bool, space: bool) -> bool {
+        if ed.read_only {
+            return false;
+        }
+        let at = (ed.cursor_row, ed.cursor_col);
+        let extends = ed.typing.as_ref().is_some_and(|run| {
+            run.deleting == deleting && run.at == at && run.last.elapsed() < UNDO_PAUSE && (run.space || !space)
+        });
+        if !extends || ed.undo_stack.is_empty() {
+            ed.push_undo();
+        }
+        true
+    }
+
+    /// Remember where the typed run stands, for the next `begin_typing`.
+    fn end_typing(ed: &mut EditorState, deleting: bool, space: bool) {
+        ed.typing = Some(TypingRun { deleting, at: (ed.cursor_row, ed.cursor_col), last: Instant::now(), space });
+    }
+
+    /// Save current buffer to disk (or through the filesystem it was opened from), in
+    /// the encoding and with the line endings the file was read with.
+    /// Returns an error if no associated path, a character the encoding cannot hold, or write fails.
     pub fn save(ed: &mut EditorState) -> std::io::Result<()> {
         let path = ed
             .path
             .clone()
-            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, "No file path"))?;
-        let mut f = fs::File::create(path)?;
-        let s = ed.buffer.to_string();
-        f.write_all(s.as_bytes())?;
+            .ok_or_else(|| std::io::Error::other("No file path"))?;
+        if ed.read_only {
+            return Err(std::io::Error::other("Lecture seule"));
+        }
+        Self::clean_up(ed);
+        let bytes = encoding::encode(&ed.buffer.to_string(), ed.encoding, ed.line_ending).map_err(std::io::Error::other)?;
+        match &ed.remote {
+            Some(remote) => remote.fs.write(&remote.path, &bytes)?,
+            None => {
+                fs::File::create(&path)?.write_all(&bytes)?;
+                ed.disk_mtime = modified(&path);
+            }
+        }
         ed.dirty = false;
+        // Le buffer correspond au fichier : annuler ou rétablir l'en écarte, et les
+        // frappes suivantes ne rejoignent pas l'étape enregistrée
+        for step in ed.undo_stack.iter_mut().chain(ed.redo_stack.iter_mut()) {
+            step.dirty = true;
+        }
+        ed.typing = None;
+        swap::discard(ed);
+        undo::save(ed);
+        marks::save(ed);
         Ok(())
     }
 
-    /// Render editor with default border style.
-    pub fn render(f: &mut Frame, area: Rect, ed: &EditorState) {
-        Self::render_with_border(f, area, ed, Style::default());
+    /// Strip trailing whitespace and add the final line break, as configured for the
+    /// file type (`trim_trailing_whitespace`, `final_newline`); one undo step.
+    fn clean_up(ed: &mut EditorState) {
+        let mut trims: Vec<Range<usize>> = Vec::new();
+        if ed.cleanup.trim_trailing_whitespace {
+            for row in 0..ed.buffer.len_lines() {
+                let text = Self::line_string(ed, row);
+                let (kept, len) = (text.trim_end_matches([' ', '\t']).chars().count(), text.chars().count());
+                if kept < len {
+                    let start = ed.buffer.line_to_char(row);
+                    trims.push(start + kept..start + len);
+                }
+            }
+        }
+        let len = ed.buffer.len_chars();
+        let missing_newline = ed.cleanup.final_newline && len > 0 && ed.buffer.char(len - 1) != '\n';
+        if trims.is_empty() && !missing_newline {
+            return;
+        }
+        ed.push_undo();
+        for range in trims.into_iter().rev() {
+            ed.remove_range(range);
+        }
+        if missing_newline {
+            ed.insert_at(ed.buffer.len_chars(), "\n");
+        }
+        Self::clamp_col(ed);
+    }
+
+    /// New modification time of the file of `ed` when another program changed it
+    /// since it was read or saved (a deleted file is not reported).
+    pub fn disk_changed(ed: &EditorState) -> Option<SystemTime> {
+        let known = ed.disk_mtime?;
+        let path = ed.path.as_deref().filter(|_| ed.remote.is_none())?;
+        modified(path).filter(|now| *now != known)
+    }
+
+    /// Current content of the file of `ed` on disk, decoded like the buffer.
+    pub fn disk_text(ed: &EditorState) -> Result<String> {
+        let path = ed.path.as_deref().filter(|_| ed.remote.is_none()).ok_or_else(|| anyhow::anyhow!("No file path"))?;
+        Ok(Self::from_bytes(path, fs::read(path)?)?.buffer.to_string())
+    }
+
+    /// Replace the buffer with the file on disk, dropping its changes and its undo
+    /// history; the cursor stays where it was when the file is long enough.
+    pub fn reload(ed: &mut EditorState) -> Result<()> {
+        let path = ed.path.clone().filter(|_| ed.remote.is_none()).ok_or_else(|| anyhow::anyhow!("No file path"))?;
+        let fresh = Self::from_bytes(&path, fs::read(&path)?)?;
+        ed.buffer = fresh.buffer;
+        ed.encoding = fresh.encoding;
+        ed.line_ending = fresh.line_ending;
+        ed.disk_mtime = modified(&path);
+        ed.dirty = false;
+        ed.undo_stack.clear();
+        ed.redo_stack.clear();
+        ed.revision += 1;
+        swap::discard(ed);
+        // Un checkout a pu changer la version HEAD
+        gutter::load_head(ed);
+        let last = ed.buffer.len_lines().saturating_sub(1);
+        ed.cursor_row = ed.cursor_row.min(last);
+        Self::clamp_col(ed);
+        ed.scroll_row = ed.scroll_row.min(ed.cursor_row);
+        ed.visual_anchor.0 = ed.visual_anchor.0.min(last);
+        Self::recompute_search_positions(ed);
+        Ok(())
     }
 
     /// Render editor with a custom border style (used to indicate focus).
@@ -92,52 +476,160 @@ impl EditorView {
         if ed.dirty {
             title = format!("● {}", title);
         }
+        match ed.mode {
+            EditorMode::Visual => title.push_str(" — VISUEL"),
+            EditorMode::VisualLine => title.push_str(" — VISUEL LIGNE"),
+            EditorMode::VisualBlock => title.push_str(" — VISUEL BLOC"),
+            _ => {}
+        }
 
         // Lignes visibles + gouttière numérotée
         let height = chunks[0].height.saturating_sub(2) as usize;
-        let start = ed.scroll_row;
-        let end = usize::min(ed.buffer.len_lines(), start + height);
         let digits = ((ed.buffer.len_lines().max(1) as f64).log10().floor() as usize) + 1;
+        let text_w = (chunks[0].width.saturating_sub(2) as usize).saturating_sub(digits + 3).max(1);
+        // Le défilement compte en lignes du buffer : avancer tant que le curseur (lignes
+        // repliées ou régions pliées au-dessus) sortirait de la vue
+        let mut start = ed.scroll_row.min(ed.cursor_row);
+        if let Some((header, _)) = Self::fold_hiding(ed, start) {
+            start = header;
+        }
+        if !ed.wrap && ed.folds.is_empty() {
+            start = start.max((ed.cursor_row + 1).saturating_sub(height));
+        }
+        while start < ed.cursor_row && Self::cursor_screen_pos(ed, start, text_w).0 >= height {
+            start = Self::next_visible(ed, start);
+        }
 
-        let mut lines: Vec<Line> = Vec::with_capacity(end - start);
-        let query = ed.last_search.clone().unwrap_or_default();
-        for row in start..end {
+        let mut lines: Vec<Line> = Vec::with_capacity(height);
+        let search = Self::search_regex(ed);
+        let current_match = ed.search_index.and_then(|i| ed.search_positions.get(i));
+        let selection = Self::selection(ed);
+        let block = Self::block(ed);
+        let brackets = Self::matching_bracket(ed);
+        let mut row = start;
+        while row < ed.buffer.len_lines() && lines.len() < height {
             let mut text = ed.buffer.line(row).to_string();
             if text.ends_with('\n') { text.pop(); }
 
-            // Gouttière
-            let gutter = format!("{:>width$} │ ", row + 1, width = digits);
+            // Le séparateur de la gouttière porte le marqueur git de la ligne
+            let (mark, mark_color) = match ed.git.marks.get(row).copied().unwrap_or_default() {
+                LineChange::Unchanged => ("│", Color::Reset),
+                LineChange::Added => ("┃", Color::Green),
+                LineChange::Modified => ("┃", Color::Yellow),
+                LineChange::DeletedBelow => ("▁", Color::Red),
+                LineChange::DeletedAbove => ("▔", Color::Red),
+            };
+            let gutter = vec![
+                Span::raw(format!("{:>width$} ", row + 1, width = digits)),
+                Span::styled(mark, Style::default().fg(mark_color)),
+                Span::raw(" "),
+            ];
             let mut spans: Vec<Span> = Vec::new();
-            spans.push(Span::raw(gutter));
 
-            if !query.is_empty() {
-                // Surlignage naïf des occurrences (ASCII sûr; approximation pour UTF-8)
-                let mut last = 0usize;
-                let mut idx = 0usize;
-                while let Some(found) = text[last..].find(&query) {
-                    let s = last + found;
-                    let e = s + query.len();
-                    if s > last {
-                        spans.push(Span::raw(text[last..s].to_string()));
+            let row_start = ed.buffer.line_to_char(row);
+            let len = text.chars().count();
+            // Tabulations développées : les plages de caractères passent par leurs colonnes
+            let (mut shown, cols) = Self::display_line(&text, ed.indent.width);
+            // Caractères invisibles : une marque grisée à leur place
+            let mut marks: Vec<Range<usize>> = Vec::new();
+            if ed.show_invisibles {
+                let trailing = text.trim_end_matches([' ', '\t', '\u{a0}']).chars().count();
+                for (i, c) in text.chars().enumerate() {
+                    shown[cols[i]] = match c {
+                        '\t' => '→',
+                        '\u{a0}' => '⍽',
+                        ' ' if i >= trailing => '·',
+                        _ => continue,
+                    };
+                    match marks.last_mut() {
+                        Some(last) if last.end == cols[i] => last.end = cols[i + 1],
+                        _ => marks.push(cols[i]..cols[i + 1]),
                     }
-                    // Style du match courant si index correspond
-                    let is_current = ed.search_index
-                        .and_then(|i| ed.search_positions.get(i))
-                        .map(|(r, c)| *r == row && *c == idx)
-                        .unwrap_or(false);
-                    let style = if is_current { Style::default().fg(Color::Black).bg(Color::Yellow) } else { Style::default().fg(Color::Yellow) };
-                    spans.push(Span::styled(text[s..e].to_string(), style));
-                    last = e;
-                    idx += 1;
                 }
-                if last < text.len() {
-                    spans.push(Span::raw(text[last..].to_string()));
+            }
+            let slice = |r: Range<usize>| shown[cols[r.start]..cols[r.end]].iter().collect::<String>();
+            if let Some((_, cols_sel)) = block.as_ref().filter(|(rows, _)| rows.contains(&row)) {
+                // Sélection en bloc : les mêmes colonnes sur chaque ligne
+                let a = cols_sel.start.min(len);
+                let b = cols_sel.end.min(len);
+                let style = Style::default().bg(Color::Blue);
+                spans.push(Span::raw(slice(0..a)));
+                spans.push(Span::styled(if a == b { String::from(" ") } else { slice(a..b) }, style));
+                spans.push(Span::raw(slice(b..len)));
+            } else if let Some(sel) = selection.as_ref().filter(|s| s.start <= row_start + len && s.end > row_start) {
+                // Sélection (mode Visuel) : prend le pas sur le surlignage de recherche
+                let a = sel.start.saturating_sub(row_start).min(len);
+                let b = (sel.end - row_start).min(len);
+                let style = Style::default().bg(Color::Blue);
+                spans.push(Span::raw(slice(0..a)));
+                if a == b {
+                    spans.push(Span::styled(" ", style)); // ligne vide ou fin de ligne sélectionnée
+                } else {
+                    spans.push(Span::styled(slice(a..b), style));
                 }
+                spans.push(Span::raw(slice(b..len)));
+            } else if let Some(regex) = search.as_ref() {
+                // Occurrences de la recherche ; la courante sur fond jaune
+                let mut last = 0usize;
+                for m in Self::line_matches(regex, &text) {
+                    spans.push(Span::raw(slice(last..m.start)));
+                    let is_current = current_match.is_some_and(|(r, c)| *r == row && *c == m);
+                    let style = if is_current { Style::default().fg(Color::Black).bg(Color::Yellow) } else { Style::default().fg(Color::Yellow) };
+                    spans.push(Span::styled(slice(m.clone()), style));
+                    last = m.end;
+                }
+                spans.push(Span::raw(slice(last..len)));
             } else {
-                spans.push(Span::raw(text));
+                spans.push(Span::raw(shown.iter().collect::<String>()));
             }
 
-            lines.push(Line::from(spans));
+            for mark in marks {
+                spans = Self::patch_cols(spans, mark, Style::default().fg(Color::DarkGray));
+            }
+
+            // Champs de l'extrait en cours ; le courant sur fond gris
+            if let Some(snippet) = ed.snippet.as_ref() {
+                for (i, stop) in snippet.stops.iter().enumerate() {
+                    let a = stop.start.max(row_start);
+                    let b = stop.end.min(row_start + len);
+                    if a < b {
+                        let style = if i == snippet.current { Style::default().bg(Color::DarkGray) } else { Style::default().add_modifier(Modifier::UNDERLINED) };
+                        spans = Self::patch_cols(spans, cols[a - row_start]..cols[b - row_start], style);
+                    }
+                }
+            }
+
+            // Crochet sous le curseur et son correspondant
+            if let Some((a, b)) = brackets {
+                for idx in [a, b].into_iter().filter(|i| (row_start..row_start + len).contains(i)) {
+                    let c = idx - row_start;
+                    spans = Self::patch_cols(spans, cols[c]..cols[c + 1], Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+                }
+            }
+
+            // Région pliée : une ligne de résumé
+            if let Some(summary) = Self::fold_summary(ed, row) {
+                spans.push(Span::styled(summary, Style::default().fg(Color::DarkGray)));
+            }
+
+            // Blame en ligne de la ligne du curseur
+            if let Some(blame) = gutter::blame_text(ed, row).filter(|_| row == ed.cursor_row) {
+                spans.push(Span::styled(format!("    {}", blame), Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)));
+            }
+
+            // Gouttière ; les suites d'une ligne repliée n'ont pas de numéro
+            if ed.wrap {
+                for (i, part) in Self::wrap_spans(spans, text_w).into_iter().enumerate() {
+                    let mut row_spans = if i == 0 { gutter.clone() } else { vec![Span::raw(format!("{:>width$} │ ", "", width = digits))] };
+                    row_spans.extend(part);
+                    lines.push(Line::from(row_spans));
+                }
+            } else {
+                let mut row_spans = gutter;
+                row_spans.extend(spans);
+                lines.push(Line::from(row_spans));
+            }
+            row = Self::next_visible(ed, row);
         }
 
         let text_widget = Paragraph::new(lines).block(
@@ -154,29 +646,39 @@ impl EditorView {
             .as_ref()
             .map(|p| p.display().to_string())
             .unwrap_or_else(|| String::from("[No Name]"));
-        let status = format!(
+        let mut status = format!(
             " {}  |  row {}, col {}  {}",
             path_str,
             ed.cursor_row + 1,
             ed.cursor_col + 1,
             if ed.dirty { "[+]" } else { "" }
         );
+        status.push_str(&format!("  |  {}  {}", ed.encoding.label(), ed.line_ending.label()));
+        if ed.read_only {
+            status.push_str("  |  lecture seule");
+        }
+        if ed.wrap {
+            status.push_str("  |  wrap");
+        }
+        if let Some(summary) = Self::search_summary(ed) {
+            status.push_str(&format!("  |  {}", summary));
+        }
+        // Une seule ligne : pas de bordure, elle ne laisserait aucune place au texte
         let status_widget = Paragraph::new(Line::from(Span::styled(
             status,
             Style::default().fg(Color::LightBlue),
-        )))
-        .block(Block::default().borders(Borders::ALL).title("Status"));
+        )));
         f.render_widget(status_widget, chunks[1]);
 
         if matches!(ed.mode, EditorMode::Command) {
-            let cmd = Paragraph::new(Line::from(Span::raw(format!(":{}", ed.cmdline))))
-                .block(Block::default().borders(Borders::ALL).title("Command"));
+            let cmd = Paragraph::new(Line::from(Span::raw(format!(":{}", ed.cmdline))));
             f.render_widget(cmd, chunks[2]);
         }
 
         // ---- Curseur (décalé par la gouttière) ----
-        let cursor_x = (digits as u16) + 3 /* espace + '│' + espace */ + (ed.cursor_col as u16) + chunks[0].x + 1;
-        let cursor_y = (ed.cursor_row.saturating_sub(ed.scroll_row) as u16) + chunks[0].y + 1;
+        let (dy, dx) = Self::cursor_screen_pos(ed, start, text_w);
+        let cursor_x = (digits as u16) + 3 /* espace + '│' + espace */ + (dx as u16) + chunks[0].x + 1;
+        let cursor_y = (dy as u16) + chunks[0].y + 1;
         let position: Position = Position {
             x: cursor_x,
             y: cursor_y,
@@ -200,21 +702,76 @@ impl EditorView {
     pub fn move_up(ed: &mut EditorState) {
         if ed.cursor_row > 0 {
             ed.cursor_row -= 1;
+            // Région repliée au-dessus : s'arrêter sur sa ligne d'en-tête
+            if let Some((header, _)) = Self::fold_hiding(ed, ed.cursor_row) {
+                ed.cursor_row = header;
+            }
         }
         Self::clamp_col(ed);
-        if ed.cursor_row < ed.scroll_row {
-            ed.scroll_row = ed.cursor_row;
-        }
+        Self::scroll_to_cursor(ed);
     }
     pub fn move_down(ed: &mut EditorState) {
-        if ed.cursor_row + 1 < ed.buffer.len_lines() {
-            ed.cursor_row += 1;
+        let next = Self::next_visible(ed, ed.cursor_row);
+        if next < ed.buffer.len_lines() {
+            ed.cursor_row = next;
         }
         Self::clamp_col(ed);
-        let visible_h = 20; // approx; on pourrait passer la hauteur
-        if ed.cursor_row >= ed.scroll_row + visible_h {
-            ed.scroll_row = ed.cursor_row.saturating_sub(visible_h - 1);
+        Self::scroll_to_cursor(ed);
+    }
+
+    /// Row shown before `row`: the header of the fold hiding the row above.
+    fn prev_visible(ed: &EditorState, row: usize) -> usize {
+        let prev = row.saturating_sub(1);
+        Self::fold_hiding(ed, prev).map_or(prev, |(header, _)| header)
+    }
+
+    /// Record the text rows of the pane showing the buffer, then scroll it to the cursor.
+    pub fn set_viewport(ed: &mut EditorState, height: usize) {
+        ed.view_height = height.max(1);
+        Self::scroll_to_cursor(ed);
+    }
+
+    /// Scroll so that the cursor line stays `scrolloff` shown lines away from the top
+    /// and bottom of the viewport (less at the ends of the buffer, or in a pane too
+    /// short for it).
+    pub fn scroll_to_cursor(ed: &mut EditorState) {
+        let height = ed.view_height.max(1);
+        let margin = ed.scrolloff.min((height - 1) / 2);
+        let back = |mut row: usize, n: usize| {
+            for _ in 0..n {
+                row = Self::prev_visible(ed, row);
+            }
+            row
+        };
+        let highest = back(ed.cursor_row, margin);
+        let lowest = back(ed.cursor_row, height - 1 - margin);
+        ed.scroll_row = ed.scroll_row.clamp(lowest, highest);
+    }
+
+    /// PageUp / PageDown (`half`: Ctrl+U / Ctrl+D): scroll the viewport and the
+    /// cursor by a page (two lines kept in view) or half a page.
+    pub fn scroll_page(ed: &mut EditorState, up: bool, half: bool) {
+        let height = ed.view_height.max(1);
+        let amount = if half { (height / 2).max(1) } else { height.saturating_sub(2).max(1) };
+        let last = ed.buffer.len_lines().saturating_sub(1);
+        for _ in 0..amount {
+            if up {
+                if ed.cursor_row == 0 {
+                    break;
+                }
+                ed.cursor_row = Self::prev_visible(ed, ed.cursor_row);
+                ed.scroll_row = Self::prev_visible(ed, ed.scroll_row);
+            } else {
+                let next = Self::next_visible(ed, ed.cursor_row);
+                if next > last {
+                    break;
+                }
+                ed.cursor_row = next;
+                ed.scroll_row = Self::next_visible(ed, ed.scroll_row).min(last);
+            }
         }
+        Self::clamp_col(ed);
+        Self::scroll_to_cursor(ed);
     }
     fn clamp_col(ed: &mut EditorState) {
         let line_len = ed.buffer.line(ed.cursor_row).chars().count();
@@ -223,42 +780,516 @@ impl EditorView {
         }
     }
 
+    /// Indentation width of a row (tabs count `tab_width`), None for a blank line.
+    fn indent_of(ed: &EditorState, row: usize) -> Option<usize> {
+        let text = Self::line_string(ed, row);
+        if text.trim().is_empty() {
+            return None;
+        }
+        Some(text.chars().take_while(|c| *c == ' ' || *c == '\t').map(|c| if c == '\t' { ed.indent.width } else { 1 }).sum())
+    }
+
+    /// Region headed by `row`: the lines after it indented deeper (blank lines
+    /// between them included), as (header, last row).
+    fn region_at(ed: &EditorState, row: usize) -> Option<(usize, usize)> {
+        let base = Self::indent_of(ed, row)?;
+        let mut last = row;
+        for next in row + 1..ed.buffer.len_lines() {
+            match Self::indent_of(ed, next) {
+                None => continue,
+                Some(indent) if indent > base => last = next,
+                Some(_) => break,
+            }
+        }
+        (last > row).then_some((row, last))
+    }
+
+    /// Region to fold for `row`: the one it heads, else the closest one around it.
+    fn fold_region(ed: &EditorState, row: usize) -> Option<(usize, usize)> {
+        if let Some(region) = Self::region_at(ed, row) {
+            return Some(region);
+        }
+        let indent = Self::indent_of(ed, row).unwrap_or(usize::MAX);
+        let header = (0..row).rev().find(|r| Self::indent_of(ed, *r).is_some_and(|i| i < indent))?;
+        Self::region_at(ed, header).filter(|(_, last)| *last >= row)
+    }
+
+    /// Summary shown after the header line of a folded region.
+    fn fold_summary(ed: &EditorState, row: usize) -> Option<String> {
+        let (header, last) = ed.folds.iter().find(|(header, _)| *header == row)?;
+        let hidden = last - header;
+        Some(format!("  ⋯ {} ligne{}", hidden, if hidden > 1 { "s" } else { "" }))
+    }
+
+    /// Fold hiding `row` (not its header line).
+    fn fold_hiding(ed: &EditorState, row: usize) -> Option<(usize, usize)> {
+        ed.folds.iter().copied().find(|(header, last)| *header < row && row <= *last)
+    }
+
+    /// Row shown after `row`: the one after its fold when it heads one.
+    fn next_visible(ed: &EditorState, row: usize) -> usize {
+        match ed.folds.iter().find(|(header, _)| *header == row) {
+            Some((_, last)) => last + 1,
+            None => row + 1,
+        }
+    }
+
+    /// `zc`: fold the region under the cursor (the folds inside it merge into it).
+    pub fn fold(ed: &mut EditorState) {
+        let Some((header, last)) = Self::fold_region(ed, ed.cursor_row) else { return; };
+        ed.folds.retain(|(h, l)| *l < header || *h > last);
+        ed.folds.push((header, last));
+        ed.folds.sort_unstable();
+        ed.cursor_row = header;
+        Self::clamp_col(ed);
+        ed.scroll_row = ed.scroll_row.min(header);
+    }
+
+    /// `zo`: open the fold headed by the cursor line.
+    pub fn unfold(ed: &mut EditorState) {
+        let row = ed.cursor_row;
+        ed.folds.retain(|(header, _)| *header != row);
+    }
+
+    /// `za`: open the fold under the cursor, or fold its region.
+    pub fn toggle_fold(ed: &mut EditorState) {
+        if ed.folds.iter().any(|(header, _)| *header == ed.cursor_row) {
+            Self::unfold(ed);
+        } else {
+            Self::fold(ed);
+        }
+    }
+
+    /// `zM`: fold every outermost region.
+    pub fn fold_all(ed: &mut EditorState) {
+        ed.folds.clear();
+        let mut row = 0;
+        while row < ed.buffer.len_lines() {
+            match Self::region_at(ed, row) {
+                Some((header, last)) => {
+                    ed.folds.push((header, last));
+                    row = last + 1;
+                }
+                None => row += 1,
+            }
+        }
+        // Le curseur remonte sur l'en-tête de la région qui le cache
+        if let Some((header, _)) = Self::fold_hiding(ed, ed.cursor_row) {
+            ed.cursor_row = header;
+            Self::clamp_col(ed);
+        }
+    }
+
+    /// `zR`: open every fold.
+    pub fn unfold_all(ed: &mut EditorState) {
+        ed.folds.clear();
+    }
+
+    /// Open the folds hiding the cursor row, after a jump into one (search, go to line).
+    pub fn open_folds_at_cursor(ed: &mut EditorState) {
+        let row = ed.cursor_row;
+        ed.folds.retain(|(header, last)| !(*header < row && row <= *last));
+    }
+
+    /// Bracket at the cursor (or just before it, where typing leaves the cursor) and
+    /// its match, as char indexes.
+    pub fn matching_bracket(ed: &EditorState) -> Option<(usize, usize)> {
+        let at = Self::cursor_to_char_idx(ed);
+        [Some(at), at.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .find_map(|idx| bracket_match(&ed.buffer, idx).map(|other| (idx, other)))
+    }
+
+    /// `%`: jump to the bracket matching the one under the cursor, or the first one
+    /// after it on the line.
+    pub fn jump_to_match(ed: &mut EditorState) {
+        let at = Self::cursor_to_char_idx(ed);
+        let line_end = ed.buffer.line_to_char(ed.cursor_row) + Self::line_string(ed, ed.cursor_row).chars().count();
+        if let Some(other) = (at..line_end).find_map(|idx| bracket_match(&ed.buffer, idx)) {
+            Self::set_cursor_char(ed, other);
+        }
+    }
+
+    /// Auto-pairing of a typed character: step over the same closing character under
+    /// the cursor, or insert an opening bracket or quote with its closing one. False
+    /// when `c` is inserted the usual way.
+    fn type_pair(ed: &mut EditorState, c: char) -> bool {
+        let at = Self::cursor_to_char_idx(ed);
+        let next = ed.buffer.get_char(at).filter(|n| *n != '\n');
+        let prev = at.checked_sub(1).and_then(|i| ed.buffer.get_char(i));
+        let closes = |ch: char| BRACKETS.iter().any(|(_, close)| *close == ch) || ch == '"' || ch == '\'';
+        if next == Some(c) && closes(c) {
+            Self::set_cursor_char(ed, at + 1);
+            if let Some(run) = ed.typing.as_mut() {
+                run.at = (ed.cursor_row, ed.cursor_col);
+            }
+            return true;
+        }
+        // Pas de paire devant un mot, ni pour une apostrophe dans un mot
+        let free_after = next.is_none_or(|n| n.is_whitespace() || closes(n));
+        let close = match c {
+            '"' | '\'' if free_after && !prev.is_some_and(char::is_alphanumeric) => c,
+            _ => match BRACKETS.iter().find(|(open, _)| *open == c) {
+                Some((_, close)) if free_after => *close,
+                _ => return false,
+            },
+        };
+        if Self::begin_typing(ed, false, false) {
+            ed.insert_at(at, &format!("{}{}", c, close));
+            ed.cursor_col += 1;
+            Self::end_typing(ed, false, false);
+            ed.dirty = true;
+            ed.search_positions.clear();
+            ed.search_index = None;
+        }
+        true
+    }
+
     // Edition (INSERT)
     pub fn insert_char(ed: &mut EditorState, c: char) {
-        ed.push_undo();
+        Self::take_placeholder(ed);
+        if ed.auto_pairs && Self::type_pair(ed, c) {
+            return;
+        }
+        if !Self::begin_typing(ed, false, c.is_whitespace()) {
+            return;
+        }
         let char_idx = Self::cursor_to_char_idx(ed);
-        ed.buffer.insert_char(char_idx, c);
+        ed.insert_at(char_idx, c.encode_utf8(&mut [0; 4]));
         ed.cursor_col += 1;
+        Self::end_typing(ed, false, c.is_whitespace());
         ed.dirty = true;
         ed.search_positions.clear();
         ed.search_index = None;
     }
     pub fn backspace(ed: &mut EditorState) {
-        ed.push_undo();
+        if Self::take_placeholder(ed) {
+            return;
+        }
         let char_idx = Self::cursor_to_char_idx(ed);
         if char_idx > 0 {
-            ed.buffer.remove(char_idx - 1..char_idx);
-            if ed.cursor_col > 0 {
-                ed.cursor_col -= 1;
-            } else if ed.cursor_row > 0 {
-                // si on supprime le \n précédent, recaler
-                ed.cursor_row -= 1;
-                ed.cursor_col = ed.buffer.line(ed.cursor_row).chars().count();
+            if !Self::begin_typing(ed, true, false) {
+                return;
             }
+            ed.remove_range(char_idx - 1..char_idx);
+            // Après un \n supprimé, le curseur reste au point de jonction
+            Self::set_cursor_char(ed, char_idx - 1);
+            Self::end_typing(ed, true, false);
             ed.dirty = true;
             ed.search_positions.clear();
             ed.search_index = None;
         }
     }
+    /// Split the line at the cursor; the new line keeps the leading whitespace
+    /// (up to the cursor) of the one it comes from.
     pub fn insert_newline(ed: &mut EditorState) {
-        ed.push_undo();
+        if !Self::begin_edit(ed) {
+            return;
+        }
         let char_idx = Self::cursor_to_char_idx(ed);
-        ed.buffer.insert(char_idx, "\n");
+        let indent: String = Self::line_string(ed, ed.cursor_row)
+            .chars()
+            .take(ed.cursor_col)
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+        ed.insert_at(char_idx, &format!("\n{}", indent));
         ed.cursor_row += 1;
-        ed.cursor_col = 0;
+        ed.cursor_col = indent.chars().count();
+        ed.dirty = true;
+        ed.search_positions.clear();
+        ed.search_index = None;
+    }
+
+    /// Tab: spaces up to the next tab stop, or a tab character, per the buffer's indentation.
+    pub fn insert_indent(ed: &mut EditorState) {
+        let text = if ed.indent.expand_tabs {
+            let (_, cols) = Self::display_line(&Self::line_string(ed, ed.cursor_row), ed.indent.width);
+            let col = cols.get(ed.cursor_col).copied().unwrap_or(0);
+            " ".repeat(ed.indent.width - col % ed.indent.width)
+        } else {
+            ed.indent.unit()
+        };
+        Self::insert_text(ed, &text);
+    }
+
+    /// Tab in a snippet or after a snippet trigger: go to the next tab stop, or
+    /// expand the snippet. False when Tab should indent.
+    pub fn snippet_tab(ed: &mut EditorState) -> bool {
+        let at = Self::cursor_to_char_idx(ed);
+        if let Some(snippet) = ed.snippet.as_ref() {
+            let inside = snippet.stops.iter().any(|s| s.start <= at && at <= s.end);
+            if inside {
+                let next = snippet.current + 1;
+                Self::enter_stop(ed, next);
+                return true;
+            }
+            ed.snippet = None;
+        }
+        let line = Self::line_string(ed, ed.cursor_row);
+        let before: String = line.chars().take(ed.cursor_col).collect();
+        let Some(found) = snippets::find(&ed.snippets, &before) else { return false; };
+        let indent: String = line.chars().take_while(|c| *c == ' ' || *c == '\t').collect();
+        let expansion = snippets::expand(&found.body, &indent, &ed.indent.unit());
+        let trigger = found.trigger.chars().count();
+        if !Self::begin_edit(ed) {
+            return true;
+        }
+        let start = at - trigger;
+        ed.remove_range(start..at);
+        ed.insert_at(start, &expansion.text);
         ed.dirty = true;
         ed.search_positions.clear();
         ed.search_index = None;
+        let stops = expansion.stops.into_iter().map(|s| start + s.start..start + s.end).collect();
+        ed.snippet = Some(SnippetSession { stops, current: 0, fresh: false });
+        Self::enter_stop(ed, 0);
+        true
+    }
+
+    /// Shift+Tab in a snippet: back to the previous tab stop. False outside a snippet.
+    pub fn snippet_back(ed: &mut EditorState) -> bool {
+        let Some(current) = ed.snippet.as_ref().map(|s| s.current) else { return false; };
+        Self::enter_stop(ed, current.saturating_sub(1));
+        true
+    }
+
+    /// Put the cursor on tab stop `index` of the snippet, its placeholder ready to be
+    /// replaced; the last stop ends the snippet.
+    fn enter_stop(ed: &mut EditorState, index: usize) {
+        let Some(snippet) = ed.snippet.as_mut() else { return; };
+        let index = index.min(snippet.stops.len() - 1);
+        let stop = snippet.stops[index].clone();
+        snippet.current = index;
+        snippet.fresh = !stop.is_empty();
+        if index + 1 == snippet.stops.len() {
+            ed.snippet = None;
+        }
+        Self::set_cursor_char(ed, stop.start);
+        ed.typing = None;
+    }
+
+    /// Remove the placeholder under the cursor before the first char typed on its
+    /// stop (its own undo step); true when one was removed.
+    fn take_placeholder(ed: &mut EditorState) -> bool {
+        let at = Self::cursor_to_char_idx(ed);
+        let Some(snippet) = ed.snippet.as_mut().filter(|s| s.fresh) else { return false; };
+        snippet.fresh = false;
+        let stop = snippet.stops[snippet.current].clone();
+        if stop.start != at || stop.is_empty() || !Self::begin_edit(ed) {
+            return false;
+        }
+        ed.remove_range(stop);
+        ed.dirty = true;
+        true
+    }
+
+    /// `>` / `<` (Shift+Tab): shift the selected lines, or the cursor line, by one
+    /// indentation level. Blank lines are not indented.
+    pub fn shift_lines(ed: &mut EditorState, dedent: bool) {
+        let (first, last) = match ed.mode {
+            EditorMode::Visual | EditorMode::VisualLine | EditorMode::VisualBlock => (ed.visual_anchor.0.min(ed.cursor_row), ed.visual_anchor.0.max(ed.cursor_row)),
+            _ => (ed.cursor_row, ed.cursor_row),
+        };
+        let unit = ed.indent.unit();
+        let mut changes: Vec<(usize, isize)> = Vec::new();
+        for row in first..=last.min(ed.buffer.len_lines().saturating_sub(1)) {
+            let text = Self::line_string(ed, row);
+            let delta = if dedent {
+                // Une tabulation, ou jusqu'à `width` espaces
+                match text.chars().next() {
+                    Some('\t') => -1,
+                    _ => -(text.chars().take(ed.indent.width).take_while(|c| *c == ' ').count() as isize),
+                }
+            } else if text.trim().is_empty() {
+                0
+            } else {
+                unit.chars().count() as isize
+            };
+            if delta != 0 {
+                changes.push((row, delta));
+            }
+        }
+        if changes.is_empty() {
+            return;
+        }
+        if !Self::begin_edit(ed) {
+            return;
+        }
+        for (row, delta) in &changes {
+            let at = ed.buffer.line_to_char(*row);
+            if *delta > 0 {
+                ed.insert_at(at, &unit);
+            } else {
+                ed.remove_range(at..at + delta.unsigned_abs());
+            }
+        }
+        // Le curseur et l'ancre suivent le texte de leur ligne
+        let shifted = |row: usize, col: usize| match changes.iter().find(|(r, _)| *r == row) {
+            Some((_, delta)) => col.saturating_add_signed(*delta),
+            None => col,
+        };
+        ed.cursor_col = shifted(ed.cursor_row, ed.cursor_col);
+        ed.visual_anchor.1 = shifted(ed.visual_anchor.0, ed.visual_anchor.1);
+        Self::clamp_col(ed);
+        ed.dirty = true;
+        ed.search_positions.clear();
+        ed.search_index = None;
+    }
+
+    /// Alt+c / `gc`: comment out the selected lines, or the cursor line, with the
+    /// markers of the file type, or uncomment them when they all are commented.
+    /// Blank lines are left alone; the change is one undo step and leaves the Visual
+    /// mode. False when the file type has no comment syntax.
+    pub fn toggle_comment(ed: &mut EditorState) -> bool {
+        let Some(syntax) = ed.comment.clone() else { return false; };
+        let (first, last) = match ed.mode {
+            EditorMode::Visual | EditorMode::VisualLine | EditorMode::VisualBlock => (ed.visual_anchor.0.min(ed.cursor_row), ed.visual_anchor.0.max(ed.cursor_row)),
+            _ => (ed.cursor_row, ed.cursor_row),
+        };
+        ed.mode = EditorMode::Normal;
+        let rows: Vec<(usize, String)> = (first..=last.min(ed.buffer.len_lines().saturating_sub(1)))
+            .map(|row| (row, Self::line_string(ed, row)))
+            .filter(|(_, text)| !text.trim().is_empty())
+            .collect();
+        if rows.is_empty() || !Self::begin_edit(ed) {
+            return true;
+        }
+        let leading = |text: &str| text.chars().take_while(|c| c.is_whitespace()).count();
+        let start_len = syntax.start.chars().count();
+        let end_len = syntax.end.as_deref().map_or(0, |e| e.chars().count());
+        let commented = |text: &str| {
+            let t = text.trim();
+            t.starts_with(&syntax.start) && syntax.end.as_deref().is_none_or(|e| t.chars().count() >= start_len + end_len && t.ends_with(e))
+        };
+        let uncomment = rows.iter().all(|(_, text)| commented(text));
+        let indent = rows.iter().map(|(_, text)| leading(text)).min().unwrap_or(0);
+        // (ligne, colonne du marqueur, caractères ajoutés (> 0) ou retirés (< 0) devant le texte)
+        let mut changes: Vec<(usize, usize, isize)> = Vec::new();
+        for (row, text) in &rows {
+            let at = ed.buffer.line_to_char(*row);
+            let lead = leading(text);
+            let chars: Vec<char> = text.chars().collect();
+            if uncomment {
+                // La fin d'abord : les positions du début restent valables
+                if end_len > 0 {
+                    let stop = chars.len() - chars.iter().rev().take_while(|c| c.is_whitespace()).count();
+                    let mut from = stop - end_len;
+                    if from > lead + start_len && chars[from - 1] == ' ' {
+                        from -= 1;
+                    }
+                    ed.remove_range(at + from..at + stop);
+                }
+                let len = if chars.get(lead + start_len) == Some(&' ') { start_len + 1 } else { start_len };
+                ed.remove_range(at + lead..at + lead + len);
+                changes.push((*row, lead, -(len as isize)));
+            } else {
+                if let Some(end) = syntax.end.as_deref() {
+                    ed.insert_at(at + chars.len(), &format!(" {}", end));
+                }
+                let prefix = format!("{} ", syntax.start);
+                ed.insert_at(at + indent, &prefix);
+                changes.push((*row, indent, prefix.chars().count() as isize));
+            }
+        }
+        // Le curseur et l'ancre suivent le texte de leur ligne
+        let shifted = |row: usize, col: usize| match changes.iter().find(|(r, _, _)| *r == row) {
+            Some((_, at, delta)) if col >= *at => col.saturating_add_signed(*delta).max(*at),
+            _ => col,
+        };
+        ed.cursor_col = shifted(ed.cursor_row, ed.cursor_col);
+        ed.visual_anchor.1 = shifted(ed.visual_anchor.0, ed.visual_anchor.1);
+        Self::clamp_col(ed);
+        ed.dirty = true;
+        ed.search_positions.clear();
+        ed.search_index = None;
+        true
+    }
+
+    /// Screen row (from the first displayed line `start`) and column of the cursor,
+    /// counting the wrapped rows of the lines above it when soft wrap is on.
+    fn cursor_screen_pos(ed: &EditorState, start: usize, text_w: usize) -> (usize, usize) {
+        let (_, cols) = Self::display_line(&Self::line_string(ed, ed.cursor_row), ed.indent.width);
+        let col = cols.get(ed.cursor_col).or(cols.last()).copied().unwrap_or(0);
+        let shown_rows = std::iter::successors(Some(start), |row| Some(Self::next_visible(ed, *row))).take_while(|row| *row < ed.cursor_row);
+        if !ed.wrap {
+            return (shown_rows.count(), col);
+        }
+        let rows_of = |row: usize| {
+            let (shown, _) = Self::display_line(&Self::line_string(ed, row), ed.indent.width);
+            let summary = Self::fold_summary(ed, row).map_or(0, |s| s.chars().count());
+            (shown.len() + summary).div_ceil(text_w).max(1)
+        };
+        let above: usize = shown_rows.map(rows_of).sum();
+        // Fin d'une ligne qui remplit exactement ses rangées : rester sur la dernière
+        let sub = (col / text_w).min(rows_of(ed.cursor_row) - 1);
+        (above + sub, col - sub * text_w)
+    }
+
+    /// Split a line's spans into rows of `width` columns (soft wrap), keeping their styles.
+    fn wrap_spans(spans: Vec<Span<'static>>, width: usize) -> Vec<Vec<Span<'static>>> {
+        let mut rows: Vec<Vec<Span>> = vec![Vec::new()];
+        let mut used = 0;
+        for span in spans {
+            let mut chunk = String::new();
+            for c in span.content.chars() {
+                if used == width {
+                    let row = rows.last_mut().expect("never empty");
+                    if !chunk.is_empty() {
+                        row.push(Span::styled(std::mem::take(&mut chunk), span.style));
+                    }
+                    rows.push(Vec::new());
+                    used = 0;
+                }
+                chunk.push(c);
+                used += 1;
+            }
+            if !chunk.is_empty() {
+                rows.last_mut().expect("never empty").push(Span::styled(chunk, span.style));
+            }
+        }
+        rows
+    }
+
+    /// Patch `style` onto the display columns `range` of a line's spans.
+    fn patch_cols(spans: Vec<Span<'static>>, range: Range<usize>, style: Style) -> Vec<Span<'static>> {
+        let mut out = Vec::with_capacity(spans.len() + 2);
+        let mut col = 0;
+        for span in spans {
+            let len = span.content.chars().count();
+            let (a, b) = (range.start.clamp(col, col + len) - col, range.end.clamp(col, col + len) - col);
+            if a == b {
+                out.push(span);
+            } else {
+                let chars: Vec<char> = span.content.chars().collect();
+                for (part, patched) in [(0..a, false), (a..b, true), (b..len, false)] {
+                    if !part.is_empty() {
+                        let text: String = chars[part].iter().collect();
+                        out.push(Span::styled(text, if patched { span.style.patch(style) } else { span.style }));
+                    }
+                }
+            }
+            col += len;
+        }
+        out
+    }
+
+    /// Characters of a line as displayed (tab characters expanded to the next tab
+    /// stop), with the display column of each char index and of the line end.
+    fn display_line(text: &str, tab_width: usize) -> (Vec<char>, Vec<usize>) {
+        let mut shown = Vec::with_capacity(text.len());
+        let mut cols = Vec::with_capacity(text.len() + 1);
+        for c in text.chars() {
+            cols.push(shown.len());
+            if c == '\t' {
+                let stop = tab_width.max(1);
+                shown.extend(std::iter::repeat_n(' ', stop - shown.len() % stop));
+            } else {
+                shown.push(c);
+            }
+        }
+        cols.push(shown.len());
+        (shown, cols)
     }
 
     fn cursor_to_char_idx(ed: &EditorState) -> usize {
@@ -266,100 +1297,625 @@ impl EditorView {
         line_start + ed.cursor_col
     }
 
+    /// Move the cursor to a char index of the buffer.
+    pub fn set_cursor_char(ed: &mut EditorState, idx: usize) {
+        let idx = idx.min(ed.buffer.len_chars());
+        ed.cursor_row = ed.buffer.char_to_line(idx);
+        ed.cursor_col = idx - ed.buffer.line_to_char(ed.cursor_row);
+        Self::scroll_to_cursor(ed);
+    }
+
+    /// Text of a line without its line break.
+    fn line_string(ed: &EditorState, row: usize) -> String {
+        let mut text = ed.buffer.line(row).to_string();
+        if text.ends_with('\n') { text.pop(); }
+        if text.ends_with('\r') { text.pop(); }
+        text
+    }
+
+    /// Non-blank segments of a line between Unicode word boundaries (words, and
+    /// punctuation marks one by one), as char ranges.
+    fn word_segments(text: &str) -> Vec<Range<usize>> {
+        let mut segments = Vec::new();
+        let mut col = 0;
+        for segment in text.split_word_bounds() {
+            let len = segment.chars().count();
+            if !segment.chars().all(char::is_whitespace) {
+                segments.push(col..col + len);
+            }
+            col += len;
+        }
+        segments
+    }
+
+    /// Identifier under the cursor (go to definition).
+    pub fn word_at_cursor(ed: &EditorState) -> Option<String> {
+        let text = Self::line_string(ed, ed.cursor_row);
+        let segment = Self::word_segments(&text).into_iter().find(|s| s.contains(&ed.cursor_col))?;
+        let word: String = text.chars().skip(segment.start).take(segment.len()).collect();
+        word.chars().next().is_some_and(|c| c.is_alphanumeric() || c == '_').then_some(word)
+    }
+
+    /// Start of the next word; an empty line counts as one.
+    fn next_word_start(ed: &EditorState) -> (usize, usize) {
+        let (row, col) = (ed.cursor_row, ed.cursor_col);
+        let text = Self::line_string(ed, row);
+        if let Some(s) = Self::word_segments(&text).into_iter().find(|s| s.start > col) {
+            return (row, s.start);
+        }
+        if row + 1 < ed.buffer.len_lines() {
+            let next = Self::word_segments(&Self::line_string(ed, row + 1));
+            return (row + 1, next.first().map_or(0, |s| s.start));
+        }
+        (row, text.chars().count())
+    }
+
+    /// Start of the current word, or of the previous one.
+    fn prev_word_start(ed: &EditorState) -> (usize, usize) {
+        let (row, col) = (ed.cursor_row, ed.cursor_col);
+        if let Some(s) = Self::word_segments(&Self::line_string(ed, row)).into_iter().rev().find(|s| s.start < col) {
+            return (row, s.start);
+        }
+        if row == 0 {
+            return (0, 0);
+        }
+        let prev = Self::word_segments(&Self::line_string(ed, row - 1));
+        (row - 1, prev.last().map_or(0, |s| s.start))
+    }
+
+    /// Last char of the current word, or of the next one (skipping empty lines).
+    fn next_word_end(ed: &EditorState) -> (usize, usize) {
+        let (row, col) = (ed.cursor_row, ed.cursor_col);
+        if let Some(s) = Self::word_segments(&Self::line_string(ed, row)).into_iter().find(|s| s.end - 1 > col) {
+            return (row, s.end - 1);
+        }
+        for next in row + 1..ed.buffer.len_lines() {
+            if let Some(s) = Self::word_segments(&Self::line_string(ed, next)).first() {
+                return (next, s.end - 1);
+            }
+        }
+        (row, col)
+    }
+
+    fn move_to(ed: &mut EditorState, (row, col): (usize, usize)) {
+        let at = ed.buffer.line_to_char(row) + col;
+        Self::set_cursor_char(ed, at);
+    }
+
+    /// `w` / Ctrl+→: to the start of the next word.
+    pub fn word_forward(ed: &mut EditorState) {
+        let target = Self::next_word_start(ed);
+        Self::move_to(ed, target);
+    }
+
+    /// `b` / Ctrl+←: to the start of the current or previous word.
+    pub fn word_back(ed: &mut EditorState) {
+        let target = Self::prev_word_start(ed);
+        Self::move_to(ed, target);
+    }
+
+    /// `e`: to the end of the current or next word.
+    pub fn word_end(ed: &mut EditorState) {
+        let target = Self::next_word_end(ed);
+        Self::move_to(ed, target);
+    }
+
+    /// Ctrl+Backspace: delete back to the start of the word (the line break at column 0).
+    pub fn delete_word_back(ed: &mut EditorState) {
+        if ed.cursor_col == 0 {
+            Self::backspace(ed);
+            return;
+        }
+        let text = Self::line_string(ed, ed.cursor_row);
+        let col = ed.cursor_col;
+        let target = Self::word_segments(&text).into_iter().rev().find(|s| s.start < col).map_or(0, |s| s.start);
+        if !Self::begin_edit(ed) {
+            return;
+        }
+        let line_start = ed.buffer.line_to_char(ed.cursor_row);
+        ed.remove_range(line_start + target..line_start + col);
+        ed.cursor_col = target;
+        ed.dirty = true;
+        ed.search_positions.clear();
+        ed.search_index = None;
+    }
+
+    /// Ctrl+Delete: delete up to the next word on the line (the line break at its end).
+    pub fn delete_word_forward(ed: &mut EditorState) {
+        let text = Self::line_string(ed, ed.cursor_row);
+        let (col, len) = (ed.cursor_col, text.chars().count());
+        let line_start = ed.buffer.line_to_char(ed.cursor_row);
+        let end = if col >= len {
+            // Fin de ligne : joindre la suivante
+            let line_end = line_start + ed.buffer.line(ed.cursor_row).len_chars();
+            if line_end == line_start + col { return; }
+            line_end
+        } else {
+            line_start + Self::word_segments(&text).into_iter().find(|s| s.start > col).map_or(len, |s| s.start)
+        };
+        if !Self::begin_edit(ed) {
+            return;
+        }
+        ed.remove_range(line_start + col..end);
+        ed.dirty = true;
+        ed.search_positions.clear();
+        ed.search_index = None;
+    }
+
+    /// `dd`: cut the cursor line into a register.
+    pub fn delete_line(ed: &mut EditorState) -> Option<EditorRegister> {
+        Self::start_visual(ed, EditorMode::VisualLine);
+        Self::delete_selection(ed)
+    }
+
+    /// `yy`: copy the cursor line into a register; the cursor stays.
+    pub fn yank_line(ed: &mut EditorState) -> Option<EditorRegister> {
+        let (row, col) = (ed.cursor_row, ed.cursor_col);
+        Self::start_visual(ed, EditorMode::VisualLine);
+        let register = Self::yank_selection(ed);
+        (ed.cursor_row, ed.cursor_col) = (row, col);
+        register
+    }
+
+    /// `J`: join the next line to the cursor line, its indentation replaced by one space.
+    pub fn join_lines(ed: &mut EditorState) {
+        let row = ed.cursor_row;
+        // La ligne vide après le dernier saut n'est pas une vraie ligne
+        if row + 1 >= ed.buffer.len_lines() || (row + 2 == ed.buffer.len_lines() && ed.buffer.line(row + 1).len_chars() == 0) {
+            return;
+        }
+        let current = Self::line_string(ed, row);
+        let next = Self::line_string(ed, row + 1);
+        let indent = next.chars().take_while(|c| c.is_whitespace()).count();
+        let join_at = ed.buffer.line_to_char(row) + current.chars().count();
+        let next_start = ed.buffer.line_to_char(row + 1);
+        let separator = if current.trim().is_empty() || next.trim().is_empty() || current.ends_with(char::is_whitespace) { "" } else { " " };
+        if !Self::begin_edit(ed) {
+            return;
+        }
+        ed.remove_range(join_at..next_start + indent);
+        ed.insert_at(join_at, separator);
+        ed.cursor_col = current.chars().count();
+        ed.dirty = true;
+        ed.search_positions.clear();
+        ed.search_index = None;
+    }
+
+    /// Alt+↑/↓: move the cursor line, or every line of the selection, one line up / down.
+    pub fn move_lines(ed: &mut EditorState, up: bool) {
+        let (first, last) = match ed.mode {
+            EditorMode::Visual | EditorMode::VisualLine | EditorMode::VisualBlock => (ed.visual_anchor.0.min(ed.cursor_row), ed.visual_anchor.0.max(ed.cursor_row)),
+            _ => (ed.cursor_row, ed.cursor_row),
+        };
+        let mut last_row = ed.buffer.len_lines().saturating_sub(1);
+        if last_row > 0 && ed.buffer.line(last_row).len_chars() == 0 {
+            last_row -= 1;
+        }
+        let (from, to) = match up {
+            true if first > 0 && last <= last_row => (first - 1, last),
+            false if last < last_row => (first, last + 1),
+            _ => return,
+        };
+        let start = ed.buffer.line_to_char(from);
+        let end = ed.buffer.line_to_char(to + 1);
+        let mut region = ed.buffer.slice(start..end).to_string();
+        let added_break = !region.ends_with('\n');
+        if added_break {
+            region.push('\n');
+        }
+        let mut lines: Vec<&str> = region.split_inclusive('\n').collect();
+        if up { lines.rotate_left(1) } else { lines.rotate_right(1) }
+        let mut moved = lines.concat();
+        if added_break {
+            moved.pop();
+        }
+        if !Self::begin_edit(ed) {
+            return;
+        }
+        ed.remove_range(start..end);
+        ed.insert_at(start, &moved);
+        if up {
+            ed.cursor_row -= 1;
+            ed.visual_anchor.0 = ed.visual_anchor.0.saturating_sub(1);
+        } else {
+            ed.cursor_row += 1;
+            ed.visual_anchor.0 += 1;
+        }
+        Self::clamp_col(ed);
+        let at = Self::cursor_to_char_idx(ed);
+        Self::set_cursor_char(ed, at);
+        ed.dirty = true;
+        ed.search_positions.clear();
+        ed.search_index = None;
+    }
+
+    /// Enter `mode` (Visual or VisualLine) with the selection anchored at the cursor.
+    pub fn start_visual(ed: &mut EditorState, mode: EditorMode) {
+        ed.visual_anchor = (ed.cursor_row, ed.cursor_col);
+        ed.mode = mode;
+    }
+
+    /// Selected chars (end exclusive) in a Visual mode; both ends are included
+    /// character-wise, whole lines line-wise.
+    pub fn selection(ed: &EditorState) -> Option<Range<usize>> {
+        let last_row = ed.buffer.len_lines().saturating_sub(1);
+        let (anchor_row, anchor_col) = (ed.visual_anchor.0.min(last_row), ed.visual_anchor.1);
+        match ed.mode {
+            EditorMode::Visual => {
+                let len = ed.buffer.len_chars();
+                let anchor = (ed.buffer.line_to_char(anchor_row) + anchor_col).min(len);
+                let cursor = Self::cursor_to_char_idx(ed).min(len);
+                Some(anchor.min(cursor)..(anchor.max(cursor) + 1).min(len))
+            }
+            EditorMode::VisualLine => {
+                let (first, last) = (anchor_row.min(ed.cursor_row), anchor_row.max(ed.cursor_row));
+                Some(ed.buffer.line_to_char(first)..ed.buffer.line_to_char(last + 1))
+            }
+            _ => None,
+        }
+    }
+
+    /// Rows (inclusive range) and char columns (end exclusive) of the block selection.
+    pub fn block(ed: &EditorState) -> Option<(std::ops::RangeInclusive<usize>, Range<usize>)> {
+        if ed.mode != EditorMode::VisualBlock {
+            return None;
+        }
+        let last_row = ed.buffer.len_lines().saturating_sub(1);
+        let (anchor_row, anchor_col) = (ed.visual_anchor.0.min(last_row), ed.visual_anchor.1);
+        let rows = anchor_row.min(ed.cursor_row)..=anchor_row.max(ed.cursor_row);
+        Some((rows, anchor_col.min(ed.cursor_col)..anchor_col.max(ed.cursor_col) + 1))
+    }
+
+    /// Char ranges of the block on each of its rows (empty past a short line's end).
+    fn block_ranges(ed: &EditorState) -> Vec<Range<usize>> {
+        let Some((rows, cols)) = Self::block(ed) else { return Vec::new(); };
+        rows.map(|row| {
+            let start = ed.buffer.line_to_char(row);
+            let len = Self::line_string(ed, row).chars().count();
+            start + cols.start.min(len)..start + cols.end.min(len)
+        })
+        .collect()
+    }
+
+    /// Copy the selection to a register and leave the Visual mode, the cursor on
+    /// the selection start.
+    pub fn yank_selection(ed: &mut EditorState) -> Option<EditorRegister> {
+        if let Some((rows, cols)) = Self::block(ed) {
+            let pieces: Vec<String> = Self::block_ranges(ed).into_iter().map(|r| ed.buffer.slice(r).to_string()).collect();
+            ed.mode = EditorMode::Normal;
+            ed.cursor_row = *rows.start();
+            ed.cursor_col = cols.start;
+            Self::clamp_col(ed);
+            return Some(EditorRegister { text: pieces.join("\n"), linewise: false, block: true });
+        }
+        let range = Self::selection(ed)?;
+        let linewise = ed.mode == EditorMode::VisualLine;
+        let mut text = ed.buffer.slice(range.clone()).to_string();
+        if linewise && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        ed.mode = EditorMode::Normal;
+        Self::set_cursor_char(ed, range.start);
+        Some(EditorRegister { text, linewise, block: false })
+    }
+
+    /// Cut the selection into a register (one undo step).
+    pub fn delete_selection(ed: &mut EditorState) -> Option<EditorRegister> {
+        if ed.mode == EditorMode::VisualBlock {
+            let ranges = Self::block_ranges(ed);
+            let register = Self::yank_selection(ed)?;
+            if ranges.iter().all(|r| r.is_empty()) || !Self::begin_edit(ed) {
+                return Some(register);
+            }
+            // De la dernière ligne à la première : les positions au-dessus restent valables
+            for range in ranges.into_iter().rev() {
+                ed.remove_range(range);
+            }
+            Self::clamp_col(ed);
+            ed.dirty = true;
+            ed.search_positions.clear();
+            ed.search_index = None;
+            return Some(register);
+        }
+        let mut range = Self::selection(ed)?;
+        let register = Self::yank_selection(ed)?;
+        if range.is_empty() {
+            return Some(register);
+        }
+        // Dernières lignes sans saut final : emporter celui qui les précède
+        if register.linewise && range.end == ed.buffer.len_chars() && range.start > 0 && ed.buffer.char(range.end - 1) != '\n' {
+            range.start -= 1;
+        }
+        if !Self::begin_edit(ed) {
+            return None;
+        }
+        ed.remove_range(range.clone());
+        Self::set_cursor_char(ed, range.start);
+        if register.linewise {
+            if range.start > 0 && ed.buffer.char(range.start - 1) != '\n' {
+                // Saut emporté : rester sur la (nouvelle) dernière ligne
+                ed.cursor_row = ed.buffer.len_lines().saturating_sub(1);
+            }
+            ed.cursor_col = 0;
+        }
+        ed.dirty = true;
+        ed.search_positions.clear();
+        ed.search_index = None;
+        Some(register)
+    }
+
+    /// Insert text at the cursor and move after it (terminal paste). One undo step.
+    pub fn insert_text(ed: &mut EditorState, text: &str) {
+        let text = text.replace("\r\n", "\n");
+        if text.is_empty() {
+            return;
+        }
+        if !Self::begin_edit(ed) {
+            return;
+        }
+        let at = Self::cursor_to_char_idx(ed);
+        ed.insert_at(at, &text);
+        Self::set_cursor_char(ed, at + text.chars().count());
+        ed.dirty = true;
+        ed.search_positions.clear();
+        ed.search_index = None;
+    }
+
+    /// Paste a register after the cursor (`before`: at the cursor); line-wise text
+    /// goes below (above) the cursor line. One undo step.
+    pub fn paste(ed: &mut EditorState, register: &EditorRegister, before: bool) {
+        if register.text.is_empty() {
+            return;
+        }
+        if !Self::begin_edit(ed) {
+            return;
+        }
+        if register.block {
+            Self::paste_block(ed, register, before);
+        } else if register.linewise {
+            let row = if before { ed.cursor_row } else { (ed.cursor_row + 1).min(ed.buffer.len_lines()) };
+            let at = ed.buffer.line_to_char(row);
+            let len = ed.buffer.len_chars();
+            if at == len && len > 0 && ed.buffer.char(len - 1) != '\n' {
+                // Après une dernière ligne sans saut final
+                ed.insert_at(at, &format!("\n{}", register.text.trim_end_matches('\n')));
+                Self::set_cursor_char(ed, at + 1);
+            } else {
+                ed.insert_at(at, &register.text);
+                Self::set_cursor_char(ed, at);
+            }
+        } else {
+            let line_len = ed.buffer.line(ed.cursor_row).chars().filter(|c| *c != '\n').count();
+            let col = if before { ed.cursor_col } else { (ed.cursor_col + 1).min(line_len) };
+            let at = ed.buffer.line_to_char(ed.cursor_row) + col;
+            ed.insert_at(at, &register.text);
+            Self::set_cursor_char(ed, at + register.text.chars().count() - 1);
+        }
+        ed.dirty = true;
+        ed.search_positions.clear();
+        ed.search_index = None;
+    }
+
+    /// Lines of a block register at the same column of the cursor line and the
+    /// following ones (after the cursor; `before`: at it). Short lines are padded
+    /// with spaces and missing lines added.
+    fn paste_block(ed: &mut EditorState, register: &EditorRegister, before: bool) {
+        let line_len = Self::line_string(ed, ed.cursor_row).chars().count();
+        let col = if before || line_len == 0 { ed.cursor_col } else { ed.cursor_col + 1 };
+        let first = ed.cursor_row;
+        for (i, piece) in register.text.split('\n').enumerate() {
+            let row = first + i;
+            if row >= ed.buffer.len_lines() {
+                ed.insert_at(ed.buffer.len_chars(), "\n");
+            }
+            let len = Self::line_string(ed, row).chars().count();
+            let start = ed.buffer.line_to_char(row);
+            if len < col {
+                if piece.is_empty() {
+                    continue;
+                }
+                ed.insert_at(start + len, &" ".repeat(col - len));
+            }
+            ed.insert_at(start + col, piece);
+        }
+        ed.cursor_row = first;
+        ed.cursor_col = col;
+        Self::clamp_col(ed);
+    }
+
+    /// `I` / `A` in block selection: type on the first line, before the block (after
+    /// it with `append`); the text is copied to the other lines as it is typed (see
+    /// `sync_block_insert`).
+    pub fn start_block_insert(ed: &mut EditorState, append: bool) {
+        let Some((rows, cols)) = Self::block(ed) else { return; };
+        let col = if append { cols.end } else { cols.start };
+        ed.mode = EditorMode::Insert;
+        ed.cursor_row = *rows.start();
+        let len = Self::line_string(ed, ed.cursor_row).chars().count();
+        if len < col && Self::begin_edit(ed) {
+            ed.insert_at(ed.buffer.line_to_char(ed.cursor_row) + len, &" ".repeat(col - len));
+            ed.dirty = true;
+        }
+        ed.cursor_col = col;
+        Self::clamp_col(ed);
+        let base_len = Self::line_string(ed, ed.cursor_row).chars().count();
+        ed.block_insert = Some(BlockInsert { first_row: *rows.start(), last_row: *rows.end(), col: ed.cursor_col, base_len, typed: String::new(), pad: append });
+        ed.typing = None;
+    }
+
+    /// Copy the text typed on the first line of a block insertion to its other lines,
+    /// in the undo step of the typing. Leaving the line, or erasing before the
+    /// insertion point, ends it.
+    pub fn sync_block_insert(ed: &mut EditorState) {
+        let Some(block) = ed.block_insert.as_ref() else { return; };
+        let len = Self::line_string(ed, block.first_row).chars().count();
+        if ed.mode != EditorMode::Insert || ed.cursor_row != block.first_row || len < block.base_len || ed.cursor_col < block.col {
+            ed.block_insert = None;
+            return;
+        }
+        let typed: String = Self::line_string(ed, block.first_row).chars().skip(block.col).take(len - block.base_len).collect();
+        if typed == block.typed || ed.undo_stack.is_empty() {
+            return;
+        }
+        let Some(block) = ed.block_insert.take() else { return; };
+        // Seule la partie changée est recopiée
+        let common = typed.chars().zip(block.typed.chars()).take_while(|(a, b)| a == b).count();
+        let old_len = block.typed.chars().count();
+        let added: String = typed.chars().skip(common).collect();
+        for row in block.first_row + 1..=block.last_row.min(ed.buffer.len_lines().saturating_sub(1)) {
+            let start = ed.buffer.line_to_char(row);
+            let mut len = Self::line_string(ed, row).chars().count();
+            if len < block.col {
+                if !block.pad || !block.typed.is_empty() {
+                    continue;
+                }
+                ed.insert_at(start + len, &" ".repeat(block.col - len));
+                len = block.col;
+            }
+            let from = block.col + common;
+            if from > len {
+                continue;
+            }
+            ed.remove_range(start + from..start + (block.col + old_len).min(len));
+            ed.insert_at(start + from, &added);
+        }
+        ed.dirty = true;
+        ed.block_insert = Some(BlockInsert { typed, ..block });
+    }
+
     /// Undo last change if any
     pub fn undo(ed: &mut EditorState) {
-        if let Some(prev) = ed.undo_stack.pop() {
-            // push current to redo
-            let current = super::super::state::EditorSnapshot {
-                buffer: ed.buffer.clone(),
-                cursor_row: ed.cursor_row,
-                cursor_col: ed.cursor_col,
-                scroll_row: ed.scroll_row,
-                dirty: ed.dirty,
-            };
-            ed.redo_stack.push(current);
-            // restore prev
-            ed.buffer = prev.buffer;
-            ed.cursor_row = prev.cursor_row;
-            ed.cursor_col = prev.cursor_col;
-            ed.scroll_row = prev.scroll_row;
-            ed.dirty = prev.dirty;
-        }
+        ed.block_insert = None;
+        ed.snippet = None;
+        ed.undo();
     }
 
     /// Redo next change if any
     pub fn redo(ed: &mut EditorState) {
-        if let Some(next) = ed.redo_stack.pop() {
-            // push current to undo
-            ed.push_undo();
-            // restore next
-            ed.buffer = next.buffer;
-            ed.cursor_row = next.cursor_row;
-            ed.cursor_col = next.cursor_col;
-            ed.scroll_row = next.scroll_row;
-            ed.dirty = next.dirty;
-        }
+        ed.block_insert = None;
+        ed.snippet = None;
+        ed.redo();
+    }
+
+    /// Regex of the current search: `last_search` with the case-insensitive and
+    /// whole-word toggles. None without a search or when the pattern is invalid.
+    pub fn search_regex(ed: &EditorState) -> Option<Regex> {
+        let query = ed.last_search.as_deref().filter(|q| !q.is_empty())?;
+        let pattern = if ed.search_whole_word { format!(r"\b(?:{})\b", query) } else { query.to_string() };
+        RegexBuilder::new(&pattern).case_insensitive(ed.search_ignore_case).build().ok()
+    }
+
+    /// Char ranges of the (non-empty) matches in a line.
+    fn line_matches(regex: &Regex, text: &str) -> Vec<Range<usize>> {
+        regex
+            .find_iter(text)
+            .filter(|m| !m.is_empty())
+            .map(|m| {
+                let start = text[..m.start()].chars().count();
+                start..start + m.as_str().chars().count()
+            })
+            .collect()
     }
 
     /// Recompute all search positions for last_search across the buffer
     pub fn recompute_search_positions(ed: &mut EditorState) {
         ed.search_positions.clear();
         ed.search_index = None;
-        let Some(q) = ed.last_search.as_ref() else { return; };
-        if q.is_empty() { return; }
+        let Some(regex) = Self::search_regex(ed) else { return; };
         for row in 0..ed.buffer.len_lines() {
             let mut text = ed.buffer.line(row).to_string();
             if text.ends_with('\n') { text.pop(); }
-            let mut last = 0usize;
-            let mut idx = 0usize;
-            while let Some(found) = text[last..].find(q) {
-                let s = last + found;
-                ed.search_positions.push((row, idx));
-                last = s + q.len();
-                idx += 1;
+            for m in Self::line_matches(&regex, &text) {
+                ed.search_positions.push((row, m));
             }
         }
     }
 
-    /// Jump to next search occurrence (wrap)
+    /// Search again as the pattern or a toggle changes: go to the first match at or
+    /// after `origin` (wrapping), or back to `origin` when nothing matches.
+    pub fn search_from(ed: &mut EditorState, origin: (usize, usize)) {
+        Self::recompute_search_positions(ed);
+        let after = ed.search_positions.iter().position(|(r, m)| (*r, m.start) >= origin);
+        match after.or(if ed.search_positions.is_empty() { None } else { Some(0) }) {
+            Some(i) => {
+                ed.search_index = Some(i);
+                Self::jump_to_search(ed);
+            }
+            None => {
+                let at = ed.buffer.line_to_char(origin.0.min(ed.buffer.len_lines().saturating_sub(1))) + origin.1;
+                Self::set_cursor_char(ed, at);
+            }
+        }
+    }
+
+    /// Jump to next search occurrence after the cursor (wrap)
     pub fn search_next(ed: &mut EditorState) {
         if ed.search_positions.is_empty() {
             Self::recompute_search_positions(ed);
         }
         if ed.search_positions.is_empty() { return; }
-        // Find current position index based on cursor
-        let current = ed.search_index.unwrap_or_else(|| {
-            // choose first occurrence after cursor
-            let mut idx0 = 0usize;
-            for (i, (row, _)) in ed.search_positions.iter().enumerate() {
-                if *row > ed.cursor_row || (*row == ed.cursor_row && 0 >= ed.cursor_col) { idx0 = i; break; }
-            }
-            idx0
-        });
-        let next = (current + 1) % ed.search_positions.len();
+        let cursor = (ed.cursor_row, ed.cursor_col);
+        let next = ed.search_positions.iter().position(|(r, m)| (*r, m.start) > cursor).unwrap_or(0);
         ed.search_index = Some(next);
         Self::jump_to_search(ed);
     }
 
-    /// Jump to previous search occurrence (wrap)
+    /// Jump to previous search occurrence before the cursor (wrap)
     pub fn search_prev(ed: &mut EditorState) {
         if ed.search_positions.is_empty() {
             Self::recompute_search_positions(ed);
         }
         if ed.search_positions.is_empty() { return; }
-        let current = ed.search_index.unwrap_or(0);
-        let prev = if current == 0 { ed.search_positions.len() - 1 } else { current - 1 };
+        let cursor = (ed.cursor_row, ed.cursor_col);
+        let prev = ed
+            .search_positions
+            .iter()
+            .rposition(|(r, m)| (*r, m.start) < cursor)
+            .unwrap_or(ed.search_positions.len() - 1);
         ed.search_index = Some(prev);
         Self::jump_to_search(ed);
     }
 
+    /// Put the cursor on the start of the current match.
     fn jump_to_search(ed: &mut EditorState) {
-        if let Some(i) = ed.search_index {
-            if let Some((row, _idx_in_row)) = ed.search_positions.get(i).copied() {
-                ed.cursor_row = row;
-                ed.cursor_col = 0;
-                if ed.cursor_row < ed.scroll_row { ed.scroll_row = ed.cursor_row; }
-            }
+        if let Some((row, m)) = ed.search_index.and_then(|i| ed.search_positions.get(i)) {
+            let at = ed.buffer.line_to_char(*row) + m.start;
+            Self::set_cursor_char(ed, at);
         }
     }
+
+    /// "occurrence 3/17" (or the match count) for the status line; "motif invalide"
+    /// when the pattern does not compile.
+    pub fn search_summary(ed: &EditorState) -> Option<String> {
+        ed.last_search.as_deref().filter(|q| !q.is_empty())?;
+        if Self::search_regex(ed).is_none() {
+            return Some(String::from("motif invalide"));
+        }
+        let total = ed.search_positions.len();
+        match ed.search_index {
+            Some(i) if total > 0 => Some(format!("occurrence {}/{}", i + 1, total)),
+            _ if total > 0 => Some(format!("{} occurrence(s)", total)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_file_rereads_late_latin1_bytes() {
+        let path = std::env::temp_dir().join(format!("paschek-stream-{}.txt", std::process::id()));
+        let mut bytes = vec![b'a'; LOAD_CHUNK + 10];
+        bytes.extend_from_slice(b"caf\xe9\n");
+        fs::write(&path, &bytes).unwrap();
+        let (tx, _rx) = mpsc::channel();
+        let result = stream_file(&path, &tx);
+        fs::remove_file(&path).unwrap();
+        let (rope, encoding, _) = result.unwrap();
+        assert_eq!(encoding, TextEncoding::Latin1);
+        assert_eq!(rope.len_bytes(), LOAD_CHUNK + 10 + "café\n".len());
+        assert!(rope.to_string().ends_with("café\n"));
+    }
 }
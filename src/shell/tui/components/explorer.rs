@@ -3,19 +3,75 @@
 //! Features:
 //! - Root confinement: prevents leaving a configured root path
 //! - Optional display of hidden files (dotfiles)
-//! - Sorted entries: directories first, then files, case-insensitive by name
+//! - Optional hiding of entries matched by `.gitignore`, `.git/info/exclude` and the
+//!   global git excludes (`I`; default from `config/explorer.toml`)
+//! - Sorted entries: directories first, then by name, size, modification time or
+//!   extension (`s` cycles the key, `S` flips the order; default from `config/explorer.toml`)
 //! - Special ".." entry to go up (hidden at root)
+//! - Breadcrumb bar above the entries: `g` selects a segment (←/→), Enter jumps to
+//!   that ancestor
+//! - `C` moves the Shell screen (process cwd) to the current directory; with
+//!   `follow_cwd` in the config, `cd` in the Shell screen moves the explorer too
+//! - Browser-style history: `H` / `L` (or Alt+←/→) go back / forward through the
+//!   visited directories
+//! - Git status badges (M, A, D, ?, …) when the directory is inside a repository
+//! - Filter-as-you-type (`/`): fuzzy (subsequence) match on names, Esc clears it
+//! - Multi-selection (Space marks entries, Esc clears the marks): yank, cut, delete
+//!   and rename then apply to every marked entry
+//! - Clipboard indicator for yanked / cut entries (see `fileops`)
+//! - Pinned directories (`b`, shared with the `bm` built-in) listed above the
+//!   entries; `1`-`9` jump to them
+//! - Directories are read on a background thread: entries appear in batches
+//!   (see `poll`) with a spinner in the title, so huge directories never freeze the UI
+//! - The current directory is watched (`notify`): entries created, removed or
+//!   renamed by other programs reload the listing, keeping the selected entry
+//! - Archives (`.zip`, `.tar`, `.tar.gz`) open as read-only directories through
+//!   `shell::vfs`; `E` extracts the selected members next to the archive
+//! - Remote hosts (`:e sftp://host/path`, see `shell::vfs::sftp`) are browsed the
+//!   same way, their files open in the editor and `E` downloads them
+//! - File type icons (see `icons`), with symlinks and git repositories marked
+//! - Disk usage mode (`U`): a background worker computes the recursive size of each
+//!   entry, shown with proportional bars and sorted largest-first
+use std::cmp::Reverse;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use notify::event::ModifyKind;
+use notify::{EventKind, RecursiveMode, Watcher};
 
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Layout, Position, Rect},
     style::{Color, Style},
-    widgets::{Block, Borders, List, ListItem},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
     Frame,
 };
 
-use crate::shell::tui::state::{DirEntryView, FileExplorerState};
+use crate::shell::{bookmarks, git, vfs};
+use crate::shell::vfs::sftp::{SftpFs, SftpUrl};
+use crate::shell::tui::components::dashboard::human_bytes;
+use crate::shell::tui::components::icons;
+use crate::shell::tui::state::{DirEntryView, DirLoad, DirWatch, DiskUsage, ExplorerSort, FileExplorerState, RemoteTarget, VfsMount};
+
+/// Pinned directories listed above the entries (and reachable with 1-9).
+const MAX_PINS: usize = 9;
+
+/// Visited directories kept for `H` (back).
+const HISTORY_LIMIT: usize = 100;
+
+/// Spinner frames shown in the title while a directory loads.
+pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+/// Interval between two batches sent by the loader thread.
+const BATCH_INTERVAL: Duration = Duration::from_millis(50);
+/// Width of the disk usage bars
+const DU_BAR: usize = 12;
+/// Quiet period after an external change before reloading (bursts reload once)
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
 
 /// Stateless explorer renderer and helper actions (refresh, navigate, activate).
 pub struct FileExplorerView;
@@ -27,15 +83,177 @@ fn within_root(root: &Path, path: &Path) -> bool {
     p.starts_with(&r)
 }
 
+/// Watch `dir` (not its subdirectories) for external changes.
+fn watch_dir(dir: &Path) -> Option<DirWatch> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).ok()?;
+    watcher.watch(dir, RecursiveMode::NonRecursive).ok()?;
+    Some(DirWatch { dir: dir.to_path_buf(), _watcher: watcher, events: rx })
+}
+
+/// Space allocated on disk for one file (its length where blocks are unknown).
+fn allocated(meta: &fs::Metadata) -> u64 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        meta.blocks() * 512
+    }
+    #[cfg(not(unix))]
+    {
+        meta.len()
+    }
+}
+
+/// Disk usage of `path` and everything below it (symlinks are not followed).
+fn usage(path: &Path, cancel: &AtomicBool) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else { return 0; };
+    let own = allocated(&meta);
+    if !meta.is_dir() || cancel.load(Ordering::Relaxed) {
+        return own;
+    }
+    own + fs::read_dir(path).map(|rd| rd.flatten().map(|e| usage(&e.path(), cancel)).sum()).unwrap_or(0)
+}
+
+/// Send the disk usage of every entry of `dir` as it is computed.
+fn disk_usage(dir: PathBuf, cancel: Arc<AtomicBool>, tx: Sender<(String, u64)>) {
+    let Ok(rd) = fs::read_dir(&dir) else { return; };
+    for entry in rd.flatten() {
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+        let size = usage(&entry.path(), &cancel);
+        if tx.send((entry.file_name().to_string_lossy().to_string(), size)).is_err() {
+            return;
+        }
+    }
+}
+
+/// Compute git badges for the direct children of `cwd` (directories inherit a child's badge).
+fn git_badges(cwd: &Path) -> HashMap<String, char> {
+    let mut badges = HashMap::new();
+    let Some(root) = git::repo_root(cwd) else { return badges; };
+    let Ok(entries) = git::status(&root) else { return badges; };
+    let cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+    for e in entries {
+        let abs = root.join(&e.path);
+        if let Ok(rel) = abs.strip_prefix(&cwd) && let Some(first) = rel.components().next() {
+            let name = first.as_os_str().to_string_lossy().to_string();
+            badges.entry(name).or_insert_with(|| e.badge());
+        }
+    }
+    badges
+}
+
+/// Sort value of an entry for the active sort key.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum SortValue {
+    None,
+    Size(u64),
+    Time(Option<SystemTime>),
+    Text(String),
+}
+
+/// Directories first, then files, each group ordered by `sort` (case-insensitive name as tiebreak).
+fn sort_entries(entries: &mut [DirEntryView], sort: ExplorerSort, desc: bool) {
+    entries.sort_by_cached_key(|e| {
+        let value = match sort {
+            ExplorerSort::Name => SortValue::None,
+            ExplorerSort::Size => SortValue::Size(e.size),
+            ExplorerSort::Modified => SortValue::Time(e.modified),
+            ExplorerSort::Extension => SortValue::Text(
+                Path::new(&e.name).extension().map(|x| x.to_string_lossy().to_lowercase()).unwrap_or_default(),
+            ),
+        };
+        (!e.is_dir, value, e.name.to_lowercase())
+    });
+    if desc {
+        let dirs = entries.iter().take_while(|e| e.is_dir).count();
+        entries[..dirs].reverse();
+        entries[dirs..].reverse();
+    }
+}
+
+/// True if every character of `query` appears in `name`, in order (case-insensitive).
+fn fuzzy_match(name: &str, query: &str) -> bool {
+    let mut chars = name.chars().flat_map(char::to_lowercase);
+    query.chars().flat_map(char::to_lowercase).all(|q| chars.any(|c| c == q))
+}
+
+/// Read `cwd` and send its entries in timed batches, then the git badges.
+/// Stops early when the receiver is dropped (another directory was opened).
+/// Entries of `dir` with their metadata; with `hide_ignored`, those matched by git ignore rules are left out.
+fn list_dir(dir: &Path, hide_ignored: bool) -> Vec<(String, Option<fs::Metadata>)> {
+    if !hide_ignored {
+        let Ok(rd) = fs::read_dir(dir) else { return Vec::new(); };
+        return rd.flatten().map(|e| (e.file_name().to_string_lossy().to_string(), e.metadata().ok())).collect();
+    }
+    // Les règles des dossiers parents et les exclusions globales s'appliquent aussi
+    ignore::WalkBuilder::new(dir)
+        .max_depth(Some(1))
+        .hidden(false)
+        .ignore(false)
+        .git_ignore(true)
+        .git_exclude(true)
+        .git_global(true)
+        .build()
+        .flatten()
+        .filter(|e| e.depth() == 1)
+        .map(|e| (e.file_name().to_string_lossy().to_string(), e.metadata().ok()))
+        .collect()
+}
+
+fn load_dir(cwd: PathBuf, show_hidden: bool, hide_ignored: bool, tx: Sender<DirLoad>) {
+    let mut batch = Vec::new();
+    let mut last_send = Instant::now();
+    for (name, meta) in list_dir(&cwd, hide_ignored) {
+        if !show_hidden && name.starts_with('.') {
+            continue;
+        }
+        let path = cwd.join(&name);
+        let is_symlink = meta.as_ref().is_some_and(|m| m.file_type().is_symlink());
+        // Un lien vers un dossier se parcourt comme un dossier
+        let is_dir = if is_symlink { path.is_dir() } else { meta.as_ref().is_some_and(|m| m.is_dir()) };
+        batch.push(DirEntryView {
+            is_repo: is_dir && !is_symlink && path.join(".git").exists(),
+            name,
+            is_dir,
+            size: meta.as_ref().map(|m| m.len()).unwrap_or(0),
+            modified: meta.and_then(|m| m.modified().ok()),
+            is_symlink,
+        });
+        if last_send.elapsed() >= BATCH_INTERVAL {
+            if tx.send(DirLoad::Entries(std::mem::take(&mut batch))).is_err() {
+                return;
+            }
+            last_send = Instant::now();
+        }
+    }
+    if !batch.is_empty() && tx.send(DirLoad::Entries(batch)).is_err() {
+        return;
+    }
+    let _ = tx.send(DirLoad::Badges(git_badges(&cwd)));
+}
+
+/// Color used for a git badge.
+fn badge_color(b: char) -> Color {
+    match b {
+        'M' => Color::Yellow,
+        'A' => Color::Green,
+        'D' => Color::Red,
+        '?' => Color::Magenta,
+        'U' => Color::LightRed,
+        _ => Color::Cyan,
+    }
+}
+
 /// Pretty-print a path relative-ish to root, replacing home prefix with `~` and truncating.
 fn short_path(p: &Path, _root: &Path) -> String {
     let display = p.display().to_string();
-    if let Some(home) = home::home_dir() {
-        if let (Ok(cp), Ok(ch)) = (p.canonicalize(), home.canonicalize()) {
-            if cp.starts_with(&ch) {
-                return display.replacen(&ch.display().to_string(), "~", 1);
-            }
-        }
+    if let Some(home) = home::home_dir()
+        && let (Ok(cp), Ok(ch)) = (p.canonicalize(), home.canonicalize())
+        && cp.starts_with(&ch)
+    {
+        return display.replacen(&ch.display().to_string(), "~", 1);
     }
     // Tronque si trop long
     if display.len() > 60 {
@@ -47,7 +265,8 @@ fn short_path(p: &Path, _root: &Path) -> String {
 }
 
 impl FileExplorerView {
-    /// Refresh the entries for the current working directory, applying filters and sorting.
+    /// Start (re)loading the current working directory in the background.
+    /// Entries are cleared and filled in by `poll`.
     pub fn refresh(state: &mut FileExplorerState) {
         let cwd = if state.cwd.as_os_str().is_empty() {
             std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
@@ -55,41 +274,286 @@ impl FileExplorerView {
             state.cwd.clone()
         };
 
-        let mut entries: Vec<DirEntryView> = Vec::new();
+        if state.vfs.is_some() {
+            state.cwd = cwd;
+            Self::refresh_virtual(state);
+            return;
+        }
 
+        state.all_entries.clear();
         // N'ajoute ".." que si on n'est pas à la racine
         if cwd != state.root {
-            entries.push(DirEntryView {
-                name: String::from(".."),
-                is_dir: true,
-            });
+            state.all_entries.push(DirEntryView { name: String::from(".."), is_dir: true, ..DirEntryView::default() });
         }
+        state.git_badges.clear();
+        // Le built-in `bm` peut avoir modifié les favoris entre-temps
+        state.pinned = bookmarks::load();
+        Self::apply_filter(state);
 
-        if let Ok(rd) = fs::read_dir(&cwd) {
-            for e in rd.flatten() {
-                let meta = e.metadata().ok();
-                let is_dir = meta.as_ref().map(|m| m.is_dir()).unwrap_or(false);
-                let name = e.file_name().to_string_lossy().to_string();
+        let (tx, rx) = mpsc::channel();
+        let (dir, show_hidden, hide_ignored) = (cwd.clone(), state.show_hidden, state.hide_ignored);
+        std::thread::spawn(move || load_dir(dir, show_hidden, hide_ignored, tx));
+        // Remplacer le récepteur fait s'arrêter l'éventuel chargement précédent
+        state.loading = Some(rx);
+        state.reselect = None;
+        if state.watch.as_ref().is_none_or(|w| w.dir != cwd) {
+            state.watch = watch_dir(&cwd);
+        }
+        state.cwd = cwd;
+        if state.du.is_some() {
+            Self::start_disk_usage(state);
+        }
+    }
 
-                if !state.show_hidden && name.starts_with('.') {
-                    continue;
-                }
+    /// (Re)start the disk usage worker on `cwd`; archives use their listed sizes.
+    fn start_disk_usage(state: &mut FileExplorerState) {
+        if let Some(du) = &state.du {
+            du.cancel.store(true, Ordering::Relaxed);
+        }
+        let cancel = Arc::new(AtomicBool::new(false));
+        let rx = if state.vfs.is_none() {
+            let (tx, rx) = mpsc::channel();
+            let (dir, stop) = (state.cwd.clone(), cancel.clone());
+            std::thread::spawn(move || disk_usage(dir, stop, tx));
+            Some(rx)
+        } else {
+            None
+        };
+        state.du = Some(DiskUsage { sizes: HashMap::new(), rx, cancel });
+    }
+
+    /// `U`: toggle the disk usage mode.
+    pub fn toggle_disk_usage(state: &mut FileExplorerState) {
+        match state.du.take() {
+            Some(du) => du.cancel.store(true, Ordering::Relaxed),
+            None => Self::start_disk_usage(state),
+        }
+        Self::resort(state);
+    }
 
-                entries.push(DirEntryView { name, is_dir });
+    /// Merge the sizes computed so far, keeping the selected entry selected.
+    fn poll_disk_usage(state: &mut FileExplorerState) {
+        let Some(du) = state.du.as_mut() else { return; };
+        let Some(rx) = du.rx.as_ref() else { return; };
+        let mut received = false;
+        loop {
+            match rx.try_recv() {
+                Ok((name, size)) => {
+                    du.sizes.insert(name, size);
+                    received = true;
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    du.rx = None;
+                    break;
+                }
             }
         }
+        if received {
+            let selected = state.entries.get(state.selected).map(|e| e.name.clone());
+            Self::resort(state);
+            if let Some(i) = selected.and_then(|name| state.entries.iter().position(|e| e.name == name)) {
+                state.selected = i;
+            }
+        }
+    }
 
-        entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+    /// Size shown for an entry in disk usage mode (None while still computing).
+    fn du_size(state: &FileExplorerState, e: &DirEntryView) -> Option<u64> {
+        let du = state.du.as_ref()?;
+        match du.sizes.get(&e.name) {
+            Some(size) => Some(*size),
+            None if du.rx.is_none() || !e.is_dir => Some(e.size),
+            None => None,
+        }
+    }
+
+    /// List the current directory of the mounted filesystem on a background thread
+    /// (a remote listing can take a while).
+    fn refresh_virtual(state: &mut FileExplorerState) {
+        let Some(mount) = &state.vfs else { return; };
+        let inner = state.cwd.strip_prefix(&mount.source).unwrap_or(Path::new("")).to_path_buf();
+        let (fs, show_hidden) = (mount.fs.clone(), state.show_hidden);
+        // ".." toujours présent : en haut, il ramène au dossier local
+        state.all_entries = vec![DirEntryView { name: String::from(".."), is_dir: true, ..DirEntryView::default() }];
+        state.git_badges.clear();
+        Self::apply_filter(state);
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let batch = fs
+                .list(&inner)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|e| show_hidden || !e.name.starts_with('.'))
+                .map(|e| DirEntryView { name: e.name, is_dir: e.is_dir, size: e.size, modified: e.modified, ..DirEntryView::default() })
+                .collect();
+            let _ = tx.send(DirLoad::Entries(batch));
         });
+        state.loading = Some(rx);
+        state.reselect = None;
+        if state.du.is_some() {
+            Self::start_disk_usage(state);
+        }
+    }
 
-        state.cwd = cwd;
-        state.entries = entries;
-        if state.selected >= state.entries.len() {
-            state.selected = state.entries.len().saturating_sub(1);
+    /// Connect to an `sftp://[user@]host[:port]/path` URL on a background thread;
+    /// `poll_connect` mounts it once connected.
+    pub fn connect(state: &mut FileExplorerState, url: &str) -> Result<(), String> {
+        let Some(url) = SftpUrl::parse(url) else {
+            return Err(format!("{} : URL sftp://[user@]hôte[:port]/chemin attendue", url));
+        };
+        if state.connecting.is_some() {
+            return Err(String::from("Une connexion est déjà en cours"));
+        }
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = SftpFs::connect(&url).and_then(|fs| {
+                let path = url.path.strip_prefix("/").unwrap_or(&url.path).to_path_buf();
+                let is_dir = fs.is_dir(&path)?;
+                Ok(RemoteTarget { source: url.base(), fs: Arc::new(fs), path, is_dir })
+            });
+            let _ = tx.send(result.map_err(|e| format!("{} : {}", url.host, e)));
+        });
+        state.connecting = Some(rx);
+        Ok(())
+    }
+
+    /// Mount the host once the connection is up: the explorer shows the remote
+    /// directory (the file's directory for a file). Returns the connection result,
+    /// with the remote file to open, if any.
+    pub fn poll_connect(state: &mut FileExplorerState) -> Option<Result<Option<PathBuf>, String>> {
+        let target = match state.connecting.as_ref()?.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return None,
+            Err(mpsc::TryRecvError::Disconnected) => Err(String::from("Connexion interrompue")),
+        };
+        state.connecting = None;
+        let target = match target {
+            Ok(target) => target,
+            Err(e) => return Some(Err(e)),
+        };
+        // Une seule monture à la fois : quitter la précédente
+        if let Some(mount) = state.vfs.take() {
+            if let Some(root) = mount.prev_root {
+                state.root = root;
+            }
+            state.cwd = mount.local;
+        }
+        let local = std::mem::replace(&mut state.cwd, PathBuf::new());
+        let prev_root = std::mem::replace(&mut state.root, target.source.clone());
+        let dir = if target.is_dir { target.path.clone() } else { target.path.parent().map(Path::to_path_buf).unwrap_or_default() };
+        state.vfs = Some(VfsMount { source: target.source.clone(), fs: target.fs, local, prev_root: Some(prev_root) });
+        state.marked.clear();
+        state.selected = 0;
+        Self::enter_dir(state, target.source.join(dir));
+        Some(Ok((!target.is_dir).then(|| target.source.join(&target.path))))
+    }
+
+    /// Reload the listing once external changes have settled, keeping the selected entry.
+    fn poll_changes(state: &mut FileExplorerState) {
+        if let Some(watch) = &state.watch {
+            for event in watch.events.try_iter().flatten() {
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_))) {
+                    state.changed_at = Some(Instant::now());
+                }
+            }
         }
+        // Dans une archive, le rechargement attend le retour sur le disque
+        if state.loading.is_none() && state.vfs.is_none() && state.changed_at.is_some_and(|t| t.elapsed() >= RELOAD_DEBOUNCE) {
+            state.changed_at = None;
+            let selected = state.entries.get(state.selected).map(|e| e.name.clone());
+            Self::refresh(state);
+            state.reselect = selected;
+        }
+    }
+
+    /// Merge entries received from the loader; call on every UI tick.
+    pub fn poll(state: &mut FileExplorerState) {
+        Self::poll_changes(state);
+        Self::poll_disk_usage(state);
+        if state.loading.is_none() && state.du.as_ref().is_some_and(|du| du.rx.is_some()) {
+            state.spinner = state.spinner.wrapping_add(1);
+        }
+        let Some(rx) = state.loading.as_ref() else { return; };
+        let mut received = false;
+        let done = loop {
+            match rx.try_recv() {
+                Ok(DirLoad::Entries(batch)) => {
+                    state.all_entries.extend(batch);
+                    received = true;
+                }
+                Ok(DirLoad::Badges(badges)) => state.git_badges = badges,
+                Err(mpsc::TryRecvError::Empty) => break false,
+                Err(mpsc::TryRecvError::Disconnected) => break true,
+            }
+        };
+        if received {
+            Self::resort(state);
+            let found = state.reselect.as_ref().and_then(|name| state.entries.iter().position(|e| e.name == *name));
+            if let Some(i) = found {
+                state.selected = i;
+            }
+        }
+        state.spinner = state.spinner.wrapping_add(1);
+        if done {
+            state.loading = None;
+            state.reselect = None;
+            if state.selected >= state.entries.len() {
+                state.selected = state.entries.len().saturating_sub(1);
+            }
+        }
+    }
+
+    /// Re-apply the current sort to the loaded entries.
+    pub fn resort(state: &mut FileExplorerState) {
+        // ".." reste en tête
+        let start = usize::from(state.all_entries.first().is_some_and(|e| e.name == ".."));
+        if let Some(du) = &state.du {
+            // Mode usage disque : les plus gros d'abord, dossiers et fichiers mêlés
+            let size = |e: &DirEntryView| du.sizes.get(&e.name).copied().unwrap_or(if e.is_dir { 0 } else { e.size });
+            state.all_entries[start..].sort_by_key(|e| Reverse(size(e)));
+        } else {
+            sort_entries(&mut state.all_entries[start..], state.sort, state.sort_desc);
+        }
+        Self::apply_filter(state);
+    }
+
+    /// Rebuild the visible entries from the filter (".." always stays).
+    fn apply_filter(state: &mut FileExplorerState) {
+        state.entries = state
+            .all_entries
+            .iter()
+            .filter(|e| e.name == ".." || fuzzy_match(&e.name, &state.filter))
+            .cloned()
+            .collect();
+    }
+
+    /// Replace the filter query and select the first match.
+    pub fn set_filter(state: &mut FileExplorerState, query: String) {
+        state.filter = query;
+        Self::apply_filter(state);
+        let first = usize::from(state.entries.first().is_some_and(|e| e.name == ".."));
+        state.selected = first.min(state.entries.len().saturating_sub(1));
+    }
+
+    /// Esc: drop the filter and show every entry again.
+    pub fn clear_filter(state: &mut FileExplorerState) {
+        state.filter_editing = false;
+        state.filter.clear();
+        Self::apply_filter(state);
+    }
+
+    /// `s`: next sort key.
+    pub fn cycle_sort(state: &mut FileExplorerState) {
+        state.sort = state.sort.next();
+        Self::resort(state);
+    }
+
+    /// `S`: flip ascending/descending.
+    pub fn toggle_sort_order(state: &mut FileExplorerState) {
+        state.sort_desc = !state.sort_desc;
+        Self::resort(state);
     }
 
     /// Wrapper without custom border style for backwards compatibility.
@@ -110,6 +574,17 @@ impl FileExplorerView {
         dirty: Option<(PathBuf, bool)>,
         pane_border: Style,
     ) {
+        // Favoris épinglés au-dessus de la liste
+        let area = if state.pinned.is_empty() {
+            area
+        } else {
+            let shown = state.pinned.len().min(MAX_PINS) as u16;
+            let [pins_area, list_area] =
+                Layout::vertical([Constraint::Length(shown + 2), Constraint::Min(3)]).areas(area);
+            Self::render_pins(f, pins_area, state, pane_border);
+            list_area
+        };
+        let du_max = state.entries.iter().filter(|e| e.name != "..").filter_map(|e| Self::du_size(state, e)).max().unwrap_or(0);
         let items: Vec<ListItem> = state
             .entries
             .iter()
@@ -122,37 +597,315 @@ impl FileExplorerView {
                     .unwrap_or(false);
 
                 let mut label =
-                    if e.is_dir { format!("📁 {}", e.name) } else { format!("📄 {}", e.name) };
+                    format!("{} {}", icons::icon(&state.icons, e), e.name);
                 if is_dirty_here && !e.is_dir {
                     label = format!("● {}", label);
                 }
+                if state.diff_mark.as_ref() == Some(&full_path) {
+                    label = format!("⇄ {}", label);
+                }
+                let marked = state.marked.contains(&full_path);
+                if marked {
+                    label = format!("✓ {}", label);
+                }
 
                 // Griser ".." si on est à la racine (normalement non affiché)
-                let style = if e.name == ".." && state.cwd == state.root {
+                let style = if e.name == ".." && state.cwd == state.root && state.vfs.is_none() {
                     Style::default().fg(Color::DarkGray)
                 } else if i == state.selected {
                     Style::default().fg(Color::Yellow)
+                } else if marked {
+                    Style::default().fg(Color::LightGreen)
                 } else {
                     Style::default()
                 };
 
-                ListItem::new(label).style(style)
+                let badge = state.git_badges.get(&e.name).copied();
+                let badge_span = match badge {
+                    Some(b) => Span::styled(format!("{} ", b), Style::default().fg(badge_color(b))),
+                    None => Span::raw("  "),
+                };
+                let mut spans = vec![badge_span];
+                if state.du.is_some() && e.name != ".." {
+                    // Barre proportionnelle au plus gros élément affiché
+                    let (size, bar) = match Self::du_size(state, e) {
+                        Some(size) => {
+                            let filled = if du_max == 0 { 0 } else { (size as f64 / du_max as f64 * DU_BAR as f64).round() as usize };
+                            (human_bytes(size), format!("{:<width$}", "█".repeat(filled), width = DU_BAR))
+                        }
+                        None => (String::from("…"), " ".repeat(DU_BAR)),
+                    };
+                    spans.push(Span::styled(format!("{:>9} ", size), Style::default().fg(Color::DarkGray)));
+                    spans.push(Span::styled(format!("{} ", bar), Style::default().fg(Color::Cyan)));
+                } else if state.du.is_some() {
+                    spans.push(Span::raw(" ".repeat(DU_BAR + 11)));
+                }
+                spans.push(Span::raw(label));
+                ListItem::new(Line::from(spans)).style(style)
             })
             .collect();
 
-        let title = format!(
-            "Explorer — {}  (root: {})",
+        let mut title = format!(
+            "Explorer [{} {}{}] — {}  (root: {})",
+            state.sort.label(),
+            if state.sort_desc { "↓" } else { "↑" },
+            if state.hide_ignored { " −ignorés" } else { "" },
             short_path(&state.cwd, &state.root),
             short_path(&state.root, &state.root)
         );
+        match &state.vfs {
+            Some(mount) if mount.fs.writable() => title = format!("{} — 🌐 distant [E] Télécharger ici", title),
+            Some(_) => title = format!("{} — 📦 lecture seule [E] Extraire ici", title),
+            None => {}
+        }
+        if let Some(du) = &state.du {
+            let total: u64 = state.all_entries.iter().filter(|e| e.name != "..").filter_map(|e| Self::du_size(state, e)).sum();
+            let pending = if du.rx.is_some() { format!(" {}", SPINNER[state.spinner % SPINNER.len()]) } else { String::new() };
+            title = format!("{} — [U] usage disque Σ {}{}", title, human_bytes(total), pending);
+        }
+        if !state.marked.is_empty() {
+            title = format!("{} — {} sélectionné(s)", title, state.marked.len());
+        }
+        if state.loading.is_some() {
+            title = format!("{} {} ({}…)", SPINNER[state.spinner % SPINNER.len()], title, state.entries.len());
+        }
 
-        let widget = List::new(items).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(pane_border)
-                .title(title),
-        );
-        f.render_widget(widget, area);
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(pane_border)
+            .title(title);
+        if state.filter_editing || !state.filter.is_empty() {
+            let style = if state.filter_editing { Style::default().fg(Color::Yellow) } else { Style::default() };
+            block = block.title_bottom(Line::styled(format!("/{}", state.filter), style));
+        }
+        if let Some(clip) = &state.clipboard {
+            let label = format!("📋 {} {}", clip.paths.len(), if clip.cut { "à déplacer" } else { "à copier" });
+            block = block.title_bottom(Line::styled(label, Style::default().fg(Color::Cyan)).right_aligned());
+        }
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let [crumbs_area, list_area] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner);
+        Self::render_crumbs(f, crumbs_area, state);
+        f.render_widget(List::new(items), list_area);
+        if state.filter_editing {
+            let x = area.x + 2 + state.filter.chars().count() as u16;
+            f.set_cursor_position(Position { x: x.min(area.right().saturating_sub(1)), y: area.bottom().saturating_sub(1) });
+        }
+    }
+
+    /// Breadcrumb segments: the root, then each directory down to `cwd`.
+    pub fn crumbs(state: &FileExplorerState) -> Vec<(String, PathBuf)> {
+        let mut crumbs = vec![(short_path(&state.root, &state.root), state.root.clone())];
+        if let Ok(rest) = state.cwd.strip_prefix(&state.root) {
+            let mut path = state.root.clone();
+            for part in rest.iter() {
+                path.push(part);
+                crumbs.push((part.to_string_lossy().to_string(), path.clone()));
+            }
+        }
+        crumbs
+    }
+
+    fn render_crumbs(f: &mut Frame, area: Rect, state: &FileExplorerState) {
+        let crumbs = Self::crumbs(state);
+        let last = crumbs.len() - 1;
+        let styled: Vec<(String, Style)> = crumbs
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, _))| {
+                let style = if state.crumb == Some(i) {
+                    Style::default().fg(Color::Black).bg(Color::Yellow)
+                } else if i == last {
+                    Style::default().fg(Color::Yellow)
+                } else {
+                    Style::default().fg(Color::Cyan)
+                };
+                (name, style)
+            })
+            .collect();
+        // Trop long : on masque les premiers dossiers sous la racine (sauf celui sélectionné)
+        let width = |segs: &[(String, Style)]| segs.iter().map(|(n, _)| n.chars().count() + 3).sum::<usize>();
+        let mut hidden = 0;
+        while styled.len() - hidden > 2 && width(&styled) - width(&styled[1..1 + hidden]) + 4 > area.width as usize {
+            if state.crumb == Some(1 + hidden) {
+                break;
+            }
+            hidden += 1;
+        }
+        let sep = Span::styled(" › ", Style::default().fg(Color::DarkGray));
+        let mut spans = vec![Span::styled(styled[0].0.clone(), styled[0].1)];
+        if hidden > 0 {
+            spans.push(sep.clone());
+            spans.push(Span::styled("…", Style::default().fg(Color::DarkGray)));
+        }
+        for (name, style) in &styled[1 + hidden..] {
+            spans.push(sep.clone());
+            spans.push(Span::styled(name.clone(), *style));
+        }
+        f.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
+    /// `g`: focus the breadcrumb bar on the parent directory.
+    pub fn begin_crumbs(state: &mut FileExplorerState) {
+        let count = Self::crumbs(state).len();
+        state.crumb = Some(count.saturating_sub(2));
+    }
+
+    pub fn move_crumb(state: &mut FileExplorerState, delta: isize) {
+        let count = Self::crumbs(state).len() as isize;
+        if let Some(i) = state.crumb {
+            state.crumb = Some((i as isize + delta).clamp(0, count - 1) as usize);
+        }
+    }
+
+    /// Jump to the selected breadcrumb segment and leave the bar.
+    pub fn jump_to_crumb(state: &mut FileExplorerState) {
+        let Some(i) = state.crumb.take() else { return; };
+        if let Some((_, dir)) = Self::crumbs(state).into_iter().nth(i)
+            && dir != state.cwd
+            && within_root(&state.root, &dir)
+        {
+            // Resélectionner le dossier par lequel on est descendu
+            let child = state.cwd.strip_prefix(&dir).ok().and_then(|r| r.iter().next()).map(|n| n.to_string_lossy().to_string());
+            Self::enter_dir(state, dir);
+            state.reselect = child;
+        }
+    }
+
+    fn render_pins(f: &mut Frame, area: Rect, state: &FileExplorerState, border: Style) {
+        let items: Vec<ListItem> = state
+            .pinned
+            .iter()
+            .take(MAX_PINS)
+            .enumerate()
+            .map(|(i, p)| {
+                let name = p.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| p.display().to_string());
+                let style = if *p == state.cwd { Style::default().fg(Color::Yellow) } else { Style::default() };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{} ", i + 1), Style::default().fg(Color::Cyan)),
+                    Span::styled(name, style),
+                    Span::styled(format!("  {}", short_path(p, &state.root)), Style::default().fg(Color::DarkGray)),
+                ]))
+            })
+            .collect();
+        let block = Block::default().borders(Borders::ALL).border_style(border).title("📌 Favoris [1-9] [b] Épingler");
+        f.render_widget(List::new(items).block(block), area);
+    }
+
+    /// Pin the selected directory (or the current one when a file or ".." is selected),
+    /// or unpin it if it already is. Returns true when it is now pinned.
+    pub fn toggle_pin(state: &mut FileExplorerState) -> std::io::Result<bool> {
+        let dir = match state.entries.get(state.selected) {
+            Some(e) if e.is_dir && e.name != ".." => state.cwd.join(&e.name),
+            _ => state.cwd.clone(),
+        };
+        let pinned = bookmarks::toggle(&dir)?;
+        state.pinned = bookmarks::load();
+        Ok(pinned)
+    }
+
+    /// Jump to the `n`-th pinned directory (0-based). Switches to another configured
+    /// root when the pin is outside the current one; fails if no root contains it.
+    pub fn jump_to_pin(state: &mut FileExplorerState, n: usize) -> Result<(), String> {
+        let Some(dir) = state.pinned.get(n).cloned() else { return Err(format!("Pas de favori n°{}", n + 1)); };
+        Self::go_to(state, dir)
+    }
+
+    /// Open `dir`, switching to the configured root that contains it when it is
+    /// outside the current one.
+    pub fn go_to(state: &mut FileExplorerState, dir: PathBuf) -> Result<(), String> {
+        if !dir.is_dir() {
+            return Err(format!("{} : dossier introuvable", dir.display()));
+        }
+        if !within_root(&state.root, &dir) {
+            match state.roots.iter().find(|r| within_root(r, &dir)) {
+                Some(root) => state.root = root.clone(),
+                None => return Err(format!("{} est hors de la racine {}", dir.display(), state.root.display())),
+            }
+        }
+        Self::enter_dir(state, dir);
+        Ok(())
+    }
+
+    /// Open the `n`-th configured root (0-based) at its top.
+    pub fn switch_root(state: &mut FileExplorerState, n: usize) -> Result<(), String> {
+        let Some(root) = state.roots.get(n).cloned() else { return Err(format!("Pas de racine n°{}", n + 1)); };
+        if !root.is_dir() {
+            return Err(format!("{} : dossier introuvable", root.display()));
+        }
+        state.root = root.clone();
+        state.selected = 0;
+        Self::enter_dir(state, root);
+        Ok(())
+    }
+
+    pub fn move_root_selection(state: &mut FileExplorerState, delta: isize) {
+        let next = state.roots_selected as isize + delta;
+        state.roots_selected = next.clamp(0, state.roots.len().saturating_sub(1) as isize) as usize;
+    }
+
+    /// Root switcher overlay (`r`).
+    pub fn render_roots(f: &mut Frame, area: Rect, state: &FileExplorerState) {
+        f.render_widget(Clear, area);
+        let items: Vec<ListItem> = state
+            .roots
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let style = if i == state.roots_selected { Style::default().fg(Color::Yellow) } else { Style::default() };
+                let current = if *r == state.root { "● " } else { "  " };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{} ", i + 1), Style::default().fg(Color::Cyan)),
+                    Span::styled(current, Style::default().fg(Color::Green)),
+                    Span::styled(short_path(r, &state.root), style),
+                ]))
+            })
+            .collect();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title("Racines — [Entrée/1-9] Ouvrir  [Esc] Fermer");
+        f.render_widget(List::new(items).block(block), area);
+    }
+
+    /// Mark or unmark the selected entry, then move to the next one.
+    pub fn toggle_mark(state: &mut FileExplorerState) {
+        let Some(entry) = state.entries.get(state.selected) else { return; };
+        if entry.name == ".." {
+            return;
+        }
+        let path = state.cwd.join(&entry.name);
+        if !state.marked.remove(&path) {
+            state.marked.insert(path);
+        }
+        Self::move_down(state);
+    }
+
+    /// Entries an operation applies to: the marked ones, or else the selected entry.
+    pub fn targets(state: &FileExplorerState) -> Vec<PathBuf> {
+        if !state.marked.is_empty() {
+            let mut paths: Vec<PathBuf> = state.marked.iter().cloned().collect();
+            paths.sort();
+            return paths;
+        }
+        state
+            .entries
+            .get(state.selected)
+            .filter(|e| e.name != "..")
+            .map(|e| vec![state.cwd.join(&e.name)])
+            .unwrap_or_default()
+    }
+
+    /// Path of the selected entry if it is a file on disk (None inside an archive).
+    pub fn selected_file(state: &FileExplorerState) -> Option<PathBuf> {
+        if state.vfs.is_some() {
+            return None;
+        }
+        state
+            .entries
+            .get(state.selected)
+            .filter(|e| !e.is_dir)
+            .map(|e| state.cwd.join(&e.name))
     }
 
     pub fn move_up(state: &mut FileExplorerState) {
@@ -168,23 +921,84 @@ impl FileExplorerView {
     }
 
     pub fn go_up(state: &mut FileExplorerState) {
-        if let Some(parent) = state.cwd.parent() {
-            if within_root(&state.root, parent) {
-                state.cwd = parent.to_path_buf();
-                Self::refresh(state);
+        // En haut d'une archive ou d'un hôte distant : retour au dossier local
+        if let Some(mount) = state.vfs.as_ref().filter(|m| m.source == state.cwd) {
+            let archive = mount.prev_root.is_none().then(|| mount.source.file_name().map(|n| n.to_string_lossy().to_string())).flatten();
+            Self::enter_dir(state, mount.local.clone());
+            // L'archive est resélectionnée dans son dossier
+            state.reselect = archive;
+            return;
+        }
+        if let Some(parent) = state.cwd.parent() && within_root(&state.root, parent) {
+            Self::enter_dir(state, parent.to_path_buf());
+        }
+    }
+
+    /// Change directory, remembering the current one for `H` (back).
+    fn enter_dir(state: &mut FileExplorerState, dir: PathBuf) {
+        if dir != state.cwd && !state.cwd.as_os_str().is_empty() {
+            state.back.push(state.cwd.clone());
+            if state.back.len() > HISTORY_LIMIT {
+                state.back.remove(0);
             }
+            state.forward.clear();
         }
+        Self::change_dir(state, dir);
+    }
+
+    /// `H` / `L`: go back / forward in the visited directories, skipping those that
+    /// disappeared or lie outside every root. Returns false when there is nowhere to go.
+    pub fn history_step(state: &mut FileExplorerState, forward: bool) -> bool {
+        loop {
+            let Some(dir) = (if forward { state.forward.pop() } else { state.back.pop() }) else { return false; };
+            let mounted = state.vfs.as_ref().is_some_and(|m| dir.starts_with(&m.source));
+            if !mounted {
+                if !dir.is_dir() {
+                    continue;
+                }
+                if !within_root(&state.root, &dir) {
+                    match state.roots.iter().find(|r| within_root(r, &dir)) {
+                        Some(root) => state.root = root.clone(),
+                        None => continue,
+                    }
+                }
+            }
+            let from = state.cwd.clone();
+            if forward { state.back.push(from.clone()) } else { state.forward.push(from.clone()) }
+            // En remontant, resélectionner le dossier d'où l'on vient
+            let child = from.strip_prefix(&dir).ok().and_then(|r| r.iter().next()).map(|n| n.to_string_lossy().to_string());
+            Self::change_dir(state, dir);
+            state.reselect = child;
+            return true;
+        }
+    }
+
+    /// Change directory (the filter only applies to the directory it was typed in).
+    /// Leaves the mounted filesystem when `dir` is outside it; its directories are
+    /// then dropped from the history.
+    fn change_dir(state: &mut FileExplorerState, dir: PathBuf) {
+        if let Some(mount) = state.vfs.take_if(|m| !dir.starts_with(&m.source)) {
+            state.marked.retain(|p| !p.starts_with(&mount.source));
+            state.back.retain(|p| !p.starts_with(&mount.source));
+            state.forward.retain(|p| !p.starts_with(&mount.source));
+            if let Some(root) = mount.prev_root.filter(|_| state.root == mount.source) {
+                state.root = root;
+            }
+        }
+        state.filter.clear();
+        state.filter_editing = false;
+        state.cwd = dir;
+        Self::refresh(state);
     }
 
     /// Activate the currently selected entry.
     /// - If directory: enter it and refresh, returns None
-    /// - If file: return its path (constrained to root)
+    /// - If archive: browse it as a directory, returns None
+    /// - If file: return its path (constrained to root; inside an archive or a
+    ///   remote host, a path under the mount for `EditorView::open_entry`)
     /// - If "..": go up and return None
     pub fn activate(state: &mut FileExplorerState) -> Option<PathBuf> {
-        if state.entries.is_empty() {
-            return None;
-        }
-        let entry = &state.entries[state.selected];
+        let entry = state.entries.get(state.selected)?;
 
         if entry.name == ".." {
             Self::go_up(state);
@@ -194,11 +1008,20 @@ impl FileExplorerView {
         let path = state.cwd.join(&entry.name);
         if entry.is_dir {
             if within_root(&state.root, &path) {
-                state.cwd = path;
-                Self::refresh(state);
+                Self::enter_dir(state, path);
             }
             None
+        } else if state.vfs.is_some() {
+            Some(path)
         } else if within_root(&state.root, &path) {
+            // Archive illisible : ouverte comme un fichier ordinaire
+            if let Some(Ok(fs)) = vfs::open(&path) {
+                let local = state.cwd.clone();
+                state.vfs = Some(VfsMount { source: path.clone(), fs, local, prev_root: None });
+                state.selected = 0;
+                Self::enter_dir(state, path);
+                return None;
+            }
             Some(path)
         } else {
             None
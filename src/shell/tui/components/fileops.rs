@@ -0,0 +1,591 @@
+//! Explorer file operations: yank (`y`), cut (`x`) and paste (`p`), plus the bulk
+//! delete / rename used on multi-selections.
+//!
+//! `c` duplicates the marked or selected entries in place (`file copy.txt`, then
+//! `file copy 2.txt`, …) through the paste worker. Renaming several entries opens
+//! an overlay where a pattern (`{name}_{n}.{ext}`) is typed with a live preview of
+//! every new name; conflicts are shown in red and block the rename.
+//!
+//! Delete moves entries to the OS trash; the last batch can be restored (`u`) where
+//! the trash can be listed (Linux / freedesktop and Windows). Permanent deletion
+//! (`remove_dir_all`) is only used behind its own confirmed keybinding.
+//!
+//! The clipboard holds paths, so it survives directory changes. Pasting first asks
+//! how to resolve name conflicts (overwrite / rename / skip, uppercase applies the
+//! choice to every remaining conflict), then copies or moves on a background thread
+//! while a progress overlay shows the bytes done. Directories are copied recursively;
+//! a cut falls back to copy + delete when a rename crosses filesystems.
+//!
+//! Inside an archive (see `shell::vfs`), `E` extracts the marked or selected members
+//! next to the archive, also on a background thread; on a remote host it downloads
+//! them to the local directory the host was opened from. `Z` packs the marked or
+//! selected entries into a new `.zip` / `.tar.gz` / `.tar` with the same progress gauge.
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Gauge, Paragraph},
+    Frame,
+};
+
+use crate::shell::tui::components::dashboard::human_bytes;
+use crate::shell::tui::components::explorer::FileExplorerView;
+use crate::shell::tui::state::{CompressState, FileClipboard, FileExplorerState, PasteEvent, PasteItem, PasteState, RenameState};
+use crate::shell::vfs::archive::{self, ArchiveKind};
+
+/// Stateless paste overlay and actions operating on PasteState.
+pub struct FileOpsView;
+
+/// One entry of a batch rename and its new name.
+pub struct RenameLine {
+    pub from: PathBuf,
+    pub name: String,
+    /// Why the new name cannot be used
+    pub problem: Option<&'static str>,
+}
+
+/// How a name conflict is resolved.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Overwrite,
+    Rename,
+    Skip,
+}
+
+const CHUNK: usize = 1 << 20;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(50);
+
+/// `dir/name`, or `dir/stem (n).ext` with the first free `n` if it exists.
+pub fn unique_name(dir: &Path, name: &OsStr) -> PathBuf {
+    let candidate = dir.join(name);
+    if fs::symlink_metadata(&candidate).is_err() {
+        return candidate;
+    }
+    let path = Path::new(name);
+    let stem = path.file_stem().unwrap_or(name).to_string_lossy();
+    let ext = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (1..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, ext)))
+        .find(|p| fs::symlink_metadata(p).is_err())
+        .unwrap_or(candidate)
+}
+
+/// Remove a file, symlink or whole directory.
+pub fn remove_path(path: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() { fs::remove_dir_all(path) } else { fs::remove_file(path) }
+}
+
+/// New name for `path` from a bulk-rename pattern (`.{ext}` disappears when the
+/// entry has no extension).
+fn expand_pattern(pattern: &str, path: &Path, n: usize) -> String {
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let ext = path.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+    let pattern = if ext.is_empty() { pattern.replace(".{ext}", "") } else { pattern.to_string() };
+    pattern.replace("{name}", &stem).replace("{ext}", &ext).replace("{n}", &n.to_string())
+}
+
+/// Free name for a duplicate of `name` in `dir`: `file copy.txt`, then
+/// `file copy 2.txt`, … (directories keep their whole name as stem).
+fn copy_name(dir: &Path, name: &OsStr, is_dir: bool) -> PathBuf {
+    let path = Path::new(name);
+    let (stem, ext) = match (is_dir, path.file_stem(), path.extension()) {
+        (false, Some(stem), Some(ext)) => (stem.to_string_lossy(), format!(".{}", ext.to_string_lossy())),
+        _ => (name.to_string_lossy(), String::new()),
+    };
+    (1..)
+        .map(|n| if n == 1 { format!("{} copy{}", stem, ext) } else { format!("{} copy {}{}", stem, n, ext) })
+        .map(|candidate| dir.join(candidate))
+        .find(|p| fs::symlink_metadata(p).is_err())
+        .unwrap_or_else(|| dir.join(name))
+}
+
+/// Total size in bytes of a file or directory tree (symlinks are not followed).
+fn tree_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else { return 0; };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path)
+        .map(|rd| rd.flatten().map(|e| tree_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Recursive copy reporting progress; stops with an error once the overlay is gone.
+struct Copier<'a> {
+    tx: &'a Sender<PasteEvent>,
+    done: u64,
+    total: u64,
+    last: Instant,
+}
+
+impl Copier<'_> {
+    fn report(&mut self, current: &Path, force: bool) -> io::Result<()> {
+        if !force && self.last.elapsed() < PROGRESS_INTERVAL {
+            return Ok(());
+        }
+        self.last = Instant::now();
+        let event = PasteEvent::Progress { done: self.done, total: self.total, current: current.display().to_string() };
+        self.tx.send(event).map_err(|_| io::Error::new(io::ErrorKind::Interrupted, "annulé"))
+    }
+
+    fn copy_tree(&mut self, from: &Path, to: &Path) -> io::Result<()> {
+        let meta = fs::symlink_metadata(from)?;
+        if meta.file_type().is_symlink() {
+            #[cfg(unix)]
+            return std::os::unix::fs::symlink(fs::read_link(from)?, to);
+            #[cfg(not(unix))]
+            return fs::copy(from, to).map(|_| ());
+        }
+        if meta.is_dir() {
+            fs::create_dir_all(to)?;
+            for entry in fs::read_dir(from)? {
+                let entry = entry?;
+                self.copy_tree(&entry.path(), &to.join(entry.file_name()))?;
+            }
+            return fs::set_permissions(to, meta.permissions());
+        }
+        self.report(from, true)?;
+        let mut src = fs::File::open(from)?;
+        let mut dst = fs::File::create(to)?;
+        let mut buf = vec![0u8; CHUNK];
+        loop {
+            let n = src.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            dst.write_all(&buf[..n])?;
+            self.done += n as u64;
+            self.report(from, false)?;
+        }
+        fs::set_permissions(to, meta.permissions())
+    }
+}
+
+/// Copy or move every item, then send the errors met on the way.
+fn run_paste(items: Vec<PasteItem>, cut: bool, tx: Sender<PasteEvent>) {
+    let total = items.iter().map(|i| tree_size(&i.from)).sum();
+    let mut copier = Copier { tx: &tx, done: 0, total, last: Instant::now() };
+    let mut errors = Vec::new();
+    for item in items {
+        let name = item.from.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let result = (|| {
+            if item.overwrite && fs::symlink_metadata(&item.to).is_ok() {
+                remove_path(&item.to)?;
+            }
+            if cut {
+                // Même système de fichiers : simple renommage
+                if fs::rename(&item.from, &item.to).is_ok() {
+                    copier.done += tree_size(&item.to);
+                    return copier.report(&item.to, true);
+                }
+                copier.copy_tree(&item.from, &item.to)?;
+                remove_path(&item.from)
+            } else {
+                copier.copy_tree(&item.from, &item.to)
+            }
+        })();
+        match result {
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => return,
+            Err(e) => errors.push(format!("{}: {}", name, e)),
+            Ok(()) => {}
+        }
+    }
+    let _ = tx.send(PasteEvent::Finished(errors));
+}
+
+/// Pack `sources` into `dest`, reporting progress like a paste.
+fn run_compress(dest: PathBuf, kind: ArchiveKind, sources: Vec<PathBuf>, tx: Sender<PasteEvent>) {
+    let total = sources.iter().map(|p| tree_size(p)).sum();
+    let (mut done, mut last) = (0, Instant::now());
+    let mut progress = |n: u64, current: &Path| {
+        done += n;
+        if last.elapsed() < PROGRESS_INTERVAL {
+            return Ok(());
+        }
+        last = Instant::now();
+        let event = PasteEvent::Progress { done, total, current: current.display().to_string() };
+        tx.send(event).map_err(|_| io::Error::new(io::ErrorKind::Interrupted, "annulé"))
+    };
+    let errors = match archive::create(&dest, kind, &sources, &mut progress) {
+        Ok(()) => Vec::new(),
+        Err(e) => vec![format!("{}: {}", dest.display(), e)],
+    };
+    let _ = tx.send(PasteEvent::Finished(errors));
+}
+
+impl FileOpsView {
+    /// Put the marked entries (or the selected one) on the clipboard, for a copy or
+    /// (with `cut`) a move. The marks are cleared.
+    pub fn yank(explorer: &mut FileExplorerState, cut: bool) {
+        let paths = FileExplorerView::targets(explorer);
+        if paths.is_empty() {
+            return;
+        }
+        explorer.clipboard = Some(FileClipboard { paths, cut });
+        explorer.marked.clear();
+    }
+
+    /// Move every path to the OS trash.
+    pub fn trash(paths: &[PathBuf]) -> Result<(), String> {
+        trash::delete_all(paths).map_err(|e| e.to_string())
+    }
+
+    /// Put back the most recently trashed item of each path; returns how many were restored.
+    #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))))]
+    pub fn restore(paths: &[PathBuf]) -> Result<usize, String> {
+        let items = trash::os_limited::list().map_err(|e| e.to_string())?;
+        // Le plus récent par chemin d'origine (un même nom peut être dans la corbeille plusieurs fois)
+        let mut latest: HashMap<PathBuf, trash::TrashItem> = HashMap::new();
+        for item in items {
+            let path = item.original_path();
+            if paths.contains(&path) && latest.get(&path).is_none_or(|l| l.time_deleted < item.time_deleted) {
+                latest.insert(path, item);
+            }
+        }
+        let count = latest.len();
+        trash::os_limited::restore_all(latest.into_values()).map_err(|e| e.to_string())?;
+        Ok(count)
+    }
+
+    #[cfg(not(any(target_os = "windows", all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android")))))]
+    pub fn restore(_paths: &[PathBuf]) -> Result<usize, String> {
+        Err(String::from("restauration depuis la corbeille non prise en charge sur ce système"))
+    }
+
+    /// Permanently delete every path; returns the errors.
+    pub fn delete(paths: &[PathBuf]) -> Vec<String> {
+        paths
+            .iter()
+            .filter_map(|p| remove_path(p).err().map(|e| format!("{}: {}", p.display(), e)))
+            .collect()
+    }
+
+    /// New name of every path for `pattern`, with the reason it cannot be applied.
+    /// A single path takes `pattern` as its new name; with several, `{name}` (name
+    /// without extension), `{ext}` and `{n}` (1, 2, …) are replaced for each one.
+    pub fn rename_plan(paths: &[PathBuf], pattern: &str) -> Vec<RenameLine> {
+        let mut lines: Vec<RenameLine> = Vec::new();
+        for (i, from) in paths.iter().enumerate() {
+            let name = if paths.len() == 1 { pattern.to_string() } else { expand_pattern(pattern, from, i + 1) };
+            let to = from.with_file_name(&name);
+            let problem = if name.is_empty() || name == "." || name == ".." {
+                Some("nom vide")
+            } else if name.contains(std::path::is_separator) {
+                Some("« / » interdit")
+            } else if to != *from && fs::symlink_metadata(&to).is_ok() {
+                Some("existe déjà")
+            } else if lines.iter().any(|l| l.from.with_file_name(&l.name) == to) {
+                Some("nom en double")
+            } else {
+                None
+            };
+            lines.push(RenameLine { from: from.clone(), name, problem });
+        }
+        lines
+    }
+
+    /// Rename every path in place following `pattern` (see `rename_plan`); entries
+    /// whose new name is invalid are left alone. Returns the errors.
+    pub fn rename(paths: &[PathBuf], pattern: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        for line in Self::rename_plan(paths, pattern) {
+            let to = line.from.with_file_name(&line.name);
+            if let Some(problem) = line.problem {
+                errors.push(format!("{}: {}", to.display(), problem));
+            } else if to != line.from && let Err(e) = fs::rename(&line.from, &to) {
+                errors.push(format!("{}: {}", line.from.display(), e));
+            }
+        }
+        errors
+    }
+
+    /// Batch rename overlay: the pattern being typed and every resulting name.
+    pub fn render_rename(f: &mut Frame, area: Rect, rename: &RenameState) {
+        f.render_widget(Clear, area);
+        let lines = Self::rename_plan(&rename.targets, &rename.pattern);
+        let problems = lines.iter().filter(|l| l.problem.is_some()).count();
+        let mut text = vec![
+            Line::from(vec![Span::raw("Motif : "), Span::styled(format!("{}▏", rename.pattern), Style::default().fg(Color::Yellow))]),
+            Line::styled("{name} nom sans extension · {ext} extension · {n} numéro (1, 2, …)", Style::default().fg(Color::DarkGray)),
+            Line::from(""),
+        ];
+        for l in &lines {
+            let old = l.from.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let mut spans = vec![Span::raw(format!("{}  →  ", old))];
+            match l.problem {
+                Some(problem) => spans.push(Span::styled(format!("{}  ({})", l.name, problem), Style::default().fg(Color::Red))),
+                None => spans.push(Span::styled(l.name.clone(), Style::default().fg(Color::LightGreen))),
+            }
+            text.push(Line::from(spans));
+        }
+        let hint = if problems > 0 { format!("{} conflit(s) — [Esc] Annuler", problems) } else { String::from("[Entrée] Renommer  [Esc] Annuler") };
+        let block = Block::default().borders(Borders::ALL).title(format!("Renommer {} éléments — {}", lines.len(), hint));
+        f.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    /// Copy the marked (or selected) entries next to themselves as `name copy.ext`,
+    /// with the paste progress overlay. Returns false when there is nothing to copy.
+    pub fn duplicate(paste: &mut PasteState, explorer: &mut FileExplorerState) -> bool {
+        let targets = FileExplorerView::targets(explorer);
+        if targets.is_empty() {
+            return false;
+        }
+        *paste = PasteState::default();
+        for from in targets {
+            let (Some(dir), Some(name)) = (from.parent(), from.file_name()) else { continue; };
+            let is_dir = fs::symlink_metadata(&from).is_ok_and(|m| m.is_dir());
+            let to = copy_name(dir, name, is_dir);
+            // Deux éléments du même dossier ne doivent pas viser le même nom
+            let to = if paste.plan.iter().any(|i| i.to == to) { unique_name(dir, to.file_name().unwrap_or(name)) } else { to };
+            paste.plan.push(PasteItem { from, to, overwrite: false });
+        }
+        explorer.marked.clear();
+        Self::start_if_resolved(paste);
+        true
+    }
+
+    /// Extract the marked (or selected) archive members into the directory holding
+    /// the archive (download them from a remote host), renaming on conflicts. Returns how many entries were requested.
+    pub fn extract_here(explorer: &mut FileExplorerState) -> Result<usize, String> {
+        let Some(mount) = explorer.vfs.as_ref() else { return Err(String::from("Pas dans une archive ni sur un hôte distant")); };
+        if explorer.extracting.is_some() {
+            return Err(String::from("Une extraction est déjà en cours"));
+        }
+        let dest = mount.local.clone();
+        let mut items: Vec<(PathBuf, PathBuf)> = Vec::new();
+        for path in FileExplorerView::targets(explorer) {
+            let (Ok(member), Some(name)) = (path.strip_prefix(&mount.source), path.file_name()) else { continue; };
+            let mut to = unique_name(&dest, name);
+            // Homonymes venant de dossiers différents de l'archive
+            let mut n = 1;
+            while items.iter().any(|(_, t)| *t == to) {
+                let stem = Path::new(name).file_stem().unwrap_or(name).to_string_lossy();
+                let ext = Path::new(name).extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+                to = unique_name(&dest, OsStr::new(&format!("{} ({}){}", stem, n, ext)));
+                n += 1;
+            }
+            items.push((member.to_path_buf(), to));
+        }
+        if items.is_empty() {
+            return Err(String::from("Rien à extraire"));
+        }
+        let (tx, rx) = mpsc::channel();
+        let (fs, count) = (mount.fs.clone(), items.len());
+        std::thread::spawn(move || {
+            let (icon, done) = if fs.writable() { ("🌐", "téléchargé(s)") } else { ("📦", "extrait(s)") };
+            let result = fs
+                .extract(&items)
+                .map(|n| format!("{} {} fichier(s) {} dans {}", icon, n, done, dest.display()))
+                .map_err(|e| format!("Extraction : {}", e));
+            let _ = tx.send(result);
+        });
+        explorer.extracting = Some(rx);
+        explorer.marked.clear();
+        Ok(count)
+    }
+
+    /// Outcome of the background extraction once it is over.
+    pub fn poll_extract(explorer: &mut FileExplorerState) -> Option<Result<String, String>> {
+        let result = match explorer.extracting.as_ref()?.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return None,
+            Err(mpsc::TryRecvError::Disconnected) => Err(String::from("Extraction interrompue")),
+        };
+        explorer.extracting = None;
+        Some(result)
+    }
+
+    /// Default archive name for the marked (or selected) entries.
+    pub fn archive_name(explorer: &FileExplorerState) -> String {
+        let targets = FileExplorerView::targets(explorer);
+        let base = match targets.as_slice() {
+            [one] => one.file_name(),
+            _ => explorer.cwd.file_name(),
+        };
+        format!("{}.zip", base.map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| String::from("archive")))
+    }
+
+    /// Start packing the marked (or selected) entries into `name` in the current
+    /// directory; the format comes from the extension.
+    pub fn compress(compress: &mut CompressState, explorer: &mut FileExplorerState, name: &str) -> Result<(), String> {
+        let Some(kind) = ArchiveKind::detect(Path::new(name)) else {
+            return Err(format!("{} : extension .zip, .tar.gz, .tgz ou .tar attendue", name));
+        };
+        let dest = explorer.cwd.join(name);
+        if fs::symlink_metadata(&dest).is_ok() {
+            return Err(format!("{} : existe déjà", dest.display()));
+        }
+        let sources = FileExplorerView::targets(explorer);
+        if sources.is_empty() {
+            return Err(String::from("Rien à compresser"));
+        }
+        if let Some(src) = sources.iter().find(|s| dest.starts_with(s)) {
+            return Err(format!("{} : l’archive ne peut pas être créée dans {}", name, src.display()));
+        }
+        let (tx, rx) = mpsc::channel();
+        let target = dest.clone();
+        std::thread::spawn(move || run_compress(target, kind, sources, tx));
+        *compress = CompressState { dest, running: Some(rx), ..CompressState::default() };
+        explorer.marked.clear();
+        Ok(())
+    }
+
+    /// Collect progress; once the archive is done, returns its summary or the error.
+    pub fn poll_compress(compress: &mut CompressState) -> Option<Result<String, String>> {
+        let rx = compress.running.as_ref()?;
+        loop {
+            match rx.try_recv() {
+                Ok(PasteEvent::Progress { done, total, current }) => {
+                    compress.done = done;
+                    compress.total = total;
+                    compress.current = current;
+                }
+                Ok(PasteEvent::Finished(errors)) => {
+                    compress.running = None;
+                    let size = fs::metadata(&compress.dest).map(|m| m.len()).unwrap_or(0);
+                    return Some(match errors.into_iter().next() {
+                        None => Ok(format!("📦 {} créé ({})", compress.dest.display(), human_bytes(size))),
+                        Some(e) => Err(e),
+                    });
+                }
+                Err(mpsc::TryRecvError::Empty) => return None,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    compress.running = None;
+                    return Some(Err(String::from("Compression interrompue")));
+                }
+            }
+        }
+    }
+
+    pub fn render_compress(f: &mut Frame, area: Rect, compress: &CompressState) {
+        f.render_widget(Clear, area);
+        let ratio = if compress.total == 0 { 0.0 } else { (compress.done as f64 / compress.total as f64).min(1.0) };
+        let name = compress.dest.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let gauge = Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title(format!("Compression de {} — {}  [Esc] Annuler", name, compress.current)))
+            .gauge_style(Style::default().fg(Color::Cyan))
+            .ratio(ratio)
+            .label(format!("{} / {}", human_bytes(compress.done), human_bytes(compress.total)));
+        f.render_widget(gauge, area);
+    }
+
+    /// Plan pasting the clipboard into the explorer's directory.
+    /// Returns false when there is nothing to paste.
+    pub fn begin_paste(paste: &mut PasteState, explorer: &FileExplorerState) -> bool {
+        let Some(clip) = explorer.clipboard.as_ref() else { return false; };
+        *paste = PasteState { cut: clip.cut, ..PasteState::default() };
+        for from in &clip.paths {
+            let Some(name) = from.file_name() else { continue; };
+            let mut to = explorer.cwd.join(name);
+            if to.starts_with(from) && to != *from {
+                paste.errors.push(format!("{}: un dossier ne peut pas être collé en lui-même", name.to_string_lossy()));
+                continue;
+            }
+            if to == *from {
+                // Coller au même endroit : une copie prend un nouveau nom, un déplacement ne fait rien
+                if clip.cut {
+                    continue;
+                }
+                to = unique_name(&explorer.cwd, name);
+            }
+            let item = PasteItem { from: from.clone(), to, overwrite: false };
+            if fs::symlink_metadata(&item.to).is_ok() {
+                paste.conflicts.push_back(item);
+            } else {
+                paste.plan.push(item);
+            }
+        }
+        Self::start_if_resolved(paste);
+        true
+    }
+
+    /// Apply a resolution to the first conflict (or to all of them).
+    pub fn resolve(paste: &mut PasteState, resolution: Resolution, all: bool) {
+        let count = if all { paste.conflicts.len() } else { 1 };
+        for mut item in paste.conflicts.drain(..count.min(paste.conflicts.len())) {
+            match resolution {
+                Resolution::Overwrite => item.overwrite = true,
+                Resolution::Rename => {
+                    let dir = item.to.parent().map(Path::to_path_buf).unwrap_or_default();
+                    item.to = unique_name(&dir, item.to.file_name().unwrap_or_default());
+                }
+                Resolution::Skip => continue,
+            }
+            paste.plan.push(item);
+        }
+        Self::start_if_resolved(paste);
+    }
+
+    fn start_if_resolved(paste: &mut PasteState) {
+        if !paste.conflicts.is_empty() || paste.running.is_some() {
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        let (items, cut) = (std::mem::take(&mut paste.plan), paste.cut);
+        std::thread::spawn(move || run_paste(items, cut, tx));
+        paste.running = Some(rx);
+    }
+
+    /// Collect progress; returns true when the paste just finished.
+    pub fn poll(paste: &mut PasteState) -> bool {
+        let Some(rx) = paste.running.as_ref() else { return false; };
+        loop {
+            match rx.try_recv() {
+                Ok(PasteEvent::Progress { done, total, current }) => {
+                    paste.done = done;
+                    paste.total = total;
+                    paste.current = current;
+                }
+                Ok(PasteEvent::Finished(errors)) => {
+                    paste.errors.extend(errors);
+                    paste.running = None;
+                    paste.finished = true;
+                    return true;
+                }
+                Err(mpsc::TryRecvError::Empty) => return false,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    paste.running = None;
+                    paste.finished = true;
+                    return true;
+                }
+            }
+        }
+    }
+
+    pub fn render(f: &mut Frame, area: Rect, paste: &PasteState) {
+        f.render_widget(Clear, area);
+        let verb = if paste.cut { "Déplacement" } else { "Copie" };
+        if let Some(item) = paste.conflicts.front() {
+            let text = vec![
+                Line::from(format!("« {} » existe déjà.", item.to.display())),
+                Line::from(format!("{} conflit(s) restant(s)", paste.conflicts.len())),
+                Line::from(""),
+                Line::from("[o] Écraser  [r] Renommer  [s] Ignorer  (O/R/S : tous)  [Esc] Annuler"),
+            ];
+            let block = Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Yellow)).title(verb);
+            f.render_widget(Paragraph::new(text).block(block), area);
+        } else if paste.finished {
+            let mut text: Vec<Line> = paste.errors.iter().map(|e| Line::styled(e.clone(), Style::default().fg(Color::Red))).collect();
+            text.push(Line::from(""));
+            text.push(Line::from("Appuie sur une touche pour fermer."));
+            let block = Block::default().borders(Borders::ALL).title(format!("{} terminée avec des erreurs", verb));
+            f.render_widget(Paragraph::new(text).block(block), area);
+        } else {
+            let ratio = if paste.total == 0 { 0.0 } else { (paste.done as f64 / paste.total as f64).min(1.0) };
+            let gauge = Gauge::default()
+                .block(Block::default().borders(Borders::ALL).title(format!("{} — {}  [Esc] Annuler", verb, paste.current)))
+                .gauge_style(Style::default().fg(Color::Cyan))
+                .ratio(ratio)
+                .label(format!("{} / {}", human_bytes(paste.done), human_bytes(paste.total)));
+            f.render_widget(gauge, area);
+        }
+    }
+}
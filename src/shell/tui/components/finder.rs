@@ -0,0 +1,174 @@
+//! Fuzzy file finder: jump to any file under the explorer root by name (Ctrl+O).
+//!
+//! The filename index is built lazily, the first time the finder opens, by a
+//! background walker that honours `.gitignore` / `.ignore` files and skips hidden
+//! entries. Paths stream in as they are found, so results appear while indexing.
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::shell::tui::components::explorer::SPINNER;
+use crate::shell::tui::state::FinderState;
+
+/// Stateless finder overlay and actions operating on FinderState.
+pub struct FinderView;
+
+/// Results kept after ranking.
+const MAX_RESULTS: usize = 200;
+/// Indexing stops after this many files (e.g. when the root is a whole home directory).
+const MAX_FILES: usize = 200_000;
+
+/// Walk `root` and send relative file paths in batches.
+fn index_files(root: PathBuf, tx: Sender<Vec<String>>) {
+    let mut batch = Vec::new();
+    let mut count = 0;
+    for entry in ignore::WalkBuilder::new(&root).build().flatten() {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let Ok(rel) = entry.path().strip_prefix(&root) else { continue; };
+        batch.push(rel.to_string_lossy().into_owned());
+        count += 1;
+        if batch.len() >= 1024 && tx.send(std::mem::take(&mut batch)).is_err() {
+            return;
+        }
+        if count >= MAX_FILES {
+            break;
+        }
+    }
+    let _ = tx.send(batch);
+}
+
+impl FinderView {
+    /// Reset the query; (re)build the index when the root changed or `reindex` is set.
+    pub fn open(state: &mut FinderState, root: &Path, reindex: bool) {
+        state.query.clear();
+        state.selected = 0;
+        if reindex || state.root.as_deref() != Some(root) {
+            state.root = Some(root.to_path_buf());
+            state.files.clear();
+            let (tx, rx) = mpsc::channel();
+            let dir = root.to_path_buf();
+            std::thread::spawn(move || index_files(dir, tx));
+            state.indexing = Some(rx);
+        }
+        Self::update_results(state);
+    }
+
+    /// Append indexed paths; results are re-ranked when new files arrived.
+    pub fn poll(state: &mut FinderState) {
+        let Some(rx) = state.indexing.as_ref() else { return; };
+        let mut received = false;
+        loop {
+            match rx.try_recv() {
+                Ok(batch) => {
+                    state.files.extend(batch);
+                    received = true;
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    state.indexing = None;
+                    break;
+                }
+            }
+        }
+        state.spinner = state.spinner.wrapping_add(1);
+        if received {
+            Self::update_results(state);
+        }
+    }
+
+    /// Rank indexed files against the query (best first). An empty query lists the first files.
+    pub fn update_results(state: &mut FinderState) {
+        if state.query.is_empty() {
+            state.results = (0..state.files.len().min(MAX_RESULTS)).map(|i| (i, Vec::new())).collect();
+        } else {
+            let matcher = SkimMatcherV2::default().ignore_case();
+            let mut scored: Vec<(i64, usize, Vec<usize>)> = state
+                .files
+                .iter()
+                .enumerate()
+                .filter_map(|(i, f)| matcher.fuzzy_indices(f, &state.query).map(|(score, idx)| (score, i, idx)))
+                .collect();
+            // Score décroissant, puis chemin le plus court
+            scored.sort_by(|a, b| b.0.cmp(&a.0).then(state.files[a.1].len().cmp(&state.files[b.1].len())));
+            scored.truncate(MAX_RESULTS);
+            state.results = scored.into_iter().map(|(_, i, idx)| (i, idx)).collect();
+        }
+        if state.selected >= state.results.len() {
+            state.selected = state.results.len().saturating_sub(1);
+        }
+    }
+
+    pub fn input(state: &mut FinderState, c: char) {
+        state.query.push(c);
+        state.selected = 0;
+        Self::update_results(state);
+    }
+
+    pub fn backspace(state: &mut FinderState) {
+        state.query.pop();
+        state.selected = 0;
+        Self::update_results(state);
+    }
+
+    pub fn move_by(state: &mut FinderState, delta: isize) {
+        let next = state.selected as isize + delta;
+        state.selected = next.clamp(0, state.results.len().saturating_sub(1) as isize) as usize;
+    }
+
+    /// Absolute path of the selected result.
+    pub fn selected_path(state: &FinderState) -> Option<PathBuf> {
+        let (i, _) = state.results.get(state.selected)?;
+        Some(state.root.as_ref()?.join(&state.files[*i]))
+    }
+
+    pub fn render(f: &mut Frame, area: Rect, state: &FinderState) {
+        f.render_widget(Clear, area);
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(3)])
+            .split(area);
+
+        let title = match state.indexing {
+            Some(_) => format!("Rechercher un fichier — {} indexés {}", state.files.len(), SPINNER[state.spinner % SPINNER.len()]),
+            None => format!("Rechercher un fichier — {} fichiers", state.files.len()),
+        };
+        let input = Paragraph::new(state.query.clone())
+            .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Yellow)).title(title));
+        f.render_widget(input, rows[0]);
+        f.set_cursor_position(Position { x: rows[0].x + 1 + state.query.chars().count() as u16, y: rows[0].y + 1 });
+
+        let height = rows[1].height.saturating_sub(2) as usize;
+        let start = (state.selected + 1).saturating_sub(height);
+        let items: Vec<ListItem> = state
+            .results
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(height)
+            .map(|(row, (i, hits))| {
+                let base = if row == state.selected { Style::default().fg(Color::Yellow) } else { Style::default() };
+                let hit = base.fg(Color::LightCyan).add_modifier(Modifier::BOLD);
+                let spans: Vec<Span> = state.files[*i]
+                    .chars()
+                    .enumerate()
+                    .map(|(ci, ch)| Span::styled(ch.to_string(), if hits.contains(&ci) { hit } else { base }))
+                    .collect();
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("[Entrée] Ouvrir  [↑/↓] Choisir  [Ctrl+R] Réindexer  [Esc] Fermer"));
+        f.render_widget(list, rows[1]);
+    }
+}
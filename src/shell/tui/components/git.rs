@@ -0,0 +1,205 @@
+//! Git screen: working tree status, staging, colored diffs and commit.
+//!
+//! Layout:
+//! - Left: status list (index/worktree columns + path)
+//! - Right: colored diff of the selected entry (staged then unstaged changes)
+//! - Bottom (while committing): commit message edited in an EditorState buffer
+use std::path::Path;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::shell::git;
+use crate::shell::tui::components::editor::EditorView;
+use crate::shell::tui::state::{EditorMode, EditorState, GitState};
+
+/// Stateless Git view and actions operating on GitState.
+pub struct GitView;
+
+/// Style for a single diff line (additions, deletions, hunks, headers).
+pub fn diff_line_style(line: &str) -> Style {
+    if line.starts_with("+++") || line.starts_with("---") || line.starts_with("diff ") || line.starts_with("index ") {
+        Style::default().fg(Color::White)
+    } else if line.starts_with('+') {
+        Style::default().fg(Color::Green)
+    } else if line.starts_with('-') {
+        Style::default().fg(Color::Red)
+    } else if line.starts_with("@@") {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default()
+    }
+}
+
+impl GitView {
+    /// (Re)load the repository status for `dir` (falling back to the process CWD),
+    /// keeping the selection in range.
+    pub fn refresh(state: &mut GitState, dir: &Path) {
+        state.root = git::repo_root(dir)
+            .or_else(|| std::env::current_dir().ok().and_then(|cwd| git::repo_root(&cwd)));
+        state.entries.clear();
+        state.diff.clear();
+        state.message_line = None;
+        let Some(root) = state.root.clone() else {
+            state.branch = None;
+            state.message_line = Some(format!("Pas de dépôt git dans {}", dir.display()));
+            return;
+        };
+        state.branch = git::current_branch(&root);
+        match git::status(&root) {
+            Ok(entries) => state.entries = entries,
+            Err(e) => state.message_line = Some(format!("git status: {}", e)),
+        }
+        if state.selected >= state.entries.len() {
+            state.selected = state.entries.len().saturating_sub(1);
+        }
+        Self::load_diff(state);
+    }
+
+    /// Load the diff of the selected entry (staged changes first, then worktree changes).
+    pub fn load_diff(state: &mut GitState) {
+        state.diff.clear();
+        state.diff_scroll = 0;
+        let (Some(root), Some(entry)) = (state.root.as_ref(), state.entries.get(state.selected)) else {
+            return;
+        };
+        let mut lines = Vec::new();
+        if entry.is_staged() {
+            match git::diff(root, entry, true) {
+                Ok(d) => lines.extend(d),
+                Err(e) => lines.push(format!("git diff --cached: {}", e)),
+            }
+        }
+        if entry.worktree != ' ' {
+            match git::diff(root, entry, false) {
+                Ok(d) => lines.extend(d),
+                Err(e) => lines.push(format!("git diff: {}", e)),
+            }
+        }
+        state.diff = lines;
+    }
+
+    pub fn move_up(state: &mut GitState) {
+        if state.selected > 0 {
+            state.selected -= 1;
+            Self::load_diff(state);
+        }
+    }
+
+    pub fn move_down(state: &mut GitState) {
+        if state.selected + 1 < state.entries.len() {
+            state.selected += 1;
+            Self::load_diff(state);
+        }
+    }
+
+    /// Stage the selected entry if it has unstaged changes, otherwise unstage it.
+    pub fn toggle_stage(state: &mut GitState) {
+        let (Some(root), Some(entry)) = (state.root.clone(), state.entries.get(state.selected).cloned()) else {
+            return;
+        };
+        let res = if entry.worktree != ' ' {
+            git::stage(&root, &entry.path)
+        } else {
+            git::unstage(&root, &entry.path)
+        };
+        Self::refresh(state, &root);
+        if let Err(e) = res {
+            state.message_line = Some(format!("{}: {}", entry.path, e));
+        }
+    }
+
+    /// Start writing a commit message in an editor buffer.
+    pub fn begin_commit(state: &mut GitState) {
+        if state.root.is_none() {
+            return;
+        }
+        if !state.entries.iter().any(|e| e.is_staged()) {
+            state.message_line = Some(String::from("Rien n’est indexé — [s] pour indexer un fichier."));
+            return;
+        }
+        let mut ed = EditorState::new_empty();
+        ed.mode = EditorMode::Insert;
+        state.message = Some(ed);
+    }
+
+    /// Commit the staged changes with the message buffer; keeps the buffer on failure.
+    pub fn finish_commit(state: &mut GitState) {
+        let (Some(root), Some(ed)) = (state.root.clone(), state.message.as_ref()) else {
+            return;
+        };
+        let message = ed.buffer.to_string();
+        if message.trim().is_empty() {
+            state.message_line = Some(String::from("Message de commit vide."));
+            return;
+        }
+        let result = match git::commit(&root, &message) {
+            Ok(summary) => {
+                state.message = None;
+                summary
+            }
+            Err(e) => format!("git commit: {}", e),
+        };
+        Self::refresh(state, &root);
+        state.message_line = Some(result);
+    }
+
+    /// Render the status list, the diff and (if active) the commit message editor.
+    pub fn render(f: &mut Frame, area: Rect, state: &GitState) {
+        let rows = if state.message.is_some() {
+            Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(5), Constraint::Length(10)])
+                .split(area)
+        } else {
+            Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(5)])
+                .split(area)
+        };
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(rows[0]);
+
+        let items: Vec<ListItem> = state
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let style = if i == state.selected { Style::default().fg(Color::Yellow) } else { Style::default() };
+                ListItem::new(Line::from(vec![
+                    Span::styled(e.index.to_string(), Style::default().fg(Color::Green)),
+                    Span::styled(e.worktree.to_string(), Style::default().fg(Color::Red)),
+                    Span::raw(" "),
+                    Span::styled(e.path.clone(), style),
+                ]))
+            })
+            .collect();
+        let branch = state.branch.clone().unwrap_or_else(|| String::from("?"));
+        let mut title = format!("Git — {}  ({} modifs)", branch, state.entries.len());
+        if let Some(msg) = &state.message_line {
+            title = format!("{} — {}", title, msg);
+        }
+        let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(list, cols[0]);
+
+        let lines: Vec<Line> = state
+            .diff
+            .iter()
+            .skip(state.diff_scroll)
+            .map(|l| Line::from(Span::styled(l.clone(), diff_line_style(l))))
+            .collect();
+        let diff = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Diff"));
+        f.render_widget(diff, cols[1]);
+
+        if let Some(ed) = &state.message {
+            EditorView::render_with_border(f, rows[1], ed, Style::default().fg(Color::Yellow));
+        }
+    }
+}
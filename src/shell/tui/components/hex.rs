@@ -0,0 +1,149 @@
+//! Hex viewer/editor for binary (non-UTF-8) files.
+//!
+//! Layout per row: offset, 16 bytes in hex, then their ASCII rendering.
+//! In edit mode, hex digits overwrite the byte under the cursor one nibble at a time.
+use std::io::Read;
+use std::path::Path;
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::shell::tui::encoding;
+use crate::shell::tui::state::{HexState, Screen};
+
+/// Bytes shown per row.
+pub const BYTES_PER_ROW: usize = 16;
+
+/// Stateless hex view and actions operating on HexState.
+pub struct HexView;
+
+/// True if the file looks binary: its first 8 KiB are not text in an encoding the
+/// editor reads (UTF-8, UTF-16 with BOM, Latin-1).
+pub fn is_binary(path: &Path) -> bool {
+    let mut head = Vec::new();
+    let Ok(file) = std::fs::File::open(path) else { return false; };
+    if file.take(8192).read_to_end(&mut head).is_err() {
+        return false;
+    }
+    encoding::looks_binary(&head)
+}
+
+impl HexView {
+    /// Load `path` into the hex view.
+    pub fn open(state: &mut HexState, path: &Path, from: Screen) {
+        *state = HexState { return_to: from, ..Default::default() };
+        state.path = Some(path.to_path_buf());
+        match std::fs::read(path) {
+            Ok(bytes) => state.bytes = bytes,
+            Err(e) => state.message_line = Some(format!("Lecture impossible: {}", e)),
+        }
+    }
+
+    /// Move the cursor by `delta` bytes, clamped to the file.
+    pub fn move_by(state: &mut HexState, delta: isize) {
+        let next = state.cursor as isize + delta;
+        state.cursor = next.clamp(0, state.bytes.len().saturating_sub(1) as isize) as usize;
+        state.low_nibble = false;
+    }
+
+    /// Overwrite the current nibble with hex digit `c`; advances after the low nibble.
+    pub fn input_hex(state: &mut HexState, c: char) {
+        let Some(d) = c.to_digit(16) else { return; };
+        let Some(byte) = state.bytes.get_mut(state.cursor) else { return; };
+        let d = d as u8;
+        *byte = if state.low_nibble { (*byte & 0xF0) | d } else { (*byte & 0x0F) | (d << 4) };
+        state.dirty = true;
+        if state.low_nibble {
+            state.low_nibble = false;
+            if state.cursor + 1 < state.bytes.len() {
+                state.cursor += 1;
+            }
+        } else {
+            state.low_nibble = true;
+        }
+    }
+
+    /// Write the bytes back to disk.
+    pub fn save(state: &mut HexState) {
+        let Some(path) = state.path.as_ref() else { return; };
+        state.message_line = Some(match std::fs::write(path, &state.bytes) {
+            Ok(()) => {
+                state.dirty = false;
+                format!("{} octets écrits", state.bytes.len())
+            }
+            Err(e) => format!("Écriture impossible: {}", e),
+        });
+    }
+
+    /// Render offset / hex / ASCII columns, keeping the cursor row visible.
+    pub fn render(f: &mut Frame, area: Rect, state: &mut HexState) {
+        let height = area.height.saturating_sub(2) as usize;
+        let cursor_row = state.cursor / BYTES_PER_ROW;
+        if cursor_row < state.scroll_row {
+            state.scroll_row = cursor_row;
+        } else if height > 0 && cursor_row >= state.scroll_row + height {
+            state.scroll_row = cursor_row + 1 - height;
+        }
+
+        let cursor_style = if state.editing {
+            Style::default().fg(Color::Black).bg(Color::LightRed)
+        } else {
+            Style::default().fg(Color::Black).bg(Color::Yellow)
+        };
+        let lines: Vec<Line> = state
+            .bytes
+            .chunks(BYTES_PER_ROW)
+            .enumerate()
+            .skip(state.scroll_row)
+            .take(height)
+            .map(|(row, chunk)| {
+                let base = row * BYTES_PER_ROW;
+                let mut spans = vec![Span::styled(format!("{:08x}  ", base), Style::default().fg(Color::DarkGray))];
+                for i in 0..BYTES_PER_ROW {
+                    let text = chunk.get(i).map(|b| format!("{:02x}", b)).unwrap_or_else(|| String::from("  "));
+                    let style = if base + i == state.cursor {
+                        cursor_style
+                    } else if chunk.get(i) == Some(&0) {
+                        Style::default().fg(Color::DarkGray)
+                    } else {
+                        Style::default()
+                    };
+                    spans.push(Span::styled(text, style));
+                    spans.push(Span::raw(if i == 7 { "  " } else { " " }));
+                }
+                spans.push(Span::styled(" │", Style::default().fg(Color::DarkGray)));
+                for (i, b) in chunk.iter().enumerate() {
+                    let ch = if b.is_ascii_graphic() || *b == b' ' { *b as char } else { '.' };
+                    let style = if base + i == state.cursor { cursor_style } else { Style::default().fg(Color::Cyan) };
+                    spans.push(Span::styled(ch.to_string(), style));
+                }
+                spans.push(Span::styled("│", Style::default().fg(Color::DarkGray)));
+                Line::from(spans)
+            })
+            .collect();
+
+        let name = state
+            .path
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut title = format!(
+            "{}{} — {} octets — 0x{:x}{}",
+            if state.dirty { "● " } else { "" },
+            name,
+            state.bytes.len(),
+            state.cursor,
+            if state.editing { " [ÉDITION]" } else { "" }
+        );
+        if let Some(msg) = &state.message_line {
+            title = format!("{} — {}", title, msg);
+        }
+        f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)), area);
+    }
+}
@@ -0,0 +1,110 @@
+//! Syntax highlighting with syntect's bundled grammars and themes.
+//!
+//! The syntax and theme sets are loaded once, on first use; prefer calling this
+//! from a background thread since that first load takes a moment. The same grammars
+//! list the definitions of a file (`symbols`): the names they scope `entity.name.*`.
+use std::path::Path;
+use std::sync::OnceLock;
+
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxReference, SyntaxSet};
+
+use crate::shell::tui::state::Symbol;
+
+const THEME: &str = "base16-ocean.dark";
+
+fn syntaxes() -> &'static SyntaxSet {
+    static SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme() -> &'static Theme {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    &THEMES.get_or_init(ThemeSet::load_defaults).themes[THEME]
+}
+
+/// Grammar matching `path` (extension, then first line).
+fn syntax_for<'a>(set: &'a SyntaxSet, path: &Path, text: &str) -> Option<&'a SyntaxReference> {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    set.find_syntax_by_extension(ext)
+        .or_else(|| set.find_syntax_by_first_line(text.lines().next().unwrap_or("")))
+}
+
+/// Whether a file type has a grammar, from its extension.
+pub fn has_grammar(path: &Path) -> bool {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    !ext.is_empty() && syntaxes().find_syntax_by_extension(ext).is_some()
+}
+
+/// Definitions in `text` (functions, types, classes, headings…): the names the
+/// grammar of `path` scopes `entity.name.<kind>`, HTML tags excepted.
+pub fn symbols(path: &Path, text: &str) -> Vec<Symbol> {
+    let set = syntaxes();
+    let Some(syntax) = syntax_for(set, path, text) else { return Vec::new(); };
+    let (name_scope, tag_scope) = (Scope::new("entity.name").expect("valid scope"), Scope::new("entity.name.tag").expect("valid scope"));
+    let mut parse = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+    let mut found: Vec<Symbol> = Vec::new();
+    for (row, line) in syntect::util::LinesWithEndings::from(text).enumerate() {
+        let Ok(ops) = parse.parse_line(line, set) else { break; };
+        let mut last = 0;
+        // Un nom découpé en plusieurs jetons est recollé
+        let mut joined_at: Option<usize> = None;
+        for i in 0..=ops.len() {
+            let pos = ops.get(i).map_or(line.len(), |(pos, _)| *pos);
+            let scope = stack.as_slice().iter().rev().find(|s| name_scope.is_prefix_of(**s) && !tag_scope.is_prefix_of(**s));
+            let token = line[last..pos].trim_end_matches(['\n', '\r']);
+            match scope {
+                Some(scope) if !token.trim().is_empty() => {
+                    match found.last_mut() {
+                        Some(sym) if joined_at == Some(last) => sym.name.push_str(token),
+                        _ => {
+                            let kind = scope.build_string().split('.').nth(2).unwrap_or("symbol").to_string();
+                            let col = line[..last].chars().count() + token.chars().take_while(|c| c.is_whitespace()).count();
+                            found.push(Symbol { name: token.to_string(), kind, path: path.to_path_buf(), row, col });
+                        }
+                    }
+                    joined_at = Some(pos);
+                }
+                _ => joined_at = None,
+            }
+            if let Some((_, op)) = ops.get(i) {
+                let _ = stack.apply(op);
+            }
+            last = pos;
+        }
+    }
+    for sym in &mut found {
+        sym.name = sym.name.trim().to_string();
+    }
+    found
+}
+
+/// Highlight `text` using the grammar matching `path` (extension, then first line).
+/// Unknown file types come back as plain lines.
+pub fn highlight(path: &Path, text: &str) -> Vec<Line<'static>> {
+    let set = syntaxes();
+    let Some(syntax) = syntax_for(set, path, text) else {
+        return text.lines().map(|l| Line::from(l.to_string())).collect();
+    };
+    let mut hl = HighlightLines::new(syntax, theme());
+    syntect::util::LinesWithEndings::from(text)
+        .map(|line| match hl.highlight_line(line, set) {
+            Ok(ranges) => Line::from(
+                ranges
+                    .into_iter()
+                    .map(|(style, s)| {
+                        let fg = Color::Rgb(style.foreground.r, style.foreground.g, style.foreground.b);
+                        Span::styled(s.trim_end_matches(['\n', '\r']).to_string(), Style::default().fg(fg))
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            Err(_) => Line::from(line.trim_end_matches(['\n', '\r']).to_string()),
+        })
+        .collect()
+}
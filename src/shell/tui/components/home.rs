@@ -39,8 +39,13 @@ impl HomeView {
             Line::from("2) Ouvrir les logs"),
             Line::from("3) Aide"),
             Line::from("4) Quitter"),
+            Line::from("5) Workspace"),
+            Line::from("6) Tableau de bord système"),
+            Line::from("7) Git"),
+            Line::from("8) Conteneurs"),
+            Line::from("9) Client HTTP"),
             Line::from(""),
-            Line::from("Astuce : vous pouvez aussi taper :l, :h, :q dans le shell."),
+            Line::from("Astuce : vous pouvez aussi taper :l, :h, :repl, :q dans le shell."),
         ];
 
         let p = Paragraph::new(lines)
@@ -0,0 +1,373 @@
+//! HTTP screen: request builder (method, URL, headers, body) and response viewer.
+//!
+//! Layout:
+//! - Left: saved requests (collection shared with the `http` built-in)
+//! - Top: method + URL, then headers and body editors side by side
+//! - Bottom: response status, headers and body (JSON pretty-printed, foldable)
+//!
+//! Requests are sent from a worker thread; the event loop polls the result.
+use std::collections::HashSet;
+use std::sync::mpsc;
+use std::thread;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+use serde_json::Value;
+
+use crate::shell::http::{self, HttpRequest, METHODS};
+use crate::shell::tui::components::dashboard::human_bytes;
+use crate::shell::tui::state::{EditorState, HttpField, HttpState};
+
+/// Stateless HTTP view and actions operating on HttpState.
+pub struct HttpView;
+
+const FIELDS: [HttpField; 6] = [
+    HttpField::Collection,
+    HttpField::Method,
+    HttpField::Url,
+    HttpField::Headers,
+    HttpField::Body,
+    HttpField::Response,
+];
+
+/// One displayed line of the response body; `fold` is the JSON path it (un)folds.
+struct BodyLine {
+    fold: Option<String>,
+    line: Line<'static>,
+}
+
+fn scalar_style(v: &Value) -> Style {
+    match v {
+        Value::String(_) => Style::default().fg(Color::Green),
+        Value::Number(_) => Style::default().fg(Color::Yellow),
+        Value::Bool(_) | Value::Null => Style::default().fg(Color::Magenta),
+        _ => Style::default(),
+    }
+}
+
+/// Flatten a JSON value into indented lines, honoring the folded paths.
+fn flatten_json(v: &Value, key: Option<String>, path: String, depth: usize, last: bool, folded: &HashSet<String>, out: &mut Vec<BodyLine>) {
+    let indent = "  ".repeat(depth);
+    let comma = if last { "" } else { "," };
+    let key_span = key.map(|k| Span::styled(format!("{}: ", k), Style::default().fg(Color::Cyan)));
+    let children: Vec<(Option<String>, String, &Value)> = match v {
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, c)| (Some(serde_json::to_string(k).unwrap_or_default()), format!("{}/{}", path, k), c))
+            .collect(),
+        Value::Array(items) => items.iter().enumerate().map(|(i, c)| (None, format!("{}/{}", path, i), c)).collect(),
+        _ => {
+            let mut spans = vec![Span::raw(format!("{}  ", indent))];
+            spans.extend(key_span);
+            spans.push(Span::styled(v.to_string(), scalar_style(v)));
+            spans.push(Span::raw(comma));
+            out.push(BodyLine { fold: None, line: Line::from(spans) });
+            return;
+        }
+    };
+    let (open, close) = if v.is_object() { ("{", "}") } else { ("[", "]") };
+    if children.is_empty() {
+        let mut spans = vec![Span::raw(format!("{}  ", indent))];
+        spans.extend(key_span);
+        spans.push(Span::raw(format!("{}{}{}", open, close, comma)));
+        out.push(BodyLine { fold: None, line: Line::from(spans) });
+        return;
+    }
+    let is_folded = folded.contains(&path);
+    let mut spans = vec![Span::raw(format!("{}{} ", indent, if is_folded { "▸" } else { "▾" }))];
+    spans.extend(key_span);
+    if is_folded {
+        spans.push(Span::raw(open));
+        spans.push(Span::styled(format!(" … {} ", children.len()), Style::default().fg(Color::DarkGray)));
+        spans.push(Span::raw(format!("{}{}", close, comma)));
+        out.push(BodyLine { fold: Some(path), line: Line::from(spans) });
+        return;
+    }
+    spans.push(Span::raw(open));
+    out.push(BodyLine { fold: Some(path), line: Line::from(spans) });
+    let n = children.len();
+    for (i, (k, p, c)) in children.into_iter().enumerate() {
+        flatten_json(c, k, p, depth + 1, i + 1 == n, folded, out);
+    }
+    out.push(BodyLine { fold: None, line: Line::from(format!("{}  {}{}", indent, close, comma)) });
+}
+
+/// Text of an editor buffer, one entry per line (without the trailing newline).
+fn buffer_lines(ed: &EditorState) -> Vec<String> {
+    ed.buffer
+        .lines()
+        .map(|l| l.to_string().trim_end_matches('\n').to_string())
+        .collect()
+}
+
+impl HttpView {
+    /// Reload the saved requests from disk.
+    pub fn reload_collection(state: &mut HttpState) {
+        state.saved = http::load_collection().into_iter().collect();
+        if state.saved_selected >= state.saved.len() {
+            state.saved_selected = state.saved.len().saturating_sub(1);
+        }
+    }
+
+    /// Request described by the builder fields.
+    pub fn current_request(state: &HttpState) -> HttpRequest {
+        HttpRequest {
+            method: METHODS[state.method].to_string(),
+            url: state.url.trim().to_string(),
+            headers: buffer_lines(&state.headers).iter().filter_map(|l| http::parse_header(l)).collect(),
+            body: state.body.buffer.to_string(),
+        }
+    }
+
+    /// Fill the builder with the selected saved request.
+    pub fn load_saved(state: &mut HttpState) {
+        let Some((name, req)) = state.saved.get(state.saved_selected).cloned() else {
+            return;
+        };
+        state.method = METHODS.iter().position(|m| m.eq_ignore_ascii_case(&req.method)).unwrap_or(0);
+        state.url = req.url;
+        let headers: Vec<String> = req.headers.iter().map(|(k, v)| format!("{}: {}", k, v)).collect();
+        state.headers = EditorState::new_empty();
+        state.headers.buffer = ropey::Rope::from_str(&headers.join("\n"));
+        state.body = EditorState::new_empty();
+        state.body.buffer = ropey::Rope::from_str(&req.body);
+        state.field = HttpField::Url;
+        state.message_line = Some(format!("« {} » chargée", name));
+    }
+
+    /// Save the current request in the collection under `name`.
+    pub fn save_as(state: &mut HttpState, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        state.message_line = Some(match http::save_request(name, &Self::current_request(state)) {
+            Ok(()) => format!("« {} » enregistrée", name),
+            Err(e) => format!("Enregistrement impossible: {}", e),
+        });
+        Self::reload_collection(state);
+    }
+
+    /// Send the current request on a worker thread.
+    pub fn send(state: &mut HttpState) {
+        if state.pending.is_some() {
+            return;
+        }
+        let req = Self::current_request(state);
+        if req.url.is_empty() {
+            state.message_line = Some(String::from("URL manquante"));
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(http::send(&req));
+        });
+        state.pending = Some(rx);
+        state.message_line = Some(String::from("Envoi…"));
+    }
+
+    /// Pick up the result of an in-flight request, if it arrived.
+    pub fn poll(state: &mut HttpState) {
+        let Some(rx) = state.pending.as_ref() else { return; };
+        let result = match rx.try_recv() {
+            Ok(r) => r,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => Err(String::from("requête interrompue")),
+        };
+        state.pending = None;
+        state.folded.clear();
+        state.response_selected = 0;
+        match result {
+            Ok(resp) => {
+                state.json = serde_json::from_str(&resp.body).ok();
+                state.response = Some(resp);
+                state.message_line = None;
+            }
+            Err(e) => {
+                state.json = None;
+                state.response = None;
+                state.message_line = Some(format!("Erreur: {}", e));
+            }
+        }
+    }
+
+    /// Move focus to the next (or previous) field.
+    pub fn cycle_field(state: &mut HttpState, forward: bool) {
+        let i = FIELDS.iter().position(|f| *f == state.field).unwrap_or(0);
+        let n = FIELDS.len();
+        state.field = FIELDS[if forward { (i + 1) % n } else { (i + n - 1) % n }];
+    }
+
+    pub fn cycle_method(state: &mut HttpState, forward: bool) {
+        let n = METHODS.len();
+        state.method = if forward { (state.method + 1) % n } else { (state.method + n - 1) % n };
+    }
+
+    fn body_lines(state: &HttpState) -> Vec<BodyLine> {
+        let mut out = Vec::new();
+        if let Some(json) = &state.json {
+            flatten_json(json, None, String::new(), 0, true, &state.folded, &mut out);
+        } else if let Some(resp) = &state.response {
+            out.extend(resp.body.lines().map(|l| BodyLine { fold: None, line: Line::from(l.to_string()) }));
+        }
+        out
+    }
+
+    /// Move the response selection by `delta` lines.
+    pub fn move_response(state: &mut HttpState, delta: isize) {
+        let len = Self::body_lines(state).len();
+        let next = state.response_selected as isize + delta;
+        state.response_selected = next.clamp(0, len.saturating_sub(1) as isize) as usize;
+    }
+
+    /// Fold or unfold the JSON object/array on the selected line.
+    pub fn toggle_fold(state: &mut HttpState) {
+        let lines = Self::body_lines(state);
+        if let Some(path) = lines.get(state.response_selected).and_then(|l| l.fold.clone())
+            && !state.folded.remove(&path)
+        {
+            state.folded.insert(path);
+        }
+    }
+
+    fn border(state: &HttpState, field: HttpField) -> Style {
+        if state.field == field { Style::default().fg(Color::Yellow) } else { Style::default() }
+    }
+
+    /// Render a small multi-line text field; places the cursor when focused.
+    fn render_text(f: &mut Frame, area: Rect, state: &HttpState, field: HttpField, ed: &EditorState, title: &str) {
+        let height = area.height.saturating_sub(2) as usize;
+        let start = (ed.cursor_row + 1).saturating_sub(height);
+        let lines: Vec<Line> = buffer_lines(ed).into_iter().skip(start).take(height).map(Line::from).collect();
+        let p = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).border_style(Self::border(state, field)).title(title.to_string()));
+        f.render_widget(p, area);
+        if state.field == field {
+            f.set_cursor_position(Position {
+                x: area.x + 1 + ed.cursor_col as u16,
+                y: area.y + 1 + (ed.cursor_row - start) as u16,
+            });
+        }
+    }
+
+    /// Render the collection, the request builder and the response.
+    pub fn render(f: &mut Frame, area: Rect, state: &HttpState) {
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(22), Constraint::Percentage(78)])
+            .split(area);
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Length(8), Constraint::Min(5)])
+            .split(cols[1]);
+        let top = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(11), Constraint::Min(10)])
+            .split(rows[0]);
+        let editors = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(rows[1]);
+
+        // Collection
+        let items: Vec<ListItem> = state
+            .saved
+            .iter()
+            .enumerate()
+            .map(|(i, (name, req))| {
+                let style = if i == state.saved_selected { Style::default().fg(Color::Yellow) } else { Style::default() };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:<7}", req.method), Style::default().fg(Color::Cyan)),
+                    Span::styled(name.clone(), style),
+                ]))
+            })
+            .collect();
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Self::border(state, HttpField::Collection))
+                .title("Collection"),
+        );
+        f.render_widget(list, cols[0]);
+
+        // Méthode + URL
+        let method = Paragraph::new(Span::styled(METHODS[state.method], Style::default().fg(Color::Cyan)))
+            .block(Block::default().borders(Borders::ALL).border_style(Self::border(state, HttpField::Method)).title("Méthode"));
+        f.render_widget(method, top[0]);
+        let url = Paragraph::new(state.url.as_str())
+            .block(Block::default().borders(Borders::ALL).border_style(Self::border(state, HttpField::Url)).title("URL"));
+        f.render_widget(url, top[1]);
+        if state.field == HttpField::Url {
+            f.set_cursor_position(Position { x: top[1].x + 1 + state.url.chars().count() as u16, y: top[1].y + 1 });
+        }
+
+        Self::render_text(f, editors[0], state, HttpField::Headers, &state.headers, "En-têtes (Clé: Valeur)");
+        Self::render_text(f, editors[1], state, HttpField::Body, &state.body, "Corps");
+
+        // Réponse
+        let mut title = match &state.response {
+            Some(r) => format!(
+                "Réponse — {} {} ({} ms, {})",
+                r.status,
+                r.status_text,
+                r.elapsed.as_millis(),
+                human_bytes(r.body.len() as u64)
+            ),
+            None => String::from("Réponse"),
+        };
+        if let Some(msg) = &state.message_line {
+            title = format!("{} — {}", title, msg);
+        }
+        let title_style = match state.response.as_ref().map(|r| r.status) {
+            Some(200..=299) => Style::default().fg(Color::Green),
+            Some(300..=399) => Style::default().fg(Color::Cyan),
+            Some(_) => Style::default().fg(Color::Red),
+            None => Style::default(),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Self::border(state, HttpField::Response))
+            .title(Span::styled(title, title_style));
+        let inner = block.inner(rows[2]);
+        f.render_widget(block, rows[2]);
+
+        let header_count = state.response.as_ref().map(|r| r.headers.len()).unwrap_or(0);
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(header_count.min(6) as u16), Constraint::Min(1)])
+            .split(inner);
+        if let Some(resp) = &state.response {
+            let headers: Vec<Line> = resp
+                .headers
+                .iter()
+                .map(|(k, v)| Line::from(vec![
+                    Span::styled(format!("{}: ", k), Style::default().fg(Color::DarkGray)),
+                    Span::raw(v.clone()),
+                ]))
+                .collect();
+            f.render_widget(Paragraph::new(headers), parts[0]);
+        }
+
+        let height = parts[1].height as usize;
+        let start = (state.response_selected + 1).saturating_sub(height);
+        let body: Vec<Line> = Self::body_lines(state)
+            .into_iter()
+            .enumerate()
+            .skip(start)
+            .take(height)
+            .map(|(i, l)| {
+                if i == state.response_selected && state.field == HttpField::Response {
+                    l.line.style(Style::default().bg(Color::DarkGray))
+                } else {
+                    l.line
+                }
+            })
+            .collect();
+        f.render_widget(Paragraph::new(body), parts[1]);
+    }
+}
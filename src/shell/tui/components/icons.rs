@@ -0,0 +1,106 @@
+//! File type icons for the explorer.
+//!
+//! Three built-in sets: `emoji` (default), `nerd` (Nerd Font glyphs) and `ascii`,
+//! picked with `style` under `[tui.icons]` in `config/theme.toml`. Terminals that
+//! draw neither emoji nor Nerd Font glyphs (`TERM=linux` or `dumb`) get the ascii
+//! set. Icons set in the config (per kind or per extension) win over the built-in set.
+use crate::shell::tui::state::{DirEntryView, IconStyle, IconTheme};
+
+/// Kind-level icons of a built-in set.
+struct KindIcons {
+    directory: &'static str,
+    file: &'static str,
+    symlink: &'static str,
+    git_repo: &'static str,
+}
+
+const EMOJI: KindIcons = KindIcons { directory: "📁", file: "📄", symlink: "🔗", git_repo: "🌱" };
+const NERD: KindIcons = KindIcons { directory: "\u{f07b}", file: "\u{f15b}", symlink: "\u{f481}", git_repo: "\u{e5fb}" };
+const ASCII: KindIcons = KindIcons { directory: "[d]", file: "[-]", symlink: "[@]", git_repo: "[g]" };
+
+/// Built-in icon for a (lowercase) extension.
+fn extension_icon(style: IconStyle, ext: &str) -> Option<&'static str> {
+    let icon = match style {
+        IconStyle::Emoji => match ext {
+            "rs" => "🦀",
+            "py" => "🐍",
+            "md" | "markdown" => "📝",
+            "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "bmp" | "ico" => "🎨",
+            "zip" | "tar" | "gz" | "tgz" | "xz" | "bz2" | "7z" | "rar" => "📦",
+            "pdf" => "📕",
+            "db" | "sqlite" | "sqlite3" | "sql" => "💾",
+            "csv" | "tsv" => "📊",
+            "html" | "htm" => "🌐",
+            "lock" => "🔒",
+            "mp3" | "wav" | "flac" | "ogg" => "🎵",
+            "mp4" | "mkv" | "webm" | "mov" | "avi" => "🎬",
+            _ => return None,
+        },
+        IconStyle::Nerd => match ext {
+            "rs" => "\u{e7a8}",
+            "py" => "\u{e606}",
+            "js" | "mjs" | "cjs" => "\u{e74e}",
+            "ts" | "tsx" => "\u{e628}",
+            "md" | "markdown" => "\u{e609}",
+            "json" => "\u{e60b}",
+            "toml" | "yaml" | "yml" | "ini" | "conf" => "\u{e615}",
+            "html" | "htm" => "\u{e736}",
+            "css" | "scss" => "\u{e749}",
+            "sh" | "bash" | "zsh" | "fish" => "\u{f489}",
+            "go" => "\u{e626}",
+            "c" | "h" => "\u{e61e}",
+            "cpp" | "cc" | "hpp" | "cxx" => "\u{e61d}",
+            "java" => "\u{e738}",
+            "lock" => "\u{f023}",
+            "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "bmp" | "ico" => "\u{f1c5}",
+            "zip" | "tar" | "gz" | "tgz" | "xz" | "bz2" | "7z" | "rar" => "\u{f410}",
+            "pdf" => "\u{f1c1}",
+            "txt" | "log" => "\u{f15c}",
+            "csv" | "tsv" => "\u{f1c3}",
+            "db" | "sqlite" | "sqlite3" | "sql" => "\u{f1c0}",
+            "mp3" | "wav" | "flac" | "ogg" => "\u{f1c7}",
+            "mp4" | "mkv" | "webm" | "mov" | "avi" => "\u{f1c8}",
+            _ => return None,
+        },
+        IconStyle::Ascii => return None,
+    };
+    Some(icon)
+}
+
+/// Style actually drawn: emoji and Nerd Font glyphs fall back to ascii on bare terminals.
+fn effective_style(style: IconStyle) -> IconStyle {
+    match std::env::var("TERM").as_deref() {
+        Ok("linux") | Ok("dumb") => IconStyle::Ascii,
+        _ => style,
+    }
+}
+
+/// Build the icon theme from the `[tui.icons]` config.
+pub fn theme(custom: crate::shell::config::IconConfig) -> IconTheme {
+    let style = custom.style.as_deref().and_then(IconStyle::parse).unwrap_or_default();
+    IconTheme { style: effective_style(style), custom }
+}
+
+/// Icon drawn before an explorer entry.
+pub fn icon<'a>(theme: &'a IconTheme, entry: &DirEntryView) -> &'a str {
+    let kinds = match theme.style {
+        IconStyle::Emoji => &EMOJI,
+        IconStyle::Nerd => &NERD,
+        IconStyle::Ascii => &ASCII,
+    };
+    let custom = &theme.custom;
+    if entry.is_symlink {
+        return custom.symlink.as_deref().unwrap_or(kinds.symlink);
+    }
+    if entry.is_repo {
+        return custom.git_repo.as_deref().unwrap_or(kinds.git_repo);
+    }
+    if entry.is_dir {
+        return custom.directory.as_deref().unwrap_or(kinds.directory);
+    }
+    let ext = entry.name.rsplit_once('.').map(|(_, e)| e.to_ascii_lowercase()).unwrap_or_default();
+    if let Some(icon) = custom.extensions.iter().find(|(k, _)| k.trim_start_matches('.').eq_ignore_ascii_case(&ext)).map(|(_, v)| v) {
+        return icon;
+    }
+    extension_icon(theme.style, &ext).or(custom.file.as_deref()).unwrap_or(kinds.file)
+}
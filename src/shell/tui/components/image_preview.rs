@@ -0,0 +1,302 @@
+//! Inline image preview for the explorer selection.
+//!
+//! Uses the terminal's graphics protocol when one is detected (kitty, iTerm2
+//! inline images, sixel) and falls back to a unicode half-block thumbnail
+//! (`▀` with truecolor foreground/background) otherwise.
+//!
+//! Graphics escapes bypass ratatui's buffer: the preview reserves an empty area
+//! during `draw`, then `after_draw` writes the escape sequence at that position
+//! (only when the image or its area changed).
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use image::{imageops::FilterType, DynamicImage, ImageFormat};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::shell::tui::state::FileExplorerState;
+
+/// Extensions previewed as images.
+const IMAGE_EXTENSIONS: [&str; 8] = ["png", "jpg", "jpeg", "gif", "bmp", "webp", "ico", "jfif"];
+
+/// True if the path looks like an image we can decode.
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| IMAGE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Path of the explorer selection if it is an image file.
+pub fn selected_image(explorer: &FileExplorerState) -> Option<PathBuf> {
+    explorer
+        .entries
+        .get(explorer.selected)
+        .filter(|e| !e.is_dir)
+        .map(|e| explorer.cwd.join(&e.name))
+        .filter(|p| is_image(p))
+}
+
+/// How images are drawn in this terminal.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Iterm,
+    Sixel,
+    HalfBlocks,
+}
+
+impl GraphicsProtocol {
+    /// Guess the protocol from the environment (`PASCHEK_GRAPHICS` overrides:
+    /// kitty, iterm, sixel or blocks).
+    pub fn detect() -> Self {
+        let var = |k: &str| std::env::var(k).unwrap_or_default().to_lowercase();
+        match var("PASCHEK_GRAPHICS").as_str() {
+            "kitty" => return Self::Kitty,
+            "iterm" => return Self::Iterm,
+            "sixel" => return Self::Sixel,
+            "blocks" => return Self::HalfBlocks,
+            _ => {}
+        }
+        let term = var("TERM");
+        let program = var("TERM_PROGRAM");
+        if term.contains("kitty") || std::env::var_os("KITTY_WINDOW_ID").is_some() || program == "ghostty" {
+            Self::Kitty
+        } else if program == "iterm.app" || program == "wezterm" {
+            Self::Iterm
+        } else if term.contains("sixel") || term == "foot" || term.starts_with("mlterm") || program == "contour" {
+            Self::Sixel
+        } else {
+            Self::HalfBlocks
+        }
+    }
+}
+
+/// Image preview pane; keeps the decoded image and what was last emitted.
+pub struct ImagePreview {
+    protocol: GraphicsProtocol,
+    loaded: Option<(PathBuf, Result<DynamicImage, String>)>,
+    /// Escape to write after the frame: (area, sequence)
+    pending: Option<(Rect, String)>,
+    /// Image and area currently on screen through a graphics protocol
+    emitted: Option<(PathBuf, Rect)>,
+    shown_this_frame: bool,
+}
+
+fn b64(data: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+/// Terminal cell size in pixels (falls back to 8x16).
+fn cell_pixels() -> (u32, u32) {
+    crossterm::terminal::window_size()
+        .ok()
+        .filter(|w| w.width > 0 && w.height > 0 && w.columns > 0 && w.rows > 0)
+        .map(|w| ((w.width / w.columns) as u32, (w.height / w.rows) as u32))
+        .unwrap_or((8, 16))
+}
+
+fn kitty_escape(img: &DynamicImage, area: Rect) -> String {
+    let mut png = Vec::new();
+    if img.write_to(&mut Cursor::new(&mut png), ImageFormat::Png).is_err() {
+        return String::new();
+    }
+    let data = b64(&png);
+    let chunks: Vec<&str> = data.as_bytes().chunks(4096).map(|c| std::str::from_utf8(c).unwrap_or("")).collect();
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        if i == 0 {
+            out.push_str(&format!("\x1b_Ga=T,f=100,q=2,c={},r={},m={};{}\x1b\\", area.width, area.height, more, chunk));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    out
+}
+
+fn iterm_escape(img: &DynamicImage, area: Rect) -> String {
+    let mut png = Vec::new();
+    if img.write_to(&mut Cursor::new(&mut png), ImageFormat::Png).is_err() {
+        return String::new();
+    }
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+        png.len(),
+        area.width,
+        area.height,
+        b64(&png)
+    )
+}
+
+/// Minimal sixel encoder using a 6x6x6 color cube.
+fn sixel_escape(img: &DynamicImage) -> String {
+    let rgb = img.to_rgb8();
+    let (w, h) = rgb.dimensions();
+    let level = |v: u8| (v as u32 * 5 + 127) / 255;
+    let index = |p: &image::Rgb<u8>| (level(p[0]) * 36 + level(p[1]) * 6 + level(p[2])) as usize;
+
+    let mut out = String::from("\x1bPq");
+    out.push_str(&format!("\"1;1;{};{}", w, h));
+    for i in 0..216u32 {
+        let (r, g, b) = (i / 36, (i / 6) % 6, i % 6);
+        out.push_str(&format!("#{};2;{};{};{}", i, r * 20, g * 20, b * 20));
+    }
+    for band in (0..h).step_by(6) {
+        let rows = (h - band).min(6);
+        // Couleurs présentes dans la bande → une passe par couleur
+        let mut used = [false; 216];
+        for y in band..band + rows {
+            for x in 0..w {
+                used[index(rgb.get_pixel(x, y))] = true;
+            }
+        }
+        for (color, _) in used.iter().enumerate().filter(|(_, u)| **u) {
+            out.push_str(&format!("#{}", color));
+            let mut run_char = '\0';
+            let mut run_len = 0;
+            let flush = |out: &mut String, c: char, n: usize| match n {
+                0 => {}
+                1..=3 => out.extend(std::iter::repeat_n(c, n)),
+                _ => out.push_str(&format!("!{}{}", n, c)),
+            };
+            for x in 0..w {
+                let mut bits = 0u8;
+                for dy in 0..rows {
+                    if index(rgb.get_pixel(x, band + dy)) == color {
+                        bits |= 1 << dy;
+                    }
+                }
+                let c = (63 + bits) as char;
+                if c == run_char {
+                    run_len += 1;
+                } else {
+                    flush(&mut out, run_char, run_len);
+                    run_char = c;
+                    run_len = 1;
+                }
+            }
+            flush(&mut out, run_char, run_len);
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+/// Half-block thumbnail: each cell shows two vertical pixels.
+fn half_block_lines(img: &DynamicImage, area: Rect) -> Vec<Line<'static>> {
+    let thumb = img.resize(area.width as u32, area.height as u32 * 2, FilterType::Triangle).to_rgb8();
+    let (w, h) = thumb.dimensions();
+    (0..h.div_ceil(2))
+        .map(|row| {
+            let spans: Vec<Span> = (0..w)
+                .map(|x| {
+                    let top = thumb.get_pixel(x, row * 2);
+                    let style = Style::default().fg(Color::Rgb(top[0], top[1], top[2]));
+                    if row * 2 + 1 < h {
+                        let bottom = thumb.get_pixel(x, row * 2 + 1);
+                        Span::styled("▀", style.bg(Color::Rgb(bottom[0], bottom[1], bottom[2])))
+                    } else {
+                        Span::styled("▀", style)
+                    }
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+impl ImagePreview {
+    pub fn new() -> Self {
+        Self {
+            protocol: GraphicsProtocol::detect(),
+            loaded: None,
+            pending: None,
+            emitted: None,
+            shown_this_frame: false,
+        }
+    }
+
+    /// Render the preview of `path` into `area` (call inside `terminal.draw`).
+    pub fn render(&mut self, f: &mut Frame, area: Rect, path: &Path) {
+        self.shown_this_frame = true;
+        if self.loaded.as_ref().map(|(p, _)| p.as_path()) != Some(path) {
+            let img = image::open(path).map_err(|e| e.to_string());
+            self.loaded = Some((path.to_path_buf(), img));
+        }
+        let Some((_, loaded)) = self.loaded.as_ref() else { return; };
+
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let title = match loaded {
+            Ok(img) => format!("Aperçu — {} ({}×{})", name, img.width(), img.height()),
+            Err(_) => format!("Aperçu — {}", name),
+        };
+        let block = Block::default().borders(Borders::ALL).title(title);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let img = match loaded {
+            Ok(img) => img,
+            Err(e) => {
+                f.render_widget(Paragraph::new(format!("Image illisible: {}", e)), inner);
+                return;
+            }
+        };
+        if self.protocol == GraphicsProtocol::HalfBlocks {
+            f.render_widget(Paragraph::new(half_block_lines(img, inner)), inner);
+            return;
+        }
+        if self.emitted.as_ref() == Some(&(path.to_path_buf(), inner)) {
+            return;
+        }
+        let (cw, ch) = cell_pixels();
+        let fitted = img.resize(inner.width as u32 * cw, inner.height as u32 * ch, FilterType::Triangle);
+        // Taille réelle en cellules après conservation du ratio
+        let cells = Rect {
+            width: fitted.width().div_ceil(cw).min(inner.width as u32) as u16,
+            height: fitted.height().div_ceil(ch).min(inner.height as u32) as u16,
+            ..inner
+        };
+        let escape = match self.protocol {
+            GraphicsProtocol::Kitty => kitty_escape(&fitted, cells),
+            GraphicsProtocol::Iterm => iterm_escape(&fitted, cells),
+            GraphicsProtocol::Sixel => sixel_escape(&fitted),
+            GraphicsProtocol::HalfBlocks => String::new(),
+        };
+        self.pending = Some((inner, escape));
+        self.emitted = Some((path.to_path_buf(), inner));
+    }
+
+    /// Write pending graphics escapes, or remove the image if it was not rendered this frame.
+    /// Returns true when the terminal must be fully redrawn to erase a sixel/iTerm image.
+    pub fn after_draw<W: Write>(&mut self, out: &mut W) -> std::io::Result<bool> {
+        let mut needs_clear = false;
+        if !self.shown_this_frame && self.emitted.take().is_some() {
+            if self.protocol == GraphicsProtocol::Kitty {
+                write!(out, "\x1b_Ga=d,q=2\x1b\\")?;
+                out.flush()?;
+            } else {
+                needs_clear = true;
+            }
+        }
+        self.shown_this_frame = false;
+        if let Some((area, escape)) = self.pending.take() {
+            if self.protocol == GraphicsProtocol::Kitty {
+                write!(out, "\x1b_Ga=d,q=2\x1b\\")?;
+            }
+            crossterm::queue!(out, crossterm::cursor::SavePosition, crossterm::cursor::MoveTo(area.x, area.y))?;
+            out.write_all(escape.as_bytes())?;
+            crossterm::queue!(out, crossterm::cursor::RestorePosition)?;
+            out.flush()?;
+        }
+        Ok(needs_clear)
+    }
+}
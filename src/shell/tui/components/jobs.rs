@@ -0,0 +1,73 @@
+//! Jobs overlay: lists commands started from the TUI with their status,
+//! and optionally the collected output of the selected job.
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::shell::tui::jobs::{JobManager, JobStatus};
+
+/// Stateless renderer for the `:jobs` overlay.
+pub struct JobsView;
+
+impl JobsView {
+    /// Render the job list (and the selected job's output when `show_output` is set).
+    pub fn render(f: &mut Frame, area: Rect, jobs: &JobManager, selected: usize, show_output: bool) {
+        f.render_widget(Clear, area);
+
+        let chunks = if show_output {
+            Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .split(area)
+        } else {
+            Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(3)])
+                .split(area)
+        };
+
+        let items: Vec<ListItem> = if jobs.jobs().is_empty() {
+            vec![ListItem::new("Aucun job.").style(Style::default().fg(Color::DarkGray))]
+        } else {
+            jobs.jobs()
+                .iter()
+                .enumerate()
+                .map(|(i, j)| {
+                    let fg = if jobs.foreground() == Some(j.id) { "*" } else { " " };
+                    let label = format!("{}[{}] {:<10} {}", fg, j.id, j.status.label(), j.command);
+                    let style = if i == selected {
+                        Style::default().fg(Color::Yellow)
+                    } else if j.status == JobStatus::Running {
+                        Style::default().fg(Color::Green)
+                    } else {
+                        Style::default()
+                    };
+                    ListItem::new(label).style(style)
+                })
+                .collect()
+        };
+
+        let title = format!(
+            "Jobs ({} en cours) — [Entrée] Sortie  [f] Premier plan  [K] Tuer  [c] Nettoyer  [Esc] Fermer",
+            jobs.running_count()
+        );
+        let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(list, chunks[0]);
+
+        if show_output {
+            let output = jobs.jobs().get(selected).map(|j| j.output()).unwrap_or_default();
+            let height = chunks[1].height.saturating_sub(2) as usize;
+            let lines: Vec<Line> = output
+                .iter()
+                .skip(output.len().saturating_sub(height))
+                .map(|l| Line::from(l.clone()))
+                .collect();
+            let p = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Output"));
+            f.render_widget(p, chunks[1]);
+        }
+    }
+}
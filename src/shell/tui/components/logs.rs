@@ -6,17 +6,29 @@ use ratatui::{
     Frame,
 };
 
+use crate::shell::config::{LogLevel, ShellConfig};
+
 /// Simple log panel that shows timestamped or raw entries, scrollable.
+/// Entries above the `log_level` of `config.toml` are dropped.
 pub struct LogPanel {
     entries: Vec<String>,
     scroll: usize,
+    level: LogLevel,
 }
 
 impl LogPanel {
     /// Create an empty log panel
-    pub fn new() -> Self { Self { entries: vec![], scroll: 0 } }
-    /// Append a log entry
-    pub fn add<S: Into<String>>(&mut self, s: S) { self.entries.push(s.into()); }
+    pub fn new() -> Self { Self { entries: vec![], scroll: 0, level: ShellConfig::get().log_level() } }
+    /// Append a log entry at `level`
+    fn log<S: Into<String>>(&mut self, level: LogLevel, s: S) {
+        if level <= self.level { self.entries.push(s.into()); }
+    }
+    /// Append an informational log entry
+    pub fn add<S: Into<String>>(&mut self, s: S) { self.log(LogLevel::Info, s); }
+    /// Append an error log entry (always kept)
+    pub fn error<S: Into<String>>(&mut self, s: S) { self.log(LogLevel::Error, s); }
+    /// Append a debug log entry
+    pub fn debug<S: Into<String>>(&mut self, s: S) { self.log(LogLevel::Debug, s); }
     /// Remove all log entries
     pub fn clear(&mut self) { self.entries.clear(); }
     /// Scroll one step up (older)
@@ -0,0 +1,254 @@
+//! Markdown preview: renders the current editor buffer (headings, lists, code blocks,
+//! links, quotes) next to or instead of the editor.
+//!
+//! The buffer is re-parsed on every frame, so the preview follows edits live.
+//! Its scroll position follows the editor's, proportionally to the rendered length.
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Parser, Tag, TagEnd};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::shell::tui::components::editor::EditorView;
+use crate::shell::tui::state::{EditorState, MarkdownPreview};
+
+/// Stateless Markdown preview renderer.
+pub struct MarkdownView;
+
+/// True if the editor holds a Markdown file.
+pub fn is_markdown(ed: &EditorState) -> bool {
+    ed.path
+        .as_ref()
+        .and_then(|p| p.extension())
+        .and_then(|e| e.to_str())
+        .map(|e| matches!(e.to_ascii_lowercase().as_str(), "md" | "markdown" | "mdown"))
+        .unwrap_or(false)
+}
+
+fn heading_style(level: HeadingLevel) -> Style {
+    let color = match level {
+        HeadingLevel::H1 => Color::LightCyan,
+        HeadingLevel::H2 => Color::LightBlue,
+        HeadingLevel::H3 => Color::LightMagenta,
+        _ => Color::White,
+    };
+    Style::default().fg(color).add_modifier(Modifier::BOLD)
+}
+
+/// Builds ratatui lines out of pulldown-cmark events.
+struct Renderer {
+    lines: Vec<Line<'static>>,
+    current: Vec<Span<'static>>,
+    styles: Vec<Style>,
+    /// One entry per open list: next number for ordered lists
+    lists: Vec<Option<u64>>,
+    quote_depth: usize,
+    in_code: bool,
+    link: Option<String>,
+}
+
+impl Renderer {
+    fn style(&self) -> Style {
+        self.styles.last().copied().unwrap_or_default()
+    }
+
+    fn push_style(&mut self, s: Style) {
+        let merged = self.style().patch(s);
+        self.styles.push(merged);
+    }
+
+    fn prefix(&self) -> String {
+        "▎ ".repeat(self.quote_depth)
+    }
+
+    fn flush(&mut self) {
+        if !self.current.is_empty() {
+            let spans = std::mem::take(&mut self.current);
+            self.lines.push(Line::from(spans));
+        }
+    }
+
+    fn blank(&mut self) {
+        self.flush();
+        if self.lines.last().is_some_and(|l| !l.spans.is_empty()) {
+            self.lines.push(Line::from(""));
+        }
+    }
+
+    fn text(&mut self, t: &str) {
+        if self.in_code {
+            for l in t.lines() {
+                self.lines.push(Line::from(vec![
+                    Span::styled(format!("{}  │ ", self.prefix()), Style::default().fg(Color::DarkGray)),
+                    Span::styled(l.to_string(), Style::default().fg(Color::Yellow)),
+                ]));
+            }
+            return;
+        }
+        if self.current.is_empty() && self.quote_depth > 0 {
+            self.current.push(Span::styled(self.prefix(), Style::default().fg(Color::DarkGray)));
+        }
+        self.current.push(Span::styled(t.to_string(), self.style()));
+    }
+
+    fn event(&mut self, ev: Event) {
+        match ev {
+            Event::Start(tag) => match tag {
+                Tag::Heading { level, .. } => {
+                    self.blank();
+                    self.current.push(Span::styled(format!("{} ", "#".repeat(level as usize)), Style::default().fg(Color::DarkGray)));
+                    self.push_style(heading_style(level));
+                }
+                Tag::Paragraph if self.lists.is_empty() => self.blank(),
+                Tag::BlockQuote(_) => {
+                    self.blank();
+                    self.quote_depth += 1;
+                    self.push_style(Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC));
+                }
+                Tag::CodeBlock(kind) => {
+                    self.blank();
+                    if let CodeBlockKind::Fenced(lang) = kind && !lang.is_empty() {
+                        self.lines.push(Line::from(Span::styled(format!("  ┌ {}", lang), Style::default().fg(Color::DarkGray))));
+                    }
+                    self.in_code = true;
+                }
+                Tag::List(start) => {
+                    if self.lists.is_empty() {
+                        self.blank();
+                    }
+                    self.flush();
+                    self.lists.push(start);
+                }
+                Tag::Item => {
+                    self.flush();
+                    let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                    let bullet = match self.lists.last_mut() {
+                        Some(Some(n)) => {
+                            *n += 1;
+                            format!("{}. ", *n - 1)
+                        }
+                        _ => String::from("• "),
+                    };
+                    self.current.push(Span::raw(format!("{}{}", self.prefix(), indent)));
+                    self.current.push(Span::styled(bullet, Style::default().fg(Color::Cyan)));
+                }
+                Tag::Emphasis => self.push_style(Style::default().add_modifier(Modifier::ITALIC)),
+                Tag::Strong => self.push_style(Style::default().add_modifier(Modifier::BOLD)),
+                Tag::Strikethrough => self.push_style(Style::default().add_modifier(Modifier::CROSSED_OUT)),
+                Tag::Link { dest_url, .. } => {
+                    self.link = Some(dest_url.to_string());
+                    self.push_style(Style::default().fg(Color::LightBlue).add_modifier(Modifier::UNDERLINED));
+                }
+                Tag::Image { dest_url, .. } => {
+                    self.text("🖼 ");
+                    self.link = Some(dest_url.to_string());
+                    self.push_style(Style::default().fg(Color::LightBlue));
+                }
+                _ => {}
+            },
+            Event::End(tag) => match tag {
+                TagEnd::Heading(_) => {
+                    self.styles.pop();
+                    self.flush();
+                }
+                TagEnd::Paragraph => self.flush(),
+                TagEnd::BlockQuote(_) => {
+                    self.flush();
+                    self.styles.pop();
+                    self.quote_depth = self.quote_depth.saturating_sub(1);
+                }
+                TagEnd::CodeBlock => {
+                    self.in_code = false;
+                    self.lines.push(Line::from(""));
+                }
+                TagEnd::List(_) => {
+                    self.flush();
+                    self.lists.pop();
+                }
+                TagEnd::Item => self.flush(),
+                TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+                    self.styles.pop();
+                }
+                TagEnd::Link | TagEnd::Image => {
+                    self.styles.pop();
+                    if let Some(url) = self.link.take() {
+                        self.current.push(Span::styled(format!(" ({})", url), Style::default().fg(Color::DarkGray)));
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(t) => self.text(&t),
+            Event::Code(c) => self.current.push(Span::styled(format!("`{}`", c), Style::default().fg(Color::Yellow))),
+            Event::SoftBreak => self.text(" "),
+            Event::HardBreak => self.flush(),
+            Event::Rule => {
+                self.blank();
+                self.lines.push(Line::from(Span::styled("─".repeat(40), Style::default().fg(Color::DarkGray))));
+            }
+            Event::TaskListMarker(done) => self.text(if done { "[x] " } else { "[ ] " }),
+            Event::Html(h) | Event::InlineHtml(h) => {
+                self.current.push(Span::styled(h.trim_end().to_string(), Style::default().fg(Color::DarkGray)));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Render Markdown source into styled lines.
+pub fn render_lines(src: &str) -> Vec<Line<'static>> {
+    let mut r = Renderer {
+        lines: Vec::new(),
+        current: Vec::new(),
+        styles: Vec::new(),
+        lists: Vec::new(),
+        quote_depth: 0,
+        in_code: false,
+        link: None,
+    };
+    let opts = pulldown_cmark::Options::ENABLE_STRIKETHROUGH | pulldown_cmark::Options::ENABLE_TASKLISTS;
+    for ev in Parser::new_ext(src, opts) {
+        r.event(ev);
+    }
+    r.flush();
+    while r.lines.first().is_some_and(|l| l.spans.is_empty()) {
+        r.lines.remove(0);
+    }
+    r.lines
+}
+
+impl MarkdownView {
+    /// Render the preview of `ed`, scrolled in step with the editor.
+    pub fn render(f: &mut Frame, area: Rect, ed: &EditorState, border: Style) {
+        let lines = render_lines(&ed.buffer.to_string());
+        let source_len = ed.buffer.len_lines().max(1);
+        let scroll = ed.scroll_row * lines.len() / source_len;
+        let p = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll as u16, 0))
+            .block(Block::default().borders(Borders::ALL).border_style(border).title("Aperçu Markdown"));
+        f.render_widget(p, area);
+    }
+
+    /// Render the editor and/or its preview according to `mode` (plain editor for non-Markdown files).
+    pub fn render_editor(f: &mut Frame, area: Rect, ed: &EditorState, border: Style, mode: MarkdownPreview) {
+        if !is_markdown(ed) {
+            EditorView::render_with_border(f, area, ed, border);
+            return;
+        }
+        match mode {
+            MarkdownPreview::Off => EditorView::render_with_border(f, area, ed, border),
+            MarkdownPreview::Split => {
+                let cols = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(area);
+                EditorView::render_with_border(f, cols[0], ed, border);
+                Self::render(f, cols[1], ed, Style::default());
+            }
+            MarkdownPreview::Full => Self::render(f, area, ed, border),
+        }
+    }
+}
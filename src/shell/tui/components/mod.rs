@@ -5,7 +5,37 @@
 //! logic lives in the parent `tui` module and `state` module.
 pub mod status;
 pub mod terminal;
+#[cfg(unix)]
+pub mod pty;
 pub mod logs;
 pub mod home;
 pub mod explorer;
-pub mod editor;
\ No newline at end of file
+pub mod editor;
+pub mod dashboard;
+pub mod git;
+pub mod jobs;
+pub mod containers;
+pub mod http;
+pub mod sqlite;
+pub mod markdown;
+pub mod image_preview;
+pub mod csv_view;
+pub mod hex;
+pub mod diff;
+pub mod scratchpad;
+pub mod tasks;
+pub mod finder;
+pub mod symbols;
+pub mod search;
+pub mod fileops;
+pub mod toast;
+pub mod highlight;
+pub mod preview;
+pub mod open_with;
+pub mod permissions;
+pub mod replace;
+pub mod tabs;
+pub mod unsaved;
+pub mod recover;
+pub mod conflict;
+pub mod icons;
@@ -0,0 +1,89 @@
+//! "Open with" menu for the selected explorer file (`o`).
+//!
+//! Lists the `open` built-in (desktop default application) followed by the
+//! programs configured under `[[open_with]]` in `config/explorer.toml`. Configured
+//! programs run as foreground jobs streamed into the Shell terminal pane.
+use std::path::{Path, PathBuf};
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem},
+    Frame,
+};
+
+use crate::shell::commands::open::system_opener;
+use crate::shell::config::OpenWithProgram;
+use crate::shell::tui::state::OpenWithState;
+
+/// Stateless menu and actions operating on OpenWithState.
+pub struct OpenWithView;
+
+impl OpenWithView {
+    /// Show the menu for `path`: the built-in first, then the programs that apply to its extension.
+    pub fn open(state: &mut OpenWithState, path: PathBuf) {
+        let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+        state.choices = std::iter::once(None)
+            .chain(
+                state
+                    .programs
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, p)| p.extensions.is_empty() || p.extensions.iter().any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(&ext)))
+                    .map(|(i, _)| Some(i)),
+            )
+            .collect();
+        state.selected = 0;
+        state.path = path;
+    }
+
+    pub fn move_by(state: &mut OpenWithState, delta: isize) {
+        let next = state.selected as isize + delta;
+        state.selected = next.clamp(0, state.choices.len().saturating_sub(1) as isize) as usize;
+    }
+
+    /// Selected configured program (None for the `open` built-in).
+    pub fn selected_program(state: &OpenWithState) -> Option<&OpenWithProgram> {
+        state.choices.get(state.selected).copied().flatten().map(|i| &state.programs[i])
+    }
+
+    /// Program and arguments for a configured command: `{}` is replaced by the
+    /// file path, which is appended when the command has no `{}`.
+    pub fn command_args(command: &str, path: &Path) -> Option<(String, Vec<String>)> {
+        let path = path.display().to_string();
+        let mut words = command.split_whitespace();
+        let program = words.next()?.to_string();
+        let mut args: Vec<String> = words.map(|w| w.replace("{}", &path)).collect();
+        if !command.contains("{}") {
+            args.push(path);
+        }
+        Some((program, args))
+    }
+
+    pub fn render(f: &mut Frame, area: Rect, state: &OpenWithState) {
+        f.render_widget(Clear, area);
+        let items: Vec<ListItem> = state
+            .choices
+            .iter()
+            .enumerate()
+            .map(|(i, choice)| {
+                let style = if i == state.selected { Style::default().fg(Color::Yellow) } else { Style::default() };
+                let (name, command) = match choice {
+                    None => (String::from("Application par défaut"), format!("open ({})", system_opener())),
+                    Some(p) => (state.programs[*p].name.clone(), state.programs[*p].command.clone()),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:<24}", name), style),
+                    Span::styled(command, Style::default().fg(Color::DarkGray)),
+                ]))
+            })
+            .collect();
+        let name = state.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title(format!("Ouvrir « {} » avec — [Entrée] Lancer  [Esc] Fermer", name));
+        f.render_widget(List::new(items).block(block), area);
+    }
+}
@@ -0,0 +1,133 @@
+//! Permissions editor overlay (`P` on an explorer entry).
+//!
+//! Shows the owner and group and the nine rwx bits as toggles with the octal
+//! value updated live; Enter applies the mode with chmod. Special bits (setuid,
+//! setgid, sticky) are kept as they are. Unix only.
+use std::path::{Path, PathBuf};
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::shell::tui::state::PermissionsState;
+
+/// Stateless permissions overlay and actions operating on PermissionsState.
+pub struct PermissionsView;
+
+const WHO: [&str; 3] = ["Propriétaire", "Groupe", "Autres"];
+const WHAT: [char; 3] = ['r', 'w', 'x'];
+
+/// Bit of the toggle at `cell` (row-major: user r/w/x, group r/w/x, other r/w/x).
+fn bit(cell: usize) -> u32 {
+    0o400 >> cell
+}
+
+/// `rwxr-xr-x` form of the permission bits.
+pub fn mode_string(mode: u32) -> String {
+    (0..9).map(|i| if mode & bit(i) != 0 { WHAT[i % 3] } else { '-' }).collect()
+}
+
+/// Name for `id` in a passwd/group style file, or the id itself.
+#[cfg(unix)]
+fn id_name(file: &str, id: u32) -> String {
+    std::fs::read_to_string(file)
+        .ok()
+        .and_then(|text| {
+            text.lines().find_map(|l| {
+                let mut fields = l.split(':');
+                let name = fields.next()?;
+                (fields.nth(1)?.parse::<u32>().ok()? == id).then(|| name.to_string())
+            })
+        })
+        .unwrap_or_else(|| id.to_string())
+}
+
+impl PermissionsView {
+    /// Load the mode and owner of `path` into the editor.
+    #[cfg(unix)]
+    pub fn open(state: &mut PermissionsState, path: &Path) -> Result<(), String> {
+        use std::os::unix::fs::MetadataExt;
+        let meta = std::fs::metadata(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mode = meta.mode() & 0o7777;
+        *state = PermissionsState {
+            path: path.to_path_buf(),
+            mode,
+            original: mode,
+            cursor: 0,
+            owner: format!("{} ({})", id_name("/etc/passwd", meta.uid()), meta.uid()),
+            group: format!("{} ({})", id_name("/etc/group", meta.gid()), meta.gid()),
+        };
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    pub fn open(_state: &mut PermissionsState, _path: &Path) -> Result<(), String> {
+        Err(String::from("permissions Unix non prises en charge sur ce système"))
+    }
+
+    /// Move the cursor in the 3×3 grid.
+    pub fn move_by(state: &mut PermissionsState, rows: isize, cols: isize) {
+        let row = (state.cursor / 3) as isize + rows;
+        let col = (state.cursor % 3) as isize + cols;
+        state.cursor = (row.clamp(0, 2) * 3 + col.clamp(0, 2)) as usize;
+    }
+
+    pub fn toggle(state: &mut PermissionsState) {
+        state.mode ^= bit(state.cursor);
+    }
+
+    /// chmod the file to the edited mode.
+    #[cfg(unix)]
+    pub fn apply(state: &PermissionsState) -> Result<PathBuf, String> {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&state.path, std::fs::Permissions::from_mode(state.mode))
+            .map(|_| state.path.clone())
+            .map_err(|e| format!("chmod {:04o} {}: {}", state.mode, state.path.display(), e))
+    }
+
+    #[cfg(not(unix))]
+    pub fn apply(_state: &PermissionsState) -> Result<PathBuf, String> {
+        Err(String::from("permissions Unix non prises en charge sur ce système"))
+    }
+
+    pub fn render(f: &mut Frame, area: Rect, state: &PermissionsState) {
+        f.render_widget(Clear, area);
+        let mut lines = vec![
+            Line::from(format!("Fichier : {}", state.path.display())),
+            Line::from(format!("Propriétaire : {}   Groupe : {}", state.owner, state.group)),
+            Line::from(""),
+            Line::from(format!("{:<14}  r    w    x", "")),
+        ];
+        for (row, who) in WHO.iter().enumerate() {
+            let mut spans = vec![Span::raw(format!("{:<14}", who))];
+            for col in 0..3 {
+                let cell = row * 3 + col;
+                let on = state.mode & bit(cell) != 0;
+                let mut style = if on { Style::default().fg(Color::Green) } else { Style::default().fg(Color::DarkGray) };
+                if cell == state.cursor {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                spans.push(Span::raw(" "));
+                spans.push(Span::styled(if on { "[✓]" } else { "[ ]" }, style));
+                spans.push(Span::raw(" "));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::from(""));
+        let changed = if state.mode != state.original { format!("   (avant : {:04o})", state.original) } else { String::new() };
+        lines.push(Line::from(vec![
+            Span::raw("Octal : "),
+            Span::styled(format!("{:04o}", state.mode), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::raw(format!("  {}{}", mode_string(state.mode), changed)),
+        ]));
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title("Permissions — [←↑↓→] Choisir  [Espace] Basculer  [Entrée] Appliquer  [Esc] Annuler");
+        f.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
@@ -0,0 +1,93 @@
+//! File preview: read-only, syntax highlighted view of the file selected in the
+//! Workspace explorer, shown in the right pane without opening a tab.
+//!
+//! Files are read and highlighted on a background thread; only the first
+//! `MAX_LINES` lines are shown.
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::shell::tui::components::highlight;
+use crate::shell::tui::state::PreviewState;
+
+/// Stateless preview pane operating on PreviewState.
+pub struct PreviewView;
+
+const MAX_LINES: usize = 200;
+
+/// First lines of `path`, highlighted; binary files get a one-line notice.
+fn load(path: &Path) -> Vec<Line<'static>> {
+    let file = match std::fs::File::open(path) {
+        Ok(f) => f,
+        Err(e) => return vec![Line::styled(format!("Lecture impossible : {}", e), Style::default().fg(Color::Red))],
+    };
+    let mut reader = BufReader::new(file);
+    let is_binary = reader.fill_buf().map(|head| head[..head.len().min(8192)].contains(&0)).unwrap_or(false);
+    if is_binary {
+        return vec![Line::styled("Fichier binaire — pas d’aperçu", Style::default().fg(Color::DarkGray))];
+    }
+    let mut text = String::new();
+    for line in reader.take(4 * 1024 * 1024).lines().take(MAX_LINES) {
+        let Ok(line) = line else { break; };
+        text.push_str(&line);
+        text.push('\n');
+    }
+    highlight::highlight(path, &text)
+}
+
+impl PreviewView {
+    /// Preview `path` (or nothing); loading starts only when the target changes.
+    pub fn request(state: &mut PreviewState, path: Option<PathBuf>) {
+        if state.path == path {
+            return;
+        }
+        state.lines.clear();
+        state.path = path.clone();
+        state.pending = path.map(|p| {
+            let (tx, rx) = mpsc::channel();
+            std::thread::spawn(move || {
+                let lines = load(&p);
+                let _ = tx.send(lines);
+            });
+            rx
+        });
+    }
+
+    /// Pick up the loaded preview; call on every UI tick.
+    pub fn poll(state: &mut PreviewState) {
+        let Some(rx) = state.pending.as_ref() else { return; };
+        match rx.try_recv() {
+            Ok(lines) => {
+                state.lines = lines;
+                state.pending = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => state.pending = None,
+        }
+    }
+
+    pub fn render(f: &mut Frame, area: Rect, state: &PreviewState, border: Style) {
+        let name = state
+            .path
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let title = if state.pending.is_some() {
+            format!("Aperçu — {} (chargement…)", name)
+        } else {
+            format!("Aperçu — {} (lecture seule, [Entrée] Ouvrir)", name)
+        };
+        let p = Paragraph::new(state.lines.clone())
+            .block(Block::default().borders(Borders::ALL).border_style(border).title(title));
+        f.render_widget(p, area);
+    }
+}
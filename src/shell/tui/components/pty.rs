@@ -0,0 +1,594 @@
+//! Pseudo-terminal backend of the TUI Shell screen.
+//!
+//! Interactive programs (`vim`, `top`, `ssh`…) need a real TTY: they are started on
+//! the slave side of a pseudo-terminal while a reader thread feeds everything they
+//! write to the master side into a small VT100/xterm screen emulator (built on
+//! `vte`). The TerminalPane draws that screen instead of its output while the
+//! program runs and forwards every key to it.
+//!
+//! Supported: cursor movement, erase, insert/delete of lines and chars, scroll
+//! regions, SGR colors (16, 256 and true color), the alternate screen, application
+//! cursor keys, and the cursor position / device attributes queries.
+//!
+//! Unix only (`openpty`); elsewhere the TUI runs these programs as plain jobs.
+
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::process::{Child, Command};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use vte::{Params, Parser, Perform};
+
+/// Programs started in a pseudo-terminal without `:pty`.
+pub const INTERACTIVE: &[&str] = &[
+    "vi", "vim", "nvim", "nano", "emacs", "micro", "hx", "top", "htop", "btop", "less", "more", "man", "ssh",
+    "python", "python3", "ipython", "node", "irb", "sqlite3", "psql", "mysql", "fzf", "tig", "lazygit", "tmux",
+];
+
+/// One character cell of the emulated screen.
+#[derive(Clone, Copy)]
+struct Cell {
+    ch: char,
+    style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self { ch: ' ', style: Style::default() }
+    }
+}
+
+/// Emulated screen fed by the program output.
+pub struct Screen {
+    rows: usize,
+    cols: usize,
+    grid: Vec<Vec<Cell>>,
+    /// Main screen kept aside while the alternate screen is shown
+    main: Option<Vec<Vec<Cell>>>,
+    row: usize,
+    col: usize,
+    saved: (usize, usize),
+    /// Next printed char goes to the start of the next line
+    wrap_pending: bool,
+    style: Style,
+    scroll_top: usize,
+    scroll_bottom: usize,
+    cursor_visible: bool,
+    app_cursor: bool,
+    /// Answers to the program's queries, written back to the PTY
+    responses: Vec<u8>,
+}
+
+impl Screen {
+    fn new(rows: usize, cols: usize) -> Self {
+        let (rows, cols) = (rows.max(1), cols.max(1));
+        Self {
+            rows,
+            cols,
+            grid: vec![vec![Cell::default(); cols]; rows],
+            main: None,
+            row: 0,
+            col: 0,
+            saved: (0, 0),
+            wrap_pending: false,
+            style: Style::default(),
+            scroll_top: 0,
+            scroll_bottom: rows - 1,
+            cursor_visible: true,
+            app_cursor: false,
+            responses: Vec::new(),
+        }
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize) {
+        let (rows, cols) = (rows.max(1), cols.max(1));
+        for grid in std::iter::once(&mut self.grid).chain(self.main.as_mut()) {
+            // Les lignes du haut disparaissent d'abord, comme dans un terminal
+            if grid.len() > rows {
+                grid.drain(..grid.len() - rows);
+            }
+            grid.resize(rows, vec![Cell::default(); cols]);
+            for line in grid.iter_mut() {
+                line.resize(cols, Cell::default());
+            }
+        }
+        self.rows = rows;
+        self.cols = cols;
+        self.row = self.row.min(rows - 1);
+        self.col = self.col.min(cols - 1);
+        self.scroll_top = 0;
+        self.scroll_bottom = rows - 1;
+        self.wrap_pending = false;
+    }
+
+    fn blank(&self) -> Cell {
+        // Les effacements gardent la couleur de fond courante
+        Cell { ch: ' ', style: Style { bg: self.style.bg, ..Style::default() } }
+    }
+
+    /// Scroll the lines of the scroll region up by `n` (new blank lines at the bottom).
+    fn scroll_up(&mut self, n: usize) {
+        let blank = vec![self.blank(); self.cols];
+        for _ in 0..n.min(self.scroll_bottom + 1 - self.scroll_top) {
+            self.grid.remove(self.scroll_top);
+            self.grid.insert(self.scroll_bottom, blank.clone());
+        }
+    }
+
+    /// Scroll the lines of the scroll region down by `n` (new blank lines at the top).
+    fn scroll_down(&mut self, n: usize) {
+        let blank = vec![self.blank(); self.cols];
+        for _ in 0..n.min(self.scroll_bottom + 1 - self.scroll_top) {
+            self.grid.remove(self.scroll_bottom);
+            self.grid.insert(self.scroll_top, blank.clone());
+        }
+    }
+
+    fn linefeed(&mut self) {
+        if self.row == self.scroll_bottom {
+            self.scroll_up(1);
+        } else if self.row + 1 < self.rows {
+            self.row += 1;
+        }
+    }
+
+    fn goto(&mut self, row: usize, col: usize) {
+        self.row = row.min(self.rows - 1);
+        self.col = col.min(self.cols - 1);
+        self.wrap_pending = false;
+    }
+
+    fn erase(&mut self, row: usize, cols: std::ops::Range<usize>) {
+        let blank = self.blank();
+        let end = cols.end.min(self.cols);
+        for cell in &mut self.grid[row][cols.start.min(end)..end] {
+            *cell = blank;
+        }
+    }
+
+    fn set_alternate(&mut self, on: bool) {
+        if on && self.main.is_none() {
+            let alt = vec![vec![Cell::default(); self.cols]; self.rows];
+            self.main = Some(std::mem::replace(&mut self.grid, alt));
+            self.saved = (self.row, self.col);
+        } else if !on && let Some(main) = self.main.take() {
+            self.grid = main;
+            let (row, col) = self.saved;
+            self.goto(row, col);
+        }
+    }
+
+    fn sgr(&mut self, params: &Params) {
+        let values: Vec<u16> = params.iter().flatten().copied().collect();
+        if values.is_empty() {
+            self.style = Style::default();
+            return;
+        }
+        let mut i = 0;
+        while i < values.len() {
+            let v = values[i];
+            i += 1;
+            match v {
+                0 => self.style = Style::default(),
+                1 => self.style = self.style.add_modifier(Modifier::BOLD),
+                2 => self.style = self.style.add_modifier(Modifier::DIM),
+                3 => self.style = self.style.add_modifier(Modifier::ITALIC),
+                4 => self.style = self.style.add_modifier(Modifier::UNDERLINED),
+                7 => self.style = self.style.add_modifier(Modifier::REVERSED),
+                22 => self.style = self.style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+                23 => self.style = self.style.remove_modifier(Modifier::ITALIC),
+                24 => self.style = self.style.remove_modifier(Modifier::UNDERLINED),
+                27 => self.style = self.style.remove_modifier(Modifier::REVERSED),
+                30..=37 => self.style.fg = Some(Color::Indexed((v - 30) as u8)),
+                90..=97 => self.style.fg = Some(Color::Indexed((v - 90 + 8) as u8)),
+                40..=47 => self.style.bg = Some(Color::Indexed((v - 40) as u8)),
+                100..=107 => self.style.bg = Some(Color::Indexed((v - 100 + 8) as u8)),
+                39 => self.style.fg = None,
+                49 => self.style.bg = None,
+                38 | 48 => {
+                    // 38;5;n (256 couleurs) ou 38;2;r;g;b (couleurs vraies)
+                    let color = match values.get(i) {
+                        Some(5) => values.get(i + 1).map(|&n| (Color::Indexed(n as u8), 2)),
+                        Some(2) => match values.get(i + 1..i + 4) {
+                            Some(&[r, g, b]) => Some((Color::Rgb(r as u8, g as u8, b as u8), 4)),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                    let Some((color, used)) = color else { break };
+                    i += used;
+                    if v == 38 {
+                        self.style.fg = Some(color);
+                    } else {
+                        self.style.bg = Some(color);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Screen lines with their colors; the cursor is drawn reversed.
+    pub fn lines(&self, show_cursor: bool) -> Vec<Line<'static>> {
+        self.grid
+            .iter()
+            .enumerate()
+            .map(|(r, row)| {
+                let mut spans: Vec<Span<'static>> = Vec::new();
+                let mut text = String::new();
+                let mut style = None;
+                for (c, cell) in row.iter().enumerate() {
+                    let mut cell_style = cell.style;
+                    if show_cursor && self.cursor_visible && (r, c) == (self.row, self.col) {
+                        cell_style = cell_style.add_modifier(Modifier::REVERSED);
+                    }
+                    if style != Some(cell_style) {
+                        if let Some(s) = style {
+                            spans.push(Span::styled(std::mem::take(&mut text), s));
+                        }
+                        style = Some(cell_style);
+                    }
+                    text.push(cell.ch);
+                }
+                if let Some(s) = style {
+                    spans.push(Span::styled(text, s));
+                }
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    /// Text of the main screen, without trailing blank lines.
+    pub fn text(&self) -> Vec<String> {
+        let grid = self.main.as_ref().unwrap_or(&self.grid);
+        let mut lines: Vec<String> = grid.iter().map(|row| row.iter().map(|c| c.ch).collect::<String>().trim_end().to_string()).collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        lines
+    }
+}
+
+/// First parameter of a CSI sequence (or `default` when missing or 0).
+fn arg(params: &Params, index: usize, default: usize) -> usize {
+    match params.iter().nth(index).and_then(|p| p.first()) {
+        Some(&0) | None => default,
+        Some(&n) => n as usize,
+    }
+}
+
+impl Perform for Screen {
+    fn print(&mut self, c: char) {
+        if self.wrap_pending {
+            self.wrap_pending = false;
+            self.col = 0;
+            self.linefeed();
+        }
+        self.grid[self.row][self.col] = Cell { ch: c, style: self.style };
+        if self.col + 1 < self.cols {
+            self.col += 1;
+        } else {
+            self.wrap_pending = true;
+        }
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            b'\n' | 0x0b | 0x0c => {
+                self.wrap_pending = false;
+                self.linefeed();
+            }
+            b'\r' => self.goto(self.row, 0),
+            0x08 => self.goto(self.row, self.col.saturating_sub(1)),
+            b'\t' => self.goto(self.row, (self.col / 8 + 1) * 8),
+            _ => {}
+        }
+    }
+
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], _ignore: bool, action: char) {
+        let private = intermediates.first() == Some(&b'?');
+        let n = arg(params, 0, 1);
+        match action {
+            'A' => self.goto(self.row.saturating_sub(n).max(self.scroll_top.min(self.row)), self.col),
+            'B' | 'e' => self.goto((self.row + n).min(if self.row <= self.scroll_bottom { self.scroll_bottom } else { self.rows - 1 }), self.col),
+            'C' | 'a' => self.goto(self.row, self.col + n),
+            'D' => self.goto(self.row, self.col.saturating_sub(n)),
+            'E' => self.goto(self.row + n, 0),
+            'F' => self.goto(self.row.saturating_sub(n), 0),
+            'G' | '`' => self.goto(self.row, n - 1),
+            'd' => self.goto(n - 1, self.col),
+            'H' | 'f' => self.goto(arg(params, 0, 1) - 1, arg(params, 1, 1) - 1),
+            'J' => {
+                let (row, col) = (self.row, self.col);
+                let rows = match arg(params, 0, 0) {
+                    0 => {
+                        self.erase(row, col..self.cols);
+                        row + 1..self.rows
+                    }
+                    1 => {
+                        self.erase(row, 0..col + 1);
+                        0..row
+                    }
+                    _ => 0..self.rows,
+                };
+                for r in rows {
+                    self.erase(r, 0..self.cols);
+                }
+            }
+            'K' => {
+                let (row, col) = (self.row, self.col);
+                match arg(params, 0, 0) {
+                    0 => self.erase(row, col..self.cols),
+                    1 => self.erase(row, 0..col + 1),
+                    _ => self.erase(row, 0..self.cols),
+                }
+            }
+            'L' | 'M' if (self.scroll_top..=self.scroll_bottom).contains(&self.row) => {
+                // Insertion / suppression de lignes : défilement de la zone sous le curseur
+                let top = self.scroll_top;
+                self.scroll_top = self.row;
+                if action == 'L' { self.scroll_down(n) } else { self.scroll_up(n) }
+                self.scroll_top = top;
+                self.col = 0;
+            }
+            '@' => {
+                let blank = self.blank();
+                let line = &mut self.grid[self.row];
+                for _ in 0..n.min(self.cols - self.col) {
+                    line.pop();
+                    line.insert(self.col, blank);
+                }
+            }
+            'P' => {
+                let blank = self.blank();
+                let line = &mut self.grid[self.row];
+                for _ in 0..n.min(self.cols - self.col) {
+                    line.remove(self.col);
+                    line.push(blank);
+                }
+            }
+            'X' => self.erase(self.row, self.col..self.col + n),
+            'S' => self.scroll_up(n),
+            'T' if !private => self.scroll_down(n),
+            'r' if !private => {
+                let top = arg(params, 0, 1) - 1;
+                let bottom = arg(params, 1, self.rows).min(self.rows) - 1;
+                if top < bottom {
+                    self.scroll_top = top;
+                    self.scroll_bottom = bottom;
+                }
+                self.goto(0, 0);
+            }
+            'm' if intermediates.is_empty() => self.sgr(params),
+            'h' | 'l' if private => {
+                let on = action == 'h';
+                for mode in params.iter().filter_map(|p| p.first()) {
+                    match mode {
+                        1 => self.app_cursor = on,
+                        25 => self.cursor_visible = on,
+                        47 | 1047 | 1049 => self.set_alternate(on),
+                        _ => {}
+                    }
+                }
+            }
+            'n' if arg(params, 0, 0) == 6 => {
+                let answer = format!("\x1b[{};{}R", self.row + 1, self.col + 1);
+                self.responses.extend_from_slice(answer.as_bytes());
+            }
+            'c' if intermediates.is_empty() => self.responses.extend_from_slice(b"\x1b[?1;2c"),
+            // Attributs secondaires (vim les demande au démarrage)
+            'c' if intermediates == b">" => self.responses.extend_from_slice(b"\x1b[>0;10;1c"),
+            's' if !private => self.saved = (self.row, self.col),
+            'u' => {
+                let (row, col) = self.saved;
+                self.goto(row, col);
+            }
+            _ => {}
+        }
+    }
+
+    fn esc_dispatch(&mut self, intermediates: &[u8], _ignore: bool, byte: u8) {
+        if !intermediates.is_empty() {
+            return;
+        }
+        match byte {
+            b'7' => self.saved = (self.row, self.col),
+            b'8' => {
+                let (row, col) = self.saved;
+                self.goto(row, col);
+            }
+            b'D' => self.linefeed(),
+            b'E' => {
+                self.linefeed();
+                self.col = 0;
+            }
+            b'M' => {
+                if self.row == self.scroll_top {
+                    self.scroll_down(1);
+                } else {
+                    self.goto(self.row.saturating_sub(1), self.col);
+                }
+            }
+            b'c' => *self = Screen::new(self.rows, self.cols),
+            _ => {}
+        }
+    }
+}
+
+/// Interactive program running in a pseudo-terminal.
+pub struct PtySession {
+    pub command: String,
+    child: Child,
+    master: File,
+    screen: Arc<Mutex<Screen>>,
+    size: (u16, u16),
+}
+
+/// Open a pseudo-terminal of `rows` × `cols`; returns (master, slave).
+fn open_pty(rows: u16, cols: u16) -> io::Result<(File, File)> {
+    use std::os::fd::FromRawFd;
+    let (mut master, mut slave) = (0, 0);
+    let size = libc::winsize { ws_row: rows, ws_col: cols, ws_xpixel: 0, ws_ypixel: 0 };
+    // SAFETY: openpty remplit deux descripteurs que l'on possède ensuite
+    unsafe {
+        if libc::openpty(&mut master, &mut slave, std::ptr::null_mut(), std::ptr::null(), &size) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok((File::from_raw_fd(master), File::from_raw_fd(slave)))
+    }
+}
+
+impl PtySession {
+    /// Start `program args` on a new pseudo-terminal of `rows` × `cols`.
+    pub fn spawn(line: &str, program: &str, args: &[&str], rows: u16, cols: u16) -> io::Result<Self> {
+        use std::os::unix::process::CommandExt;
+        let (master, slave) = open_pty(rows, cols)?;
+        let mut command = Command::new(program);
+        command
+            .args(args)
+            .env("TERM", "xterm-256color")
+            .stdin(slave.try_clone()?)
+            .stdout(slave.try_clone()?)
+            .stderr(slave);
+        // SAFETY: seuls des appels async-signal-safe entre fork et exec
+        unsafe {
+            command.pre_exec(|| {
+                // Nouvelle session dont le PTY devient le terminal de contrôle
+                if libc::setsid() < 0 || libc::ioctl(0, libc::TIOCSCTTY, 0) < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        let child = command.spawn()?;
+        // `command` garde des copies de l'esclave : le lecteur ne verrait jamais la fin
+        drop(command);
+
+        let screen = Arc::new(Mutex::new(Screen::new(rows as usize, cols as usize)));
+        let mut reader = master.try_clone()?;
+        let sink = screen.clone();
+        thread::spawn(move || {
+            let mut parser = Parser::new();
+            let mut buf = [0u8; 8192];
+            // EIO quand le programme se termine et ferme l'esclave
+            while let Ok(n) = reader.read(&mut buf) {
+                if n == 0 {
+                    break;
+                }
+                if let Ok(mut screen) = sink.lock() {
+                    parser.advance(&mut *screen, &buf[..n]);
+                }
+            }
+        });
+        Ok(Self { command: line.to_string(), child, master, screen, size: (rows, cols) })
+    }
+
+    /// Follow the size of the pane.
+    pub fn resize(&mut self, rows: u16, cols: u16) {
+        if (rows, cols) == self.size || rows == 0 || cols == 0 {
+            return;
+        }
+        self.size = (rows, cols);
+        if let Ok(mut screen) = self.screen.lock() {
+            screen.resize(rows as usize, cols as usize);
+        }
+        use std::os::fd::AsRawFd;
+        let size = libc::winsize { ws_row: rows, ws_col: cols, ws_xpixel: 0, ws_ypixel: 0 };
+        // SAFETY: ioctl sur notre propre descripteur maître ; le noyau envoie SIGWINCH
+        unsafe { libc::ioctl(self.master.as_raw_fd(), libc::TIOCSWINSZ, &size) };
+    }
+
+    /// Exit code once the program has ended (-1 when killed by a signal); answers
+    /// its pending queries otherwise.
+    pub fn poll(&mut self) -> Option<i32> {
+        if let Some(status) = self.child.try_wait().ok().flatten() {
+            return Some(status.code().unwrap_or(-1));
+        }
+        let responses = self.screen.lock().map(|mut s| std::mem::take(&mut s.responses)).unwrap_or_default();
+        if !responses.is_empty() {
+            let _ = self.master.write_all(&responses);
+        }
+        None
+    }
+
+    /// Send bytes typed or pasted by the user.
+    pub fn write(&mut self, bytes: &[u8]) {
+        let _ = self.master.write_all(bytes);
+    }
+
+    /// Forward a key press.
+    pub fn send_key(&mut self, key: KeyEvent) {
+        let app_cursor = self.screen.lock().map(|s| s.app_cursor).unwrap_or(false);
+        let bytes = key_bytes(key, app_cursor);
+        self.write(&bytes);
+    }
+
+    /// Colored screen lines.
+    pub fn lines(&self) -> Vec<Line<'static>> {
+        self.screen.lock().map(|s| s.lines(true)).unwrap_or_default()
+    }
+
+    /// Text left on the main screen (copied to the output when the program ends).
+    pub fn text(&self) -> Vec<String> {
+        self.screen.lock().map(|s| s.text()).unwrap_or_default()
+    }
+}
+
+impl Drop for PtySession {
+    /// Closing the pane must not leave the program behind.
+    fn drop(&mut self) {
+        if self.child.try_wait().ok().flatten().is_none() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+/// Bytes an xterm sends for `key`.
+fn key_bytes(key: KeyEvent, app_cursor: bool) -> Vec<u8> {
+    let cursor = |c: char| if app_cursor { format!("\x1bO{}", c) } else { format!("\x1b[{}", c) };
+    let mut bytes = match key.code {
+        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) => match c.to_ascii_lowercase() {
+            c @ 'a'..='z' => vec![c as u8 & 0x1f],
+            ' ' | '@' => vec![0],
+            '[' => vec![0x1b],
+            '\\' => vec![0x1c],
+            ']' => vec![0x1d],
+            '^' => vec![0x1e],
+            '_' => vec![0x1f],
+            c => c.to_string().into_bytes(),
+        },
+        KeyCode::Char(c) => c.to_string().into_bytes(),
+        KeyCode::Enter => vec![b'\r'],
+        KeyCode::Backspace => vec![0x7f],
+        KeyCode::Tab => vec![b'\t'],
+        KeyCode::BackTab => b"\x1b[Z".to_vec(),
+        KeyCode::Esc => vec![0x1b],
+        KeyCode::Up => cursor('A').into_bytes(),
+        KeyCode::Down => cursor('B').into_bytes(),
+        KeyCode::Right => cursor('C').into_bytes(),
+        KeyCode::Left => cursor('D').into_bytes(),
+        KeyCode::Home => cursor('H').into_bytes(),
+        KeyCode::End => cursor('F').into_bytes(),
+        KeyCode::Insert => b"\x1b[2~".to_vec(),
+        KeyCode::Delete => b"\x1b[3~".to_vec(),
+        KeyCode::PageUp => b"\x1b[5~".to_vec(),
+        KeyCode::PageDown => b"\x1b[6~".to_vec(),
+        KeyCode::F(n @ 1..=4) => format!("\x1bO{}", (b'P' + n - 1) as char).into_bytes(),
+        KeyCode::F(n @ 5..=12) => {
+            let code = [15, 17, 18, 19, 20, 21, 23, 24][(n - 5) as usize];
+            format!("\x1b[{}~", code).into_bytes()
+        }
+        _ => Vec::new(),
+    };
+    if key.modifiers.contains(KeyModifiers::ALT) && !bytes.is_empty() {
+        bytes.insert(0, 0x1b);
+    }
+    bytes
+}
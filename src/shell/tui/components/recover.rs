@@ -0,0 +1,41 @@
+//! Swap file recovery prompt, shown when a file is opened while a swap copy newer
+//! than the file remains from a previous session: [r] loads the copy into the
+//! buffer, [d] deletes it, Esc keeps the file as saved.
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::shell::tui::state::EditorState;
+
+/// Stateless renderer for the recovery prompt.
+pub struct RecoverView;
+
+impl RecoverView {
+    /// Render the file, the time of its swap copy and the choices.
+    pub fn render(f: &mut Frame, area: Rect, ed: &EditorState, swapped_at: SystemTime) {
+        f.render_widget(Clear, area);
+        let name = ed.path.as_ref().map(|p| p.display().to_string()).unwrap_or_default();
+        let when = DateTime::<Local>::from(swapped_at).format("%d/%m/%Y %H:%M:%S");
+        let lines = vec![
+            Line::from(Span::styled(name, Style::default().add_modifier(Modifier::BOLD))),
+            Line::from(""),
+            Line::from(format!("Une copie de secours du {} contient des modifications non enregistrées.", when)),
+            Line::from(""),
+            Line::from(Span::styled(
+                "[r] Récupérer  [d] Supprimer la copie  [Esc] Ignorer",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ];
+        let widget = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title("Récupération"));
+        f.render_widget(widget, area);
+    }
+}
@@ -0,0 +1,240 @@
+//! Search and replace in the editor buffer (`:s` / `:%s` in the command line, Ctrl+R).
+//!
+//! Syntax: `[%]s/pattern/replacement/[g][c][i]`. `%` covers the whole buffer (else
+//! the cursor line), `g` replaces every match of a line (else the first one), `i`
+//! ignores case and `c` asks for each match: [y] replace, [n] skip, [a] all the
+//! remaining ones, [q] / Esc stop. The pattern is a regex and the replacement may use
+//! its groups (`$1`, `${name}`); `\/` stands for a literal delimiter. However many
+//! matches it replaces, one run is undone in a single step.
+use std::ops::Range;
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use regex::RegexBuilder;
+
+use crate::shell::tui::state::{EditorState, ReplaceState};
+
+use super::editor::EditorView;
+
+const SYNTAX: &str = "syntaxe : [%]s/motif/remplacement/[gci]";
+
+/// Search-and-replace helpers and the confirmation popup.
+pub struct ReplaceView;
+
+/// Text of a buffer line, without its line break.
+fn line_text(ed: &EditorState, row: usize) -> String {
+    let mut text = ed.buffer.line(row).to_string();
+    if text.ends_with('\n') {
+        text.pop();
+    }
+    text
+}
+
+/// Byte offset of the char column `col` (the end of `text` past the last char).
+fn byte_of(text: &str, col: usize) -> usize {
+    text.char_indices().nth(col).map_or(text.len(), |(i, _)| i)
+}
+
+/// Split on `delim`; `\delim` keeps a literal delimiter, other escapes are left to the regex.
+fn split_escaped(s: &str, delim: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        let part = parts.last_mut().expect("never empty");
+        match c {
+            '\\' => match chars.next() {
+                Some(next) if next == delim => part.push(next),
+                Some(next) => {
+                    part.push('\\');
+                    part.push(next);
+                }
+                None => part.push('\\'),
+            },
+            c if c == delim => parts.push(String::new()),
+            c => part.push(c),
+        }
+    }
+    parts
+}
+
+impl ReplaceView {
+    /// Whether an editor command line is a substitute command (`s/…`, `%s/…`).
+    pub fn is_command(cmd: &str) -> bool {
+        let rest = cmd.strip_prefix('%').unwrap_or(cmd);
+        rest.strip_prefix('s')
+            .and_then(|r| r.chars().next())
+            .is_some_and(|c| !c.is_alphanumeric() && !c.is_whitespace())
+    }
+
+    /// Parse a substitute command for `ed`: the session, and whether each match is confirmed (`c`).
+    /// An empty pattern reuses the last search.
+    pub fn parse(cmd: &str, ed: &EditorState) -> Result<(ReplaceState, bool), String> {
+        let cmd = cmd.trim();
+        let (whole, rest) = match cmd.strip_prefix('%') {
+            Some(rest) => (true, rest),
+            None => (false, cmd),
+        };
+        let rest = rest.strip_prefix('s').ok_or_else(|| SYNTAX.to_string())?;
+        let delim = rest
+            .chars()
+            .next()
+            .filter(|c| !c.is_alphanumeric() && !c.is_whitespace())
+            .ok_or_else(|| SYNTAX.to_string())?;
+        let parts = split_escaped(&rest[delim.len_utf8()..], delim);
+        if !(2..=3).contains(&parts.len()) {
+            return Err(SYNTAX.to_string());
+        }
+        let pattern = match (parts[0].as_str(), ed.last_search.as_deref()) {
+            ("", Some(last)) if !last.is_empty() => last.to_string(),
+            ("", _) => return Err(String::from("motif vide")),
+            (p, _) => p.to_string(),
+        };
+        let (mut global, mut confirm, mut ignore_case) = (false, false, false);
+        for flag in parts.get(2).map(String::as_str).unwrap_or_default().chars() {
+            match flag {
+                'g' => global = true,
+                'c' => confirm = true,
+                'i' => ignore_case = true,
+                other => return Err(format!("option inconnue : {}", other)),
+            }
+        }
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map_err(|e| format!("motif invalide : {}", e))?;
+        let (first, last) = if whole { (0, ed.buffer.len_lines().saturating_sub(1)) } else { (ed.cursor_row, ed.cursor_row) };
+        let session = ReplaceState {
+            regex,
+            replacement: parts[1].clone(),
+            global,
+            row: first,
+            col: 0,
+            last_row: last,
+            hit: None,
+            replaced: 0,
+        };
+        Ok((session, confirm))
+    }
+
+    /// Find the next match from the session position and put the cursor on it;
+    /// false when none is left.
+    pub fn find_next(ed: &mut EditorState, rs: &mut ReplaceState) -> bool {
+        rs.hit = None;
+        while rs.row <= rs.last_row && rs.row < ed.buffer.len_lines() {
+            let text = line_text(ed, rs.row);
+            if rs.col <= text.chars().count()
+                && let Some(m) = rs.regex.find_at(&text, byte_of(&text, rs.col))
+            {
+                let start = text[..m.start()].chars().count();
+                let range = start..start + m.as_str().chars().count();
+                let at = ed.buffer.line_to_char(rs.row) + start;
+                rs.hit = Some((rs.row, range));
+                EditorView::set_cursor_char(ed, at);
+                return true;
+            }
+            rs.row += 1;
+            rs.col = 0;
+        }
+        false
+    }
+
+    /// Replacement text for the current match, with its groups expanded.
+    pub fn expansion(ed: &EditorState, rs: &ReplaceState) -> Option<String> {
+        let (row, range) = rs.hit.as_ref()?;
+        let text = line_text(ed, *row);
+        let caps = rs.regex.captures_at(&text, byte_of(&text, range.start))?;
+        let mut out = String::new();
+        caps.expand(&rs.replacement, &mut out);
+        Some(out)
+    }
+
+    /// Replace the current match and move past it. The first replacement of a
+    /// session saves the undo snapshot.
+    pub fn replace(ed: &mut EditorState, rs: &mut ReplaceState) {
+        let Some(text) = Self::expansion(ed, rs) else { return; };
+        let Some((row, range)) = rs.hit.take() else { return; };
+        if rs.replaced == 0 {
+            ed.push_undo();
+        }
+        let at = ed.buffer.line_to_char(row);
+        ed.remove_range(at + range.start..at + range.end);
+        ed.insert_at(at + range.start, &text);
+        rs.replaced += 1;
+        ed.dirty = true;
+        ed.search_positions.clear();
+        ed.search_index = None;
+        // Ne pas rechercher dans le texte inséré ; une correspondance vide avance d'un caractère
+        Self::advance(rs, row, range.start + text.chars().count() + usize::from(range.is_empty()));
+    }
+
+    /// Leave the current match as is.
+    pub fn skip(rs: &mut ReplaceState) {
+        if let Some((row, range)) = rs.hit.take() {
+            Self::advance(rs, row, range.end + usize::from(range.is_empty()));
+        }
+    }
+
+    /// Replace every remaining match (from the current one).
+    pub fn replace_all(ed: &mut EditorState, rs: &mut ReplaceState) {
+        while rs.hit.is_some() || Self::find_next(ed, rs) {
+            Self::replace(ed, rs);
+        }
+    }
+
+    fn advance(rs: &mut ReplaceState, row: usize, col: usize) {
+        if rs.global {
+            (rs.row, rs.col) = (row, col);
+        } else {
+            (rs.row, rs.col) = (row + 1, 0);
+        }
+    }
+
+    /// Outcome message once a session is over.
+    pub fn summary(rs: &ReplaceState) -> String {
+        match rs.replaced {
+            0 => String::from("Aucun remplacement"),
+            1 => String::from("1 remplacement"),
+            n => format!("{} remplacements", n),
+        }
+    }
+
+    /// Confirmation popup: the line of the current match, the match highlighted,
+    /// and what it becomes.
+    pub fn render(f: &mut Frame, area: Rect, ed: &EditorState, rs: &ReplaceState) {
+        let mut lines: Vec<Line> = Vec::new();
+        if let Some((row, range)) = rs.hit.as_ref() {
+            let chars: Vec<char> = line_text(ed, *row).chars().collect();
+            let Range { start, end } = range.clone();
+            let text = |r: Range<usize>| chars[r].iter().collect::<String>();
+            lines.push(Line::from(Span::styled(format!("Ligne {}", row + 1), Style::default().fg(Color::DarkGray))));
+            lines.push(Line::from(vec![
+                Span::raw(text(0..start)),
+                Span::styled(text(start..end), Style::default().fg(Color::Black).bg(Color::Yellow)),
+                Span::raw(text(end..chars.len())),
+            ]));
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::raw("→ "),
+                Span::styled(
+                    Self::expansion(ed, rs).unwrap_or_default(),
+                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                ),
+            ]));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("[y] Remplacer  [n] Passer  [a] Tous  [q] Terminer   ({} fait(s))", rs.replaced),
+            Style::default().fg(Color::DarkGray),
+        )));
+        f.render_widget(Clear, area);
+        let widget = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title("Remplacer"));
+        f.render_widget(widget, area);
+    }
+}
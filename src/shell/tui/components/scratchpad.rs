@@ -0,0 +1,87 @@
+//! Scratchpad: a quick-notes pane toggled with F2 over any screen.
+//!
+//! Notes live in `<config dir>/paschek/scratchpad.md` (e.g. `~/.config/paschek/`
+//! on Linux) and are written back after every change.
+use std::path::PathBuf;
+
+use ratatui::{
+    layout::{Position, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::shell::config::paths;
+use crate::shell::tui::state::{EditorState, ScratchpadState};
+
+/// Stateless scratchpad view and actions operating on ScratchpadState.
+pub struct ScratchpadView;
+
+/// Location of the notes file.
+pub fn scratchpad_path() -> PathBuf {
+    paths::config_dir().join("scratchpad.md")
+}
+
+impl ScratchpadView {
+    /// Show or hide the pane; notes are loaded from disk the first time it opens.
+    pub fn toggle(state: &mut ScratchpadState) {
+        state.open = !state.open;
+        if state.open && !state.loaded {
+            let mut ed = EditorState::new_empty();
+            if let Ok(text) = std::fs::read_to_string(scratchpad_path()) {
+                ed.buffer = ropey::Rope::from_str(&text);
+            }
+            state.editor = ed;
+            state.loaded = true;
+        }
+    }
+
+    /// Write the notes if they changed since the last save.
+    pub fn autosave(state: &mut ScratchpadState) {
+        if !state.editor.dirty {
+            return;
+        }
+        let path = scratchpad_path();
+        let result = path
+            .parent()
+            .map(std::fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| std::fs::write(&path, state.editor.buffer.to_string()));
+        state.message_line = result.err().map(|e| format!("Écriture impossible: {}", e));
+        state.editor.dirty = false;
+    }
+
+    /// Render the pane docked on the right of `area`.
+    pub fn render(f: &mut Frame, area: Rect, state: &ScratchpadState) {
+        let width = (area.width * 2 / 5).max(30).min(area.width);
+        let pane = Rect { x: area.x + area.width - width, width, ..area };
+        f.render_widget(Clear, pane);
+
+        let ed = &state.editor;
+        let height = pane.height.saturating_sub(2) as usize;
+        let start = (ed.cursor_row + 1).saturating_sub(height);
+        let lines: Vec<Line> = ed
+            .buffer
+            .lines()
+            .skip(start)
+            .take(height)
+            .map(|l| Line::from(l.to_string().trim_end_matches('\n').to_string()))
+            .collect();
+        let title = match &state.message_line {
+            Some(msg) => format!("Notes — {}", msg),
+            None => String::from("Notes [F2] Fermer — sauvegarde auto"),
+        };
+        let p = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::LightMagenta))
+                .title(title),
+        );
+        f.render_widget(p, pane);
+        f.set_cursor_position(Position {
+            x: pane.x + 1 + ed.cursor_col as u16,
+            y: pane.y + 1 + (ed.cursor_row - start) as u16,
+        });
+    }
+}
@@ -0,0 +1,210 @@
+//! Content search panel: ripgrep-style regex search across the explorer root.
+//!
+//! Files are walked in parallel (`ignore`'s parallel walker, so `.gitignore` is
+//! honoured and hidden/binary files are skipped) and matching lines stream into the
+//! result list. Smart case: the search is case-insensitive unless the query has an
+//! uppercase letter. Enter on a result opens the file at the line with the match
+//! highlighted.
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+
+use ignore::WalkState;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+use regex::{Regex, RegexBuilder};
+
+use crate::shell::tui::components::explorer::SPINNER;
+use crate::shell::tui::state::{GrepHit, Screen, SearchState};
+
+/// Stateless search panel and actions operating on SearchState.
+pub struct SearchView;
+
+/// The search stops once this many matching lines were found.
+const MAX_HITS: usize = 10_000;
+/// Larger files are skipped.
+const MAX_FILE_SIZE: u64 = 4 * 1024 * 1024;
+/// Displayed context around a match in long lines.
+const MAX_LINE_CHARS: usize = 300;
+
+/// Search one file and send its first match per line.
+fn search_file(root: &Path, path: &Path, re: &Regex, tx: &Sender<GrepHit>, found: &AtomicUsize) -> bool {
+    let Ok(bytes) = std::fs::read(path) else { return true; };
+    if bytes[..bytes.len().min(8192)].contains(&0) {
+        return true;
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    let rel = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+    for (line_no, line) in text.lines().enumerate() {
+        let Some(m) = re.find(line) else { continue; };
+        let col = line[..m.start()].chars().count();
+        let hit = GrepHit {
+            path: rel.clone(),
+            line: line_no,
+            col,
+            matched: m.as_str().to_string(),
+            text: line.to_string(),
+        };
+        if tx.send(hit).is_err() || found.fetch_add(1, Ordering::SeqCst) + 1 >= MAX_HITS {
+            return false;
+        }
+    }
+    true
+}
+
+/// Walk `root` in parallel, sending hits until done, cancelled or capped.
+fn run_search(root: PathBuf, re: Regex, tx: Sender<GrepHit>, cancel: Arc<AtomicBool>) {
+    let found = Arc::new(AtomicUsize::new(0));
+    ignore::WalkBuilder::new(&root).build_parallel().run(|| {
+        let (root, re, tx, cancel, found) = (root.clone(), re.clone(), tx.clone(), cancel.clone(), found.clone());
+        Box::new(move |entry| {
+            if cancel.load(Ordering::SeqCst) {
+                return WalkState::Quit;
+            }
+            let Ok(entry) = entry else { return WalkState::Continue; };
+            let is_small_file = entry.file_type().is_some_and(|t| t.is_file())
+                && entry.metadata().map(|m| m.len() <= MAX_FILE_SIZE).unwrap_or(false);
+            if is_small_file && !search_file(&root, entry.path(), &re, &tx, &found) {
+                return WalkState::Quit;
+            }
+            WalkState::Continue
+        })
+    });
+}
+
+impl SearchView {
+    /// Show the panel for `root`: runs `query` when given, otherwise focuses the query field
+    /// (previous results are kept while the root is unchanged).
+    pub fn open(state: &mut SearchState, root: &Path, query: Option<&str>, from: Screen) {
+        if state.root != root {
+            Self::cancel(state);
+            *state = SearchState { root: root.to_path_buf(), ..SearchState::default() };
+        }
+        state.return_to = from;
+        match query {
+            Some(q) => {
+                state.query = q.to_string();
+                Self::start(state);
+            }
+            None => state.editing = true,
+        }
+    }
+
+    fn cancel(state: &mut SearchState) {
+        if let Some(cancel) = state.cancel.take() {
+            cancel.store(true, Ordering::SeqCst);
+        }
+        state.pending = None;
+    }
+
+    /// Start a new search for the current query (cancels the previous one).
+    pub fn start(state: &mut SearchState) {
+        Self::cancel(state);
+        state.hits.clear();
+        state.selected = 0;
+        state.message_line = None;
+        state.editing = false;
+        if state.query.is_empty() {
+            return;
+        }
+        let smart_case = !state.query.chars().any(char::is_uppercase);
+        let re = match RegexBuilder::new(&state.query).case_insensitive(smart_case).build() {
+            Ok(re) => re,
+            Err(e) => {
+                state.message_line = Some(format!("Regex invalide: {}", e));
+                state.editing = true;
+                return;
+            }
+        };
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (root, flag) = (state.root.clone(), cancel.clone());
+        std::thread::spawn(move || run_search(root, re, tx, flag));
+        state.pending = Some(rx);
+        state.cancel = Some(cancel);
+    }
+
+    /// Collect hits found since the last tick.
+    pub fn poll(state: &mut SearchState) {
+        let Some(rx) = state.pending.as_ref() else { return; };
+        loop {
+            match rx.try_recv() {
+                Ok(hit) => state.hits.push(hit),
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    state.pending = None;
+                    state.cancel = None;
+                    if state.hits.len() >= MAX_HITS {
+                        state.message_line = Some(format!("limité à {} résultats", MAX_HITS));
+                    }
+                    break;
+                }
+            }
+        }
+        state.spinner = state.spinner.wrapping_add(1);
+    }
+
+    pub fn move_by(state: &mut SearchState, delta: isize) {
+        let next = state.selected as isize + delta;
+        state.selected = next.clamp(0, state.hits.len().saturating_sub(1) as isize) as usize;
+    }
+
+    /// Render the query field and the results (`path:line  text`, match highlighted).
+    pub fn render(f: &mut Frame, area: Rect, state: &SearchState) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(3)])
+            .split(area);
+
+        let border = if state.editing { Style::default().fg(Color::Yellow) } else { Style::default() };
+        let input = Paragraph::new(state.query.clone())
+            .block(Block::default().borders(Borders::ALL).border_style(border).title(format!("Rechercher dans {} (regex)", state.root.display())));
+        f.render_widget(input, rows[0]);
+        if state.editing {
+            f.set_cursor_position(Position { x: rows[0].x + 1 + state.query.chars().count() as u16, y: rows[0].y + 1 });
+        }
+
+        let height = rows[1].height.saturating_sub(2) as usize;
+        let start = (state.selected + 1).saturating_sub(height);
+        let items: Vec<ListItem> = state
+            .hits
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(height)
+            .map(|(i, h)| {
+                let selected = i == state.selected && !state.editing;
+                let base = if selected { Style::default().bg(Color::DarkGray) } else { Style::default() };
+                // Contexte autour du match pour les lignes très longues
+                let chars: Vec<char> = h.text.chars().collect();
+                let from = h.col.saturating_sub(MAX_LINE_CHARS / 3);
+                let match_len = h.matched.chars().count();
+                let before: String = chars[from..h.col].iter().collect();
+                let after: String = chars[(h.col + match_len).min(chars.len())..].iter().take(MAX_LINE_CHARS).collect();
+                ListItem::new(Line::from(vec![
+                    Span::styled(h.path.display().to_string(), base.fg(Color::Magenta)),
+                    Span::styled(format!(":{}  ", h.line + 1), base.fg(Color::Green)),
+                    Span::styled(before.trim_start().to_string(), base),
+                    Span::styled(h.matched.clone(), base.fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                    Span::styled(after, base),
+                ]))
+            })
+            .collect();
+        let files = state.hits.iter().map(|h| &h.path).collect::<HashSet<_>>().len();
+        let mut title = format!("{} résultats dans {} fichiers", state.hits.len(), files);
+        if state.pending.is_some() {
+            title = format!("{} {}", SPINNER[state.spinner % SPINNER.len()], title);
+        }
+        if let Some(msg) = &state.message_line {
+            title = format!("{} — {}", title, msg);
+        }
+        f.render_widget(List::new(items).block(Block::default().borders(Borders::ALL).title(title)), rows[1]);
+    }
+}
@@ -0,0 +1,299 @@
+//! SQLite data screen: table list, paged row browser and ad-hoc SQL.
+//!
+//! Layout:
+//! - Left: tables of the database opened from the explorer
+//! - Right: rows of the selected table (one page at a time) or the result of the last query
+//! - Bottom: SQL input line
+use std::io::Read;
+use std::path::Path;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Cell, List, ListItem, Paragraph, Row, Table},
+    Frame,
+};
+use rusqlite::{types::ValueRef, Connection, OpenFlags};
+
+use crate::shell::tui::state::{DataFocus, Screen, SqliteState};
+
+/// Rows per page when browsing a table.
+const PAGE_SIZE: usize = 100;
+/// Maximum rows kept from an ad-hoc query.
+const MAX_QUERY_ROWS: usize = 5000;
+/// Maximum displayed width of a column.
+const MAX_COL_WIDTH: usize = 30;
+
+/// Stateless SQLite view and actions operating on SqliteState.
+pub struct SqliteView;
+
+/// True if the file starts with the SQLite header.
+pub fn is_sqlite(path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map(|_| &header == b"SQLite format 3\0")
+        .unwrap_or(false)
+}
+
+fn cell_text(v: ValueRef) -> String {
+    match v {
+        ValueRef::Null => String::from("NULL"),
+        ValueRef::Integer(i) => i.to_string(),
+        ValueRef::Real(r) => r.to_string(),
+        ValueRef::Text(t) => String::from_utf8_lossy(t).into_owned(),
+        ValueRef::Blob(b) => format!("<blob {} o>", b.len()),
+    }
+}
+
+/// Run a statement returning rows; yields (columns, rows), at most `limit` rows.
+fn query(conn: &Connection, sql: &str, limit: usize) -> rusqlite::Result<(Vec<String>, Vec<Vec<String>>)> {
+    let mut stmt = conn.prepare(sql)?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let n = columns.len();
+    let mut out = Vec::new();
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        if out.len() >= limit {
+            break;
+        }
+        out.push((0..n).map(|i| row.get_ref(i).map(cell_text).unwrap_or_default()).collect());
+    }
+    Ok((columns, out))
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+impl SqliteView {
+    /// Open a database file and list its tables.
+    pub fn open(state: &mut SqliteState, path: &Path, from: Screen) {
+        *state = SqliteState { return_to: from, ..Default::default() };
+        match Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE) {
+            Ok(conn) => {
+                state.conn = Some(conn);
+                state.path = Some(path.to_path_buf());
+                Self::load_tables(state);
+                Self::browse_selected(state);
+            }
+            Err(e) => state.message_line = Some(format!("Ouverture impossible: {}", e)),
+        }
+    }
+
+    fn load_tables(state: &mut SqliteState) {
+        let Some(conn) = state.conn.as_ref() else { return; };
+        match query(conn, "SELECT name FROM sqlite_master WHERE type IN ('table','view') ORDER BY name", usize::MAX) {
+            Ok((_, rows)) => state.tables = rows.into_iter().filter_map(|r| r.into_iter().next()).collect(),
+            Err(e) => state.message_line = Some(e.to_string()),
+        }
+        if state.table_selected >= state.tables.len() {
+            state.table_selected = state.tables.len().saturating_sub(1);
+        }
+    }
+
+    /// Browse the table selected in the list, from its first page.
+    pub fn browse_selected(state: &mut SqliteState) {
+        state.table = state.tables.get(state.table_selected).cloned();
+        state.page = 0;
+        state.col_offset = 0;
+        if let (Some(conn), Some(table)) = (state.conn.as_ref(), state.table.as_ref()) {
+            state.total_rows = conn
+                .query_row(&format!("SELECT COUNT(*) FROM {}", quote_ident(table)), [], |r| r.get::<_, i64>(0))
+                .map(|n| n as usize)
+                .unwrap_or(0);
+        }
+        Self::load_page(state);
+    }
+
+    fn load_page(state: &mut SqliteState) {
+        let (Some(conn), Some(table)) = (state.conn.as_ref(), state.table.as_ref()) else {
+            return;
+        };
+        let sql = format!("SELECT * FROM {} LIMIT {} OFFSET {}", quote_ident(table), PAGE_SIZE, state.page * PAGE_SIZE);
+        match query(conn, &sql, PAGE_SIZE) {
+            Ok((columns, rows)) => {
+                state.columns = columns;
+                state.rows = rows;
+                state.message_line = None;
+            }
+            Err(e) => state.message_line = Some(e.to_string()),
+        }
+        state.row_selected = 0;
+    }
+
+    /// Move to the next (or previous) page of the browsed table.
+    pub fn change_page(state: &mut SqliteState, forward: bool) {
+        if state.table.is_none() {
+            return;
+        }
+        let pages = state.total_rows.div_ceil(PAGE_SIZE).max(1);
+        if forward && state.page + 1 < pages {
+            state.page += 1;
+        } else if !forward && state.page > 0 {
+            state.page -= 1;
+        } else {
+            return;
+        }
+        Self::load_page(state);
+    }
+
+    /// Execute the SQL line: queries fill the grid, other statements report affected rows.
+    pub fn run_sql(state: &mut SqliteState) {
+        let Some(conn) = state.conn.as_ref() else { return; };
+        let sql = state.sql.trim().to_string();
+        if sql.is_empty() {
+            return;
+        }
+        let result = match conn.prepare(&sql).map(|s| s.column_count() > 0) {
+            Ok(true) => query(conn, &sql, MAX_QUERY_ROWS).map(Some),
+            Ok(false) => conn.execute_batch(&sql).map(|_| None),
+            Err(e) => Err(e),
+        };
+        let changes = conn.changes();
+        match result {
+            Ok(Some((columns, rows))) => {
+                state.message_line = Some(format!("{} ligne(s)", rows.len()));
+                state.table = None;
+                state.columns = columns;
+                state.rows = rows;
+                state.row_selected = 0;
+                state.col_offset = 0;
+                state.focus = DataFocus::Grid;
+            }
+            Ok(None) => {
+                state.message_line = Some(format!("OK — {} ligne(s) modifiée(s)", changes));
+                // Le schéma a pu changer (CREATE/DROP)
+                Self::load_tables(state);
+            }
+            Err(e) => state.message_line = Some(format!("Erreur SQL: {}", e)),
+        }
+    }
+
+    pub fn move_row(state: &mut SqliteState, delta: isize) {
+        let next = state.row_selected as isize + delta;
+        state.row_selected = next.clamp(0, state.rows.len().saturating_sub(1) as isize) as usize;
+    }
+
+    pub fn scroll_columns(state: &mut SqliteState, right: bool) {
+        if right {
+            if state.col_offset + 1 < state.columns.len() {
+                state.col_offset += 1;
+            }
+        } else {
+            state.col_offset = state.col_offset.saturating_sub(1);
+        }
+    }
+
+    fn border(state: &SqliteState, focus: DataFocus) -> Style {
+        if state.focus == focus { Style::default().fg(Color::Yellow) } else { Style::default() }
+    }
+
+    /// Render the table list, the grid and the SQL line.
+    pub fn render(f: &mut Frame, area: Rect, state: &SqliteState) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(3)])
+            .split(area);
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(20), Constraint::Percentage(80)])
+            .split(rows[0]);
+
+        let name = state
+            .path
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let items: Vec<ListItem> = state
+            .tables
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                let style = if i == state.table_selected { Style::default().fg(Color::Yellow) } else { Style::default() };
+                ListItem::new(t.clone()).style(style)
+            })
+            .collect();
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Self::border(state, DataFocus::Tables))
+                .title(format!("Tables — {}", name)),
+        );
+        f.render_widget(list, cols[0]);
+
+        // Grille : colonnes visibles à partir de col_offset, largeur bornée
+        let visible: Vec<usize> = (state.col_offset..state.columns.len()).collect();
+        let widths: Vec<Constraint> = visible
+            .iter()
+            .map(|&c| {
+                let w = state
+                    .rows
+                    .iter()
+                    .map(|r| r.get(c).map(|v| v.chars().count()).unwrap_or(0))
+                    .chain(std::iter::once(state.columns[c].chars().count()))
+                    .max()
+                    .unwrap_or(4)
+                    .min(MAX_COL_WIDTH);
+                Constraint::Length(w as u16)
+            })
+            .collect();
+        let header = Row::new(visible.iter().map(|&c| Cell::from(state.columns[c].clone())))
+            .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+        let height = cols[1].height.saturating_sub(3) as usize;
+        let start = (state.row_selected + 1).saturating_sub(height);
+        let body: Vec<Row> = state
+            .rows
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(height)
+            .map(|(i, r)| {
+                let cells = visible.iter().map(|&c| {
+                    let v = r.get(c).cloned().unwrap_or_default();
+                    let style = if v == "NULL" { Style::default().fg(Color::DarkGray) } else { Style::default() };
+                    Cell::from(v).style(style)
+                });
+                let style = if i == state.row_selected && state.focus == DataFocus::Grid {
+                    Style::default().bg(Color::DarkGray)
+                } else {
+                    Style::default()
+                };
+                Row::new(cells).style(style)
+            })
+            .collect();
+
+        let mut title = match &state.table {
+            Some(t) => {
+                let pages = state.total_rows.div_ceil(PAGE_SIZE).max(1);
+                format!("{} — page {}/{} ({} lignes)", t, state.page + 1, pages, state.total_rows)
+            }
+            None if !state.columns.is_empty() => String::from("Résultat"),
+            None => String::from("Données"),
+        };
+        if let Some(msg) = &state.message_line {
+            title = format!("{} — {}", title, msg);
+        }
+        let table = Table::new(body, widths).header(header).column_spacing(2).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Self::border(state, DataFocus::Grid))
+                .title(title),
+        );
+        f.render_widget(table, cols[1]);
+
+        let sql = Paragraph::new(Line::from(state.sql.clone())).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Self::border(state, DataFocus::Sql))
+                .title("SQL — [Entrée] Exécuter"),
+        );
+        f.render_widget(sql, rows[1]);
+        if state.focus == DataFocus::Sql {
+            f.set_cursor_position(Position { x: rows[1].x + 1 + state.sql.chars().count() as u16, y: rows[1].y + 1 });
+        }
+    }
+}
@@ -2,19 +2,20 @@ use chrono::Local;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::Style,
-    text::Line,
+    text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
-use crate::shell::prompt::Theme;
+use crate::shell::prompt::{theme::tui_color, Theme};
 
 /// Status bar displayed at the bottom of every screen.
 ///
-/// Left side shows the shell name and current time; right side displays
-/// contextual hints controlled by the parent screen.
+/// Left side shows the shell name, current time and running jobs in the prompt
+/// theme's colors; right side displays contextual hints controlled by the parent screen.
 pub struct StatusBar {
     theme: Theme,
     right_hint: String,
+    running_jobs: usize,
 }
 
 impl StatusBar {
@@ -23,14 +24,25 @@ impl StatusBar {
         Self {
             theme,
             right_hint: String::from(""),
+            running_jobs: 0,
         }
     }
 
+    /// Switch to another prompt Theme.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
     /// Update the right-hand hint text.
     pub fn set_hint<S: Into<String>>(&mut self, s: S) {
         self.right_hint = s.into();
     }
 
+    /// Update the running-jobs counter shown on the left side.
+    pub fn set_jobs(&mut self, running: usize) {
+        self.running_jobs = running;
+    }
+
     /// Render the status bar into the provided area.
     pub fn render(&mut self, f: &mut Frame, area: Rect) {
         let cols = Layout::default()
@@ -38,10 +50,19 @@ impl StatusBar {
             .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
             .split(area);
 
-        let left = Paragraph::new(Line::from(format!(
-            " PascheK Shell • {}",
-            Local::now().format("%H:%M:%S")
-        )))
+        let symbol = Style::default().fg(tui_color(self.theme.symbol_color));
+        let mut spans = vec![
+            Span::styled(" PascheK Shell", Style::default().fg(tui_color(self.theme.shell_color))),
+            Span::styled(" • ", symbol),
+            Span::styled(
+                Local::now().format("%H:%M:%S").to_string(),
+                Style::default().fg(tui_color(self.theme.time_color)),
+            ),
+        ];
+        if self.running_jobs > 0 {
+            spans.push(Span::styled(format!(" • ⚙ {} job(s)", self.running_jobs), symbol));
+        }
+        let left = Paragraph::new(Line::from(spans))
         .block(Block::default().borders(Borders::ALL).title("Status"));
 
         let right = Paragraph::new(Line::from(self.right_hint.clone()))
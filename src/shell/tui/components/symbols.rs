@@ -0,0 +1,247 @@
+//! Go to symbol (Ctrl+K) and go to definition (F12, `gd`).
+//!
+//! Symbols are the definitions the syntax grammars name (`entity.name.*` scopes):
+//! functions, types, classes, Markdown headings… The overlay lists those of the
+//! current buffer; Tab switches to the whole workspace, indexed once per explorer
+//! root by a background walker that honours `.gitignore` like the file finder.
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::shell::tui::components::explorer::SPINNER;
+use crate::shell::tui::components::highlight;
+use crate::shell::tui::state::{EditorState, Symbol, SymbolsState};
+
+/// Stateless symbol overlay and actions operating on SymbolsState.
+pub struct SymbolsView;
+
+/// Results kept after ranking.
+const MAX_RESULTS: usize = 500;
+/// Larger files are not parsed (generated code, minified bundles…).
+const MAX_FILE_BYTES: u64 = 512 * 1024;
+/// Indexing stops after this many parsed files.
+const MAX_FILES: usize = 5_000;
+
+/// Walk `root` and send the symbols of the files that have a grammar, a few files at a time.
+fn index_files(root: PathBuf, tx: Sender<Vec<Symbol>>) {
+    let mut batch = Vec::new();
+    let mut count = 0;
+    for entry in ignore::WalkBuilder::new(&root).build().flatten() {
+        let path = entry.path();
+        if !entry.file_type().is_some_and(|t| t.is_file()) || !highlight::has_grammar(path) {
+            continue;
+        }
+        if entry.metadata().map_or(true, |m| m.len() > MAX_FILE_BYTES) {
+            continue;
+        }
+        let Ok(text) = fs::read_to_string(path) else { continue; };
+        batch.extend(highlight::symbols(path, &text));
+        count += 1;
+        if count % 16 == 0 && tx.send(std::mem::take(&mut batch)).is_err() {
+            return;
+        }
+        if count >= MAX_FILES {
+            break;
+        }
+    }
+    let _ = tx.send(batch);
+}
+
+impl SymbolsView {
+    /// Symbols of an editor buffer (none for a buffer too large to parse).
+    pub fn buffer_symbols(ed: &EditorState) -> Vec<Symbol> {
+        if ed.buffer.len_bytes() as u64 > MAX_FILE_BYTES {
+            return Vec::new();
+        }
+        let path = ed.path.clone().unwrap_or_default();
+        highlight::symbols(&path, &ed.buffer.to_string())
+    }
+
+    /// Open the overlay on the symbols of `ed`, or on the workspace when there is no buffer.
+    pub fn open(state: &mut SymbolsState, ed: Option<&EditorState>, root: &Path) {
+        state.query.clear();
+        state.selected = 0;
+        state.buffer = ed.map(Self::buffer_symbols).unwrap_or_default();
+        state.workspace = ed.is_none();
+        if state.workspace {
+            Self::index(state, root, false);
+        }
+        Self::update_results(state);
+    }
+
+    /// (Re)build the workspace index when the root changed or `reindex` is set.
+    pub fn index(state: &mut SymbolsState, root: &Path, reindex: bool) {
+        if reindex || state.root.as_deref() != Some(root) {
+            state.root = Some(root.to_path_buf());
+            state.index.clear();
+            let (tx, rx) = mpsc::channel();
+            let dir = root.to_path_buf();
+            std::thread::spawn(move || index_files(dir, tx));
+            state.indexing = Some(rx);
+        }
+    }
+
+    /// Whether the workspace index of `root` is complete.
+    pub fn indexed(state: &SymbolsState, root: &Path) -> bool {
+        state.indexing.is_none() && state.root.as_deref() == Some(root)
+    }
+
+    /// Switch between the current buffer and the workspace.
+    pub fn toggle_scope(state: &mut SymbolsState, root: &Path) {
+        state.workspace = !state.workspace;
+        if state.workspace {
+            Self::index(state, root, false);
+        }
+        state.selected = 0;
+        Self::update_results(state);
+    }
+
+    /// Append indexed symbols; returns true once indexing has just completed.
+    pub fn poll(state: &mut SymbolsState) -> bool {
+        let Some(rx) = state.indexing.as_ref() else { return false; };
+        let mut received = false;
+        let mut done = false;
+        loop {
+            match rx.try_recv() {
+                Ok(batch) => {
+                    state.index.extend(batch);
+                    received = true;
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    state.indexing = None;
+                    done = true;
+                    break;
+                }
+            }
+        }
+        state.spinner = state.spinner.wrapping_add(1);
+        if received && state.workspace {
+            Self::update_results(state);
+        }
+        done
+    }
+
+    /// Symbols the overlay currently lists.
+    fn listed(state: &SymbolsState) -> &[Symbol] {
+        if state.workspace { &state.index } else { &state.buffer }
+    }
+
+    /// Rank the listed symbols against the query (best first). An empty query keeps
+    /// the file order.
+    pub fn update_results(state: &mut SymbolsState) {
+        let symbols = Self::listed(state);
+        state.results = if state.query.is_empty() {
+            (0..symbols.len().min(MAX_RESULTS)).map(|i| (i, Vec::new())).collect()
+        } else {
+            let matcher = SkimMatcherV2::default().ignore_case();
+            let mut scored: Vec<(i64, usize, Vec<usize>)> = symbols
+                .iter()
+                .enumerate()
+                .filter_map(|(i, s)| matcher.fuzzy_indices(&s.name, &state.query).map(|(score, idx)| (score, i, idx)))
+                .collect();
+            // Score décroissant, puis nom le plus court
+            scored.sort_by(|a, b| b.0.cmp(&a.0).then(symbols[a.1].name.len().cmp(&symbols[b.1].name.len())));
+            scored.truncate(MAX_RESULTS);
+            scored.into_iter().map(|(_, i, idx)| (i, idx)).collect()
+        };
+        if state.selected >= state.results.len() {
+            state.selected = state.results.len().saturating_sub(1);
+        }
+    }
+
+    pub fn input(state: &mut SymbolsState, c: char) {
+        state.query.push(c);
+        state.selected = 0;
+        Self::update_results(state);
+    }
+
+    pub fn backspace(state: &mut SymbolsState) {
+        state.query.pop();
+        state.selected = 0;
+        Self::update_results(state);
+    }
+
+    pub fn move_by(state: &mut SymbolsState, delta: isize) {
+        let next = state.selected as isize + delta;
+        state.selected = next.clamp(0, state.results.len().saturating_sub(1) as isize) as usize;
+    }
+
+    /// Selected symbol.
+    pub fn selected(state: &SymbolsState) -> Option<Symbol> {
+        let (i, _) = state.results.get(state.selected)?;
+        Self::listed(state).get(*i).cloned()
+    }
+
+    /// Definition named `name` among `symbols`; an `impl` block only when nothing
+    /// else has that name.
+    pub fn definition<'a>(symbols: &'a [Symbol], name: &str) -> Option<&'a Symbol> {
+        let mut named = symbols.iter().filter(|s| s.name == name);
+        named.clone().find(|s| s.kind != "impl").or_else(|| named.next())
+    }
+
+    pub fn render(f: &mut Frame, area: Rect, state: &SymbolsState) {
+        f.render_widget(Clear, area);
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(3)])
+            .split(area);
+
+        let title = match (state.workspace, &state.indexing) {
+            (false, _) => format!("Symboles du fichier — {}", state.buffer.len()),
+            (true, Some(_)) => format!("Symboles du workspace — {} indexés {}", state.index.len(), SPINNER[state.spinner % SPINNER.len()]),
+            (true, None) => format!("Symboles du workspace — {}", state.index.len()),
+        };
+        let input = Paragraph::new(state.query.clone())
+            .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Yellow)).title(title));
+        f.render_widget(input, rows[0]);
+        f.set_cursor_position(Position { x: rows[0].x + 1 + state.query.chars().count() as u16, y: rows[0].y + 1 });
+
+        let symbols = Self::listed(state);
+        let height = rows[1].height.saturating_sub(2) as usize;
+        let start = (state.selected + 1).saturating_sub(height);
+        let items: Vec<ListItem> = state
+            .results
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(height)
+            .map(|(row, (i, hits))| {
+                let sym = &symbols[*i];
+                let base = if row == state.selected { Style::default().fg(Color::Yellow) } else { Style::default() };
+                let hit = base.fg(Color::LightCyan).add_modifier(Modifier::BOLD);
+                let mut spans = vec![Span::styled(format!("{:<10} ", sym.kind), Style::default().fg(Color::DarkGray))];
+                spans.extend(
+                    sym.name
+                        .chars()
+                        .enumerate()
+                        .map(|(ci, ch)| Span::styled(ch.to_string(), if hits.contains(&ci) { hit } else { base })),
+                );
+                let place = if state.workspace {
+                    let file = state.root.as_deref().and_then(|r| sym.path.strip_prefix(r).ok()).unwrap_or(&sym.path);
+                    format!("  {}:{}", file.display(), sym.row + 1)
+                } else {
+                    format!("  :{}", sym.row + 1)
+                };
+                spans.push(Span::styled(place, Style::default().fg(Color::DarkGray)));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("[Entrée] Aller  [Tab] Fichier/Workspace  [Ctrl+R] Réindexer  [Esc] Fermer"),
+        );
+        f.render_widget(list, rows[1]);
+    }
+}
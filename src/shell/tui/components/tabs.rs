@@ -0,0 +1,57 @@
+//! Tab picker overlay (Ctrl+T in the editor, `:ls` in its command line): every
+//! open buffer with its modified marker and directory. Enter or 1-9 focus a tab,
+//! `x` closes it.
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem},
+    Frame,
+};
+
+use crate::shell::tui::state::EditorTabs;
+
+/// Stateless renderer for the tab picker.
+pub struct TabsView;
+
+impl TabsView {
+    /// Move the picker selection, clamped to the open tabs.
+    pub fn move_by(selected: &mut usize, tabs: &EditorTabs, delta: isize) {
+        let last = tabs.tabs.len().saturating_sub(1) as isize;
+        *selected = (*selected as isize + delta).clamp(0, last) as usize;
+    }
+
+    /// Render the open tabs; `▶` marks the current one.
+    pub fn render(f: &mut Frame, area: Rect, tabs: &EditorTabs, selected: usize) {
+        f.render_widget(Clear, area);
+        let items: Vec<ListItem> = tabs
+            .tabs
+            .iter()
+            .enumerate()
+            .map(|(i, tab)| {
+                let ed = &tab.state;
+                let name = ed
+                    .path
+                    .as_ref()
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| String::from("[No Name]"));
+                let dir = ed.path.as_ref().and_then(|p| p.parent()).map(|p| p.display().to_string()).unwrap_or_default();
+                let key = if i < 9 { (i + 1).to_string() } else { String::from(" ") };
+                let line = Line::from(vec![
+                    Span::raw(format!("{} {}  ", if i == tabs.current { "▶" } else { " " }, key)),
+                    Span::styled(
+                        format!("{}{}", if ed.dirty { "● " } else { "" }, name),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(format!("  {}", dir), Style::default().fg(Color::DarkGray)),
+                ]);
+                let style = if i == selected { Style::default().bg(Color::DarkGray) } else { Style::default() };
+                ListItem::new(line).style(style)
+            })
+            .collect();
+        let title = format!("Onglets ({}) — [Entrée/1-9] Aller  [x] Fermer l’onglet  [Esc] Quitter", tabs.tabs.len());
+        let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(list, area);
+    }
+}
@@ -0,0 +1,134 @@
+//! Task runner panel: project tasks detected in the workspace directory.
+//!
+//! Sources: Makefile targets, cargo commands (Cargo.toml), package.json scripts
+//! and justfile recipes. The project directory is the explorer's directory or its
+//! nearest parent (up to the explorer root) holding one of these files.
+//! Enter runs the task as a foreground job streamed into the Shell terminal pane.
+use std::path::{Path, PathBuf};
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem},
+    Frame,
+};
+
+use crate::shell::tui::state::{TaskEntry, TaskState};
+
+/// Stateless task panel and actions operating on TaskState.
+pub struct TaskView;
+
+const MAKEFILES: [&str; 3] = ["GNUmakefile", "Makefile", "makefile"];
+const JUSTFILES: [&str; 3] = ["justfile", "Justfile", ".justfile"];
+const CARGO_COMMANDS: [&str; 6] = ["build", "check", "test", "run", "clippy", "fmt"];
+
+fn task(source: &str, name: &str, program: &str, args: &[&str]) -> TaskEntry {
+    TaskEntry {
+        source: source.to_string(),
+        name: name.to_string(),
+        program: program.to_string(),
+        args: args.iter().map(|a| a.to_string()).collect(),
+    }
+}
+
+/// `name:` rule/recipe names at the start of a line (skips variables, special and pattern targets).
+fn rule_names(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for line in text.lines() {
+        if line.starts_with(|c: char| c.is_whitespace() || c == '#' || c == '.') || line.contains(":=") {
+            continue;
+        }
+        let Some((head, _)) = line.split_once(':') else { continue; };
+        // `just` : les paramètres suivent le nom de la recette ; `VAR = a:b` est une affectation
+        let mut words = head.split_whitespace();
+        let Some(name) = words.next() else { continue; };
+        let assignment = words.next().is_some_and(|w| w.starts_with(['=', '?', '+', '!']) && w.contains('='));
+        if assignment || name.contains(['=', '%', '$']) {
+            continue;
+        }
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Tasks defined in `dir`, in source order: make, cargo, npm, just.
+fn detect(dir: &Path) -> Vec<TaskEntry> {
+    let mut tasks = Vec::new();
+    if let Some(text) = MAKEFILES.iter().find_map(|f| std::fs::read_to_string(dir.join(f)).ok()) {
+        tasks.extend(rule_names(&text).iter().map(|t| task("make", t, "make", &[t])));
+    }
+    if dir.join("Cargo.toml").is_file() {
+        tasks.extend(CARGO_COMMANDS.iter().map(|c| task("cargo", c, "cargo", &[c])));
+    }
+    if let Ok(text) = std::fs::read_to_string(dir.join("package.json"))
+        && let Ok(json) = serde_json::from_str::<serde_json::Value>(&text)
+        && let Some(scripts) = json.get("scripts").and_then(|s| s.as_object())
+    {
+        tasks.extend(scripts.keys().map(|k| task("npm", k, "npm", &["run", k])));
+    }
+    if let Some(text) = JUSTFILES.iter().find_map(|f| std::fs::read_to_string(dir.join(f)).ok()) {
+        tasks.extend(rule_names(&text).iter().map(|r| task("just", r, "just", &[r])));
+    }
+    tasks
+}
+
+impl TaskView {
+    /// Find the project directory from `cwd` (walking up to `root`) and list its tasks.
+    pub fn refresh(state: &mut TaskState, cwd: &Path, root: &Path) {
+        let mut dir: Option<PathBuf> = Some(cwd.to_path_buf());
+        *state = TaskState::default();
+        while let Some(d) = dir {
+            let tasks = detect(&d);
+            if !tasks.is_empty() {
+                state.root = d;
+                state.tasks = tasks;
+                return;
+            }
+            if d == root {
+                break;
+            }
+            dir = d.parent().filter(|p| p.starts_with(root)).map(Path::to_path_buf);
+        }
+        state.root = cwd.to_path_buf();
+        state.message_line = Some(String::from("Aucune tâche (Makefile, Cargo.toml, package.json, justfile)"));
+    }
+
+    pub fn move_up(state: &mut TaskState) {
+        state.selected = state.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(state: &mut TaskState) {
+        if state.selected + 1 < state.tasks.len() {
+            state.selected += 1;
+        }
+    }
+
+    /// Command line of a task, as shown in the terminal pane.
+    pub fn command_line(t: &TaskEntry) -> String {
+        std::iter::once(t.program.as_str()).chain(t.args.iter().map(String::as_str)).collect::<Vec<_>>().join(" ")
+    }
+
+    pub fn render(f: &mut Frame, area: Rect, state: &TaskState) {
+        let items: Vec<ListItem> = state
+            .tasks
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                let style = if i == state.selected { Style::default().fg(Color::Yellow) } else { Style::default() };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:<6} ", t.source), Style::default().fg(Color::Cyan)),
+                    Span::styled(format!("{:<28}", t.name), style),
+                    Span::styled(Self::command_line(t), Style::default().fg(Color::DarkGray)),
+                ]))
+            })
+            .collect();
+        let mut title = format!("Tâches — {}", state.root.display());
+        if let Some(msg) = &state.message_line {
+            title = format!("{} — {}", title, msg);
+        }
+        f.render_widget(List::new(items).block(Block::default().borders(Borders::ALL).title(title)), area);
+    }
+}
@@ -3,9 +3,15 @@
 //! Responsibilities:
 //! - Render a scrollable output area and an input line
 //! - Provide simple input editing (left/right, backspace, delete)
-//! - Maintain a command history navigable with Up/Down
+//! - Maintain a command history navigable with Up/Down, searchable with Ctrl+R
 //! - Expose helpers used by the TUI event loop (clear, scroll, etc.)
+//! - Host interactive programs in a pseudo-terminal (Unix only, see `super::pty`):
+//!   while one runs, the pane shows its screen and every key goes to it
 
+use std::cell::Cell;
+use std::io;
+
+use crossterm::event::KeyEvent;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
@@ -14,16 +20,27 @@ use ratatui::{
     Frame,
 };
 
+use crate::shell::history::{HistoryStore, ReverseSearch, SearchKey, SearchStep};
+#[cfg(unix)]
+use crate::shell::tui::components::pty::PtySession;
+
 /// Interactive terminal pane with output buffer, input editor, and command history.
 pub struct TerminalPane {
     output: Vec<String>,
     scroll: usize,
     input: String,
     cursor: usize,
-    // Command history (newest at the end)
-    history: Vec<String>,
+    // Command history shared with the REPL (newest at the end)
+    history: HistoryStore,
     // When navigating history: current index into history or None when editing fresh input
     history_pos: Option<usize>,
+    // Ctrl+R search in progress, shown in place of the input line
+    search: Option<ReverseSearch>,
+    // Interactive program running in a pseudo-terminal, if any
+    #[cfg(unix)]
+    pty: Option<PtySession>,
+    // Inner size (rows, cols) of the pane at the last render, for the pseudo-terminal
+    view_size: Cell<(u16, u16)>,
 }
 
 impl TerminalPane {
@@ -32,18 +49,30 @@ impl TerminalPane {
         Self {
             output: vec![
                 "Welcome to PascheK Shell TUI".into(),
-                "Tape :h pour l’aide, :l pour les logs, :q pour quitter.".into(),
+                "Tape :h pour l’aide, :l pour les logs, :repl pour revenir au REPL, :q pour quitter.".into(),
             ],
             scroll: 0,
             input: String::new(),
             cursor: 0,
-            history: Vec::new(),
+            history: HistoryStore::open(),
             history_pos: None,
+            search: None,
+            #[cfg(unix)]
+            pty: None,
+            view_size: Cell::new((24, 80)),
         }
     }
 
     /// Render the terminal output and input line with borders and titles.
     pub fn render(&self, f: &mut Frame, area: Rect) {
+        self.view_size.set((area.height.saturating_sub(2), area.width.saturating_sub(2)));
+        #[cfg(unix)]
+        if let Some(pty) = &self.pty {
+            let screen = Paragraph::new(pty.lines())
+                .block(Block::default().borders(Borders::ALL).title(format!("Terminal — {}", pty.command)));
+            f.render_widget(screen, area);
+            return;
+        }
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Min(3), Constraint::Length(3)])
@@ -63,18 +92,28 @@ impl TerminalPane {
             .block(Block::default().borders(Borders::ALL).title("Terminal"));
         f.render_widget(out, chunks[0]);
 
-        let prompted = format!("$ {}", self.input);
+        let (prompted, title) = match &self.search {
+            Some(search) => (search.label(self.history.entries()), "Input — [Ctrl+R] Plus ancien  [Entrée] Lancer  [Esc] Annuler"),
+            None => (format!("$ {}", self.input), "Input"),
+        };
         let input_line = Paragraph::new(Line::from(Span::styled(
             prompted,
             Style::default().fg(Color::Cyan),
         )))
-        .block(Block::default().borders(Borders::ALL).title("Input"));
+        .block(Block::default().borders(Borders::ALL).title(title));
         f.render_widget(input_line, chunks[1]);
     }
 
     // Input
     /// Insert a character at the cursor position (like typical terminals)
     pub fn insert_char(&mut self, c: char) { self.input.insert(self.cursor, c); self.cursor += 1; }
+
+    /// Insert pasted text at the cursor; line breaks become spaces (single input line).
+    pub fn insert_str(&mut self, text: &str) {
+        for c in text.trim_end_matches(['\r', '\n']).chars().filter(|c| *c != '\r') {
+            self.insert_char(if c == '\n' { ' ' } else { c });
+        }
+    }
     /// Delete character before the cursor, if any
     pub fn backspace(&mut self) { if self.cursor > 0 { self.cursor -= 1; self.input.remove(self.cursor); } }
     /// Delete character under the cursor, if any
@@ -91,6 +130,12 @@ impl TerminalPane {
     pub fn clear_input(&mut self) { self.input.clear(); self.cursor = 0; self.history_pos = None; }
     /// Borrow the current input line
     pub fn current_line(&self) -> &str { &self.input }
+
+    /// Output of the last command: the lines after its `$ ` echo (all output if none).
+    pub fn last_output(&self) -> String {
+        let start = self.output.iter().rposition(|l| l.starts_with("$ ")).map_or(0, |i| i + 1);
+        self.output[start..].join("\n")
+    }
     /// Replace input line and set cursor at end
     fn set_input_from_history(&mut self, s: String) { self.input = s; self.cursor = self.input.len(); }
 
@@ -104,13 +149,90 @@ impl TerminalPane {
     /// Scroll output one step down (newer messages)
     pub fn scroll_down(&mut self) { if self.scroll > 0 { self.scroll -= 1; } }
 
+    // Pseudo-terminal
+    /// Run `program args` in a pseudo-terminal the size of the pane.
+    #[cfg(unix)]
+    pub fn start_pty(&mut self, line: &str, program: &str, args: &[&str]) -> io::Result<()> {
+        let (rows, cols) = self.view_size.get();
+        self.pty = Some(PtySession::spawn(line, program, args, rows.max(1), cols.max(1))?);
+        Ok(())
+    }
+    /// Whether an interactive program owns the pane
+    #[cfg(unix)]
+    pub fn pty_active(&self) -> bool { self.pty.is_some() }
+    /// Forward a key press to the interactive program
+    #[cfg(unix)]
+    pub fn pty_key(&mut self, key: KeyEvent) { if let Some(pty) = self.pty.as_mut() { pty.send_key(key); } }
+    /// Forward pasted text to the interactive program
+    #[cfg(unix)]
+    pub fn pty_paste(&mut self, text: &str) { if let Some(pty) = self.pty.as_mut() { pty.write(text.as_bytes()); } }
+    /// Keep the pseudo-terminal at the pane size; once the program has ended, copy
+    /// what it left on screen to the output and return its exit code.
+    #[cfg(unix)]
+    pub fn poll_pty(&mut self) -> Option<i32> {
+        let pty = self.pty.as_mut()?;
+        let (rows, cols) = self.view_size.get();
+        pty.resize(rows, cols);
+        let code = pty.poll()?;
+        let text = pty.text();
+        self.pty = None;
+        self.output.extend(text);
+        Some(code)
+    }
+    // Sans pseudo-terminal (Windows) : aucun programme ne prend le panneau
+    #[cfg(not(unix))]
+    pub fn start_pty(&mut self, _line: &str, _program: &str, _args: &[&str]) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "pseudo-terminals are not supported on this platform"))
+    }
+    #[cfg(not(unix))]
+    pub fn pty_active(&self) -> bool { false }
+    #[cfg(not(unix))]
+    pub fn pty_key(&mut self, _key: KeyEvent) {}
+    #[cfg(not(unix))]
+    pub fn pty_paste(&mut self, _text: &str) {}
+    #[cfg(not(unix))]
+    pub fn poll_pty(&mut self) -> Option<i32> { None }
+
     // History
-    /// Push the executed command to history if not empty and not a duplicate of the last entry
+    /// Start a Ctrl+R search (the history is re-read first, for the REPL's commands).
+    pub fn start_search(&mut self) {
+        self.history.reload();
+        self.search = Some(ReverseSearch::default());
+    }
+
+    /// Return true while a Ctrl+R search is shown.
+    pub fn searching(&self) -> bool {
+        self.search.is_some()
+    }
+
+    /// Feed a key to the search. Returns true when the match was accepted: it is
+    /// then the input line, ready to run.
+    pub fn search_key(&mut self, key: SearchKey) -> bool {
+        let Some(search) = self.search.as_mut() else { return false; };
+        match search.key(key, self.history.entries()) {
+            SearchStep::Continue => false,
+            SearchStep::Run(line) => {
+                self.search = None;
+                self.set_input_from_history(line);
+                true
+            }
+            SearchStep::Cancel => {
+                self.search = None;
+                false
+            }
+        }
+    }
+
+    /// Re-read the shared history (after `history -c`)
+    pub fn reload_history(&mut self) {
+        self.history.reload();
+        self.history_pos = None;
+    }
+
+    /// Push the executed command to the persistent history if not empty and not a duplicate of the last entry
     pub fn push_history_if_new(&mut self, line: &str) {
-        let trimmed = line.trim();
-        if trimmed.is_empty() { return; }
-        if self.history.last().map(|s| s.as_str()) != Some(trimmed) {
-            self.history.push(trimmed.to_string());
+        if let Err(e) = self.history.push(line) {
+            self.push_output(format!("history: {}", e));
         }
         self.history_pos = None;
     }
@@ -121,13 +243,13 @@ impl TerminalPane {
             None => {
                 let i = self.history.len() - 1;
                 self.history_pos = Some(i);
-                self.set_input_from_history(self.history[i].clone());
+                self.set_input_from_history(self.history.get(i).unwrap_or_default().to_string());
             }
             Some(i) => {
                 if i > 0 {
                     let ni = i - 1;
                     self.history_pos = Some(ni);
-                    self.set_input_from_history(self.history[ni].clone());
+                    self.set_input_from_history(self.history.get(ni).unwrap_or_default().to_string());
                 }
             }
         }
@@ -138,7 +260,7 @@ impl TerminalPane {
             if i + 1 < self.history.len() {
                 let ni = i + 1;
                 self.history_pos = Some(ni);
-                self.set_input_from_history(self.history[ni].clone());
+                self.set_input_from_history(self.history.get(ni).unwrap_or_default().to_string());
             } else {
                 // Exited history back to fresh input
                 self.history_pos = None;
@@ -146,4 +268,4 @@ impl TerminalPane {
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,55 @@
+//! Toasts: short-lived notifications drawn above the status bar, on every screen.
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::shell::tui::state::Toast;
+
+/// Stateless toast rendering and helpers operating on `Option<Toast>`.
+pub struct ToastView;
+
+const INFO_DURATION: Duration = Duration::from_secs(4);
+const ERROR_DURATION: Duration = Duration::from_secs(8);
+
+impl ToastView {
+    /// Show an information toast (replaces the current one).
+    pub fn info(toast: &mut Option<Toast>, message: impl Into<String>) {
+        *toast = Some(Toast { message: message.into(), error: false, until: Instant::now() + INFO_DURATION });
+    }
+
+    /// Show an error toast, kept on screen a little longer.
+    pub fn error(toast: &mut Option<Toast>, message: impl Into<String>) {
+        *toast = Some(Toast { message: message.into(), error: true, until: Instant::now() + ERROR_DURATION });
+    }
+
+    /// Drop the toast once its time is up; call on every UI tick.
+    pub fn expire(toast: &mut Option<Toast>) {
+        if toast.as_ref().is_some_and(|t| Instant::now() >= t.until) {
+            *toast = None;
+        }
+    }
+
+    /// Render in the bottom-right corner of `area` (the screen area above the status bar).
+    pub fn render(f: &mut Frame, area: Rect, toast: &Option<Toast>) {
+        let Some(t) = toast else { return; };
+        let width = (t.message.chars().count() as u16 + 4).min(area.width);
+        let height = 3.min(area.height);
+        let rect = Rect {
+            x: area.right().saturating_sub(width + 1),
+            y: area.bottom().saturating_sub(height),
+            width,
+            height,
+        };
+        let color = if t.error { Color::Red } else { Color::Green };
+        f.render_widget(Clear, rect);
+        let p = Paragraph::new(Line::from(t.message.clone()))
+            .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(color)));
+        f.render_widget(p, rect);
+    }
+}
@@ -0,0 +1,56 @@
+//! Unsaved-changes confirmation, shown before closing a modified tab (Ctrl+W, `x`
+//! in the tab picker) or quitting the TUI while tabs are modified: [s] saves them
+//! first, [d] drops the changes, Esc cancels.
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::shell::tui::state::{EditorTabs, PendingClose, UnsavedState};
+
+/// Stateless renderer for the unsaved-changes confirmation.
+pub struct UnsavedView;
+
+impl UnsavedView {
+    /// Modified tabs concerned: the one being closed, or all of them before quitting.
+    pub fn dirty_tabs(tabs: &EditorTabs, action: PendingClose) -> Vec<usize> {
+        let concerned = |i: &usize| match action {
+            PendingClose::Tab(idx) => *i == idx,
+            PendingClose::Quit => true,
+        };
+        (0..tabs.tabs.len()).filter(concerned).filter(|i| tabs.tabs[*i].state.dirty).collect()
+    }
+
+    /// Render the modified files and the choices.
+    pub fn render(f: &mut Frame, area: Rect, tabs: &EditorTabs, unsaved: &UnsavedState) {
+        f.render_widget(Clear, area);
+        let dirty = Self::dirty_tabs(tabs, unsaved.action);
+        let mut lines: Vec<Line> = vec![Line::from(match unsaved.action {
+            PendingClose::Tab(_) => String::from("Ce fichier a des modifications non enregistrées :"),
+            PendingClose::Quit if dirty.len() == 1 => String::from("Avant de quitter, 1 fichier n’est pas enregistré :"),
+            PendingClose::Quit => format!("Avant de quitter, {} fichiers ne sont pas enregistrés :", dirty.len()),
+        })];
+        lines.push(Line::from(""));
+        for idx in &dirty {
+            let name = tabs.tabs[*idx]
+                .state
+                .path
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|| String::from("[No Name]"));
+            lines.push(Line::from(Span::styled(format!("  ● {}", name), Style::default().add_modifier(Modifier::BOLD))));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "[s] Enregistrer  [d] Abandonner les modifications  [Esc] Annuler",
+            Style::default().fg(Color::DarkGray),
+        )));
+        let widget = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title("Modifications non enregistrées"));
+        f.render_widget(widget, area);
+    }
+}
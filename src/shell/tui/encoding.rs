@@ -0,0 +1,212 @@
+//! Text encodings and line endings of the files opened in the editor.
+//!
+//! A file is detected as UTF-8 (with or without BOM), UTF-16 (LE / BE, from its
+//! BOM) or Latin-1 (anything that is not valid UTF-8 and has no control bytes).
+//! The editor works on LF-only text: CRLF files are converted on open and written
+//! back with CRLF, in their original encoding.
+
+/// Encoding a buffer is read from and written back in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+/// Line break written between the lines of a buffer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Control bytes that do not occur in text (tab, line breaks, form feed and escape do)
+fn is_binary_control(b: u8) -> bool {
+    b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0C | 0x1B)
+}
+
+/// Whether the first bytes of a file look like binary data rather than text in one
+/// of the supported encodings.
+pub fn looks_binary(head: &[u8]) -> bool {
+    if head.starts_with(UTF16LE_BOM) || head.starts_with(UTF16BE_BOM) {
+        return false;
+    }
+    if head.contains(&0) {
+        return true;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => false,
+        // Un caractère multi-octets coupé en fin de fenêtre n'est pas une erreur
+        Err(e) if e.error_len().is_none() => false,
+        Err(_) => head.iter().any(|b| is_binary_control(*b)),
+    }
+}
+
+impl TextEncoding {
+    /// Encoding of a file from its first bytes (the whole file when it is small).
+    pub fn detect(head: &[u8]) -> Self {
+        if head.starts_with(UTF8_BOM) {
+            TextEncoding::Utf8Bom
+        } else if head.starts_with(UTF16LE_BOM) {
+            TextEncoding::Utf16Le
+        } else if head.starts_with(UTF16BE_BOM) {
+            TextEncoding::Utf16Be
+        } else {
+            match std::str::from_utf8(head) {
+                Ok(_) => TextEncoding::Utf8,
+                Err(e) if e.error_len().is_none() => TextEncoding::Utf8,
+                Err(_) => TextEncoding::Latin1,
+            }
+        }
+    }
+
+    /// Name shown in the editor status bar.
+    pub fn label(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf8Bom => "UTF-8 BOM",
+            TextEncoding::Utf16Le => "UTF-16 LE",
+            TextEncoding::Utf16Be => "UTF-16 BE",
+            TextEncoding::Latin1 => "Latin-1",
+        }
+    }
+
+    fn bom(&self) -> &'static [u8] {
+        match self {
+            TextEncoding::Utf8Bom => UTF8_BOM,
+            TextEncoding::Utf16Le => UTF16LE_BOM,
+            TextEncoding::Utf16Be => UTF16BE_BOM,
+            TextEncoding::Utf8 | TextEncoding::Latin1 => &[],
+        }
+    }
+}
+
+impl LineEnding {
+    /// Name shown in the editor status bar.
+    pub fn label(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "LF",
+            LineEnding::Crlf => "CRLF",
+        }
+    }
+}
+
+/// Decodes a file chunk by chunk into LF-only text.
+pub struct Decoder {
+    encoding: TextEncoding,
+    /// Decided at the first line break
+    line_ending: Option<LineEnding>,
+    /// Bytes of a character cut by the end of the previous chunk
+    pending: Vec<u8>,
+    /// A '\r' ending the previous chunk, maybe the first half of a CRLF
+    held_cr: bool,
+    started: bool,
+}
+
+impl Decoder {
+    pub fn new(encoding: TextEncoding) -> Self {
+        Decoder { encoding, line_ending: None, pending: Vec::new(), held_cr: false, started: false }
+    }
+
+    /// Line ending of the text decoded so far (LF until a CRLF is seen first).
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending.unwrap_or_default()
+    }
+
+    /// Decode the next chunk; `last` flushes what is held back for the next one.
+    pub fn decode(&mut self, bytes: &[u8], last: bool) -> Result<String, String> {
+        self.pending.extend_from_slice(bytes);
+        if !self.started {
+            // La marque d'ordre des octets n'appartient pas au texte
+            let bom = self.encoding.bom();
+            if self.pending.len() < bom.len() && !last {
+                return Ok(String::new());
+            }
+            if self.pending.starts_with(bom) {
+                self.pending.drain(..bom.len());
+            }
+            self.started = true;
+        }
+        let mut text = if self.held_cr { String::from("\r") } else { String::new() };
+        self.held_cr = false;
+        match self.encoding {
+            TextEncoding::Utf8 | TextEncoding::Utf8Bom => {
+                let valid = match std::str::from_utf8(&self.pending) {
+                    Ok(s) => s.len(),
+                    Err(e) if e.error_len().is_none() && !last => e.valid_up_to(),
+                    Err(_) => return Err(String::from("Contenu UTF-8 invalide")),
+                };
+                text.push_str(std::str::from_utf8(&self.pending[..valid]).expect("validated above"));
+                self.pending.drain(..valid);
+            }
+            TextEncoding::Latin1 => {
+                text.extend(self.pending.drain(..).map(char::from));
+            }
+            TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+                let mut units: Vec<u16> = self
+                    .pending
+                    .chunks_exact(2)
+                    .map(|b| match self.encoding {
+                        TextEncoding::Utf16Le => u16::from_le_bytes([b[0], b[1]]),
+                        _ => u16::from_be_bytes([b[0], b[1]]),
+                    })
+                    .collect();
+                // Demi-paire de substitution en fin de bloc : attendre l'autre moitié
+                let keep_surrogate = !last && units.last().is_some_and(|u| (0xD800..0xDC00).contains(u));
+                if keep_surrogate {
+                    units.pop();
+                }
+                let used = units.len() * 2;
+                text.extend(char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)));
+                self.pending.drain(..used);
+                if last && !self.pending.is_empty() {
+                    self.pending.clear();
+                    text.push(char::REPLACEMENT_CHARACTER);
+                }
+            }
+        }
+        if !last && text.ends_with('\r') {
+            text.pop();
+            self.held_cr = true;
+        }
+        if self.line_ending.is_none() && let Some(i) = text.find('\n') {
+            let crlf = text[..i].ends_with('\r');
+            self.line_ending = Some(if crlf { LineEnding::Crlf } else { LineEnding::Lf });
+        }
+        if self.line_ending == Some(LineEnding::Crlf) {
+            text = text.replace("\r\n", "\n");
+        }
+        Ok(text)
+    }
+}
+
+/// Bytes of a buffer's LF-only text in `encoding`, with `line_ending` between lines.
+pub fn encode(text: &str, encoding: TextEncoding, line_ending: LineEnding) -> Result<Vec<u8>, String> {
+    let text = match line_ending {
+        LineEnding::Lf => std::borrow::Cow::Borrowed(text),
+        LineEnding::Crlf => std::borrow::Cow::Owned(text.replace("\r\n", "\n").replace('\n', "\r\n")),
+    };
+    let mut bytes = encoding.bom().to_vec();
+    match encoding {
+        TextEncoding::Utf8 | TextEncoding::Utf8Bom => bytes.extend_from_slice(text.as_bytes()),
+        TextEncoding::Utf16Le => bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes)),
+        TextEncoding::Utf16Be => bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes)),
+        TextEncoding::Latin1 => {
+            for c in text.chars() {
+                let Ok(b) = u8::try_from(u32::from(c)) else {
+                    return Err(format!("« {} » ne peut pas être enregistré en Latin-1", c));
+                };
+                bytes.push(b);
+            }
+        }
+    }
+    Ok(bytes)
+}
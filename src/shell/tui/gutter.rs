@@ -0,0 +1,143 @@
+//! Git markers of the editor buffers.
+//!
+//! The gutter shows the lines added (green), modified (yellow) or deleted (red, on
+//! the line above the deletion) since HEAD. The HEAD version of a file is read when
+//! its tab opens, after a reload and when leaving the Git screen; markers then follow
+//! every change through a time-boxed line diff. `:set blame` (Alt+b) adds the author,
+//! date and summary of the last commit of the cursor line, from `git blame` run in
+//! the background on the buffer text.
+
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use similar::{DiffOp, TextDiff};
+
+use crate::shell::git;
+use crate::shell::tui::encoding::{self, Decoder};
+use crate::shell::tui::state::{EditorState, LineChange};
+
+/// Buffers larger than this get neither markers nor blame.
+const MAX_BYTES: usize = 1 << 20;
+/// Time given to the line diff before it settles for a coarser result.
+const DIFF_TIMEOUT: Duration = Duration::from_millis(50);
+/// Delay between two blame runs while the buffer changes.
+const BLAME_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Read the HEAD version of the file of a buffer; markers and blame are recomputed
+/// on the next update.
+pub fn load_head(ed: &mut EditorState) {
+    ed.git.revision = None;
+    ed.git.blame_revision = None;
+    ed.git.marks.clear();
+    ed.git.repo = ed.path.as_deref().filter(|_| ed.remote.is_none()).and_then(git::locate);
+    let small = ed.buffer.len_bytes() <= MAX_BYTES;
+    ed.git.head = ed.git.repo.as_ref().filter(|_| small).and_then(|(root, rel)| {
+        let bytes = git::head_blob(root, rel).ok()?;
+        Decoder::new(ed.encoding).decode(&bytes, true).ok()
+    });
+}
+
+/// Recompute the markers of a buffer changed since the last update.
+pub fn update_marks(ed: &mut EditorState) {
+    if ed.git.revision == Some(ed.revision) {
+        return;
+    }
+    ed.git.revision = Some(ed.revision);
+    ed.git.marks.clear();
+    let Some(head) = ed.git.head.as_deref() else { return; };
+    if ed.buffer.len_bytes() > MAX_BYTES {
+        return;
+    }
+    let text = ed.buffer.to_string();
+    let diff = TextDiff::configure().timeout(DIFF_TIMEOUT).diff_lines(head, &text);
+    let mut marks = vec![LineChange::Unchanged; ed.buffer.len_lines()];
+    for op in diff.ops() {
+        match *op {
+            DiffOp::Equal { .. } => {}
+            DiffOp::Insert { new_index, new_len, .. } => {
+                marks.iter_mut().skip(new_index).take(new_len).for_each(|m| *m = LineChange::Added);
+            }
+            DiffOp::Replace { new_index, new_len, .. } => {
+                marks.iter_mut().skip(new_index).take(new_len).for_each(|m| *m = LineChange::Modified);
+            }
+            DiffOp::Delete { new_index, .. } => {
+                let (row, change) = match new_index {
+                    0 => (0, LineChange::DeletedAbove),
+                    n => (n - 1, LineChange::DeletedBelow),
+                };
+                if let Some(mark) = marks.get_mut(row).filter(|m| **m == LineChange::Unchanged) {
+                    *mark = change;
+                }
+            }
+        }
+    }
+    ed.git.marks = marks;
+}
+
+/// Show or hide the inline blame; refused for a file outside a repository.
+pub fn set_blame(ed: &mut EditorState, on: bool) -> Result<(), String> {
+    if on && ed.git.repo.is_none() {
+        return Err(String::from("Pas de dépôt git pour ce fichier"));
+    }
+    ed.git.blame = on;
+    Ok(())
+}
+
+/// Collect a finished blame and start a new one when the buffer changed since the
+/// last run. A failure turns the blame off and is returned.
+pub fn poll_blame(ed: &mut EditorState) -> Option<String> {
+    if !ed.git.blame {
+        return None;
+    }
+    if let Some(rx) = ed.git.blame_running.as_ref() {
+        match rx.try_recv() {
+            Ok((revision, Ok(lines))) => {
+                ed.git.blame_lines = lines;
+                ed.git.blame_revision = Some(revision);
+            }
+            Ok((_, Err(e))) => {
+                ed.git.blame_running = None;
+                ed.git.blame = false;
+                return Some(format!("git blame : {}", e));
+            }
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => {}
+        }
+        ed.git.blame_running = None;
+    }
+    let due = ed.git.blame_started.is_none_or(|t| t.elapsed() >= BLAME_INTERVAL);
+    if ed.git.blame_revision == Some(ed.revision) || !due || ed.buffer.len_bytes() > MAX_BYTES {
+        return None;
+    }
+    let (root, rel) = ed.git.repo.clone()?;
+    // git compare les octets du fichier : mêmes encodage et fins de ligne
+    let contents = match encoding::encode(&ed.buffer.to_string(), ed.encoding, ed.line_ending) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            ed.git.blame = false;
+            return Some(e);
+        }
+    };
+    let revision = ed.revision;
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send((revision, git::blame(&root, &rel, &contents).map_err(|e| e.to_string())));
+    });
+    ed.git.blame_running = Some(rx);
+    ed.git.blame_started = Some(Instant::now());
+    None
+}
+
+/// Inline blame of a buffer line: author, date and summary of its last commit.
+pub fn blame_text(ed: &EditorState, row: usize) -> Option<String> {
+    if !ed.git.blame {
+        return None;
+    }
+    let line = ed.git.blame_lines.get(row)?;
+    if !line.committed {
+        return Some(String::from("Non commité"));
+    }
+    let date = DateTime::from_timestamp(line.time, 0)?.with_timezone(&Local).format("%d/%m/%Y");
+    Some(format!("{}, {} • {}", line.author, date, line.summary))
+}
@@ -0,0 +1,272 @@
+//! Job manager for commands launched from the TUI Shell screen.
+//!
+//! Every system command started from the TUI runs as a child process with its
+//! stdout/stderr collected by reader threads, so the event loop never blocks:
+//! - Foreground jobs stream their output into the TerminalPane as it arrives
+//! - Background jobs (trailing `&`) keep running and are listed in `:jobs`
+//!
+//! The manager is polled on each tick to refresh job statuses. The `jobs`, `fg`
+//! and `bg` built-ins of the Shell screen act on these jobs (see [`JobManager::builtin`]).
+
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::shell::executor::{self, Redirections};
+
+/// Lifecycle of a job.
+#[derive(Clone, PartialEq, Eq)]
+pub enum JobStatus {
+    Running,
+    Exited(i32),
+    Killed,
+}
+
+impl JobStatus {
+    /// Short label used by the jobs overlay.
+    pub fn label(&self) -> String {
+        match self {
+            JobStatus::Running => "running".into(),
+            JobStatus::Exited(code) => format!("exit {}", code),
+            JobStatus::Killed => "killed".into(),
+        }
+    }
+}
+
+/// A single command started from the TUI.
+pub struct Job {
+    pub id: usize,
+    pub command: String,
+    pub status: JobStatus,
+    child: Option<Child>,
+    /// Lines collected from stdout and stderr (interleaved in arrival order)
+    output: Arc<Mutex<Vec<String>>>,
+    /// Number of lines already forwarded to the TerminalPane while attached
+    forwarded: usize,
+    /// Reader threads still draining stdout/stderr
+    open_streams: Arc<AtomicUsize>,
+}
+
+impl Job {
+    /// Snapshot of the collected output.
+    pub fn output(&self) -> Vec<String> {
+        self.output.lock().map(|o| o.clone()).unwrap_or_default()
+    }
+}
+
+/// Owns every job started from the TUI and tracks which one is in the foreground.
+pub struct JobManager {
+    jobs: Vec<Job>,
+    next_id: usize,
+    foreground: Option<usize>,
+}
+
+/// Forward every line read from `stream` into the shared output buffer.
+fn pipe_lines<R: Read + Send + 'static>(stream: R, sink: Arc<Mutex<Vec<String>>>, open: Arc<AtomicUsize>) {
+    open.fetch_add(1, Ordering::SeqCst);
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            if let Ok(mut out) = sink.lock() {
+                out.push(line);
+            }
+        }
+        open.fetch_sub(1, Ordering::SeqCst);
+    });
+}
+
+impl JobManager {
+    pub fn new() -> Self {
+        Self { jobs: Vec::new(), next_id: 1, foreground: None }
+    }
+
+    /// Spawn `cmd args` as a new job, its streams going to the files of `redir`.
+    /// Foreground jobs become attached to the terminal. A redirection file that cannot
+    /// be opened is an `InvalidInput` error.
+    pub fn spawn(&mut self, line: &str, cmd: &str, args: &[&str], redir: &Redirections, background: bool) -> std::io::Result<usize> {
+        self.launch(&mut executor::command(cmd, args), line, redir, background)
+    }
+
+    /// Same as `spawn` without redirections, with `dir` as the working directory of the process.
+    pub fn spawn_in(&mut self, dir: &Path, line: &str, cmd: &str, args: &[&str], background: bool) -> std::io::Result<usize> {
+        self.launch(executor::command(cmd, args).current_dir(dir), line, &Redirections::default(), background)
+    }
+
+    fn launch(&mut self, command: &mut Command, line: &str, redir: &Redirections, background: bool) -> std::io::Result<usize> {
+        command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+        // `< f`, `> f`, `2> f` : le fichier remplace l'entrée vide ou le tube
+        executor::apply_redirections(command, redir).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        let mut child = command.spawn()?;
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let open_streams = Arc::new(AtomicUsize::new(0));
+        if let Some(out) = child.stdout.take() {
+            pipe_lines(out, output.clone(), open_streams.clone());
+        }
+        if let Some(err) = child.stderr.take() {
+            pipe_lines(err, output.clone(), open_streams.clone());
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.jobs.push(Job {
+            id,
+            command: line.to_string(),
+            status: JobStatus::Running,
+            child: Some(child),
+            output,
+            forwarded: 0,
+            open_streams,
+        });
+        if !background {
+            self.foreground = Some(id);
+        }
+        Ok(id)
+    }
+
+    /// Refresh the status of running jobs (non-blocking).
+    pub fn poll(&mut self) {
+        for job in self.jobs.iter_mut().filter(|j| j.status == JobStatus::Running) {
+            if let Some(child) = job.child.as_mut() && let Ok(Some(status)) = child.try_wait() {
+                // Pas de code de sortie : arrêté par un signal (Ctrl+C, kill…)
+                job.status = status.code().map_or(JobStatus::Killed, JobStatus::Exited);
+                job.child = None;
+            }
+        }
+    }
+
+    /// All jobs, oldest first.
+    pub fn jobs(&self) -> &[Job] {
+        &self.jobs
+    }
+
+    /// Job with the given id, if still tracked.
+    pub fn get(&self, id: usize) -> Option<&Job> {
+        self.jobs.iter().find(|j| j.id == id)
+    }
+
+    /// Number of jobs still running.
+    pub fn running_count(&self) -> usize {
+        self.jobs.iter().filter(|j| j.status == JobStatus::Running).count()
+    }
+
+    /// Id of the job currently attached to the terminal, if any.
+    pub fn foreground(&self) -> Option<usize> {
+        self.foreground
+    }
+
+    /// Run `jobs`, `fg [%n]` or `bg [%n]` on the TUI's jobs, with the messages of the
+    /// REPL built-ins; None for any other command. `fg` attaches the job to the
+    /// terminal; TUI jobs are never stopped, so `bg` has nothing to resume.
+    pub fn builtin(&mut self, cmd: &str, args: &[&str]) -> Option<Vec<String>> {
+        let spec = args.first().copied();
+        let found = match spec {
+            Some(spec) => match spec.trim_start_matches('%').parse::<usize>() {
+                Ok(id) => self.get(id).map(|j| j.id).ok_or_else(|| format!("{}: no such job", spec)),
+                Err(_) => Err(format!("{}: invalid job number", spec)),
+            },
+            None => self
+                .jobs
+                .iter()
+                .rfind(|j| cmd == "fg" && j.status == JobStatus::Running)
+                .map(|j| j.id)
+                .ok_or_else(|| String::from("no current job")),
+        };
+        let out = match cmd {
+            "jobs" if self.jobs.is_empty() => vec![String::from("Aucun job (cmd & pour lancer en arrière-plan)")],
+            "jobs" => self.jobs.iter().map(|j| format!("[{}] {:<10} {}", j.id, j.status.label(), j.command)).collect(),
+            "fg" => match found {
+                Ok(id) => {
+                    self.bring_to_foreground(id);
+                    self.get(id).map(|j| vec![j.command.clone()]).unwrap_or_default()
+                }
+                Err(e) => vec![format!("fg: {}", e)],
+            },
+            "bg" => match found {
+                Ok(id) => vec![format!("bg: job {} already in background", id)],
+                Err(e) => vec![format!("bg: {}", e)],
+            },
+            _ => return None,
+        };
+        Some(out)
+    }
+
+    /// Attach a job to the terminal; its not-yet-forwarded output will be streamed.
+    pub fn bring_to_foreground(&mut self, id: usize) {
+        if self.jobs.iter().any(|j| j.id == id) {
+            self.foreground = Some(id);
+        }
+    }
+
+    /// Take the output lines of the foreground job that were not forwarded yet.
+    /// Returns the new lines and, once the job has finished and its output is fully read,
+    /// its final status (the job is then detached).
+    pub fn drain_foreground(&mut self) -> (Vec<String>, Option<JobStatus>) {
+        let Some(id) = self.foreground else { return (Vec::new(), None); };
+        let Some(job) = self.jobs.iter_mut().find(|j| j.id == id) else {
+            self.foreground = None;
+            return (Vec::new(), None);
+        };
+        // État lu avant la sortie : les lecteurs ferment leur flux après leur dernière
+        // ligne, donc une fois le job fini cette copie est la dernière
+        let done = job.status != JobStatus::Running && job.open_streams.load(Ordering::SeqCst) == 0;
+        let lines: Vec<String> = job
+            .output
+            .lock()
+            .map(|o| o[job.forwarded.min(o.len())..].to_vec())
+            .unwrap_or_default();
+        job.forwarded += lines.len();
+
+        if !done {
+            (lines, None)
+        } else {
+            let status = job.status.clone();
+            self.foreground = None;
+            (lines, Some(status))
+        }
+    }
+
+    /// Kill a running job.
+    pub fn kill(&mut self, id: usize) -> std::io::Result<()> {
+        if let Some(job) = self.jobs.iter_mut().find(|j| j.id == id) && let Some(child) = job.child.as_mut() {
+            child.kill()?;
+            let _ = child.wait();
+            job.child = None;
+            job.status = JobStatus::Killed;
+        }
+        Ok(())
+    }
+
+    /// Interrupt a running job like Ctrl+C in a terminal (SIGINT; killed elsewhere).
+    pub fn interrupt(&mut self, id: usize) -> std::io::Result<()> {
+        #[cfg(unix)]
+        if let Some(child) = self.jobs.iter().find(|j| j.id == id).and_then(|j| j.child.as_ref()) {
+            // SAFETY: simple envoi de signal à un PID dont on est le parent
+            if unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGINT) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            return Ok(());
+        }
+        self.kill(id)
+    }
+
+    /// Forget finished jobs (keeps running ones and the foreground job).
+    pub fn clear_finished(&mut self) {
+        let fg = self.foreground;
+        self.jobs.retain(|j| j.status == JobStatus::Running || Some(j.id) == fg);
+    }
+}
+
+impl Drop for JobManager {
+    /// Leaving the TUI must not leave orphaned children behind.
+    fn drop(&mut self) {
+        for job in &mut self.jobs {
+            if let Some(child) = job.child.as_mut() {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+        }
+    }
+}
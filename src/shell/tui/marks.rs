@@ -0,0 +1,97 @@
+//! Editor marks kept across sessions.
+//!
+//! `m a`–`m z` mark a position in the current file and `m A`–`m Z` a position in any
+//! file. Marks move with their lines while the file is edited. Lowercase marks are
+//! saved per file in `~/.local/state/paschek/marks/` (named like the swap files),
+//! uppercase ones together in `marks/global.json`; they are written when a mark is
+//! set and when the file is saved, and read back when it opens.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::shell::tui::state::EditorState;
+use crate::shell::tui::swap;
+
+/// Uppercase mark saved in `global.json`.
+#[derive(Serialize, Deserialize)]
+pub struct GlobalMark {
+    pub path: PathBuf,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Whether `c` names a mark that can be set.
+pub fn is_mark(c: char) -> bool {
+    c.is_ascii_alphabetic()
+}
+
+fn global_file() -> PathBuf {
+    swap::state_dir("marks").join("global.json")
+}
+
+fn read_global() -> BTreeMap<char, GlobalMark> {
+    fs::read_to_string(global_file())
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Local file of a buffer: files of a virtual filesystem keep no marks.
+fn local_path(ed: &EditorState) -> Option<&Path> {
+    ed.path.as_deref().filter(|_| ed.remote.is_none())
+}
+
+/// Saved uppercase mark `c`, when its file is not open.
+pub fn global(c: char) -> Option<GlobalMark> {
+    read_global().remove(&c)
+}
+
+/// Read the saved marks of a freshly opened file.
+pub fn load(ed: &mut EditorState) {
+    let Some(path) = local_path(ed) else { return; };
+    let key = canonical(path);
+    let local: BTreeMap<char, (usize, usize)> = fs::read_to_string(swap::state_file("marks", path, "json"))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let global: Vec<(char, (usize, usize))> = read_global()
+        .into_iter()
+        .filter(|(_, m)| canonical(&m.path) == key)
+        .map(|(c, m)| (c, (m.row, m.col)))
+        .collect();
+    ed.marks.extend(local.into_iter().filter(|(c, _)| c.is_ascii_lowercase()));
+    ed.marks.extend(global);
+}
+
+/// Write the marks of a buffer.
+pub fn save(ed: &EditorState) {
+    let Some(path) = local_path(ed) else { return; };
+    let _ = fs::create_dir_all(swap::state_dir("marks"));
+    let target = swap::state_file("marks", path, "json");
+    let local: BTreeMap<char, (usize, usize)> = ed.marks.iter().filter(|(c, _)| c.is_ascii_lowercase()).map(|(c, p)| (*c, *p)).collect();
+    if local.is_empty() {
+        let _ = fs::remove_file(target);
+    } else if let Ok(json) = serde_json::to_string(&local) {
+        let _ = fs::write(target, json);
+    }
+    // Marques globales : celles de ce fichier sont remplacées par celles du buffer
+    let key = canonical(path);
+    let mut all = read_global();
+    let before = all.len();
+    all.retain(|_, m| canonical(&m.path) != key);
+    let mut changed = all.len() != before;
+    for (c, (row, col)) in ed.marks.iter().filter(|(c, _)| c.is_ascii_uppercase()) {
+        all.insert(*c, GlobalMark { path: key.clone(), row: *row, col: *col });
+        changed = true;
+    }
+    if changed && let Ok(json) = serde_json::to_string(&all) {
+        let _ = fs::write(global_file(), json);
+    }
+}
@@ -7,36 +7,78 @@
 //! - Explorer: a file browser limited to a root directory
 //! - Editor: a basic text editor with ropey for efficient editing
 //! - Workspace: a split view combining Explorer and Editor with focus switching
+//! - Dashboard: live CPU / memory / disk / network graphs
+//! - Git: working tree status, staging, colored diffs and commit
 //!
 //! Interaction model:
 //! - Global overlay for Help (ephemeral, closes on next key)
 //! - Status bar with contextual hints
-//! - Shell supports TUI commands prefixed with ':' (e.g., :q, :l, :h, :fs, :e <path>)
+//! - Shell supports TUI commands prefixed with ':' (e.g., :q, :l, :h, :fs, :e <path>, :jobs)
 //! - TerminalPane supports input editing, history navigation, and cursor movement
+//! - System commands run as jobs (trailing `&` for background) so the UI never blocks
+//! - Editor yanks and Ctrl+Y in the shell go to the system clipboard; pasting in the
+//!   terminal (bracketed paste) types into the shell line or the editor
 //!
 //! Error handling is user-friendly: most failures surface as messages in the
 //! TerminalPane output or the Logs panel rather than panicking.
 
+mod clipboard;
+mod encoding;
 mod command_mode;
 mod components;
+mod jobs;
 mod state;
+mod gutter;
+mod marks;
+mod snippets;
+mod swap;
+mod undo;
 
-use crate::shell::{prompt::Theme, tui::state::Focus};
+use crate::shell::{engine::{Dispatch, External, Output, ShellEngine}, history::SearchKey, commands::{open::open_default, CommandRegistry}, config::{expand_home, paths, EditorConfig, ShellConfig, ExplorerConfig, ThemeConfig}, prompt::theme, parser, tui::state::Focus};
 use command_mode::TuiCommandHandler;
 use components::{
-    editor::EditorView,
+    containers::ContainerView,
+    csv_view::{self, CsvView},
+    dashboard::Dashboard,
+    diff::DiffView,
+    editor::{self, EditorView},
     explorer::FileExplorerView,
+    fileops::{FileOpsView, Resolution},
+    finder::FinderView,
+    git::GitView,
+    hex::{self, HexView},
+    http::HttpView,
     home::HomeView,
+    icons,
+    jobs::JobsView,
+    image_preview::{self, ImagePreview},
     logs::LogPanel,
+    markdown::MarkdownView,
+    open_with::OpenWithView,
+    permissions::PermissionsView,
+    replace::ReplaceView,
+    preview::PreviewView,
+    scratchpad::ScratchpadView,
+    search::SearchView,
+    symbols::SymbolsView,
+    sqlite::{self as sqlite_view, SqliteView},
     status::StatusBar,
+    tabs::TabsView,
+    unsaved::UnsavedView,
+    recover::RecoverView,
+    conflict::ConflictView,
+    toast::ToastView,
+    tasks::TaskView,
     terminal::TerminalPane,
 };
-use state::{EditorMode, Overlay, Screen, TuiState};
+use jobs::{JobManager, JobStatus};
+use state::{EditorMode, EditorTabs, ExplorerSort, Jump, MarkdownPreview, Overlay, Screen, TuiState};
 
 use crossterm::{
     event::{self, Event, KeyCode, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    event::{DisableBracketedPaste, EnableBracketedPaste},
 };
 
 use ratatui::{
@@ -44,12 +86,12 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
     text::Line,
-    widgets::{Block, Borders, Clear, Paragraph},
-    Terminal,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame, Terminal,
 };
 
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 /// Starts the PascheK Shell TUI event loop.
@@ -60,39 +102,239 @@ use std::time::{Duration, Instant};
 /// 3. Renders the current screen and processes input in a loop
 /// 4. Restores the terminal on exit
 ///
+/// `roots` (from `--root`) override the roots configured in `config/explorer.toml`;
+/// the Shell screen runs its commands through `shell`, the REPL's engine.
+///
 /// Returns an io::Result so terminal errors are propagated to the caller.
-pub fn start_tui() -> io::Result<()> {
+pub fn start_tui(roots: &[PathBuf], shell: &mut ShellEngine) -> io::Result<()> {
     // Passage en mode TUI (écran alternatif + raw mode)
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // --- État & composants ---
-    let mut state = TuiState::default();
-    // Démarrage sur la page d'accueil
-    state.screen = Screen::Home;
-    // Le focus sera appliqué quand on entrera en Workspace
-    state.focus = Focus::Explorer;
-
-    // Définir la racine: HOME (sinon fallback sur CWD)
-    let home_root = home::home_dir().unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
-    state.explorer.root = home_root.clone();
-    // Démarrer dans la racine
+    let shell_config = ShellConfig::get();
+    let mut state = TuiState {
+        // Écran de départ (`default_screen` de config.toml, l'accueil sinon)
+        screen: shell_config.default_screen.as_deref().and_then(Screen::parse).unwrap_or(Screen::Home),
+        // Le focus sera appliqué quand on entrera en Workspace
+        focus: Focus::Explorer,
+        ..TuiState::default()
+    };
+
+    // Préférences depuis config/explorer.toml
+    let cfg = ExplorerConfig::load_from_file(paths::config_file("explorer.toml")).unwrap_or_default();
+    state.explorer.sort = cfg.sort.as_deref().and_then(ExplorerSort::parse).unwrap_or_default();
+    state.explorer.sort_desc = cfg.descending.unwrap_or(false);
+    state.explorer.hide_ignored = cfg.hide_gitignored.unwrap_or(false);
+    state.explorer.follow_cwd = cfg.follow_cwd.unwrap_or(false);
+    state.explorer.show_hidden = shell_config.show_hidden();
+    state.open_with.programs = cfg.open_with;
+
+    // Indentation par type de fichier depuis config/editor.toml
+    state.tabs.config = EditorConfig::load_from_file(paths::config_file("editor.toml")).unwrap_or_default();
+
+    // Icônes depuis la section [tui.icons] de config/theme.toml
+    let icon_config = ThemeConfig::load_from_file(paths::config_file("theme.toml")).map(|t| t.tui.icons).unwrap_or_default();
+    state.explorer.icons = icons::theme(icon_config);
+
+    // Racines: --root, sinon la config, sinon HOME (fallback sur CWD)
+    let wanted: Vec<PathBuf> = if roots.is_empty() {
+        cfg.roots.iter().map(|r| expand_home(r)).collect()
+    } else {
+        roots.to_vec()
+    };
+    let mut invalid = Vec::new();
+    for root in wanted {
+        match root.canonicalize() {
+            Ok(dir) if dir.is_dir() => {
+                if !state.explorer.roots.contains(&dir) {
+                    state.explorer.roots.push(dir);
+                }
+            }
+            _ => invalid.push(root),
+        }
+    }
+    if state.explorer.roots.is_empty() {
+        state.explorer.roots.push(home::home_dir().unwrap_or_else(|| std::env::current_dir().unwrap_or_default()));
+    }
+    if !invalid.is_empty() {
+        let names: Vec<String> = invalid.iter().map(|p| p.display().to_string()).collect();
+        ToastView::error(&mut state.toast, format!("Racine introuvable : {}", names.join(", ")));
+    }
+    // Démarrer dans la première racine
+    state.explorer.root = state.explorer.roots[0].clone();
     state.explorer.cwd = state.explorer.root.clone();
     // (re)charger le listing
     FileExplorerView::refresh(&mut state.explorer);
+    // Écran de départ : charger ce que la page d'accueil chargerait
+    match state.screen {
+        Screen::Git => {
+            let dir = state.explorer.cwd.clone();
+            GitView::refresh(&mut state.git, &dir);
+        }
+        Screen::Containers => ContainerView::refresh(&mut state.containers),
+        Screen::Http => HttpView::reload_collection(&mut state.http),
+        _ => {}
+    }
 
-    let mut status = StatusBar::new(Theme::default());
+    let mut status = StatusBar::new(shell.prompt().lock().unwrap().theme().clone());
     let mut term = TerminalPane::new();
     let mut logs = LogPanel::new();
-    let home = HomeView::default();
+    let mut jobs = JobManager::new();
+    let mut dashboard = Dashboard::new();
+    let mut image_preview = ImagePreview::new();
+    let home = HomeView;
 
     let tick_rate = Duration::from_millis(100);
     let mut last_tick = Instant::now();
+    let mut last_swap = Instant::now();
+    let mut last_watch = Instant::now();
+    let mut theme_stamp = theme::active_stamp();
 
     while state.running {
+        // `exit` dont la confirmation a été refusée : le shell continue
+        if shell.exit_requested().is_some() && !quit_pending(&state) {
+            shell.take_exit();
+        }
+        // Jobs: rafraîchir les statuts et streamer la sortie du job au premier plan
+        jobs.poll();
+        let (new_lines, finished) = jobs.drain_foreground();
+        for l in new_lines {
+            term.push_output(l);
+        }
+        if let Some(st) = finished && st != JobStatus::Exited(0) {
+            term.push_output(format!("[{}]", st.label()));
+        }
+        // Programme interactif (PTY) terminé : retour à la saisie
+        if let Some(code) = term.poll_pty() && code != 0 {
+            term.push_output(format!("[exit {}]", code));
+        }
+        status.set_jobs(jobs.running_count());
+        if state.screen == Screen::Dashboard {
+            dashboard.tick();
+        }
+        HttpView::poll(&mut state.http);
+        FileExplorerView::poll(&mut state.explorer);
+        FinderView::poll(&mut state.finder);
+        if SymbolsView::poll(&mut state.symbols) && let Some(word) = state.symbols.pending_definition.take() {
+            match SymbolsView::definition(&state.symbols.index, &word).cloned() {
+                Some(sym) => goto_symbol(&mut state, &sym),
+                None => ToastView::error(&mut state.toast, format!("Définition introuvable : {}", word)),
+            }
+        }
+        SearchView::poll(&mut state.search);
+        ToastView::expire(&mut state.toast);
+        // Aperçu du fichier sélectionné tant que l'explorateur du Workspace a le focus
+        let preview_target = if state.screen == Screen::Workspace && state.focus == Focus::Explorer {
+            FileExplorerView::selected_file(&state.explorer).filter(|p| !image_preview::is_image(p))
+        } else {
+            None
+        };
+        PreviewView::request(&mut state.preview, preview_target);
+        PreviewView::poll(&mut state.preview);
+        match FileOpsView::poll_extract(&mut state.explorer) {
+            Some(Ok(msg)) => ToastView::info(&mut state.toast, msg),
+            Some(Err(e)) => ToastView::error(&mut state.toast, e),
+            None => {}
+        }
+        match FileExplorerView::poll_connect(&mut state.explorer) {
+            Some(Ok(Some(file))) => match EditorView::open_entry(file.clone(), &state.explorer) {
+                Ok(ed) => {
+                    open_tab(&mut state, ed);
+                    state.screen = Screen::Workspace;
+                    state.focus = Focus::Editor;
+                }
+                Err(e) => report_errors(&mut state, &mut logs, "Ouverture", &[format!("{}: {}", file.display(), e)]),
+            },
+            Some(Ok(None)) => {
+                state.screen = Screen::Workspace;
+                state.focus = Focus::Explorer;
+                ToastView::info(&mut state.toast, format!("🌐 Connecté à {}", state.explorer.root.display()));
+            }
+            Some(Err(e)) => ToastView::error(&mut state.toast, e),
+            None => {}
+        }
+        match EditorView::poll_load(&mut state.load) {
+            Some(Ok(ed)) => {
+                if state.overlay == Overlay::Loading {
+                    state.overlay = Overlay::None;
+                }
+                if ed.read_only {
+                    ToastView::info(&mut state.toast, "Fichier volumineux ouvert en lecture seule (Alt+r ou :set noreadonly pour modifier)");
+                }
+                open_tab(&mut state, ed);
+                state.screen = Screen::Workspace;
+                state.focus = Focus::Editor;
+            }
+            Some(Err(e)) => {
+                if state.overlay == Overlay::Loading {
+                    state.overlay = Overlay::None;
+                }
+                ToastView::error(&mut state.toast, e);
+            }
+            None => {}
+        }
+        // Copies de secours des onglets modifiés
+        if last_swap.elapsed() >= swap::SWAP_INTERVAL {
+            last_swap = Instant::now();
+            for tab in state.tabs.tabs.iter_mut() {
+                swap::autosave(&mut tab.state);
+            }
+        }
+        // Fichiers ouverts modifiés par un autre programme : recharger, ou demander
+        // quand l'onglet a des modifications non enregistrées
+        if last_watch.elapsed() >= editor::WATCH_INTERVAL {
+            last_watch = Instant::now();
+            watch_open_files(&mut state);
+            // Thème changé (`theme set`) ou son fichier modifié : recolorer la barre de statut
+            let stamp = theme::active_stamp();
+            if stamp != theme_stamp {
+                theme_stamp = stamp;
+                status.set_theme(theme::load_active().1);
+            }
+        }
+        // Onglet ouvert alors qu'une copie de secours plus récente que le fichier existe
+        if state.overlay == Overlay::None
+            && let Some(ed) = state.tabs.current_mut().filter(|ed| !ed.swap_checked)
+        {
+            ed.swap_checked = true;
+            if let Some(at) = swap::pending_recovery(ed) {
+                state.recover = Some(at);
+                state.overlay = Overlay::Recover;
+            }
+        }
+        if let Some(result) = FileOpsView::poll_compress(&mut state.compress) {
+            if state.overlay == Overlay::Compress {
+                state.overlay = Overlay::None;
+            }
+            match result {
+                Ok(msg) => ToastView::info(&mut state.toast, msg),
+                Err(e) => ToastView::error(&mut state.toast, e),
+            }
+            FileExplorerView::refresh(&mut state.explorer);
+        }
+        if FileOpsView::poll(&mut state.paste) {
+            if state.paste.cut {
+                state.explorer.clipboard = None;
+            }
+            FileExplorerView::refresh(&mut state.explorer);
+            if state.paste.errors.is_empty() {
+                state.overlay = Overlay::None;
+            }
+        }
+        // Un saut (recherche, ligne) dans une région pliée la déplie
+        if let Some(ed) = state.tabs.current_mut() {
+            EditorView::open_folds_at_cursor(ed);
+            EditorView::sync_block_insert(ed);
+            gutter::update_marks(ed);
+            if let Some(e) = gutter::poll_blame(ed) {
+                ToastView::error(&mut state.toast, e);
+            }
+        }
+
         terminal.draw(|f| {
             let area = f.area();
 
@@ -107,7 +349,7 @@ pub fn start_tui() -> io::Result<()> {
                 Screen::Home => {
                     home.render(f, chunks[0]);
                     // Hints par défaut
-                    let hints = "[1] Shell  [2] Shell+Logs  [3] Aide  [5] Workspace  [4/q] Quitter";
+                    let hints = "[1] Shell  [2] Shell+Logs  [3] Aide  [5] Workspace  [6] Système  [7] Git  [8] Conteneurs  [9] HTTP  [4/q] Quitter";
                     status.set_hint(hints);
                     status.render(f, chunks[1]);
                 }
@@ -197,7 +439,7 @@ pub fn start_tui() -> io::Result<()> {
                     }
 
                     // Hauteur dynamique: contenu (1..3 lignes) + 2 pour les bordures
-                    let content_lines: u16 = (lines.len().max(1).min(3)) as u16;
+                    let content_lines: u16 = lines.len().clamp(1, 3) as u16;
                     let tab_height: u16 = content_lines + 2;
                     let vchunks = Layout::default()
                         .direction(Direction::Vertical)
@@ -209,8 +451,13 @@ pub fn start_tui() -> io::Result<()> {
                         .block(Block::default().borders(Borders::ALL).border_style(editor_border).title(tabs_title));
                     f.render_widget(tabs_widget, vchunks[0]);
 
-                    if let Some(ed) = state.tabs.current() {
-                        EditorView::render_with_border(f, vchunks[1], ed, editor_border);
+                    let selected_image = if explorer_focused { image_preview::selected_image(&state.explorer) } else { None };
+                    if let Some(img) = selected_image {
+                        image_preview.render(f, vchunks[1], &img);
+                    } else if state.preview.path.is_some() {
+                        PreviewView::render(f, vchunks[1], &state.preview, editor_border);
+                    } else if !state.tabs.is_empty() {
+                        render_editor_area(f, vchunks[1], &mut state.tabs, editor_border, state.md_preview);
                     } else {
                         let p = Paragraph::new(Line::from(
                             "Aucun fichier ouvert — sélectionne un fichier à gauche ou tape :e <path>",
@@ -226,8 +473,8 @@ pub fn start_tui() -> io::Result<()> {
 
                     // Hints dynamiques dans la status bar
                     let hints = match state.focus {
-                        Focus::Explorer => "[Tab] Éditeur  [Entrée] Ouvrir  [.] Cachés  [q] Accueil",
-                        Focus::Editor => "[Tab] Explorer  [Ctrl+S] Sauver  [Ctrl+F] Rechercher  [Ctrl+G] Aller à la ligne",
+                        Focus::Explorer => "[Tab] Éditeur  [Entrée] Ouvrir  [/] Filtrer  [Espace] Sélection  [y/x/p] Copier/Couper/Coller  [.] Cachés  [I] Ignorés  [s/S] Tri  [q] Accueil",
+                        Focus::Editor => "[Esc] Explorer  [Ctrl+S] Sauver  [Ctrl+F] Rechercher  [Ctrl+R] Remplacer  [Ctrl+G] Aller à la ligne",
                     };
                     status.set_hint(hints);
 
@@ -245,14 +492,87 @@ pub fn start_tui() -> io::Result<()> {
                     } else {
                         term.render(f, chunks[0]);
                     }
-                    status.set_hint(
-                        "Tape :fs pour Workspace, :e <path> pour ouvrir, :h Aide, :l Logs, :q Quitter",
-                    );
+                    status.set_hint(if term.pty_active() {
+                        "Programme interactif : toutes les touches lui sont transmises"
+                    } else {
+                        "Tape :fs pour Workspace, :e <path> pour ouvrir, :h Aide, :l Logs, :q Quitter"
+                    });
                     status.render(f, chunks[1]);
                 }
                 Screen::Explorer => {
-                    FileExplorerView::render(f, chunks[0], &state.explorer, None);
-                    status.set_hint("[Tab] Éditeur  [Entrée] Ouvrir  [.] Cachés  [q] Quitter");
+                    if let Some(img) = image_preview::selected_image(&state.explorer) {
+                        let cols = Layout::default()
+                            .direction(Direction::Horizontal)
+                            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                            .split(chunks[0]);
+                        FileExplorerView::render(f, cols[0], &state.explorer, None);
+                        image_preview.render(f, cols[1], &img);
+                    } else {
+                        FileExplorerView::render(f, chunks[0], &state.explorer, None);
+                    }
+                    status.set_hint("[Tab] Éditeur  [Entrée] Ouvrir  [/] Filtrer  [Espace] Sélection  [y/x/p] Copier/Couper/Coller  [.] Cachés  [I] Ignorés  [s/S] Tri  [q] Quitter");
+                    status.render(f, chunks[1]);
+                }
+                Screen::Dashboard => {
+                    dashboard.render(f, chunks[0]);
+                    status.set_hint("[q/Esc] Accueil");
+                    status.render(f, chunks[1]);
+                }
+                Screen::Git => {
+                    GitView::render(f, chunks[0], &state.git);
+                    if state.git.message.is_some() {
+                        status.set_hint("[Ctrl+S] Valider le commit  [Esc] Annuler");
+                    } else {
+                        status.set_hint("[s] (Dés)indexer  [c] Commit  [r] Rafraîchir  [PgUp/PgDn] Diff  [q] Accueil");
+                    }
+                    status.render(f, chunks[1]);
+                }
+                Screen::Containers => {
+                    ContainerView::render(f, chunks[0], &state.containers, &jobs);
+                    status.set_hint("[Tab] Conteneurs/Images  [s] Start  [x] Stop  [l] Logs  [r] Rafraîchir  [PgUp/PgDn] Logs  [q] Accueil");
+                    status.render(f, chunks[1]);
+                }
+                Screen::Http => {
+                    HttpView::render(f, chunks[0], &state.http);
+                    status.set_hint("[Tab] Champ suivant  [Ctrl+R/F5] Envoyer  [Ctrl+S] Enregistrer  [Entrée] Plier/Charger  [Esc] Accueil");
+                    status.render(f, chunks[1]);
+                }
+                Screen::Data => {
+                    SqliteView::render(f, chunks[0], &state.sqlite);
+                    status.set_hint("[Tab] Focus  [Entrée] Table/Exécuter  [n/p] Page  [h/l] Colonnes  [:] SQL  [q] Retour");
+                    status.render(f, chunks[1]);
+                }
+                Screen::Table => {
+                    CsvView::render(f, chunks[0], &mut state.csv);
+                    status.set_hint("[hjkl] Naviguer  [s] Trier  [/] Filtrer  [e] Éditer le texte  [q] Retour");
+                    status.render(f, chunks[1]);
+                }
+                Screen::Hex => {
+                    HexView::render(f, chunks[0], &mut state.hex);
+                    if state.hex.editing {
+                        status.set_hint("[0-9a-f] Écrire  [Esc] Fin d’édition  [Ctrl+S] Enregistrer");
+                    } else {
+                        status.set_hint("[hjkl] Naviguer  [i] Éditer  [Ctrl+S] Enregistrer  [q] Retour");
+                    }
+                    status.render(f, chunks[1]);
+                }
+                Screen::Diff => {
+                    DiffView::render(f, chunks[0], &state.diff);
+                    status.set_hint("[s] Côte à côte/Unifié  [j/k/PgUp/PgDn] Défiler  [q] Retour");
+                    status.render(f, chunks[1]);
+                }
+                Screen::Tasks => {
+                    TaskView::render(f, chunks[0], &state.tasks);
+                    status.set_hint("[j/k] Naviguer  [Entrée] Lancer dans le terminal  [r] Recharger  [q] Retour");
+                    status.render(f, chunks[1]);
+                }
+                Screen::Search => {
+                    SearchView::render(f, chunks[0], &state.search);
+                    if state.search.editing {
+                        status.set_hint("[Entrée] Rechercher  [Esc] Résultats");
+                    } else {
+                        status.set_hint("[j/k] Naviguer  [Entrée] Ouvrir à la ligne  [/] Modifier la recherche  [q] Retour");
+                    }
                     status.render(f, chunks[1]);
                 }
                 Screen::Editor => {
@@ -303,7 +623,7 @@ pub fn start_tui() -> io::Result<()> {
                         }
                     }
                     // Hauteur dynamique: contenu (1..3 lignes) + 2 pour les bordures
-                    let content_lines: u16 = (lines.len().max(1).min(3)) as u16;
+                    let content_lines: u16 = lines.len().clamp(1, 3) as u16;
                     let tab_height: u16 = content_lines + 2;
                     let vchunks = Layout::default()
                         .direction(Direction::Vertical)
@@ -314,18 +634,25 @@ pub fn start_tui() -> io::Result<()> {
                         .block(Block::default().borders(Borders::ALL).title(tabs_title));
                     f.render_widget(tabs_widget, vchunks[0]);
 
-                    if let Some(ed) = state.tabs.current() {
-                        EditorView::render(f, vchunks[1], ed);
+                    if !state.tabs.is_empty() {
+                        // Volets côte à côte : le volet actif est encadré
+                        let border = if state.tabs.split.is_some() { Style::default().fg(Color::Yellow) } else { Style::default() };
+                        render_editor_area(f, vchunks[1], &mut state.tabs, border, state.md_preview);
                     } else {
                         let p = Paragraph::new(Line::from("Éditeur sans buffer — ouvrez un fichier."))
                             .block(Block::default().borders(Borders::ALL).title("Editor"));
                         f.render_widget(p, vchunks[1]);
                     }
-                    status.set_hint("[Ctrl+S] Sauver  [Ctrl+F] Rechercher  [Ctrl+G] Aller à la ligne  [Tab] Explorer");
+                    status.set_hint("[Ctrl+S] Sauver  [Ctrl+F] Rechercher  [Ctrl+R] Remplacer  [Ctrl+G] Aller à la ligne  [Tab] Explorer");
                     status.render(f, chunks[1]);
                 }
             }
 
+            // Bloc-notes ancré à droite, au-dessus de l'écran courant
+            if state.scratchpad.open {
+                ScratchpadView::render(f, chunks[0], &state.scratchpad);
+            }
+
             // Overlay d'aide (éphémère) — se ferme à la prochaine touche
             if state.overlay == Overlay::Help {
                 let popup = centered_rect(60, 40, area);
@@ -335,10 +662,67 @@ pub fn start_tui() -> io::Result<()> {
                     Line::from("PascheK TUI — Aide"),
                     Line::from(""),
                     Line::from(":q        → Quitter"),
+                    Line::from(":repl     → Revenir au REPL (historique et dossier partagés)"),
                     Line::from(":l        → Ouvrir/fermer les logs (sticky)"),
                     Line::from(":h        → Ouvrir/fermer cette aide (éphémère)"),
                     Line::from(":fs       → Ouvrir l’espace de travail (Explorer + Editeur)"),
                     Line::from(":e <path> → Ouvrir un fichier dans l’éditeur"),
+                    Line::from(":e sftp://hôte/chemin → Parcourir / éditer à distance (hôtes de ~/.ssh/config)"),
+                    Line::from(":jobs     → Lister les jobs (cmd & pour l’arrière-plan)"),
+                    Line::from(":git      → Statut git, indexation, diff et commit"),
+                    Line::from(":docker   → Conteneurs et images (start/stop/logs)"),
+                    Line::from(":http     → Client HTTP et requêtes enregistrées"),
+                    Line::from(":tasks    → Tâches du projet (make, cargo, npm, just)"),
+                    Line::from(":grep <re>→ Rechercher dans le contenu des fichiers (F dans l’explorateur)"),
+                    Line::from("Ctrl+P    → Aperçu Markdown (éditeur, fichiers .md)"),
+                    Line::from("Ctrl+O    → Rechercher un fichier dans toute la racine (flou)"),
+                    Line::from("F2        → Bloc-notes persistant (sauvegarde automatique)"),
+                    Line::from("Ctrl+D    → Diff du buffer avec HEAD (D ×2 dans l’explorateur : deux fichiers)"),
+                    Line::from("Alt+v / V → Éditeur : sélection caractères / lignes (v / V en mode Normal)"),
+                    Line::from("            y copier, d couper (presse-papiers système) ; Alt+p / Alt+P (p / P) coller"),
+                    Line::from("Alt+d / y → Éditeur : couper / copier la ligne (dd / yy) ; Alt+j (J) joindre ; Alt+↑/↓ déplacer ligne(s)"),
+                    Line::from("Alt+r     → Éditeur : lecture seule on/off (:set readonly / noreadonly) ; gros fichiers ouverts ainsi"),
+                    Line::from("Fichier modifié sur le disque → rechargé ; si l’onglet est modifié : [r] recharger, [k] garder, [d] diff"),
+                    Line::from("Copie de secours : buffers modifiés copiés toutes les 15 s ; proposée à la réouverture après un arrêt"),
+                    Line::from("Alt+z     → Éditeur : replier les lignes longues (:set wrap / :set nowrap)"),
+                    Line::from("Alt+i     → Éditeur : afficher tabulations, espaces insécables et de fin de ligne (:set list / nolist)"),
+                    Line::from("Tab       → Éditeur : après un déclencheur, insérer l’extrait ([[snippets]] de config/editor.toml) ; Tab / Maj+Tab : champ suivant / précédent"),
+                    Line::from("Tab       → Éditeur : indenter (config/editor.toml) ; Maj+Tab, > / < sur une sélection : décaler"),
+                    Line::from("Ctrl+←/→  → Éditeur : mot précédent / suivant (w b e en mode Normal) ; Ctrl+Retour/Suppr efface un mot"),
+                    Line::from("Ctrl+T    → Éditeur : liste des onglets (aussi :ls) ; :e ouvre dans un nouvel onglet"),
+                    Line::from("Alt+%     → Éditeur : aller au crochet correspondant (% en mode Normal) ; ( [ { \" ' fermés à la frappe"),
+                    Line::from("Alt+b     → Éditeur : blame git de la ligne courante (:set blame) ; la gouttière marque les changements depuis HEAD"),
+                    Line::from("Alt+s / o → Éditeur : deux volets côte à côte (:vsplit, :only), passer à l’autre volet"),
+                    Line::from("Alt+\" a   → Éditeur : registre a–z pour la copie / le collage suivant (\"a yy, \"a p ; \"A ajoute, \"0–\"9 historique)"),
+                    Line::from("Alt+m a   → Éditeur : poser la marque a (m a) ; Alt+' a y revient ('a ligne, `a position, '' retour)"),
+                    Line::from("Alt+, / . → Éditeur : saut précédent / suivant (Ctrl+O / Tab en mode Normal) : recherche, ligne, symbole, fichier ouvert"),
+                    Line::from("Ctrl+K    → Éditeur : aller à un symbole du fichier (Tab : du workspace)"),
+                    Line::from("F12       → Éditeur : aller à la définition du mot sous le curseur (gd en mode Normal)"),
+                    Line::from("Alt+B     → Éditeur : sélection en bloc (Ctrl+V en mode Normal) ; I / A tape sur chaque ligne, y / d / p copient, coupent, collent le bloc"),
+                    Line::from("PgUp/PgDn → Éditeur : page précédente / suivante ; Alt+PgUp/PgDn (Ctrl+U/D en mode Normal) : demi-page ; scrolloff dans config/editor.toml"),
+                    Line::from("Alt+c     → Éditeur : commenter / décommenter la ligne ou la sélection (gc) ; comment dans config/editor.toml"),
+                    Line::from("Alt+f / F → Éditeur : plier / déplier la région (za zc zo), tout plier / déplier (zM zR)"),
+                    Line::from("Ctrl+Z/Y  → Éditeur : annuler / rétablir, par mot tapé (persistent_undo dans config/editor.toml)"),
+                    Line::from("Ctrl+F    → Éditeur : recherche regex en direct (Alt+C casse, Alt+W mot entier) ; n/N ou F3/Maj+F3"),
+                    Line::from("Ctrl+R    → Éditeur : remplacer (%s/motif/rempl./gci, aussi :s en mode commande)"),
+                    Line::from("help      → Shell : commandes internes du REPL (cd, theme, history, jobs, fg…), mêmes résultats ici"),
+                    Line::from("Ctrl+R    → Shell : recherche dans l’historique (Ctrl+R : plus ancien, Entrée lance, Esc annule)"),
+                    Line::from("Ctrl+Y    → Shell : copier la ligne saisie, sinon la sortie de la dernière commande"),
+                    Line::from("Ctrl+C    → Shell : interrompre la commande au premier plan, sinon abandonner la saisie"),
+                    Line::from(":pty cmd  → Shell : lancer une commande dans un pseudo-terminal (vim, top, ssh… y vont d’eux-mêmes)"),
+                    Line::from("y / x / p → Copier / couper / coller dans l’explorateur"),
+                    Line::from("c / R     → Dupliquer ; renommer (plusieurs marqués : motif {name}_{n}.{ext})"),
+                    Line::from("b / 1-9   → Épingler un dossier / aller au favori (built-in bm)"),
+                    Line::from("r         → Changer de racine (config roots ou --root)"),
+                    Line::from("Entrée    → Sur un .zip/.tar(.gz) : parcourir l’archive ; E y extrait la sélection"),
+                    Line::from("Z         → Compresser la sélection (.zip, .tar.gz ou .tar)"),
+                    Line::from("U         → Usage disque : tailles récursives, plus gros d’abord"),
+                    Line::from("g         → Fil d’Ariane : ←/→ choisir un dossier parent, Entrée y aller"),
+                    Line::from("H / L     → Dossier précédent / suivant dans l’historique (Alt+←/→)"),
+                    Line::from("C         → cd du shell dans le dossier de l’explorateur (follow_cwd : l’inverse)"),
+                    Line::from("P         → Permissions (bits rwx, chmod) de l’entrée sélectionnée"),
+                    Line::from("o         → Ouvrir avec (application par défaut ou programme configuré)"),
+                    Line::from("Suppr     → Corbeille (u : annuler) ; Maj+Suppr : suppression définitive"),
                     Line::from(""),
                     Line::from("Cette fenêtre se fermera à la prochaine touche."),
                 ];
@@ -348,17 +732,26 @@ pub fn start_tui() -> io::Result<()> {
             } else if state.overlay == Overlay::Input {
                 let popup = centered_rect(60, 20, area);
                 f.render_widget(Clear, popup);
+                let marked = state.explorer.marked.len();
                 let label = state
                     .overlay_input
                     .as_ref()
                     .map(|i| match i.kind {
-                        state::InputKind::NewEntry => "Nouveau (fichier ou dossier/) :",
-                        state::InputKind::RenameEntry => "Renommer (nouveau nom) :",
-                        state::InputKind::DeleteConfirm => "Confirmer suppression (tape 'y') :",
-                        state::InputKind::SearchText => "Rechercher :",
-                        state::InputKind::GotoLine => "Aller à la ligne :",
+                        state::InputKind::NewEntry => String::from("Nouveau (fichier ou dossier/) :"),
+                        state::InputKind::RenameEntry => String::from("Renommer (nouveau nom) :"),
+                        state::InputKind::DeleteConfirm if marked > 0 => {
+                            format!("Supprimer définitivement {} éléments ? (tape 'y') :", marked)
+                        }
+                        state::InputKind::DeleteConfirm => String::from("Supprimer définitivement (tape 'y') :"),
+                        state::InputKind::QuitConfirm => String::from("Quitter PascheK Shell ? (tape 'y') :"),
+                        state::InputKind::SearchText => search_label(state.tabs.current()),
+                        state::InputKind::ReplaceText => String::from("Remplacer ([%]s/motif/remplacement/[gci]) :"),
+                        state::InputKind::GotoLine => String::from("Aller à la ligne :"),
+                        state::InputKind::HttpSaveName => String::from("Enregistrer la requête sous :"),
+                        state::InputKind::Compress if marked > 1 => format!("Compresser {} éléments dans (.zip, .tar.gz, .tar) :", marked),
+                        state::InputKind::Compress => String::from("Compresser dans (.zip, .tar.gz, .tar) :"),
                     })
-                    .unwrap_or("");
+                    .unwrap_or_default();
                 let value = state
                     .overlay_input
                     .as_ref()
@@ -366,223 +759,1252 @@ pub fn start_tui() -> io::Result<()> {
                     .unwrap_or_default();
                 let text = vec![Line::from(label), Line::from(value)];
                 let p = Paragraph::new(text)
+                    .wrap(Wrap { trim: false })
                     .block(Block::default().borders(Borders::ALL).title("Input"));
                 f.render_widget(p, popup);
+            } else if state.overlay == Overlay::Jobs {
+                let popup = centered_rect(80, 70, area);
+                JobsView::render(f, popup, &jobs, state.jobs_selected, state.jobs_show_output);
+            } else if state.overlay == Overlay::Finder {
+                let popup = centered_rect(70, 70, area);
+                FinderView::render(f, popup, &state.finder);
+            } else if state.overlay == Overlay::Symbols {
+                let popup = centered_rect(70, 70, area);
+                SymbolsView::render(f, popup, &state.symbols);
+            } else if state.overlay == Overlay::Paste {
+                let popup = centered_rect(60, 20, area);
+                FileOpsView::render(f, popup, &state.paste);
+            } else if state.overlay == Overlay::Permissions {
+                let popup = centered_rect(60, 40, area);
+                PermissionsView::render(f, popup, &state.permissions);
+            } else if state.overlay == Overlay::OpenWith {
+                let popup = centered_rect(60, 40, area);
+                OpenWithView::render(f, popup, &state.open_with);
+            } else if state.overlay == Overlay::Compress {
+                let popup = centered_rect(60, 20, area);
+                FileOpsView::render_compress(f, popup, &state.compress);
+            } else if state.overlay == Overlay::Unsaved {
+                if let Some(unsaved) = state.unsaved.as_ref() {
+                    let popup = centered_rect(60, 35, area);
+                    UnsavedView::render(f, popup, &state.tabs, unsaved);
+                }
+            } else if state.overlay == Overlay::Recover {
+                if let (Some(ed), Some(at)) = (state.tabs.current(), state.recover) {
+                    let popup = centered_rect(60, 25, area);
+                    RecoverView::render(f, popup, ed, at);
+                }
+            } else if state.overlay == Overlay::Conflict {
+                if let Some(ed) = state.conflict.and_then(|i| state.tabs.tabs.get(i)) {
+                    let popup = centered_rect(60, 25, area);
+                    ConflictView::render(f, popup, &ed.state);
+                }
+            } else if state.overlay == Overlay::Loading {
+                let popup = centered_rect(60, 20, area);
+                EditorView::render_load(f, popup, &state.load);
+            } else if state.overlay == Overlay::Roots {
+                let popup = centered_rect(50, 40, area);
+                FileExplorerView::render_roots(f, popup, &state.explorer);
+            } else if state.overlay == Overlay::Rename {
+                let popup = centered_rect(70, 60, area);
+                FileOpsView::render_rename(f, popup, &state.rename);
+            } else if state.overlay == Overlay::Tabs {
+                let popup = centered_rect(60, 50, area);
+                TabsView::render(f, popup, &state.tabs, state.tabs_selected);
+            } else if state.overlay == Overlay::Replace
+                && let (Some(ed), Some(rs)) = (state.tabs.current(), state.replace.as_ref())
+            {
+                let popup = centered_rect(70, 30, area);
+                ReplaceView::render(f, popup, ed, rs);
             }
+            ToastView::render(f, chunks[0], &state.toast);
         })?;
+        // Aperçu image via protocole graphique : écrit après le rendu ratatui
+        if image_preview.after_draw(terminal.backend_mut())? {
+            terminal.clear()?;
+        }
 
         // ----- Gestion des événements clavier -----
-        let timeout = tick_rate
+        let mut timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_millis(0));
+        // Un programme interactif redessine souvent : rafraîchir plus vite
+        if term.pty_active() {
+            timeout = timeout.min(Duration::from_millis(20));
+        }
 
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                // 1) Accueil : navigation directe
-                if state.screen == Screen::Home {
+            let event = event::read()?;
+            if let Event::Paste(text) = &event {
+                paste_text(&mut state, &mut term, text);
+            }
+            if let Event::Key(key) = event {
+                // Programme interactif dans le shell : toutes les touches lui reviennent
+                if state.screen == Screen::Shell && state.overlay == Overlay::None && term.pty_active() {
+                    term.pty_key(key);
+                    continue;
+                }
+                // 0) Bloc-notes : F2 bascule ; capte le clavier tant qu'il est ouvert
+                if key.code == KeyCode::F(2) && state.overlay == Overlay::None {
+                    ScratchpadView::toggle(&mut state.scratchpad);
+                    continue;
+                }
+                // Ctrl+O : recherche de fichier floue dans toute la racine (saut précédent en mode Normal)
+                let normal = state.screen == Screen::Editor && state.tabs.current().is_some_and(|ed| ed.mode == EditorMode::Normal);
+                if key.code == KeyCode::Char('o') && key.modifiers.contains(KeyModifiers::CONTROL) && state.overlay == Overlay::None && !normal {
+                    let root = state.explorer.root.clone();
+                    FinderView::open(&mut state.finder, &root, false);
+                    state.overlay = Overlay::Finder;
+                    continue;
+                }
+                // Ctrl+K : symboles du fichier courant ; F12 : définition du mot sous le curseur
+                let editing = matches!(state.screen, Screen::Workspace | Screen::Editor);
+                if editing && state.overlay == Overlay::None {
+                    if key.code == KeyCode::Char('k') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                        let root = state.explorer.root.clone();
+                        SymbolsView::open(&mut state.symbols, state.tabs.current(), &root);
+                        state.overlay = Overlay::Symbols;
+                        continue;
+                    }
+                    if key.code == KeyCode::F(12) && (state.screen == Screen::Editor || state.focus == Focus::Editor) {
+                        goto_definition(&mut state);
+                        continue;
+                    }
+                }
+                // Overlay Symboles : saisie de la requête, Entrée va au symbole
+                if state.overlay == Overlay::Symbols {
+                    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
                     match key.code {
-                        KeyCode::Char('1') => {
-                            state.screen = Screen::Shell;
+                        KeyCode::Esc => state.overlay = Overlay::None,
+                        KeyCode::Up => SymbolsView::move_by(&mut state.symbols, -1),
+                        KeyCode::Down => SymbolsView::move_by(&mut state.symbols, 1),
+                        KeyCode::PageUp => SymbolsView::move_by(&mut state.symbols, -10),
+                        KeyCode::PageDown => SymbolsView::move_by(&mut state.symbols, 10),
+                        KeyCode::Char('p') if ctrl => SymbolsView::move_by(&mut state.symbols, -1),
+                        KeyCode::Char('n') if ctrl => SymbolsView::move_by(&mut state.symbols, 1),
+                        KeyCode::Tab => {
+                            let root = state.explorer.root.clone();
+                            SymbolsView::toggle_scope(&mut state.symbols, &root);
                         }
-                        KeyCode::Char('2') => {
-                            state.screen = Screen::Shell;
-                            state.show_logs = true;
+                        KeyCode::Char('r') if ctrl => {
+                            let root = state.explorer.root.clone();
+                            SymbolsView::index(&mut state.symbols, &root, true);
+                            if !state.symbols.workspace {
+                                SymbolsView::toggle_scope(&mut state.symbols, &root);
+                            }
                         }
-                        KeyCode::Char('3') => {
-                            state.screen = Screen::Shell;
-                            state.overlay = Overlay::Help;
+                        KeyCode::Backspace => SymbolsView::backspace(&mut state.symbols),
+                        KeyCode::Char(c) if !ctrl => SymbolsView::input(&mut state.symbols, c),
+                        KeyCode::Enter => {
+                            if let Some(sym) = SymbolsView::selected(&state.symbols) {
+                                state.overlay = Overlay::None;
+                                goto_symbol(&mut state, &sym);
+                            }
                         }
-                        KeyCode::Char('5') => {
-                            state.screen = Screen::Workspace; // Workspace (pas Explorer)
-                            state.focus = Focus::Explorer;
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 0bis) Overlay Finder (avant les écrans) : saisie de la requête, Entrée ouvre le fichier
+                if state.overlay == Overlay::Finder {
+                    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                    match key.code {
+                        KeyCode::Esc => state.overlay = Overlay::None,
+                        KeyCode::Up => FinderView::move_by(&mut state.finder, -1),
+                        KeyCode::Down => FinderView::move_by(&mut state.finder, 1),
+                        KeyCode::Char('p') if ctrl => FinderView::move_by(&mut state.finder, -1),
+                        KeyCode::Char('n') if ctrl => FinderView::move_by(&mut state.finder, 1),
+                        KeyCode::Char('r') if ctrl => {
+                            let root = state.explorer.root.clone();
+                            FinderView::open(&mut state.finder, &root, true);
                         }
-                        KeyCode::Char('4') | KeyCode::Char('q') => {
-                            state.running = false;
+                        KeyCode::Backspace => FinderView::backspace(&mut state.finder),
+                        KeyCode::Char(c) if !ctrl => FinderView::input(&mut state.finder, c),
+                        KeyCode::Enter => {
+                            if let Some(path) = FinderView::selected_path(&state.finder) {
+                                state.overlay = Overlay::None;
+                                let from = state.screen;
+                                if !open_in_viewer(&mut state, &path, from)
+                                    && let Ok(ed) = EditorView::open_path(path, &state.explorer.root)
+                                {
+                                    open_tab(&mut state, ed);
+                                    state.screen = Screen::Workspace;
+                                    state.focus = Focus::Editor;
+                                }
+                            }
                         }
                         _ => {}
                     }
                     continue;
                 }
 
-                // 2) Overlay Help: se ferme à la prochaine touche
-                if state.overlay == Overlay::Help {
-                    state.overlay = Overlay::None;
+                // 0ter) Overlay Coller : conflits de noms, puis progression
+                if state.overlay == Overlay::Paste {
+                    let paste = &mut state.paste;
+                    if !paste.conflicts.is_empty() {
+                        match key.code {
+                            KeyCode::Char('o') => FileOpsView::resolve(paste, Resolution::Overwrite, false),
+                            KeyCode::Char('r') => FileOpsView::resolve(paste, Resolution::Rename, false),
+                            KeyCode::Char('s') => FileOpsView::resolve(paste, Resolution::Skip, false),
+                            KeyCode::Char('O') => FileOpsView::resolve(paste, Resolution::Overwrite, true),
+                            KeyCode::Char('R') => FileOpsView::resolve(paste, Resolution::Rename, true),
+                            KeyCode::Char('S') => FileOpsView::resolve(paste, Resolution::Skip, true),
+                            KeyCode::Esc => {
+                                *paste = state::PasteState::default();
+                                state.overlay = Overlay::None;
+                            }
+                            _ => {}
+                        }
+                    } else if paste.running.is_some() {
+                        // Fermer le récepteur interrompt la copie au prochain bloc
+                        if key.code == KeyCode::Esc {
+                            paste.running = None;
+                            state.overlay = Overlay::None;
+                            FileExplorerView::refresh(&mut state.explorer);
+                        }
+                    } else {
+                        state.overlay = Overlay::None;
+                    }
                     continue;
                 }
 
-                // 2bis) Overlay Input: capter la saisie avant le reste
-                if state.overlay == Overlay::Input {
+                // 0ter bis) Compression en cours : Esc l'annule (l'archive partielle est supprimée)
+                if state.overlay == Overlay::Compress {
+                    if key.code == KeyCode::Esc {
+                        state.compress.running = None;
+                        state.overlay = Overlay::None;
+                        ToastView::info(&mut state.toast, "Compression annulée");
+                    }
+                    continue;
+                }
+
+                // Ouverture d'un gros fichier : Esc abandonne la lecture
+                if state.overlay == Overlay::Loading {
+                    if key.code == KeyCode::Esc {
+                        state.load.running = None;
+                        state.overlay = Overlay::None;
+                        ToastView::info(&mut state.toast, "Ouverture annulée");
+                    }
+                    continue;
+                }
+
+                // 0quater) Menu « Ouvrir avec » : built-in `open` ou programme configuré dans le terminal
+                if state.overlay == Overlay::OpenWith {
                     match key.code {
-                        KeyCode::Esc => { state.overlay = Overlay::None; state.overlay_input = None; }
-                        KeyCode::Backspace => {
-                            if let Some(inp) = state.overlay_input.as_mut() { inp.buffer.pop(); }
-                        }
+                        KeyCode::Esc | KeyCode::Char('q') => state.overlay = Overlay::None,
+                        KeyCode::Char('j') | KeyCode::Down => OpenWithView::move_by(&mut state.open_with, 1),
+                        KeyCode::Char('k') | KeyCode::Up => OpenWithView::move_by(&mut state.open_with, -1),
                         KeyCode::Enter => {
-                            use std::fs;
-                            if let Some(inp) = state.overlay_input.take() {
-                                match inp.kind {
-                                    state::InputKind::NewEntry => {
-                                        let name = inp.buffer.trim();
-                                        if !name.is_empty() {
-                                            let path = state.explorer.cwd.join(name);
-                                            let res = if name.ends_with('/') { fs::create_dir_all(&path) } else { fs::File::create(&path).map(|_| ()) };
-                                            let _ = res; // Optionally handle errors
-                                            FileExplorerView::refresh(&mut state.explorer);
-                                        }
-                                    }
-                                    state::InputKind::RenameEntry => {
-                                        if let Some(entry) = state.explorer.entries.get(state.explorer.selected) {
-                                            if entry.name != ".." {
-                                                let from = state.explorer.cwd.join(&entry.name);
-                                                let to = state.explorer.cwd.join(inp.buffer.trim());
-                                                let _ = std::fs::rename(from, to);
-                                                FileExplorerView::refresh(&mut state.explorer);
-                                            }
-                                        }
-                                    }
-                                    state::InputKind::DeleteConfirm => {
-                                        if inp.buffer.trim().eq_ignore_ascii_case("y") {
-                                            if let Some(entry) = state.explorer.entries.get(state.explorer.selected) {
-                                                if entry.name != ".." {
-                                                    let path = state.explorer.cwd.join(&entry.name);
-                                                    let _ = if entry.is_dir { std::fs::remove_dir_all(path) } else { std::fs::remove_file(path) };
-                                                    FileExplorerView::refresh(&mut state.explorer);
-                                                }
-                                            }
-                                        }
-                                    }
-                                    state::InputKind::SearchText => {
-                                        let q = inp.buffer;
-                                        if !q.is_empty() {
-                                            if let Some(ed) = state.tabs.current_mut() {
-                                                ed.last_search = Some(q.clone());
-                                                // Cherche à partir de la position courante (ligne courante)
-                                                let start_line = ed.cursor_row;
-                                                let total = ed.buffer.len_lines();
-                                                let mut found: Option<usize> = None;
-                                                for row in start_line..total {
-                                                    let mut txt = ed.buffer.line(row).to_string();
-                                                    if txt.ends_with('\n') { txt.pop(); }
-                                                    if txt.contains(&q) { found = Some(row); break; }
-                                                }
-                                                if found.is_none() {
-                                                    for row in 0..start_line {
-                                                        let mut txt = ed.buffer.line(row).to_string();
-                                                        if txt.ends_with('\n') { txt.pop(); }
-                                                        if txt.contains(&q) { found = Some(row); break; }
-                                                    }
-                                                }
-                                                if let Some(row) = found {
-                                                    ed.cursor_row = row;
-                                                    ed.cursor_col = 0;
-                                                    if ed.cursor_row < ed.scroll_row { ed.scroll_row = ed.cursor_row; }
-                                                }
-                                            }
-                                        }
-                                    }
-                                    state::InputKind::GotoLine => {
-                                        if let Ok(n) = inp.buffer.trim().parse::<usize>() {
-                                            if let Some(ed) = state.tabs.current_mut() {
-                                                let line = n.saturating_sub(1).min(ed.buffer.len_lines().saturating_sub(1));
-                                                ed.cursor_row = line;
-                                                ed.cursor_col = 0;
-                                                if ed.cursor_row < ed.scroll_row { ed.scroll_row = ed.cursor_row; }
+                            state.overlay = Overlay::None;
+                            let path = state.open_with.path.clone();
+                            match OpenWithView::selected_program(&state.open_with) {
+                                None => match open_default(&path) {
+                                    Ok(()) => ToastView::info(&mut state.toast, format!("Ouvert : {}", path.display())),
+                                    Err(e) => ToastView::error(&mut state.toast, format!("open {} : {}", path.display(), e)),
+                                },
+                                Some(p) => {
+                                    if let Some((program, args)) = OpenWithView::command_args(&p.command, &path) {
+                                        let line = std::iter::once(program.clone()).chain(args.iter().cloned()).collect::<Vec<_>>().join(" ");
+                                        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                                        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                                        match jobs.spawn_in(&dir, &line, &program, &args, false) {
+                                            Ok(_) => {
+                                                term.push_output(format!("$ {}", line));
+                                                state.screen = Screen::Shell;
                                             }
+                                            Err(e) => ToastView::error(&mut state.toast, format!("{} : {}", program, e)),
                                         }
                                     }
                                 }
                             }
-                            state.overlay = Overlay::None;
                         }
-                        KeyCode::Char(c) => {
-                            if let Some(inp) = state.overlay_input.as_mut() { inp.buffer.push(c); }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 0quinquies) Éditeur de permissions : bascule des bits rwx, Entrée applique chmod
+                if state.overlay == Overlay::Permissions {
+                    let ps = &mut state.permissions;
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') => state.overlay = Overlay::None,
+                        KeyCode::Left | KeyCode::Char('h') => PermissionsView::move_by(ps, 0, -1),
+                        KeyCode::Right | KeyCode::Char('l') => PermissionsView::move_by(ps, 0, 1),
+                        KeyCode::Up | KeyCode::Char('k') => PermissionsView::move_by(ps, -1, 0),
+                        KeyCode::Down | KeyCode::Char('j') => PermissionsView::move_by(ps, 1, 0),
+                        KeyCode::Char(' ') => PermissionsView::toggle(ps),
+                        KeyCode::Enter => {
+                            state.overlay = Overlay::None;
+                            match PermissionsView::apply(ps) {
+                                Ok(path) => ToastView::info(&mut state.toast, format!("{:04o} → {}", ps.mode, path.display())),
+                                Err(e) => ToastView::error(&mut state.toast, e),
+                            }
                         }
                         _ => {}
                     }
                     continue;
                 }
 
-                // 3) Écran Explorer : navigation & ouverture
-                if state.screen == Screen::Explorer {
-                    use KeyCode::*;
+                // 0decies) Modifications non enregistrées avant de fermer un onglet ou de quitter
+                if state.overlay == Overlay::Unsaved {
                     match key.code {
-                        Char('j') | Down => FileExplorerView::move_down(&mut state.explorer),
-                        Char('k') | Up => FileExplorerView::move_up(&mut state.explorer),
-                        Char('h') | Backspace => FileExplorerView::go_up(&mut state.explorer),
-                        Char('N') => {
-                            state.overlay = Overlay::Input;
-                            state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::NewEntry, buffer: String::new() });
+                        KeyCode::Char('s') | KeyCode::Char('S') => resolve_unsaved(&mut state, true),
+                        KeyCode::Char('d') | KeyCode::Char('D') => resolve_unsaved(&mut state, false),
+                        KeyCode::Esc | KeyCode::Char('q') => {
+                            state.overlay = state.unsaved.take().map_or(Overlay::None, |u| u.back_to);
                         }
-                        Char('R') => {
-                            state.overlay = Overlay::Input;
-                            state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::RenameEntry, buffer: String::new() });
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 0undecies) Copie de secours d'une session précédente
+                if state.overlay == Overlay::Recover {
+                    match key.code {
+                        KeyCode::Char('r') | KeyCode::Char('R') => {
+                            state.overlay = Overlay::None;
+                            state.recover = None;
+                            if let Some(ed) = state.tabs.current_mut() {
+                                match swap::recover(ed) {
+                                    Ok(()) => ToastView::info(&mut state.toast, "Modifications récupérées (Ctrl+S pour enregistrer)"),
+                                    Err(e) => ToastView::error(&mut state.toast, format!("Récupération impossible : {}", e)),
+                                }
+                            }
                         }
-                        Delete => {
-                            state.overlay = Overlay::Input;
-                            state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::DeleteConfirm, buffer: String::new() });
+                        KeyCode::Char('d') | KeyCode::Char('D') => {
+                            state.overlay = Overlay::None;
+                            state.recover = None;
+                            if let Some(path) = state.tabs.current().and_then(|ed| ed.path.clone()) {
+                                swap::remove(&path);
+                            }
                         }
-                        Char('.') => {
-                            state.explorer.show_hidden = !state.explorer.show_hidden;
-                            FileExplorerView::refresh(&mut state.explorer);
+                        KeyCode::Esc | KeyCode::Char('q') => {
+                            state.overlay = Overlay::None;
+                            state.recover = None;
                         }
-                        Char('l') | Enter => {
-                            if let Some(path) = FileExplorerView::activate(&mut state.explorer) {
-                                match EditorView::open_path(path, &state.explorer.root) {
-                                    Ok(ed) => {
-                                        state.tabs.open_or_focus(ed);
-                                        state.screen = Screen::Workspace; // bascule en Workspace
-                                        state.focus = Focus::Editor;
-                                    }
-                                    Err(_e) => {
-                                        // TODO: pousser un message dans logs/term
-                                    }
-                                }
-                            }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 0duodecies) Fichier modifié sur le disque et dans l'onglet
+                if state.overlay == Overlay::Conflict {
+                    match key.code {
+                        KeyCode::Char('r') | KeyCode::Char('R') => resolve_conflict(&mut state, ConflictChoice::Reload),
+                        KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Esc => resolve_conflict(&mut state, ConflictChoice::Keep),
+                        KeyCode::Char('d') | KeyCode::Char('D') => resolve_conflict(&mut state, ConflictChoice::Diff),
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 0nonies) Sélecteur d'onglets de l'éditeur
+                if state.overlay == Overlay::Tabs {
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') => state.overlay = Overlay::None,
+                        KeyCode::Down | KeyCode::Char('j') => TabsView::move_by(&mut state.tabs_selected, &state.tabs, 1),
+                        KeyCode::Up | KeyCode::Char('k') => TabsView::move_by(&mut state.tabs_selected, &state.tabs, -1),
+                        KeyCode::Enter => {
+                            let idx = state.tabs_selected;
+                            focus_tab(&mut state, idx);
                         }
-                        Char('q') | Esc => {
-                            state.screen = Screen::Home;
+                        KeyCode::Char(c @ '1'..='9') => focus_tab(&mut state, c as usize - '1' as usize),
+                        KeyCode::Char('x') | KeyCode::Delete => {
+                            let idx = state.tabs_selected;
+                            request_close_tab(&mut state, idx);
                         }
                         _ => {}
                     }
                     continue;
                 }
 
-                // 4) Écran Workspace : focus & raccourcis
-                if state.screen == Screen::Workspace {
-                    match state.focus {
-                        Focus::Explorer => {
-                            use crossterm::event::KeyCode::*;
-                            match key.code {
-                                KeyCode::Tab => {
+                // 0octies) Remplacement confirmé : y / n / a / q sur chaque occurrence
+                if state.overlay == Overlay::Replace {
+                    let mut done = true;
+                    if let (Some(ed), Some(rs)) = (state.tabs.current_mut(), state.replace.as_mut()) {
+                        match key.code {
+                            KeyCode::Char('y') => ReplaceView::replace(ed, rs),
+                            KeyCode::Char('n') => ReplaceView::skip(rs),
+                            KeyCode::Char('a') => ReplaceView::replace_all(ed, rs),
+                            KeyCode::Char('q') | KeyCode::Esc => {}
+                            _ => continue,
+                        }
+                        done = matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) || !ReplaceView::find_next(ed, rs);
+                    }
+                    if done {
+                        finish_replace(&mut state);
+                    }
+                    continue;
+                }
+
+                // 0septies) Renommage par lot : le motif se tape, l'aperçu suit
+                if state.overlay == Overlay::Rename {
+                    match key.code {
+                        KeyCode::Esc => state.overlay = Overlay::None,
+                        KeyCode::Backspace => {
+                            state.rename.pattern.pop();
+                        }
+                        KeyCode::Char(c) => state.rename.pattern.push(c),
+                        KeyCode::Enter => {
+                            let plan = FileOpsView::rename_plan(&state.rename.targets, &state.rename.pattern);
+                            if let Some(problem) = plan.iter().find_map(|l| l.problem.map(|p| format!("{} : {}", l.name, p))) {
+                                ToastView::error(&mut state.toast, format!("Renommage impossible — {}", problem));
+                            } else {
+                                state.overlay = Overlay::None;
+                                let errors = FileOpsView::rename(&state.rename.targets, &state.rename.pattern);
+                                report_errors(&mut state, &mut logs, "Renommage", &errors);
+                                state.explorer.marked.clear();
+                                FileExplorerView::refresh(&mut state.explorer);
+                            }
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 0sexies) Sélecteur de racine de l'explorateur
+                if state.overlay == Overlay::Roots {
+                    let choice = match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') => {
+                            state.overlay = Overlay::None;
+                            None
+                        }
+                        KeyCode::Char('j') | KeyCode::Down => {
+                            FileExplorerView::move_root_selection(&mut state.explorer, 1);
+                            None
+                        }
+                        KeyCode::Char('k') | KeyCode::Up => {
+                            FileExplorerView::move_root_selection(&mut state.explorer, -1);
+                            None
+                        }
+                        KeyCode::Enter => Some(state.explorer.roots_selected),
+                        KeyCode::Char(c @ '1'..='9') => Some(c as usize - '1' as usize),
+                        _ => None,
+                    };
+                    if let Some(n) = choice {
+                        state.overlay = Overlay::None;
+                        if let Err(e) = FileExplorerView::switch_root(&mut state.explorer, n) {
+                            ToastView::error(&mut state.toast, e);
+                        }
+                    }
+                    continue;
+                }
+
+                if state.scratchpad.open && state.overlay == Overlay::None {
+                    let ed = &mut state.scratchpad.editor;
+                    match key.code {
+                        KeyCode::Esc => state.scratchpad.open = false,
+                        KeyCode::Left => EditorView::move_left(ed),
+                        KeyCode::Right => EditorView::move_right(ed),
+                        KeyCode::Up => EditorView::move_up(ed),
+                        KeyCode::Down => EditorView::move_down(ed),
+                        KeyCode::Backspace => EditorView::backspace(ed),
+                        KeyCode::Enter => EditorView::insert_newline(ed),
+                        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => EditorView::insert_char(ed, c),
+                        _ => {}
+                    }
+                    ScratchpadView::autosave(&mut state.scratchpad);
+                    continue;
+                }
+
+                // 1) Accueil : navigation directe
+                if state.screen == Screen::Home {
+                    match key.code {
+                        KeyCode::Char('1') => {
+                            state.screen = Screen::Shell;
+                        }
+                        KeyCode::Char('2') => {
+                            state.screen = Screen::Shell;
+                            state.show_logs = true;
+                        }
+                        KeyCode::Char('3') => {
+                            state.screen = Screen::Shell;
+                            state.overlay = Overlay::Help;
+                        }
+                        KeyCode::Char('5') => {
+                            state.screen = Screen::Workspace; // Workspace (pas Explorer)
+                            state.focus = Focus::Explorer;
+                        }
+                        KeyCode::Char('6') => {
+                            state.screen = Screen::Dashboard;
+                        }
+                        KeyCode::Char('7') => {
+                            let dir = state.explorer.cwd.clone();
+                            GitView::refresh(&mut state.git, &dir);
+                            state.screen = Screen::Git;
+                        }
+                        KeyCode::Char('8') => {
+                            ContainerView::refresh(&mut state.containers);
+                            state.screen = Screen::Containers;
+                        }
+                        KeyCode::Char('9') => {
+                            HttpView::reload_collection(&mut state.http);
+                            state.screen = Screen::Http;
+                        }
+                        KeyCode::Char('4') | KeyCode::Char('q') => {
+                            request_quit(&mut state);
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 1bis) Tableau de bord : lecture seule
+                if state.screen == Screen::Dashboard {
+                    if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                        state.screen = Screen::Home;
+                    }
+                    continue;
+                }
+
+                // 2) Overlay Help: se ferme à la prochaine touche
+                if state.overlay == Overlay::Help {
+                    state.overlay = Overlay::None;
+                    continue;
+                }
+
+                // 2ter) Overlay Jobs: navigation, sortie, premier plan, kill
+                if state.overlay == Overlay::Jobs {
+                    let selected_id = jobs.jobs().get(state.jobs_selected).map(|j| j.id);
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') => {
+                            state.overlay = Overlay::None;
+                        }
+                        KeyCode::Char('j') | KeyCode::Down if state.jobs_selected + 1 < jobs.jobs().len() => state.jobs_selected += 1,
+                        KeyCode::Char('k') | KeyCode::Up => {
+                            state.jobs_selected = state.jobs_selected.saturating_sub(1);
+                        }
+                        KeyCode::Enter | KeyCode::Char('o') => {
+                            state.jobs_show_output = !state.jobs_show_output;
+                        }
+                        KeyCode::Char('f') => {
+                            if let Some(id) = selected_id {
+                                jobs.bring_to_foreground(id);
+                                term.push_output(format!("[{}] → premier plan", id));
+                                state.overlay = Overlay::None;
+                                state.screen = Screen::Shell;
+                            }
+                        }
+                        KeyCode::Char('K') | KeyCode::Char('x') => {
+                            if let Some(id) = selected_id {
+                                match jobs.kill(id) {
+                                    Ok(()) => logs.add(format!("job [{}] killed", id)),
+                                    Err(e) => logs.error(format!("job [{}] kill error: {}", id, e)),
+                                }
+                            }
+                        }
+                        KeyCode::Char('c') => {
+                            jobs.clear_finished();
+                            state.jobs_selected = 0;
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 2bis) Overlay Input: capter la saisie avant le reste
+                if state.overlay == Overlay::Input {
+                    let searching = state.overlay_input.as_ref().is_some_and(|i| i.kind == state::InputKind::SearchText);
+                    if searching {
+                        // Recherche en direct : bascules Alt+C / Alt+W, surlignage à chaque frappe
+                        if let Some(ed) = state.tabs.current_mut() {
+                            let alt = key.modifiers.contains(KeyModifiers::ALT);
+                            match key.code {
+                                KeyCode::Char('c') if alt => ed.search_ignore_case = !ed.search_ignore_case,
+                                KeyCode::Char('w') if alt => ed.search_whole_word = !ed.search_whole_word,
+                                KeyCode::Esc => {
+                                    ed.last_search = None;
+                                    EditorView::search_from(ed, ed.search_origin);
+                                }
+                                _ => {}
+                            }
+                            if alt {
+                                EditorView::search_from(ed, ed.search_origin);
+                                continue;
+                            }
+                        }
+                    }
+                    match key.code {
+                        KeyCode::Esc => { state.overlay = Overlay::None; state.overlay_input = None; }
+                        KeyCode::Backspace => {
+                            if let Some(inp) = state.overlay_input.as_mut() { inp.buffer.pop(); }
+                        }
+                        KeyCode::Enter => {
+                            use std::fs;
+                            if let Some(inp) = state.overlay_input.take() {
+                                match inp.kind {
+                                    state::InputKind::NewEntry => {
+                                        let name = inp.buffer.trim();
+                                        if !name.is_empty() {
+                                            let path = state.explorer.cwd.join(name);
+                                            // create_new : ne jamais écraser un fichier existant
+                                            let res = if name.ends_with(std::path::is_separator) { fs::create_dir_all(&path) } else { fs::File::create_new(&path).map(|_| ()) };
+                                            if let Err(e) = res {
+                                                report_errors(&mut state, &mut logs, "Création", &[format!("{}: {}", path.display(), e)]);
+                                            }
+                                            FileExplorerView::refresh(&mut state.explorer);
+                                        }
+                                    }
+                                    state::InputKind::RenameEntry => {
+                                        let targets = FileExplorerView::targets(&state.explorer);
+                                        if !targets.is_empty() && !inp.buffer.trim().is_empty() {
+                                            let errors = FileOpsView::rename(&targets, inp.buffer.trim());
+                                            report_errors(&mut state, &mut logs, "Renommage", &errors);
+                                            state.explorer.marked.clear();
+                                            FileExplorerView::refresh(&mut state.explorer);
+                                        }
+                                    }
+                                    state::InputKind::QuitConfirm => {
+                                        if inp.buffer.trim().eq_ignore_ascii_case("y") {
+                                            state.running = false;
+                                        }
+                                    }
+                                    state::InputKind::DeleteConfirm => {
+                                        if inp.buffer.trim().eq_ignore_ascii_case("y") {
+                                            let targets = FileExplorerView::targets(&state.explorer);
+                                            if !targets.is_empty() {
+                                                let errors = FileOpsView::delete(&targets);
+                                                report_errors(&mut state, &mut logs, "Suppression", &errors);
+                                                state.explorer.marked.clear();
+                                                FileExplorerView::refresh(&mut state.explorer);
+                                            }
+                                        }
+                                    }
+                                    state::InputKind::Compress => {
+                                        let name = inp.buffer.trim().to_string();
+                                        if !name.is_empty() {
+                                            match FileOpsView::compress(&mut state.compress, &mut state.explorer, &name) {
+                                                Ok(()) => state.overlay = Overlay::Compress,
+                                                Err(e) => ToastView::error(&mut state.toast, e),
+                                            }
+                                        }
+                                    }
+                                    state::InputKind::SearchText => {
+                                        // Le curseur est déjà sur l'occurrence (recherche en direct)
+                                        if let Some(ed) = state.tabs.current() {
+                                            if (ed.cursor_row, ed.cursor_col) != ed.search_origin {
+                                                let (row, col) = ed.search_origin;
+                                                state.jumps.record(Jump::of(ed).map(|j| Jump { row, col, ..j }));
+                                            }
+                                            if !inp.buffer.is_empty() && ed.search_positions.is_empty() {
+                                                let msg = EditorView::search_summary(ed).unwrap_or_else(|| String::from("Aucune occurrence"));
+                                                ToastView::info(&mut state.toast, msg);
+                                            }
+                                        }
+                                    }
+                                    state::InputKind::GotoLine => {
+                                        if let Ok(n) = inp.buffer.trim().parse::<usize>()
+                                            && let Some(ed) = state.tabs.current_mut()
+                                        {
+                                            state.jumps.record(Jump::of(ed));
+                                            let line = n.saturating_sub(1).min(ed.buffer.len_lines().saturating_sub(1));
+                                            ed.cursor_row = line;
+                                            ed.cursor_col = 0;
+                                            if ed.cursor_row < ed.scroll_row { ed.scroll_row = ed.cursor_row; }
+                                        }
+                                    }
+                                    state::InputKind::ReplaceText => start_replace(&mut state, &inp.buffer),
+                                    state::InputKind::HttpSaveName => {
+                                        HttpView::save_as(&mut state.http, &inp.buffer);
+                                    }
+                                }
+                            }
+                            // Garder l'overlay ouvert par la saisie (compression, remplacement)
+                            if state.overlay == Overlay::Input {
+                                state.overlay = Overlay::None;
+                            }
+                        }
+                        KeyCode::Char(c) => {
+                            if let Some(inp) = state.overlay_input.as_mut() { inp.buffer.push(c); }
+                        }
+                        _ => {}
+                    }
+                    if searching && matches!(key.code, KeyCode::Char(_) | KeyCode::Backspace) {
+                        let query = state.overlay_input.as_ref().map(|i| i.buffer.clone()).unwrap_or_default();
+                        if let Some(ed) = state.tabs.current_mut() {
+                            ed.last_search = Some(query).filter(|q| !q.is_empty());
+                            EditorView::search_from(ed, ed.search_origin);
+                        }
+                    }
+                    continue;
+                }
+
+                // 2quater) Écran Git : statut, indexation, commit
+                if state.screen == Screen::Git {
+                    if let Some(ed) = state.git.message.as_mut() {
+                        // Saisie du message de commit
+                        if key.modifiers.contains(KeyModifiers::CONTROL) {
+                            if key.code == KeyCode::Char('s') {
+                                GitView::finish_commit(&mut state.git);
+                            }
+                            continue;
+                        }
+                        match key.code {
+                            KeyCode::Esc => state.git.message = None,
+                            KeyCode::Enter => EditorView::insert_newline(ed),
+                            KeyCode::Backspace => EditorView::backspace(ed),
+                            KeyCode::Left => EditorView::move_left(ed),
+                            KeyCode::Right => EditorView::move_right(ed),
+                            KeyCode::Up => EditorView::move_up(ed),
+                            KeyCode::Down => EditorView::move_down(ed),
+                            KeyCode::Char(c) => EditorView::insert_char(ed, c),
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Char('j') | KeyCode::Down => GitView::move_down(&mut state.git),
+                        KeyCode::Char('k') | KeyCode::Up => GitView::move_up(&mut state.git),
+                        KeyCode::Char('s') | KeyCode::Char(' ') => GitView::toggle_stage(&mut state.git),
+                        KeyCode::Char('c') => GitView::begin_commit(&mut state.git),
+                        KeyCode::Char('r') => {
+                            let dir = state.git.root.clone().unwrap_or_else(|| state.explorer.cwd.clone());
+                            GitView::refresh(&mut state.git, &dir);
+                        }
+                        KeyCode::PageDown if state.git.diff_scroll + 10 < state.git.diff.len() => state.git.diff_scroll += 10,
+                        KeyCode::PageUp => state.git.diff_scroll = state.git.diff_scroll.saturating_sub(10),
+                        KeyCode::Char('q') | KeyCode::Esc => {
+                            // Les badges de l'explorer et les marqueurs de l'éditeur reflètent le nouvel état
+                            FileExplorerView::refresh(&mut state.explorer);
+                            for tab in &mut state.tabs.tabs {
+                                gutter::load_head(&mut tab.state);
+                            }
+                            state.screen = Screen::Home;
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 2quinquies) Conteneurs : liste, start/stop, logs suivis
+                if state.screen == Screen::Containers {
+                    let cs = &mut state.containers;
+                    match key.code {
+                        KeyCode::Char('j') | KeyCode::Down => ContainerView::move_down(cs),
+                        KeyCode::Char('k') | KeyCode::Up => ContainerView::move_up(cs),
+                        KeyCode::Tab => ContainerView::toggle_tab(cs),
+                        KeyCode::Char('s') => ContainerView::container_action(cs, "start"),
+                        KeyCode::Char('x') => ContainerView::container_action(cs, "stop"),
+                        KeyCode::Char('l') | KeyCode::Enter => ContainerView::follow_logs(cs, &mut jobs),
+                        KeyCode::Char('r') => ContainerView::refresh(cs),
+                        KeyCode::PageUp => {
+                            let len = cs.logs_job.and_then(|id| jobs.get(id)).map(|j| j.output().len()).unwrap_or(0);
+                            cs.logs_scroll = (cs.logs_scroll + 10).min(len);
+                        }
+                        KeyCode::PageDown => cs.logs_scroll = cs.logs_scroll.saturating_sub(10),
+                        KeyCode::Char('q') | KeyCode::Esc => {
+                            ContainerView::stop_logs(cs, &mut jobs);
+                            state.screen = Screen::Home;
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 2sexies) Client HTTP : construction de la requête et lecture de la réponse
+                if state.screen == Screen::Http {
+                    use state::HttpField;
+                    let hs = &mut state.http;
+                    if key.modifiers.contains(KeyModifiers::CONTROL) {
+                        match key.code {
+                            KeyCode::Char('r') => HttpView::send(hs),
+                            KeyCode::Char('s') => {
+                                state.overlay = Overlay::Input;
+                                state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::HttpSaveName, buffer: String::new() });
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::F(5) => HttpView::send(hs),
+                        KeyCode::Tab => HttpView::cycle_field(hs, true),
+                        KeyCode::BackTab => HttpView::cycle_field(hs, false),
+                        KeyCode::Esc => state.screen = Screen::Home,
+                        code => match hs.field {
+                            HttpField::Collection => match code {
+                                KeyCode::Char('j') | KeyCode::Down if hs.saved_selected + 1 < hs.saved.len() => hs.saved_selected += 1,
+                                KeyCode::Char('k') | KeyCode::Up => hs.saved_selected = hs.saved_selected.saturating_sub(1),
+                                KeyCode::Enter => HttpView::load_saved(hs),
+                                KeyCode::Char('r') => HttpView::reload_collection(hs),
+                                KeyCode::Char('q') => state.screen = Screen::Home,
+                                _ => {}
+                            },
+                            HttpField::Method => match code {
+                                KeyCode::Right | KeyCode::Down | KeyCode::Char('j') | KeyCode::Char(' ') => HttpView::cycle_method(hs, true),
+                                KeyCode::Left | KeyCode::Up | KeyCode::Char('k') => HttpView::cycle_method(hs, false),
+                                KeyCode::Enter => HttpView::send(hs),
+                                KeyCode::Char('q') => state.screen = Screen::Home,
+                                _ => {}
+                            },
+                            HttpField::Url => match code {
+                                KeyCode::Enter => HttpView::send(hs),
+                                KeyCode::Backspace => { hs.url.pop(); }
+                                KeyCode::Char(c) => hs.url.push(c),
+                                _ => {}
+                            },
+                            HttpField::Headers | HttpField::Body => {
+                                let ed = if hs.field == HttpField::Headers { &mut hs.headers } else { &mut hs.body };
+                                match code {
+                                    KeyCode::Enter => EditorView::insert_newline(ed),
+                                    KeyCode::Backspace => EditorView::backspace(ed),
+                                    KeyCode::Left => EditorView::move_left(ed),
+                                    KeyCode::Right => EditorView::move_right(ed),
+                                    KeyCode::Up => EditorView::move_up(ed),
+                                    KeyCode::Down => EditorView::move_down(ed),
+                                    KeyCode::Char(c) => EditorView::insert_char(ed, c),
+                                    _ => {}
+                                }
+                            }
+                            HttpField::Response => match code {
+                                KeyCode::Char('j') | KeyCode::Down => HttpView::move_response(hs, 1),
+                                KeyCode::Char('k') | KeyCode::Up => HttpView::move_response(hs, -1),
+                                KeyCode::PageDown => HttpView::move_response(hs, 10),
+                                KeyCode::PageUp => HttpView::move_response(hs, -10),
+                                KeyCode::Enter | KeyCode::Char(' ') => HttpView::toggle_fold(hs),
+                                KeyCode::Char('q') => state.screen = Screen::Home,
+                                _ => {}
+                            },
+                        },
+                    }
+                    continue;
+                }
+
+                // 2septies) Données SQLite : tables, pages, SQL ad hoc
+                if state.screen == Screen::Data {
+                    use state::DataFocus;
+                    let ds = &mut state.sqlite;
+                    if ds.focus == DataFocus::Sql {
+                        match key.code {
+                            KeyCode::Enter => SqliteView::run_sql(ds),
+                            KeyCode::Esc => ds.focus = DataFocus::Grid,
+                            KeyCode::Tab => ds.focus = DataFocus::Tables,
+                            KeyCode::Backspace => { ds.sql.pop(); }
+                            KeyCode::Char(c) => ds.sql.push(c),
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Tab => {
+                            ds.focus = if ds.focus == DataFocus::Tables { DataFocus::Grid } else { DataFocus::Sql };
+                        }
+                        KeyCode::Char(':') => ds.focus = DataFocus::Sql,
+                        KeyCode::Char('j') | KeyCode::Down => {
+                            if ds.focus == DataFocus::Tables {
+                                if ds.table_selected + 1 < ds.tables.len() { ds.table_selected += 1; }
+                            } else {
+                                SqliteView::move_row(ds, 1);
+                            }
+                        }
+                        KeyCode::Char('k') | KeyCode::Up => {
+                            if ds.focus == DataFocus::Tables {
+                                ds.table_selected = ds.table_selected.saturating_sub(1);
+                            } else {
+                                SqliteView::move_row(ds, -1);
+                            }
+                        }
+                        KeyCode::Enter if ds.focus == DataFocus::Tables => {
+                            SqliteView::browse_selected(ds);
+                            ds.focus = DataFocus::Grid;
+                        }
+                        KeyCode::Char('n') | KeyCode::PageDown => SqliteView::change_page(ds, true),
+                        KeyCode::Char('p') | KeyCode::PageUp => SqliteView::change_page(ds, false),
+                        KeyCode::Char('h') | KeyCode::Left => SqliteView::scroll_columns(ds, false),
+                        KeyCode::Char('l') | KeyCode::Right => SqliteView::scroll_columns(ds, true),
+                        KeyCode::Char('q') | KeyCode::Esc => {
+                            // Libère la connexion en quittant
+                            let back = ds.return_to;
+                            *ds = state::SqliteState::default();
+                            state.screen = back;
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 2octies) Tableau CSV : navigation, tri, filtre
+                if state.screen == Screen::Table {
+                    let cs = &mut state.csv;
+                    if cs.filter_editing {
+                        match key.code {
+                            KeyCode::Enter | KeyCode::Esc => cs.filter_editing = false,
+                            KeyCode::Backspace => { cs.filter.pop(); CsvView::rebuild_view(cs); }
+                            KeyCode::Char(c) => { cs.filter.push(c); CsvView::rebuild_view(cs); }
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Char('j') | KeyCode::Down => CsvView::move_row(cs, 1),
+                        KeyCode::Char('k') | KeyCode::Up => CsvView::move_row(cs, -1),
+                        KeyCode::PageDown => CsvView::move_row(cs, 20),
+                        KeyCode::PageUp => CsvView::move_row(cs, -20),
+                        KeyCode::Char('g') | KeyCode::Home => cs.selected_row = 0,
+                        KeyCode::Char('G') | KeyCode::End => CsvView::move_row(cs, isize::MAX / 2),
+                        KeyCode::Char('h') | KeyCode::Left => CsvView::move_col(cs, false),
+                        KeyCode::Char('l') | KeyCode::Right => CsvView::move_col(cs, true),
+                        KeyCode::Char('s') => CsvView::toggle_sort(cs),
+                        KeyCode::Char('/') => cs.filter_editing = true,
+                        KeyCode::Char('e') => {
+                            // Ouvre le texte brut dans l’éditeur
+                            if let Some(path) = cs.path.clone()
+                                && let Ok(ed) = EditorView::open_path(path, &state.explorer.root)
+                            {
+                                open_tab(&mut state, ed);
+                                state.screen = Screen::Workspace;
+                                state.focus = Focus::Editor;
+                            }
+                        }
+                        KeyCode::Char('q') | KeyCode::Esc => state.screen = cs.return_to,
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 2nonies) Vue hexadécimale : navigation et édition d’octets
+                if state.screen == Screen::Hex {
+                    let hs = &mut state.hex;
+                    let row = hex::BYTES_PER_ROW as isize;
+                    if key.modifiers.contains(KeyModifiers::CONTROL) {
+                        if key.code == KeyCode::Char('s') {
+                            HexView::save(hs);
+                        }
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Left => HexView::move_by(hs, -1),
+                        KeyCode::Right => HexView::move_by(hs, 1),
+                        KeyCode::Up => HexView::move_by(hs, -row),
+                        KeyCode::Down => HexView::move_by(hs, row),
+                        KeyCode::PageUp => HexView::move_by(hs, -row * 16),
+                        KeyCode::PageDown => HexView::move_by(hs, row * 16),
+                        KeyCode::Esc if hs.editing => {
+                            hs.editing = false;
+                            hs.low_nibble = false;
+                        }
+                        KeyCode::Char(c) if hs.editing => HexView::input_hex(hs, c),
+                        KeyCode::Char('h') => HexView::move_by(hs, -1),
+                        KeyCode::Char('l') => HexView::move_by(hs, 1),
+                        KeyCode::Char('k') => HexView::move_by(hs, -row),
+                        KeyCode::Char('j') => HexView::move_by(hs, row),
+                        KeyCode::Char('g') | KeyCode::Home => HexView::move_by(hs, isize::MIN / 2),
+                        KeyCode::Char('G') | KeyCode::End => HexView::move_by(hs, isize::MAX / 2),
+                        KeyCode::Char('i') => hs.editing = true,
+                        KeyCode::Char('Q') => state.screen = hs.return_to,
+                        KeyCode::Char('q') | KeyCode::Esc => {
+                            if hs.dirty {
+                                hs.message_line = Some(String::from("Modifications non enregistrées — Ctrl+S ou Q pour quitter"));
+                            } else {
+                                state.screen = hs.return_to;
+                            }
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 2decies) Vue diff : défilement et bascule unifié / côte à côte
+                if state.screen == Screen::Diff {
+                    let ds = &mut state.diff;
+                    match key.code {
+                        KeyCode::Char('j') | KeyCode::Down => DiffView::scroll_by(ds, 1),
+                        KeyCode::Char('k') | KeyCode::Up => DiffView::scroll_by(ds, -1),
+                        KeyCode::PageDown => DiffView::scroll_by(ds, 20),
+                        KeyCode::PageUp => DiffView::scroll_by(ds, -20),
+                        KeyCode::Char('g') | KeyCode::Home => ds.scroll = 0,
+                        KeyCode::Char('G') | KeyCode::End => DiffView::scroll_by(ds, isize::MAX / 2),
+                        KeyCode::Char('s') => {
+                            ds.side_by_side = !ds.side_by_side;
+                            ds.scroll = 0;
+                        }
+                        KeyCode::Char('q') | KeyCode::Esc => state.screen = ds.return_to,
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 2undecies) Tâches du projet : Entrée lance la tâche au premier plan du terminal
+                if state.screen == Screen::Tasks {
+                    match key.code {
+                        KeyCode::Char('j') | KeyCode::Down => TaskView::move_down(&mut state.tasks),
+                        KeyCode::Char('k') | KeyCode::Up => TaskView::move_up(&mut state.tasks),
+                        KeyCode::Char('r') => {
+                            let (cwd, root) = (state.explorer.cwd.clone(), state.explorer.root.clone());
+                            TaskView::refresh(&mut state.tasks, &cwd, &root);
+                        }
+                        KeyCode::Enter => {
+                            if let Some(t) = state.tasks.tasks.get(state.tasks.selected).cloned() {
+                                let line = TaskView::command_line(&t);
+                                let args: Vec<&str> = t.args.iter().map(String::as_str).collect();
+                                match jobs.spawn_in(&state.tasks.root, &line, &t.program, &args, false) {
+                                    Ok(_) => {
+                                        term.push_output(format!("$ {}  ({})", line, state.tasks.root.display()));
+                                        state.screen = Screen::Shell;
+                                    }
+                                    Err(e) => state.tasks.message_line = Some(format!("{}: {}", t.program, e)),
+                                }
+                            }
+                        }
+                        KeyCode::Char('q') | KeyCode::Esc => state.screen = Screen::Home,
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 2duodecies) Recherche dans le contenu : saisie de la regex puis saut à la ligne
+                if state.screen == Screen::Search {
+                    let ss = &mut state.search;
+                    if ss.editing {
+                        match key.code {
+                            KeyCode::Enter => SearchView::start(ss),
+                            KeyCode::Esc if ss.hits.is_empty() => state.screen = ss.return_to,
+                            KeyCode::Esc => ss.editing = false,
+                            KeyCode::Backspace => { ss.query.pop(); }
+                            KeyCode::Char(c) => ss.query.push(c),
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Char('j') | KeyCode::Down => SearchView::move_by(ss, 1),
+                        KeyCode::Char('k') | KeyCode::Up => SearchView::move_by(ss, -1),
+                        KeyCode::PageDown => SearchView::move_by(ss, 20),
+                        KeyCode::PageUp => SearchView::move_by(ss, -20),
+                        KeyCode::Char('/') | KeyCode::Char('i') => ss.editing = true,
+                        KeyCode::Enter => {
+                            if let Some(hit) = ss.hits.get(ss.selected).cloned() {
+                                match EditorView::open_path(ss.root.join(&hit.path), &state.explorer.root) {
+                                    Ok(ed) => {
+                                        open_tab(&mut state, ed);
+                                        if let Some(ed) = state.tabs.current_mut() {
+                                            ed.cursor_row = hit.line.min(ed.buffer.len_lines().saturating_sub(1));
+                                            ed.cursor_col = hit.col;
+                                            ed.scroll_row = ed.cursor_row.saturating_sub(5);
+                                            // Surligne le match comme une recherche de l’éditeur
+                                            ed.last_search = Some(regex::escape(&hit.matched));
+                                            EditorView::recompute_search_positions(ed);
+                                            let (row, col) = (ed.cursor_row, ed.cursor_col);
+                                            ed.search_index = ed.search_positions.iter().position(|(r, m)| *r == row && m.start == col);
+                                        }
+                                        state.screen = Screen::Workspace;
+                                        state.focus = Focus::Editor;
+                                    }
+                                    Err(e) => ss.message_line = Some(format!("{}: {}", hit.path.display(), e)),
+                                }
+                            }
+                        }
+                        KeyCode::Char('q') | KeyCode::Esc => state.screen = ss.return_to,
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 3) Écran Explorer : navigation & ouverture
+                if state.screen == Screen::Explorer {
+                    use KeyCode::*;
+                    if state.explorer.filter_editing {
+                        filter_key(&mut state.explorer, key.code);
+                        continue;
+                    }
+                    if state.explorer.crumb.is_some() {
+                        crumb_key(&mut state.explorer, key.code);
+                        continue;
+                    }
+                    if state.explorer.vfs.is_some() && vfs_key(&mut state, key.code) {
+                        continue;
+                    }
+                    match key.code {
+                        Char('/') => state.explorer.filter_editing = true,
+                        Char('g') => FileExplorerView::begin_crumbs(&mut state.explorer),
+                        Esc if !state.explorer.filter.is_empty() => FileExplorerView::clear_filter(&mut state.explorer),
+                        Esc if !state.explorer.marked.is_empty() => state.explorer.marked.clear(),
+                        Char(' ') => FileExplorerView::toggle_mark(&mut state.explorer),
+                        Char('j') | Down => FileExplorerView::move_down(&mut state.explorer),
+                        Char('k') | Up => FileExplorerView::move_up(&mut state.explorer),
+                        Char('h') | Backspace => FileExplorerView::go_up(&mut state.explorer),
+                        Char('H') => { FileExplorerView::history_step(&mut state.explorer, false); }
+                        Char('L') => { FileExplorerView::history_step(&mut state.explorer, true); }
+                        Left if key.modifiers.contains(KeyModifiers::ALT) => { FileExplorerView::history_step(&mut state.explorer, false); }
+                        Right if key.modifiers.contains(KeyModifiers::ALT) => { FileExplorerView::history_step(&mut state.explorer, true); }
+                        Char('N') => {
+                            state.overlay = Overlay::Input;
+                            state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::NewEntry, buffer: String::new() });
+                        }
+                        Char('R') => begin_rename(&mut state),
+                        Char('C') => shell_cd_here(&mut state, &mut term),
+                        Char('c') if FileOpsView::duplicate(&mut state.paste, &mut state.explorer) => state.overlay = Overlay::Paste,
+                        Delete if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                            state.overlay = Overlay::Input;
+                            state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::DeleteConfirm, buffer: String::new() });
+                        }
+                        Delete => trash_targets(&mut state),
+                        Char('u') => undo_trash(&mut state),
+                        Char('P') => open_permissions(&mut state),
+                        Char('U') => FileExplorerView::toggle_disk_usage(&mut state.explorer),
+                        Char('Z') => {
+                            let buffer = FileOpsView::archive_name(&state.explorer);
+                            state.overlay = Overlay::Input;
+                            state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::Compress, buffer });
+                        }
+                        Char('r') => open_roots(&mut state),
+                        Char('b') => match FileExplorerView::toggle_pin(&mut state.explorer) {
+                            Ok(true) => ToastView::info(&mut state.toast, "📌 Dossier épinglé"),
+                            Ok(false) => ToastView::info(&mut state.toast, "Favori retiré"),
+                            Err(e) => ToastView::error(&mut state.toast, format!("Favoris : {}", e)),
+                        },
+                        Char(c @ '1'..='9') => {
+                            if let Err(e) = FileExplorerView::jump_to_pin(&mut state.explorer, c as usize - '1' as usize) {
+                                ToastView::error(&mut state.toast, e);
+                            }
+                        }
+                        Char('o') => {
+                            if let Some(path) = FileExplorerView::selected_file(&state.explorer) {
+                                OpenWithView::open(&mut state.open_with, path);
+                                state.overlay = Overlay::OpenWith;
+                            }
+                        }
+                        Char('.') => {
+                            state.explorer.show_hidden = !state.explorer.show_hidden;
+                            FileExplorerView::refresh(&mut state.explorer);
+                        }
+                        Char('I') => {
+                            state.explorer.hide_ignored = !state.explorer.hide_ignored;
+                            FileExplorerView::refresh(&mut state.explorer);
+                        }
+                        Char('D') => mark_for_diff(&mut state, Screen::Explorer),
+                        Char('y') => FileOpsView::yank(&mut state.explorer, false),
+                        Char('x') => FileOpsView::yank(&mut state.explorer, true),
+                        Char('p') if FileOpsView::begin_paste(&mut state.paste, &state.explorer) => state.overlay = Overlay::Paste,
+                        Char('F') => {
+                            let root = state.explorer.root.clone();
+                            SearchView::open(&mut state.search, &root, None, Screen::Explorer);
+                            state.screen = Screen::Search;
+                        },
+                        Char('s') => FileExplorerView::cycle_sort(&mut state.explorer),
+                        Char('S') => FileExplorerView::toggle_sort_order(&mut state.explorer),
+                        Char('l') | Enter => {
+                            if let Some(path) = FileExplorerView::activate(&mut state.explorer) {
+                                if open_in_viewer(&mut state, &path, Screen::Explorer) {
+                                    continue;
+                                }
+                                match EditorView::open_entry(path.clone(), &state.explorer) {
+                                    Ok(ed) => {
+                                        open_tab(&mut state, ed);
+                                        state.screen = Screen::Workspace; // bascule en Workspace
+                                        state.focus = Focus::Editor;
+                                    }
+                                    Err(e) => report_errors(&mut state, &mut logs, "Ouverture", &[format!("{}: {}", path.display(), e)]),
+                                }
+                            }
+                        }
+                        Char('q') | Esc => {
+                            state.screen = Screen::Home;
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // 4) Écran Workspace : focus & raccourcis
+                if state.screen == Screen::Workspace {
+                    match state.focus {
+                        Focus::Explorer => {
+                            use crossterm::event::KeyCode::*;
+                            if state.explorer.filter_editing {
+                                filter_key(&mut state.explorer, key.code);
+                                continue;
+                            }
+                            if state.explorer.crumb.is_some() {
+                                crumb_key(&mut state.explorer, key.code);
+                                continue;
+                            }
+                            if state.explorer.vfs.is_some() && vfs_key(&mut state, key.code) {
+                                continue;
+                            }
+                            match key.code {
+                                Char('/') => state.explorer.filter_editing = true,
+                                Char('g') => FileExplorerView::begin_crumbs(&mut state.explorer),
+                                Esc if !state.explorer.filter.is_empty() => FileExplorerView::clear_filter(&mut state.explorer),
+                                Esc if !state.explorer.marked.is_empty() => state.explorer.marked.clear(),
+                                Char(' ') => FileExplorerView::toggle_mark(&mut state.explorer),
+                                KeyCode::Tab => {
                                     state.focus = Focus::Editor;
                                 } // Tab -> focus à droite
                                 Char('N') => {
                                     state.overlay = Overlay::Input;
                                     state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::NewEntry, buffer: String::new() });
                                 }
-                                Char('R') => {
+                                Char('R') => begin_rename(&mut state),
+                                Char('C') => shell_cd_here(&mut state, &mut term),
+                                Char('c') if FileOpsView::duplicate(&mut state.paste, &mut state.explorer) => state.overlay = Overlay::Paste,
+                                Delete if key.modifiers.contains(KeyModifiers::SHIFT) => {
                                     state.overlay = Overlay::Input;
-                                    state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::RenameEntry, buffer: String::new() });
+                                    state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::DeleteConfirm, buffer: String::new() });
                                 }
-                                Delete => {
+                                Delete => trash_targets(&mut state),
+                                Char('u') => undo_trash(&mut state),
+                                Char('P') => open_permissions(&mut state),
+                                Char('U') => FileExplorerView::toggle_disk_usage(&mut state.explorer),
+                                Char('Z') => {
+                                    let buffer = FileOpsView::archive_name(&state.explorer);
                                     state.overlay = Overlay::Input;
-                                    state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::DeleteConfirm, buffer: String::new() });
+                                    state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::Compress, buffer });
+                                }
+                                Char('r') => open_roots(&mut state),
+                                Char('b') => match FileExplorerView::toggle_pin(&mut state.explorer) {
+                                    Ok(true) => ToastView::info(&mut state.toast, "📌 Dossier épinglé"),
+                                    Ok(false) => ToastView::info(&mut state.toast, "Favori retiré"),
+                                    Err(e) => ToastView::error(&mut state.toast, format!("Favoris : {}", e)),
+                                },
+                                Char(c @ '1'..='9') => {
+                                    if let Err(e) = FileExplorerView::jump_to_pin(&mut state.explorer, c as usize - '1' as usize) {
+                                        ToastView::error(&mut state.toast, e);
+                                    }
+                                }
+                                Char('o') => {
+                                    if let Some(path) = FileExplorerView::selected_file(&state.explorer) {
+                                        OpenWithView::open(&mut state.open_with, path);
+                                        state.overlay = Overlay::OpenWith;
+                                    }
                                 }
                                 Char('j') | Down => FileExplorerView::move_down(&mut state.explorer),
                                 Char('k') | Up => FileExplorerView::move_up(&mut state.explorer),
                                 Char('h') | Backspace => FileExplorerView::go_up(&mut state.explorer),
+                                Char('H') => { FileExplorerView::history_step(&mut state.explorer, false); }
+                                Char('L') => { FileExplorerView::history_step(&mut state.explorer, true); }
+                                Left if key.modifiers.contains(KeyModifiers::ALT) => { FileExplorerView::history_step(&mut state.explorer, false); }
+                                Right if key.modifiers.contains(KeyModifiers::ALT) => { FileExplorerView::history_step(&mut state.explorer, true); }
                                 Char('.') => {
                                     state.explorer.show_hidden = !state.explorer.show_hidden;
                                     FileExplorerView::refresh(&mut state.explorer);
                                 }
+                                Char('I') => {
+                                    state.explorer.hide_ignored = !state.explorer.hide_ignored;
+                                    FileExplorerView::refresh(&mut state.explorer);
+                                }
+                                Char('D') => mark_for_diff(&mut state, Screen::Workspace),
+                                Char('y') => FileOpsView::yank(&mut state.explorer, false),
+                                Char('x') => FileOpsView::yank(&mut state.explorer, true),
+                                Char('p') if FileOpsView::begin_paste(&mut state.paste, &state.explorer) => state.overlay = Overlay::Paste,
+                                Char('F') => {
+                                    let root = state.explorer.root.clone();
+                                    SearchView::open(&mut state.search, &root, None, Screen::Workspace);
+                                    state.screen = Screen::Search;
+                                },
+                                Char('s') => FileExplorerView::cycle_sort(&mut state.explorer),
+                                Char('S') => FileExplorerView::toggle_sort_order(&mut state.explorer),
                                 Char('l') | Enter => {
                                     if let Some(path) = FileExplorerView::activate(&mut state.explorer) {
-                                        match EditorView::open_path(path, &state.explorer.root) {
+                                        if open_in_viewer(&mut state, &path, Screen::Workspace) {
+                                            continue;
+                                        }
+                                        match EditorView::open_entry(path.clone(), &state.explorer) {
                                             Ok(ed) => {
-                                                state.tabs.open_or_focus(ed);
+                                                open_tab(&mut state, ed);
                                                 state.focus = Focus::Editor;
                                             }
-                                            Err(_e) => { /* TODO: logs */ }
+                                            Err(e) => report_errors(&mut state, &mut logs, "Ouverture", &[format!("{}: {}", path.display(), e)]),
                                         }
                                     }
                                 }
@@ -597,16 +2019,39 @@ pub fn start_tui() -> io::Result<()> {
                             use crossterm::event::{KeyCode::*, KeyModifiers};
                             let modifiers = key.modifiers;
 
+                            // Alt+" / Alt+m / Alt+' puis une lettre : registre de la prochaine copie ou du
+                            // prochain collage, pose d'une marque, saut à une marque
+                            if let Some(pending) = state.tabs.current_mut().and_then(|ed| ed.pending.take_if(|p| matches!(p, '"' | 'm' | '\''))) {
+                                match (pending, key.code) {
+                                    ('"', code) => select_register(&mut state.registers, code),
+                                    ('m', Char(c)) if marks::is_mark(c) => set_mark(&mut state, c),
+                                    ('\'', Char(c)) if marks::is_mark(c) || c == '\'' => jump_to_mark(&mut state, c, true),
+                                    _ => {}
+                                }
+                                continue;
+                            }
+
                             if modifiers.contains(KeyModifiers::CONTROL) {
                                 match key.code {
                                     Char('s') => {
-                                        if let Some(ed) = state.tabs.current_mut() { let _ = EditorView::save(ed); }
+                                        if let Some(ed) = state.tabs.current_mut() { save_editor(ed, &mut state.toast); }
                                     } // Ctrl+S
                                     Char('z') => { if let Some(ed) = state.tabs.current_mut() { EditorView::undo(ed); } } // Ctrl+Z
                                     Char('y') => { if let Some(ed) = state.tabs.current_mut() { EditorView::redo(ed); } } // Ctrl+Y
+                                    Char('p') => { state.md_preview = state.md_preview.next(); } // Ctrl+P
+                                    Char('d') => open_head_diff(&mut state, Screen::Workspace), // Ctrl+D
+                                    Char('f') => open_search_prompt(&mut state), // Ctrl+F
+                                    Char('g') => { state.overlay = Overlay::Input; state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::GotoLine, buffer: String::new() }); } // Ctrl+G
+                                    Char('r') => open_replace_prompt(&mut state), // Ctrl+R
+                                    Char('t') => open_tab_picker(&mut state), // Ctrl+T
+                                    Left => { if let Some(ed) = state.tabs.current_mut() { EditorView::word_back(ed); } } // Ctrl+←
+                                    Right => { if let Some(ed) = state.tabs.current_mut() { EditorView::word_forward(ed); } } // Ctrl+→
+                                    // Ctrl+Backspace arrive en Ctrl+H dans la plupart des terminaux
+                                    Backspace | Char('h') => { if let Some(ed) = state.tabs.current_mut() { EditorView::delete_word_back(ed); } }
+                                    Delete => { if let Some(ed) = state.tabs.current_mut() { EditorView::delete_word_forward(ed); } } // Ctrl+Suppr
                                     Char('w') => {
-                                        state.tabs.close_current();
-                                        if state.tabs.is_empty() { state.focus = Focus::Explorer; }
+                                        let idx = state.tabs.current;
+                                        request_close_tab(&mut state, idx);
                                     } // Ctrl+W
                                     PageDown => { state.tabs.next(); } // Ctrl+PageDown
                                     PageUp => { state.tabs.prev(); }   // Ctrl+PageUp
@@ -621,29 +2066,79 @@ pub fn start_tui() -> io::Result<()> {
                             if modifiers.contains(KeyModifiers::ALT) {
                                 match key.code {
                                     Left => { state.tabs.prev(); continue; }
+                                    Char('s') => { state.tabs.toggle_split(); continue; } // volets côte à côte
+                                    Char('o') => { state.tabs.switch_pane(); continue; }
+                                    // Alt+, / Alt+. : saut précédent / suivant (Ctrl+O ouvre la recherche de fichier)
+                                    Char(',') => { walk_jumps(&mut state, true); continue; }
+                                    Char('.') => { walk_jumps(&mut state, false); continue; }
                                     Right => { state.tabs.next(); continue; }
+                                    Backspace => { if let Some(ed) = state.tabs.current_mut() { EditorView::delete_word_back(ed); } continue; }
                                     _ => {}
                                 }
+                                // Alt+v / Alt+V : sélection (mode Visuel) ; Alt+p / Alt+P : coller le registre
+                                // Alt+d / Alt+y : couper / copier la ligne ; Alt+j : joindre ; Alt+↑/↓ : déplacer
+                                if let Some(ed) = state.tabs.current_mut() {
+                                    match key.code {
+                                        Up => { EditorView::move_lines(ed, true); continue; }
+                                        Down => { EditorView::move_lines(ed, false); continue; }
+                                        PageUp => { EditorView::scroll_page(ed, true, true); continue; }
+                                        PageDown => { EditorView::scroll_page(ed, false, true); continue; }
+                                        Char('d') => { store_register(&mut state.registers, EditorView::delete_line(ed)); continue; }
+                                        Char('y') => { store_register(&mut state.registers, EditorView::yank_line(ed)); continue; }
+                                        Char('j') => { EditorView::join_lines(ed); continue; }
+                                        Char('c') => { toggle_comment(ed, &mut state.toast); continue; }
+                                        Char('%') => { state.jumps.record(Jump::of(ed)); EditorView::jump_to_match(ed); continue; }
+                                        Char('f') => { EditorView::toggle_fold(ed); continue; }
+                                        Char('F') => {
+                                            if ed.folds.is_empty() { EditorView::fold_all(ed) } else { EditorView::unfold_all(ed) }
+                                            continue;
+                                        }
+                                        Char('z') => { ed.wrap = !ed.wrap; continue; } // repli des lignes longues
+                                        Char('i') => { ed.show_invisibles = !ed.show_invisibles; continue; }
+                                        Char(c @ ('"' | 'm' | '\'')) => { ed.pending = Some(c); continue; }
+                                        Char('b') => {
+                                            if let Err(e) = gutter::set_blame(ed, !ed.git.blame) { ToastView::error(&mut state.toast, e); }
+                                            continue;
+                                        }
+                                        Char('r') => { ed.read_only = !ed.read_only; continue; }
+                                        Char('v') => { EditorView::start_visual(ed, EditorMode::Visual); continue; }
+                                        Char('V') => { EditorView::start_visual(ed, EditorMode::VisualLine); continue; }
+                                        Char('B') => { toggle_block_selection(ed); continue; }
+                                        Char('p') => { paste_from(ed, &mut state.registers, &mut state.toast, false); continue; }
+                                        Char('P') => { paste_from(ed, &mut state.registers, &mut state.toast, true); continue; }
+                                        _ => {}
+                                    }
+                                }
                             }
 
                             // F-keys fallback (macOS Terminal friendly): F5 ← précédent, F6 → suivant
                             match key.code {
                                 KeyCode::F(5) => { state.tabs.prev(); continue; }
                                 KeyCode::F(6) => { state.tabs.next(); continue; }
+                                // F3 / Maj+F3 : occurrence suivante / précédente de la recherche
+                                KeyCode::F(3) => { search_step(&mut state, modifiers.contains(KeyModifiers::SHIFT)); continue; }
                                 _ => {}
                             }
 
                             if let Some(ed) = state.tabs.current_mut() {
+                                if matches!(ed.mode, EditorMode::Visual | EditorMode::VisualLine | EditorMode::VisualBlock) {
+                                    visual_key(ed, &mut state.registers, &mut state.toast, key.code);
+                                    continue;
+                                }
                                 match key.code {
                                     Left => EditorView::move_left(ed),
                                     Right => EditorView::move_right(ed),
                                     Up => EditorView::move_up(ed),
                                     Down => EditorView::move_down(ed),
+                                    PageUp => EditorView::scroll_page(ed, true, false),
+                                    PageDown => EditorView::scroll_page(ed, false, false),
                                     Backspace => EditorView::backspace(ed),
                                     Enter => EditorView::insert_newline(ed),
-                                    KeyCode::Tab | Esc => {
+                                    KeyCode::Tab if !EditorView::snippet_tab(ed) => EditorView::insert_indent(ed),
+                                    KeyCode::BackTab if !EditorView::snippet_back(ed) => EditorView::shift_lines(ed, true),
+                                    Esc => {
                                         state.focus = Focus::Explorer;
-                                    } // Tab/Esc → focus à gauche
+                                    } // Esc → focus à gauche (Tab indente)
                                     Char(c) => EditorView::insert_char(ed, c),
                                     _ => {}
                                 }
@@ -663,14 +2158,38 @@ pub fn start_tui() -> io::Result<()> {
                     let modifiers = key.modifiers;
                     if modifiers.contains(KeyModifiers::CONTROL) {
                         match key.code {
-                            Char('s') => { if let Some(ed) = state.tabs.current_mut() { let _ = EditorView::save(ed); } }
+                            Char('s') => { if let Some(ed) = state.tabs.current_mut() { save_editor(ed, &mut state.toast); } }
                             Char('z') => { if let Some(ed) = state.tabs.current_mut() { EditorView::undo(ed); } }
                             Char('y') => { if let Some(ed) = state.tabs.current_mut() { EditorView::redo(ed); } }
-                            Char('f') => { state.overlay = Overlay::Input; state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::SearchText, buffer: String::new() }); }
+                            Char('p') => { state.md_preview = state.md_preview.next(); }
+                            // Ctrl+D / Ctrl+U : demi-page hors du mode Insertion (:diff reste disponible)
+                            Char(c @ ('d' | 'u')) if state.tabs.current().is_some_and(|ed| !matches!(ed.mode, EditorMode::Insert | EditorMode::Command)) => {
+                                if let Some(ed) = state.tabs.current_mut() { EditorView::scroll_page(ed, c == 'u', true); }
+                            }
+                            Char('d') => open_head_diff(&mut state, Screen::Editor),
+                            Char('f') => open_search_prompt(&mut state),
                             Char('g') => { state.overlay = Overlay::Input; state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::GotoLine, buffer: String::new() }); }
+                            Char('r') => open_replace_prompt(&mut state),
+                            Char('t') => open_tab_picker(&mut state),
+                            Char('v') => {
+                                if let Some(ed) = state.tabs.current_mut().filter(|ed| !matches!(ed.mode, EditorMode::Insert | EditorMode::Command)) {
+                                    toggle_block_selection(ed);
+                                }
+                            }
+                            // Ctrl+O / Ctrl+I : liste des sauts (Ctrl+I arrive en Tab dans la plupart des terminaux)
+                            Char('o') => walk_jumps(&mut state, true),
+                            Char('i') => walk_jumps(&mut state, false),
+                            Left => { if let Some(ed) = state.tabs.current_mut() { EditorView::word_back(ed); } }
+                            Right => { if let Some(ed) = state.tabs.current_mut() { EditorView::word_forward(ed); } }
+                            Backspace | Char('h') => {
+                                if let Some(ed) = state.tabs.current_mut().filter(|ed| ed.mode == EditorMode::Insert) { EditorView::delete_word_back(ed); }
+                            }
+                            Delete => {
+                                if let Some(ed) = state.tabs.current_mut().filter(|ed| ed.mode == EditorMode::Insert) { EditorView::delete_word_forward(ed); }
+                            }
                             Char('w') => {
-                                state.tabs.close_current();
-                                if state.tabs.is_empty() { state.screen = Screen::Workspace; state.focus = Focus::Explorer; }
+                                let idx = state.tabs.current;
+                                request_close_tab(&mut state, idx);
                             }
                             PageDown => { state.tabs.next(); }
                             PageUp => { state.tabs.prev(); }
@@ -683,45 +2202,120 @@ pub fn start_tui() -> io::Result<()> {
 
                     // Alt+←/→ et F5/F6 (fallbacks pour macOS Terminal)
                     if modifiers.contains(KeyModifiers::ALT) {
-                        match key.code { Left => { state.tabs.prev(); }, Right => { state.tabs.next(); }, _ => {} }
+                        match key.code {
+                            Left => { state.tabs.prev(); }
+                            Char('s') => { state.tabs.toggle_split(); }
+                            Char('o') => { state.tabs.switch_pane(); }
+                            Right => { state.tabs.next(); }
+                            Backspace => {
+                                if let Some(ed) = state.tabs.current_mut().filter(|ed| ed.mode == EditorMode::Insert) { EditorView::delete_word_back(ed); }
+                            }
+                            Up => { if let Some(ed) = state.tabs.current_mut() { EditorView::move_lines(ed, true); } }
+                            Down => { if let Some(ed) = state.tabs.current_mut() { EditorView::move_lines(ed, false); } }
+                            _ => {}
+                        }
                         continue;
                     }
                     match key.code { KeyCode::F(5) => { state.tabs.prev(); continue; }, KeyCode::F(6) => { state.tabs.next(); continue; }, _ => {} }
+                    if key.code == KeyCode::F(3) {
+                        search_step(&mut state, key.modifiers.contains(KeyModifiers::SHIFT));
+                        continue;
+                    }
 
                     // Édition du buffer de l'onglet courant
                     let mut open_path_req: Option<PathBuf> = None;
+                    let mut diff_req = false;
+                    let mut replace_req: Option<String> = None;
+                    let mut tabs_req = false;
+                    let mut definition_req = false;
+                    let mut split_req: Option<bool> = None;
+                    let mut mark_req: Option<char> = None;
+                    let mut jump_mark_req: Option<(char, bool)> = None;
+                    let mut jump_req: Option<bool> = None;
                     {
                         if let Some(ed) = state.tabs.current_mut() {
                         use KeyCode::*;
                         match ed.mode {
-                            EditorMode::Normal => match key.code {
-                                Char('i') => ed.mode = EditorMode::Insert,
-                                Char(':') => { ed.mode = EditorMode::Command; ed.cmdline.clear(); }
-                                Left => EditorView::move_left(ed),
-                                Right => EditorView::move_right(ed),
-                                Up => EditorView::move_up(ed),
-                                Down => EditorView::move_down(ed),
-                                Esc | KeyCode::Tab => { state.screen = Screen::Workspace; state.focus = Focus::Explorer; }
-                                _ => {}
+                            EditorMode::Normal => match (ed.pending.take(), key.code) {
+                                (Some('d'), Char('d')) => store_register(&mut state.registers, EditorView::delete_line(ed)),
+                                (Some('y'), Char('y')) => store_register(&mut state.registers, EditorView::yank_line(ed)),
+                                (Some('z'), Char('a')) => EditorView::toggle_fold(ed),
+                                (Some('z'), Char('c')) => EditorView::fold(ed),
+                                (Some('z'), Char('o')) => EditorView::unfold(ed),
+                                (Some('z'), Char('M')) => EditorView::fold_all(ed),
+                                (Some('z'), Char('R')) => EditorView::unfold_all(ed),
+                                (Some('g'), Char('d')) => definition_req = true,
+                                (Some('g'), Char('c')) => toggle_comment(ed, &mut state.toast),
+                                (Some('"'), code) => select_register(&mut state.registers, code),
+                                (Some('m'), Char(c)) if marks::is_mark(c) => mark_req = Some(c),
+                                (Some(p @ ('\'' | '`')), Char(c)) if marks::is_mark(c) || matches!(c, '\'' | '`') => jump_mark_req = Some((c, p == '`')),
+                                (_, Char(c @ ('d' | 'y' | 'z' | 'g' | '"' | 'm' | '\'' | '`'))) => ed.pending = Some(c),
+                                (_, Char('J')) => EditorView::join_lines(ed),
+                                (_, Char('%')) => { state.jumps.record(Jump::of(ed)); EditorView::jump_to_match(ed) }
+                                (_, Char('i')) => ed.mode = EditorMode::Insert,
+                                (_, Char(':')) => { ed.mode = EditorMode::Command; ed.cmdline.clear(); }
+                                (_, Char('w')) => EditorView::word_forward(ed),
+                                (_, Char('b')) => EditorView::word_back(ed),
+                                (_, Char('e')) => EditorView::word_end(ed),
+                                (_, Char('n')) => { state.jumps.record(Jump::of(ed)); EditorView::search_next(ed) }
+                                (_, Char('N')) => { state.jumps.record(Jump::of(ed)); EditorView::search_prev(ed) }
+                                (_, Char('v')) => EditorView::start_visual(ed, EditorMode::Visual),
+                                (_, Char('V')) => EditorView::start_visual(ed, EditorMode::VisualLine),
+                                (_, Char('p')) => paste_from(ed, &mut state.registers, &mut state.toast, false),
+                                (_, Char('P')) => paste_from(ed, &mut state.registers, &mut state.toast, true),
+                                (_, Left) => EditorView::move_left(ed),
+                                (_, Right) => EditorView::move_right(ed),
+                                (_, Up) => EditorView::move_up(ed),
+                                (_, Down) => EditorView::move_down(ed),
+                                (_, PageUp) => EditorView::scroll_page(ed, true, false),
+                                (_, PageDown) => EditorView::scroll_page(ed, false, false),
+                                (_, KeyCode::Tab) => jump_req = Some(false),
+                                (_, Esc) => { state.screen = Screen::Workspace; state.focus = Focus::Explorer; }
+                                (_, _) => {}
                             },
+                            EditorMode::Visual | EditorMode::VisualLine | EditorMode::VisualBlock => visual_key(ed, &mut state.registers, &mut state.toast, key.code),
                             EditorMode::Insert => match key.code {
-                                Esc => ed.mode = EditorMode::Normal,
+                                Esc => { ed.mode = EditorMode::Normal; ed.snippet = None; }
                                 Enter => EditorView::insert_newline(ed),
                                 Backspace => EditorView::backspace(ed),
+                                KeyCode::Tab if !EditorView::snippet_tab(ed) => EditorView::insert_indent(ed),
+                                BackTab if !EditorView::snippet_back(ed) => EditorView::shift_lines(ed, true),
                                 Left => EditorView::move_left(ed),
                                 Right => EditorView::move_right(ed),
                                 Up => EditorView::move_up(ed),
                                 Down => EditorView::move_down(ed),
+                                PageUp => EditorView::scroll_page(ed, true, false),
+                                PageDown => EditorView::scroll_page(ed, false, false),
                                 Char(c) => EditorView::insert_char(ed, c),
                                 _ => {}
                             },
                             EditorMode::Command => match key.code {
                                 Enter => {
-                                    let cmd = ed.cmdline.trim();
+                                    // Copie : le garde de `wq` enregistre le tampon
+                                    let cmdline = ed.cmdline.trim().to_string();
+                                    let cmd = cmdline.as_str();
                                     match cmd {
                                         "q" => { state.screen = Screen::Workspace; state.focus = Focus::Explorer; }
-                                        "w" => { let _ = EditorView::save(ed); }
-                                        "wq" => { let _ = EditorView::save(ed); state.screen = Screen::Workspace; state.focus = Focus::Explorer; }
+                                        "w" => { save_editor(ed, &mut state.toast); }
+                                        "wq" if save_editor(ed, &mut state.toast) => { state.screen = Screen::Workspace; state.focus = Focus::Explorer; }
+                                        "preview" => { state.md_preview = state.md_preview.next(); }
+                                        "diff" => { diff_req = true; }
+                                        "ls" | "tabs" => { tabs_req = true; }
+                                        "vsplit" | "vs" => { split_req = Some(true); }
+                                        "only" => { split_req = Some(false); }
+                                        "set wrap" => { ed.wrap = true; }
+                                        "set nowrap" => { ed.wrap = false; }
+                                        "set wrap!" => { ed.wrap = !ed.wrap; }
+                                        "set list" => { ed.show_invisibles = true; }
+                                        "set nolist" => { ed.show_invisibles = false; }
+                                        "set list!" => { ed.show_invisibles = !ed.show_invisibles; }
+                                        "set blame" | "set noblame" | "set blame!" => {
+                                            let on = match cmd { "set blame" => true, "set noblame" => false, _ => !ed.git.blame };
+                                            if let Err(e) = gutter::set_blame(ed, on) { ToastView::error(&mut state.toast, e); }
+                                        }
+                                        "set readonly" | "set ro" => { ed.read_only = true; }
+                                        "set noreadonly" | "set noro" => { ed.read_only = false; }
+                                        other if ReplaceView::is_command(other) => { replace_req = Some(other.to_string()); }
                                         other if other.starts_with("e ") => {
                                             let p = PathBuf::from(other.trim_start_matches("e ").trim());
                                             open_path_req = Some(p);
@@ -738,15 +2332,63 @@ pub fn start_tui() -> io::Result<()> {
                         }
                         }
                     }
+                    if diff_req {
+                        open_head_diff(&mut state, Screen::Editor);
+                    }
+                    if let Some(cmd) = replace_req.take() {
+                        start_replace(&mut state, &cmd);
+                    }
+                    if tabs_req {
+                        open_tab_picker(&mut state);
+                    }
+                    if definition_req {
+                        goto_definition(&mut state);
+                    }
+                    if let Some(c) = mark_req {
+                        set_mark(&mut state, c);
+                    }
+                    if let Some((c, exact)) = jump_mark_req {
+                        jump_to_mark(&mut state, c, exact);
+                    }
+                    if let Some(back) = jump_req {
+                        walk_jumps(&mut state, back);
+                    }
+                    if split_req.is_some_and(|on| on != state.tabs.split.is_some()) {
+                        state.tabs.toggle_split();
+                    }
                     if let Some(p) = open_path_req.take() {
-                        if let Ok(new_ed) = EditorView::open_path(p, &state.explorer.root) { state.tabs.open_or_focus(new_ed); }
+                        if p.starts_with("sftp:") {
+                            open_remote(&mut state, &p.to_string_lossy());
+                        } else if !open_in_viewer(&mut state, &p, Screen::Editor)
+                            && let Ok(new_ed) = EditorView::open_path(p, &state.explorer.root)
+                        {
+                            open_tab(&mut state, new_ed);
+                        }
                     }
                     continue;
                 }
 
+                // 6bis) Recherche Ctrl+R : Entrée lance la correspondance (traitée plus bas)
+                if term.searching() {
+                    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                    let search_key = match key.code {
+                        KeyCode::Char('r') if ctrl => Some(SearchKey::Older),
+                        KeyCode::Char('c' | 'g') if ctrl => Some(SearchKey::Cancel),
+                        KeyCode::Char(c) if !ctrl => Some(SearchKey::Char(c)),
+                        KeyCode::Backspace => Some(SearchKey::Backspace),
+                        KeyCode::Enter => Some(SearchKey::Accept),
+                        KeyCode::Esc => Some(SearchKey::Cancel),
+                        _ => None,
+                    };
+                    let accepted = search_key.is_some_and(|k| term.search_key(k));
+                    if !accepted {
+                        continue;
+                    }
+                }
+
                 // 6) Écran Shell : édition / exécution
                 match key.code {
-                    KeyCode::Esc => state.running = false,
+                    KeyCode::Esc => request_quit(&mut state),
 
                     // Scroll du terminal (ou logs avec Shift)
                     KeyCode::PageUp => {
@@ -785,16 +2427,33 @@ pub fn start_tui() -> io::Result<()> {
                             if line == ":fs" || line == ":files" {
                                 state.screen = Screen::Workspace;
                                 state.focus = Focus::Explorer;
-                            } else if let Some(rest) = line.strip_prefix(":e ") {
-                                let path = PathBuf::from(rest.trim());
-                                match EditorView::open_path(path, &state.explorer.root) {
-                                    Ok(ed) => {
-                                        state.tabs.open_or_focus(ed);
-                                        state.screen = Screen::Workspace;
-                                        state.focus = Focus::Editor;
+                            } else if let Some(url) = line.strip_prefix(":e ").map(str::trim).filter(|r| r.starts_with("sftp://")) {
+                                open_remote(&mut state, url);
+                            } else if let Some(rest) = line.strip_prefix(":pty ") {
+                                // N'importe quelle commande dans un pseudo-terminal
+                                term.push_output(format!("$ {}", rest.trim()));
+                                match parser::tokenize(rest).and_then(parser::words) {
+                                    Ok(words) if !words.is_empty() => {
+                                        let args: Vec<&str> = words[1..].iter().map(String::as_str).collect();
+                                        if let Err(e) = term.start_pty(rest.trim(), &words[0], &args) {
+                                            term.push_output(format!("pty: {}: {}", words[0], e));
+                                        }
                                     }
-                                    Err(e) => {
-                                        term.push_output(format!(":e error: {}", e));
+                                    Ok(_) => term.push_output("usage: :pty <commande>"),
+                                    Err(e) => term.push_output(format!("syntax error: {}", e)),
+                                }
+                            } else if let Some(rest) = line.strip_prefix(":e ") {
+                                let path = PathBuf::from(rest.trim());
+                                if !open_in_viewer(&mut state, &path, Screen::Shell) {
+                                    match EditorView::open_path(path, &state.explorer.root) {
+                                        Ok(ed) => {
+                                            open_tab(&mut state, ed);
+                                            state.screen = Screen::Workspace;
+                                            state.focus = Focus::Editor;
+                                        }
+                                        Err(e) => {
+                                            term.push_output(format!(":e error: {}", e));
+                                        }
                                     }
                                 }
                             } else {
@@ -805,13 +2464,23 @@ pub fn start_tui() -> io::Result<()> {
                             // Commande shell réelle (simple)
                             term.push_output(format!("$ {}", line));
                             term.push_history_if_new(&line);
-                            run_shell_like(&line, &mut term, &mut logs);
+                            let moved = run_shell_like(&line, shell, &mut terminal, &mut term, &mut logs, &mut jobs, &mut status);
+                            // `exit [code]` : quitte le TUI, puis le REPL avec ce code
+                            if shell.exit_requested().is_some() {
+                                request_quit(&mut state);
+                            }
+                            // follow_cwd : l'explorateur suit les `cd` du shell
+                            if let Some(dir) = moved.filter(|_| state.explorer.follow_cwd)
+                                && let Err(e) = FileExplorerView::go_to(&mut state.explorer, dir)
+                            {
+                                term.push_output(format!("(explorer) {}", e));
+                            }
                         }
                         term.clear_input();
                     }
 
                     // Saisie
-                    KeyCode::Char(c) => term.insert_char(c),
+                    KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => term.insert_char(c),
 
                     _ => {}
                 }
@@ -822,6 +2491,29 @@ pub fn start_tui() -> io::Result<()> {
                         KeyCode::Char('a') => term.move_to_start(), // Ctrl+A
                         KeyCode::Char('e') => term.move_to_end(),   // Ctrl+E
                         KeyCode::Char('l') => term.clear_output(),  // Ctrl+L
+                        KeyCode::Char('r') => term.start_search(),  // Ctrl+R
+                        KeyCode::Char('c') => {
+                            // Ctrl+C : interrompt le job au premier plan, sinon abandonne la saisie
+                            match jobs.foreground() {
+                                Some(id) => {
+                                    if let Err(e) = jobs.interrupt(id) {
+                                        term.push_output(format!("[{}] interrupt: {}", id, e));
+                                    }
+                                }
+                                None => {
+                                    term.push_output(format!("$ {}^C", term.current_line()));
+                                    term.clear_input();
+                                }
+                            }
+                        }
+                        KeyCode::Char('y') => {
+                            // Ctrl+Y : ligne saisie, sinon sortie de la dernière commande
+                            let text = if term.current_line().is_empty() { term.last_output() } else { term.current_line().to_string() };
+                            match clipboard::copy(&text) {
+                                Ok(()) => ToastView::info(&mut state.toast, "Copié dans le presse-papiers"),
+                                Err(e) => ToastView::error(&mut state.toast, format!("Presse-papiers : {}", e)),
+                            }
+                        }
                         _ => {}
                     }
                 }
@@ -834,13 +2526,765 @@ pub fn start_tui() -> io::Result<()> {
         }
     }
 
+    // Sortie normale : les modifications ont été enregistrées ou abandonnées
+    for tab in state.tabs.tabs.iter_mut() {
+        swap::discard(&mut tab.state);
+    }
+
     // Restauration du terminal
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(terminal.backend_mut(), DisableBracketedPaste, LeaveAlternateScreen)?;
     terminal.show_cursor()?;
     Ok(())
 }
 
+/// Open files that have a dedicated viewer (SQLite, CSV/TSV, binary → hex) instead of
+/// the text editor, and read large text files in the background. Returns false when
+/// `path` should go to the editor.
+fn open_in_viewer(state: &mut TuiState, path: &Path, from: Screen) -> bool {
+    if !path.is_file() || !editor::within_root(&state.explorer.root, path) {
+        return false;
+    }
+    if sqlite_view::is_sqlite(path) {
+        SqliteView::open(&mut state.sqlite, path, from);
+        state.screen = Screen::Data;
+    } else if csv_view::is_csv(path) {
+        CsvView::open(&mut state.csv, path, from);
+        state.screen = Screen::Table;
+    } else if hex::is_binary(path) {
+        HexView::open(&mut state.hex, path, from);
+        state.screen = Screen::Hex;
+    } else if let Some(size) = EditorView::large_file_size(path) {
+        // Déjà ouvert dans un onglet : ne pas le relire
+        if let Some(idx) = state.tabs.position_of(path) {
+            state.tabs.focus(idx);
+            state.screen = Screen::Workspace;
+            state.focus = Focus::Editor;
+        } else {
+            EditorView::start_load(&mut state.load, path, size);
+            state.overlay = Overlay::Loading;
+        }
+    } else {
+        return false;
+    }
+    true
+}
+
+/// Keys while the editor is in a Visual mode (Editor screen, or Alt+v / Alt+V in the workspace).
+fn visual_key(ed: &mut state::EditorState, registers: &mut state::Registers, toast: &mut Option<state::Toast>, code: KeyCode) {
+    match ed.pending.take() {
+        Some('"') => {
+            select_register(registers, code);
+            return;
+        }
+        Some('g') => {
+            if code == KeyCode::Char('c') {
+                toggle_comment(ed, toast);
+            }
+            return;
+        }
+        _ => {}
+    }
+    match code {
+        KeyCode::Left | KeyCode::Char('h') => EditorView::move_left(ed),
+        KeyCode::Right | KeyCode::Char('l') => EditorView::move_right(ed),
+        KeyCode::Up | KeyCode::Char('k') => EditorView::move_up(ed),
+        KeyCode::Down | KeyCode::Char('j') => EditorView::move_down(ed),
+        KeyCode::PageUp => EditorView::scroll_page(ed, true, false),
+        KeyCode::PageDown => EditorView::scroll_page(ed, false, false),
+        KeyCode::Char('y') => store_register(registers, EditorView::yank_selection(ed)),
+        KeyCode::Char('d') | KeyCode::Char('x') => store_register(registers, EditorView::delete_selection(ed)),
+        KeyCode::Char(c @ ('"' | 'g')) => ed.pending = Some(c),
+        // I / A : taper devant / après le bloc, sur chacune de ses lignes
+        KeyCode::Char('I') if ed.mode == EditorMode::VisualBlock => EditorView::start_block_insert(ed, false),
+        KeyCode::Char('A') if ed.mode == EditorMode::VisualBlock => EditorView::start_block_insert(ed, true),
+        KeyCode::Char('>') => EditorView::shift_lines(ed, false),
+        KeyCode::Char('<') => EditorView::shift_lines(ed, true),
+        // v / V : changer de type de sélection, ou sortir si c'est le même
+        KeyCode::Char('v') => ed.mode = if ed.mode == EditorMode::Visual { EditorMode::Normal } else { EditorMode::Visual },
+        KeyCode::Char('V') => ed.mode = if ed.mode == EditorMode::VisualLine { EditorMode::Normal } else { EditorMode::VisualLine },
+        KeyCode::Esc => {
+            ed.mode = EditorMode::Normal;
+            registers.selected = None;
+        }
+        _ => {}
+    }
+}
+
+/// Ctrl+V (Alt+B in the workspace): block selection, or back to the Normal mode.
+fn toggle_block_selection(ed: &mut state::EditorState) {
+    match ed.mode {
+        EditorMode::VisualBlock => ed.mode = EditorMode::Normal,
+        EditorMode::Visual | EditorMode::VisualLine => ed.mode = EditorMode::VisualBlock,
+        _ => EditorView::start_visual(ed, EditorMode::VisualBlock),
+    }
+}
+
+/// Alt+c / `gc`: toggle the comment of the selected lines or the cursor line.
+fn toggle_comment(ed: &mut state::EditorState, toast: &mut Option<state::Toast>) {
+    if !EditorView::toggle_comment(ed) {
+        ToastView::error(toast, "Pas de syntaxe de commentaire pour ce type de fichier (comment dans config/editor.toml)");
+    }
+}
+
+/// Save a buffer; a failure (read-only buffer, character its encoding cannot hold,
+/// write error) is shown in a toast.
+fn save_editor(ed: &mut state::EditorState, toast: &mut Option<state::Toast>) -> bool {
+    match EditorView::save(ed) {
+        Ok(()) => true,
+        Err(e) => {
+            ToastView::error(toast, format!("Enregistrement impossible : {}", e));
+            false
+        }
+    }
+}
+
+/// Key after `"`: a letter or digit chooses the register of the next yank or paste.
+fn select_register(registers: &mut state::Registers, code: KeyCode) {
+    registers.selected = match code {
+        KeyCode::Char(c) if c.is_ascii_alphanumeric() => Some(c),
+        _ => None,
+    };
+}
+
+/// Keep a cut or copied text at the top of the numbered history, and in the register
+/// chosen with `"x` (an uppercase letter appends to it) or else in the unnamed register
+/// and on the system clipboard.
+fn store_register(registers: &mut state::Registers, taken: Option<state::EditorRegister>) {
+    let selected = registers.selected.take();
+    let Some(taken) = taken else { return; };
+    registers.history.push_front(taken.clone());
+    registers.history.truncate(state::REGISTER_HISTORY);
+    match selected {
+        Some(c) if c.is_ascii_uppercase() => {
+            let register = registers.named.entry(c.to_ascii_lowercase()).or_default();
+            if register.text.is_empty() {
+                *register = taken;
+            } else {
+                // Ajout de lignes entières : elles commencent sur une nouvelle ligne
+                if (register.linewise || taken.linewise) && !register.text.ends_with('\n') {
+                    register.text.push('\n');
+                }
+                register.text.push_str(&taken.text);
+                register.linewise |= taken.linewise;
+            }
+        }
+        Some(c) if c.is_ascii_lowercase() => {
+            registers.named.insert(c, taken);
+        }
+        _ => {
+            let _ = clipboard::copy(&taken.text);
+            registers.unnamed = taken;
+        }
+    }
+}
+
+/// Close a tab, asking first when it has unsaved changes.
+fn request_close_tab(state: &mut TuiState, idx: usize) {
+    if idx >= state.tabs.tabs.len() { return; }
+    if state.tabs.tabs[idx].state.dirty {
+        state.unsaved = Some(state::UnsavedState { action: state::PendingClose::Tab(idx), back_to: state.overlay });
+        state.overlay = Overlay::Unsaved;
+    } else {
+        close_tab(state, idx);
+    }
+}
+
+/// Close a tab; once none is left the keyboard goes back to the explorer.
+fn close_tab(state: &mut TuiState, idx: usize) {
+    if let Some(tab) = state.tabs.tabs.get_mut(idx) {
+        swap::discard(&mut tab.state);
+    }
+    state.tabs.close(idx);
+    TabsView::move_by(&mut state.tabs_selected, &state.tabs, 0);
+    if state.tabs.is_empty() {
+        if state.overlay == Overlay::Tabs { state.overlay = Overlay::None; }
+        if state.screen == Screen::Editor { state.screen = Screen::Workspace; }
+        state.focus = Focus::Explorer;
+    }
+}
+
+/// Quit the TUI, asking first when tabs have unsaved changes (or always, with
+/// `confirm_exit` in config.toml).
+fn request_quit(state: &mut TuiState) {
+    let dirty = state.tabs.tabs.iter().any(|t| t.state.dirty);
+    if !dirty && ShellConfig::get().confirm_exit() {
+        state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::QuitConfirm, buffer: String::new() });
+        state.overlay = Overlay::Input;
+    } else {
+        leave_to_repl(state);
+    }
+}
+
+/// Go back to the REPL (`:repl`), asking first only when tabs have unsaved changes.
+fn leave_to_repl(state: &mut TuiState) {
+    if state.tabs.tabs.iter().any(|t| t.state.dirty) {
+        state.unsaved = Some(state::UnsavedState { action: state::PendingClose::Quit, back_to: state.overlay });
+        state.overlay = Overlay::Unsaved;
+    } else {
+        state.running = false;
+    }
+}
+
+/// Whether leaving is waiting for a confirmation (unsaved tabs, `confirm_exit`).
+fn quit_pending(state: &TuiState) -> bool {
+    match state.overlay {
+        Overlay::Unsaved => state.unsaved.as_ref().is_some_and(|u| matches!(u.action, state::PendingClose::Quit)),
+        Overlay::Input => state.overlay_input.as_ref().is_some_and(|i| matches!(i.kind, state::InputKind::QuitConfirm)),
+        _ => false,
+    }
+}
+
+/// Answer the unsaved-changes confirmation: save the modified tabs (`save`) or drop
+/// their changes, then close the tab or quit. A failed save cancels.
+fn resolve_unsaved(state: &mut TuiState, save: bool) {
+    let Some(unsaved) = state.unsaved.take() else { return; };
+    state.overlay = unsaved.back_to;
+    if save {
+        for idx in UnsavedView::dirty_tabs(&state.tabs, unsaved.action) {
+            if !save_editor(&mut state.tabs.tabs[idx].state, &mut state.toast) {
+                return;
+            }
+        }
+    }
+    match unsaved.action {
+        state::PendingClose::Tab(idx) => close_tab(state, idx),
+        state::PendingClose::Quit => state.running = false,
+    }
+}
+
+/// Ctrl+T in the editor (`:ls`): list the open tabs, the current one selected.
+fn open_tab_picker(state: &mut TuiState) {
+    if state.tabs.is_empty() { return; }
+    state.tabs_selected = state.tabs.current;
+    state.overlay = Overlay::Tabs;
+}
+
+/// Focus a tab from the picker and hand the keyboard to the editor.
+fn focus_tab(state: &mut TuiState, idx: usize) {
+    if idx >= state.tabs.tabs.len() { return; }
+    state.tabs.focus(idx);
+    state.overlay = Overlay::None;
+    if state.screen == Screen::Workspace { state.focus = Focus::Editor; }
+}
+
+/// Jump to a symbol: its tab is focused (the file opened when needed) and the cursor
+/// placed on its name.
+fn goto_symbol(state: &mut TuiState, sym: &state::Symbol) {
+    // Symbole d'un buffer sans fichier : l'onglet courant
+    let path = Some(sym.path.as_path()).filter(|p| !p.as_os_str().is_empty());
+    goto_position(state, path, sym.row, sym.col);
+}
+
+/// Put the cursor at `row` / `col` of the tab editing `path` (opened when needed),
+/// or of the current tab without a path. The position left goes to the jump list.
+fn goto_position(state: &mut TuiState, path: Option<&Path>, row: usize, col: usize) {
+    state.jumps.record(state.tabs.current().and_then(Jump::of));
+    show_position(state, path, row, col);
+}
+
+/// `goto_position` without recording a jump.
+fn show_position(state: &mut TuiState, path: Option<&Path>, row: usize, col: usize) {
+    if let Some(path) = path {
+        match state.tabs.position_of(path) {
+            Some(idx) => state.tabs.focus(idx),
+            None => match EditorView::open_path(path, &state.explorer.root) {
+                Ok(ed) => state.tabs.open_or_focus(ed),
+                Err(e) => {
+                    ToastView::error(&mut state.toast, format!("{}: {}", path.display(), e));
+                    return;
+                }
+            },
+        }
+    }
+    if let Some(ed) = state.tabs.current_mut() {
+        ed.cursor_row = row.min(ed.buffer.len_lines().saturating_sub(1));
+        ed.cursor_col = col.min(ed.buffer.line(ed.cursor_row).len_chars());
+        ed.scroll_row = ed.cursor_row.saturating_sub(5);
+    }
+    if state.screen != Screen::Editor {
+        state.screen = Screen::Workspace;
+        state.focus = Focus::Editor;
+    }
+}
+
+/// Open `ed` in a tab (or focus the tab editing its file), recording the position
+/// left in the jump list.
+fn open_tab(state: &mut TuiState, ed: state::EditorState) {
+    state.jumps.record(state.tabs.current().and_then(Jump::of));
+    state.tabs.open_or_focus(ed);
+}
+
+/// Ctrl+O / Ctrl+I: walk the jump list back or forward, across tabs.
+fn walk_jumps(state: &mut TuiState, back: bool) {
+    let jump = if back {
+        let here = state.tabs.current().and_then(Jump::of);
+        state.jumps.back(here)
+    } else {
+        state.jumps.forward()
+    };
+    match jump {
+        Some(j) => show_position(state, Some(&j.path), j.row, j.col),
+        None => ToastView::info(&mut state.toast, if back { "Début de la liste des sauts" } else { "Fin de la liste des sauts" }),
+    }
+}
+
+/// `m x`: mark the cursor position. An uppercase mark names one position among all
+/// files, so it leaves the other tabs.
+fn set_mark(state: &mut TuiState, c: char) {
+    let current = state.tabs.current;
+    if c.is_ascii_uppercase() {
+        for (i, tab) in state.tabs.tabs.iter_mut().enumerate() {
+            if i != current && tab.state.marks.remove(&c).is_some() {
+                marks::save(&tab.state);
+            }
+        }
+    }
+    if let Some(ed) = state.tabs.current_mut() {
+        ed.marks.insert(c, (ed.cursor_row, ed.cursor_col));
+        marks::save(ed);
+    }
+}
+
+/// `'x` / `` `x ``: go to the line (first non-blank char) or the exact position of a
+/// mark; `''` goes back to where the last jump to a mark started.
+fn jump_to_mark(state: &mut TuiState, c: char, exact: bool) {
+    let c = if c == '`' { '\'' } else { c };
+    let Some(from) = state.tabs.current().map(|ed| (ed.cursor_row, ed.cursor_col)) else { return; };
+    let here = state.tabs.current().and_then(|ed| ed.marks.get(&c).copied());
+    let target = match here {
+        Some(pos) => Some((None, pos)),
+        // Marque globale : dans un autre onglet, sinon dans un fichier fermé
+        None if c.is_ascii_uppercase() => state
+            .tabs
+            .tabs
+            .iter()
+            .find_map(|t| Some((t.state.path.clone(), *t.state.marks.get(&c)?)))
+            .or_else(|| marks::global(c).map(|m| (Some(m.path), (m.row, m.col)))),
+        None => None,
+    };
+    let Some((path, (row, col))) = target else {
+        ToastView::error(&mut state.toast, format!("Marque '{} non définie", c));
+        return;
+    };
+    if path.is_none() && let Some(ed) = state.tabs.current_mut() {
+        ed.marks.insert('\'', from);
+    }
+    goto_position(state, path.as_deref(), row, col);
+    if let Some(ed) = state.tabs.current_mut().filter(|_| !exact) {
+        ed.cursor_col = ed.buffer.line(ed.cursor_row).chars().take_while(|ch| *ch == ' ' || *ch == '\t').count();
+    }
+}
+
+/// F12 / `gd`: go to the definition of the word under the cursor, looked up in the
+/// current buffer, the other tabs, then the workspace index (built first if needed).
+fn goto_definition(state: &mut TuiState) {
+    let Some(word) = state.tabs.current().and_then(EditorView::word_at_cursor) else {
+        ToastView::error(&mut state.toast, "Aucun identifiant sous le curseur");
+        return;
+    };
+    let current = state.tabs.current;
+    let order = std::iter::once(current).chain((0..state.tabs.tabs.len()).filter(|&i| i != current));
+    for idx in order {
+        let Some(tab) = state.tabs.tabs.get(idx) else { continue; };
+        let symbols = SymbolsView::buffer_symbols(&tab.state);
+        if let Some(sym) = SymbolsView::definition(&symbols, &word) {
+            let sym = sym.clone();
+            // Buffer sans fichier : le saut part de l'onglet courant
+            state.jumps.record(state.tabs.current().and_then(Jump::of));
+            state.tabs.focus(idx);
+            let path = Some(sym.path.as_path()).filter(|p| !p.as_os_str().is_empty());
+            show_position(state, path, sym.row, sym.col);
+            return;
+        }
+    }
+    let root = state.explorer.root.clone();
+    if SymbolsView::indexed(&state.symbols, &root) {
+        match SymbolsView::definition(&state.symbols.index, &word).cloned() {
+            Some(sym) => goto_symbol(state, &sym),
+            None => ToastView::error(&mut state.toast, format!("Définition introuvable : {}", word)),
+        }
+    } else {
+        // Résolue à la fin de l'indexation (voir la boucle principale)
+        SymbolsView::index(&mut state.symbols, &root, false);
+        ToastView::info(&mut state.toast, format!("Indexation des symboles pour « {} »…", word));
+        state.symbols.pending_definition = Some(word);
+    }
+}
+
+/// Ctrl+F in the editor: live search prompt, prefilled with the last search.
+fn open_search_prompt(state: &mut TuiState) {
+    let Some(ed) = state.tabs.current_mut() else { return; };
+    ed.search_origin = (ed.cursor_row, ed.cursor_col);
+    let buffer = ed.last_search.clone().unwrap_or_default();
+    EditorView::recompute_search_positions(ed);
+    state.overlay = Overlay::Input;
+    state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::SearchText, buffer });
+}
+
+/// Prompt label of the search: toggles and live match count.
+fn search_label(ed: Option<&state::EditorState>) -> String {
+    let Some(ed) = ed else { return String::from("Rechercher (regex) :"); };
+    let count = match EditorView::search_summary(ed) {
+        Some(summary) => summary,
+        None if ed.last_search.is_some() => String::from("aucune occurrence"),
+        None => String::new(),
+    };
+    format!(
+        "Rechercher (regex)  [Alt+C] {}  [Alt+W] {}  {}",
+        if ed.search_ignore_case { "casse ignorée" } else { "casse exacte" },
+        if ed.search_whole_word { "mot entier" } else { "partout" },
+        count
+    )
+}
+
+/// n / N, F3 / Shift+F3: next or previous match of the editor search.
+fn search_step(state: &mut TuiState, backwards: bool) {
+    let Some(ed) = state.tabs.current_mut() else { return; };
+    state.jumps.record(Jump::of(ed));
+    if backwards { EditorView::search_prev(ed) } else { EditorView::search_next(ed) }
+    if ed.search_positions.is_empty() {
+        ToastView::info(&mut state.toast, EditorView::search_summary(ed).unwrap_or_else(|| String::from("Aucune recherche (Ctrl+F)")));
+    }
+}
+
+/// Ctrl+R in the editor: substitute prompt, prefilled with the last search.
+fn open_replace_prompt(state: &mut TuiState) {
+    let Some(ed) = state.tabs.current() else { return; };
+    let buffer = match ed.last_search.as_deref().filter(|q| !q.is_empty()) {
+        Some(q) => format!("%s/{}/", q.replace('/', "\\/")),
+        None => String::from("%s/"),
+    };
+    state.overlay = Overlay::Input;
+    state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::ReplaceText, buffer });
+}
+
+/// Run a substitute command on the current editor: at once, or match by match
+/// through the Replace overlay with the `c` flag.
+fn start_replace(state: &mut TuiState, cmd: &str) {
+    let Some(ed) = state.tabs.current_mut() else { return; };
+    if ed.read_only {
+        ToastView::error(&mut state.toast, "Buffer en lecture seule");
+        return;
+    }
+    match ReplaceView::parse(cmd, ed) {
+        Err(e) => ToastView::error(&mut state.toast, e),
+        Ok((mut rs, true)) => {
+            if ReplaceView::find_next(ed, &mut rs) {
+                state.replace = Some(rs);
+                state.overlay = Overlay::Replace;
+            } else {
+                ToastView::info(&mut state.toast, "Aucune occurrence");
+            }
+        }
+        Ok((mut rs, false)) => {
+            ReplaceView::replace_all(ed, &mut rs);
+            ToastView::info(&mut state.toast, ReplaceView::summary(&rs));
+        }
+    }
+}
+
+/// Close the Replace overlay and report how many matches were replaced.
+fn finish_replace(state: &mut TuiState) {
+    state.overlay = Overlay::None;
+    if let Some(rs) = state.replace.take() {
+        ToastView::info(&mut state.toast, ReplaceView::summary(&rs));
+    }
+}
+
+/// Register for p / P: the one chosen with `"x` (`"0`–`"9` being the history), else
+/// the system clipboard when it holds something else than the last yank (text copied
+/// in another application), else the unnamed register.
+fn paste_register(registers: &mut state::Registers) -> Option<state::EditorRegister> {
+    match registers.selected.take() {
+        Some(c) if c.is_ascii_digit() => registers.history.get(c as usize - '0' as usize).cloned(),
+        Some(c) => registers.named.get(&c.to_ascii_lowercase()).cloned(),
+        None => match clipboard::paste() {
+            Some(text) if !text.is_empty() && text != registers.unnamed.text => Some(state::EditorRegister { text, linewise: false, block: false }),
+            _ => Some(registers.unnamed.clone()),
+        },
+    }
+}
+
+/// p / P: paste a register after / before the cursor; an empty register is reported.
+fn paste_from(ed: &mut state::EditorState, registers: &mut state::Registers, toast: &mut Option<state::Toast>, before: bool) {
+    let chosen = registers.selected;
+    match paste_register(registers) {
+        Some(register) => EditorView::paste(ed, &register, before),
+        None => ToastView::error(toast, format!("Registre \"{} vide", chosen.unwrap_or_default())),
+    }
+}
+
+/// Text pasted in the terminal (bracketed paste): into the shell line, or the editor.
+fn paste_text(state: &mut TuiState, term: &mut TerminalPane, text: &str) {
+    if state.overlay != Overlay::None || state.scratchpad.open {
+        return;
+    }
+    let in_editor = state.screen == Screen::Editor || (state.screen == Screen::Workspace && state.focus == Focus::Editor);
+    if state.screen == Screen::Shell && term.pty_active() {
+        term.pty_paste(text);
+    } else if state.screen == Screen::Shell {
+        term.insert_str(text);
+    } else if let Some(ed) = state.tabs.current_mut().filter(|_| in_editor) {
+        match ed.mode {
+            EditorMode::Command => ed.cmdline.push_str(text.lines().next().unwrap_or_default()),
+            EditorMode::Visual | EditorMode::VisualLine | EditorMode::VisualBlock => {
+                ed.mode = EditorMode::Normal;
+                EditorView::insert_text(ed, text);
+            }
+            _ => EditorView::insert_text(ed, text),
+        }
+    }
+}
+
+/// Keys typed while the explorer filter field is open: the list narrows on every change.
+fn filter_key(explorer: &mut state::FileExplorerState, code: KeyCode) {
+    match code {
+        KeyCode::Esc => FileExplorerView::clear_filter(explorer),
+        KeyCode::Enter => explorer.filter_editing = false,
+        KeyCode::Up => FileExplorerView::move_up(explorer),
+        KeyCode::Down => FileExplorerView::move_down(explorer),
+        KeyCode::Backspace => {
+            let mut query = explorer.filter.clone();
+            query.pop();
+            FileExplorerView::set_filter(explorer, query);
+        }
+        KeyCode::Char(c) => {
+            let query = format!("{}{}", explorer.filter, c);
+            FileExplorerView::set_filter(explorer, query);
+        }
+        _ => {}
+    }
+}
+
+/// Keys while the breadcrumb bar is focused (`g` in the explorer).
+fn crumb_key(explorer: &mut state::FileExplorerState, code: KeyCode) {
+    match code {
+        KeyCode::Left | KeyCode::Char('h') => FileExplorerView::move_crumb(explorer, -1),
+        KeyCode::Right | KeyCode::Char('l') => FileExplorerView::move_crumb(explorer, 1),
+        KeyCode::Home => FileExplorerView::move_crumb(explorer, isize::MIN / 2),
+        KeyCode::Enter => FileExplorerView::jump_to_crumb(explorer),
+        KeyCode::Esc | KeyCode::Char('g') | KeyCode::Char('q') => explorer.crumb = None,
+        _ => {}
+    }
+}
+
+/// `D` in the explorer: mark the selected file, or diff it against the marked one.
+fn mark_for_diff(state: &mut TuiState, from: Screen) {
+    let Some(path) = FileExplorerView::selected_file(&state.explorer) else { return; };
+    match state.explorer.diff_mark.take() {
+        Some(left) if left != path => {
+            DiffView::open_files(&mut state.diff, &left, &path, from);
+            state.screen = Screen::Diff;
+        }
+        // Même fichier : on retire la marque
+        Some(_) => {}
+        None => state.explorer.diff_mark = Some(path),
+    }
+}
+
+/// Delete key in the explorer: move the marked (or selected) entries to the trash.
+fn trash_targets(state: &mut TuiState) {
+    let targets = FileExplorerView::targets(&state.explorer);
+    if targets.is_empty() {
+        return;
+    }
+    match FileOpsView::trash(&targets) {
+        Ok(()) => {
+            ToastView::info(&mut state.toast, format!("{} élément(s) mis à la corbeille — [u] Annuler", targets.len()));
+            state.explorer.trashed = Some(state::TrashedBatch { paths: targets });
+            state.explorer.marked.clear();
+        }
+        Err(e) => ToastView::error(&mut state.toast, format!("Corbeille : {}", e)),
+    }
+    FileExplorerView::refresh(&mut state.explorer);
+}
+
+/// Report failed filesystem operations ("path: OS error"): all of them go to the
+/// log panel, the first one (with a count of the others) to a toast.
+fn report_errors(state: &mut TuiState, logs: &mut LogPanel, action: &str, errors: &[String]) {
+    let Some(first) = errors.first() else { return; };
+    for e in errors {
+        logs.error(format!("❌ {} : {}", action, e));
+    }
+    let more = if errors.len() > 1 { format!(" (+{} autre(s), :l pour les logs)", errors.len() - 1) } else { String::new() };
+    ToastView::error(&mut state.toast, format!("{} : {}{}", action, first, more));
+}
+
+/// `R` in the explorer: rename the selected entry, or open the batch rename overlay
+/// when several entries are marked.
+fn begin_rename(state: &mut TuiState) {
+    let targets = FileExplorerView::targets(&state.explorer);
+    if targets.len() > 1 {
+        state.rename = state::RenameState { targets, pattern: String::from("{name}_{n}.{ext}") };
+        state.overlay = Overlay::Rename;
+    } else {
+        state.overlay = Overlay::Input;
+        state.overlay_input = Some(state::InputOverlay { kind: state::InputKind::RenameEntry, buffer: String::new() });
+    }
+}
+
+/// `C` in the explorer: move the Shell screen (process cwd) to the explorer's directory.
+fn shell_cd_here(state: &mut TuiState, term: &mut TerminalPane) {
+    let dir = state.explorer.cwd.clone();
+    match std::env::set_current_dir(&dir) {
+        Ok(()) => {
+            term.push_output(format!("(cd) -> {}", dir.display()));
+            ToastView::info(&mut state.toast, format!("Shell : cd {}", dir.display()));
+        }
+        Err(e) => ToastView::error(&mut state.toast, format!("cd {}: {}", dir.display(), e)),
+    }
+}
+
+/// `P` in the explorer: edit the permissions of the selected entry.
+fn open_permissions(state: &mut TuiState) {
+    let explorer = &state.explorer;
+    let Some(entry) = explorer.entries.get(explorer.selected).filter(|e| e.name != "..") else { return; };
+    let path = explorer.cwd.join(&entry.name);
+    match PermissionsView::open(&mut state.permissions, &path) {
+        Ok(()) => state.overlay = Overlay::Permissions,
+        Err(e) => ToastView::error(&mut state.toast, e),
+    }
+}
+
+/// `u` in the explorer: restore the last batch sent to the trash.
+fn undo_trash(state: &mut TuiState) {
+    let Some(batch) = state.explorer.trashed.take() else { return; };
+    match FileOpsView::restore(&batch.paths) {
+        Ok(n) => ToastView::info(&mut state.toast, format!("{} élément(s) restauré(s)", n)),
+        Err(e) => ToastView::error(&mut state.toast, format!("Annulation impossible : {}", e)),
+    }
+    FileExplorerView::refresh(&mut state.explorer);
+}
+
+/// Reload the clean tabs whose file another program changed; the first modified tab
+/// in that case gets the conflict prompt (only over the editing screens).
+fn watch_open_files(state: &mut TuiState) {
+    let mut reloaded = Vec::new();
+    for (i, tab) in state.tabs.tabs.iter_mut().enumerate() {
+        if EditorView::disk_changed(&tab.state).is_none() {
+            continue;
+        }
+        if !tab.state.dirty {
+            if EditorView::reload(&mut tab.state).is_ok() {
+                reloaded.push(tab.state.path.as_ref().and_then(|p| p.file_name()).map(|n| n.to_string_lossy().to_string()).unwrap_or_default());
+            }
+        } else if state.conflict.is_none()
+            && state.overlay == Overlay::None
+            && matches!(state.screen, Screen::Workspace | Screen::Editor)
+        {
+            state.conflict = Some(i);
+            state.overlay = Overlay::Conflict;
+        }
+    }
+    if !reloaded.is_empty() {
+        ToastView::info(&mut state.toast, format!("Rechargé (modifié sur le disque) : {}", reloaded.join(", ")));
+    }
+}
+
+/// Answer of the external change conflict.
+enum ConflictChoice {
+    Reload,
+    Keep,
+    Diff,
+}
+
+fn resolve_conflict(state: &mut TuiState, choice: ConflictChoice) {
+    state.overlay = Overlay::None;
+    let Some(idx) = state.conflict.take() else { return; };
+    let Some(tab) = state.tabs.tabs.get_mut(idx) else { return; };
+    let ed = &mut tab.state;
+    match choice {
+        ConflictChoice::Reload => {
+            if let Err(e) = EditorView::reload(ed) {
+                ToastView::error(&mut state.toast, format!("Rechargement impossible : {}", e));
+            }
+        }
+        // La prochaine sauvegarde écrase la version du disque
+        ConflictChoice::Keep => ed.disk_mtime = EditorView::disk_changed(ed).or(ed.disk_mtime),
+        // La question revient au retour de la vue diff
+        ConflictChoice::Diff => match EditorView::disk_text(ed) {
+            Ok(disk) => {
+                let name = ed.path.as_ref().and_then(|p| p.file_name()).map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                let buffer = ed.buffer.to_string();
+                DiffView::open_texts(&mut state.diff, format!("{} (disque)", name), &disk, format!("{} (buffer)", name), &buffer, state.screen);
+                state.screen = Screen::Diff;
+            }
+            Err(e) => ToastView::error(&mut state.toast, format!("Lecture impossible : {}", e)),
+        },
+    }
+}
+
+/// Diff the current editor buffer against HEAD (errors are shown in the diff title).
+fn open_head_diff(state: &mut TuiState, from: Screen) {
+    let Some(ed) = state.tabs.current() else { return; };
+    DiffView::open_buffer_vs_head(&mut state.diff, ed, from);
+    state.screen = Screen::Diff;
+}
+
+/// Explorer keys inside an archive or on a remote host: `E` extracts (downloads),
+/// file operations are refused. Returns true when the key was handled here.
+fn vfs_key(state: &mut TuiState, code: KeyCode) -> bool {
+    let remote = state.explorer.vfs.as_ref().is_some_and(|m| m.fs.writable());
+    match code {
+        KeyCode::Char('E') => {
+            let verb = if remote { "🌐 Téléchargement" } else { "📦 Extraction" };
+            match FileOpsView::extract_here(&mut state.explorer) {
+                Ok(n) => ToastView::info(&mut state.toast, format!("{} de {} élément(s)…", verb, n)),
+                Err(e) => ToastView::error(&mut state.toast, e),
+            }
+            true
+        }
+        KeyCode::Char('N' | 'R' | 'c' | 'C' | 'u' | 'P' | 'b' | 'o' | 'D' | 'y' | 'x' | 'p' | 'Z') | KeyCode::Delete => {
+            let msg = if remote { "Indisponible sur un hôte distant — [E] Télécharger ici" } else { "Archive en lecture seule — [E] Extraire ici" };
+            ToastView::error(&mut state.toast, msg);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// `:e sftp://…`: connect in the background; `FileExplorerView::poll_connect` then
+/// mounts the host in the explorer.
+fn open_remote(state: &mut TuiState, url: &str) {
+    match FileExplorerView::connect(&mut state.explorer, url) {
+        Ok(()) => ToastView::info(&mut state.toast, format!("🌐 Connexion à {}…", url)),
+        Err(e) => ToastView::error(&mut state.toast, e),
+    }
+}
+
+/// Open the root switcher on the current root.
+fn open_roots(state: &mut TuiState) {
+    state.explorer.roots_selected = state.explorer.roots.iter().position(|r| *r == state.explorer.root).unwrap_or(0);
+    state.overlay = Overlay::Roots;
+}
+
+/// Editor area of the Workspace and Editor screens: the current tab, beside the other
+/// pane when the area is split. `border` frames the focused pane.
+fn render_editor_area(f: &mut Frame, area: Rect, tabs: &mut EditorTabs, border: Style, md: MarkdownPreview) {
+    // Hauteur de texte d'un volet : sans bordures, barre d'état ni ligne de commande
+    if let Some(ed) = tabs.current_mut() {
+        let command = u16::from(ed.mode == EditorMode::Command);
+        EditorView::set_viewport(ed, area.height.saturating_sub(3 + command) as usize);
+    }
+    let Some(right_focused) = tabs.split.as_ref().map(|s| s.right_focused) else {
+        if let Some(ed) = tabs.current() {
+            MarkdownView::render_editor(f, area, ed, border, md);
+        }
+        return;
+    };
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    let (focused, other) = if right_focused { (cols[1], cols[0]) } else { (cols[0], cols[1]) };
+    // Le volet actif en dernier : c'est lui qui place le curseur du terminal
+    tabs.with_other_pane(|ed| MarkdownView::render_editor(f, other, ed, Style::default(), md));
+    if let Some(ed) = tabs.current() {
+        MarkdownView::render_editor(f, focused, ed, border, md);
+    }
+}
+
 /// Compute a centered rectangle that takes `percent_x` by `percent_y` of the given area.
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let v = Layout::default()
@@ -862,44 +3306,176 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     h[1]
 }
 
-/// Minimal shell-like command execution used by the Shell screen.
+/// Output of the engine in the Shell screen: every line goes to the Terminal pane,
+/// errors are also recorded in the LogPanel. The programs of compound lines run as
+/// jobs or in a PTY, like single ones (see `wait_external`).
+struct PaneOutput<'a> {
+    terminal: &'a mut Terminal<CrosstermBackend<io::Stdout>>,
+    term: &'a mut TerminalPane,
+    logs: &'a mut LogPanel,
+    jobs: &'a mut JobManager,
+}
+
+impl Output for PaneOutput<'_> {
+    fn line(&mut self, line: &str) {
+        self.term.push_output(line);
+    }
+
+    fn error(&mut self, line: &str) {
+        self.term.push_output(line);
+        self.logs.error(line);
+    }
+
+    fn clear(&mut self) {
+        self.term.clear_output();
+    }
+
+    fn run_external(&mut self, external: &External, registry: &CommandRegistry) -> i32 {
+        match run_external(external, registry, self.term, self.logs, self.jobs) {
+            Some(status) => status,
+            None => wait_external(self.terminal, self.term, self.jobs),
+        }
+    }
+}
+
+/// Shell-like command execution used by the Shell screen, through the engine shared
+/// with the REPL.
 ///
 /// Behavior:
-/// - Implements a built-in `cd <path>` that changes process CWD
-/// - Otherwise runs the command via PATH, capturing stdout/stderr
-/// - Prints outputs to the Terminal pane; logs failed execution
-fn run_shell_like(line: &str, term: &mut TerminalPane, logs: &mut LogPanel) {
-    let mut parts = line.split_whitespace();
-    if let Some(cmd) = parts.next() {
-        let args: Vec<&str> = parts.collect();
-
-        if cmd == "cd" {
-            use std::env;
-            if let Some(path) = args.get(0) {
-                match env::set_current_dir(path) {
-                    Ok(()) => term.push_output(format!("(cd) -> {}", path)),
-                    Err(e) => term.push_output(format!("cd: {}: {}", path, e)),
-                }
-            } else {
-                term.push_output("usage: cd <path>");
+/// - Built-ins (`cd`, `history`, `theme`, `help`…) run in the engine and write into
+///   the Terminal pane; returns the new directory when the line moved the shell
+/// - `jobs`, `fg` and `bg` act on the TUI's own jobs (see `JobManager::builtin`)
+/// - Otherwise spawns the command via PATH (see `run_external`)
+/// - Logs directory changes
+fn run_shell_like(line: &str, shell: &mut ShellEngine, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, term: &mut TerminalPane, logs: &mut LogPanel, jobs: &mut JobManager, status: &mut StatusBar) -> Option<PathBuf> {
+    logs.debug(format!("$ {}", line));
+    // `jobs`, `fg`, `bg` : les jobs du TUI (le `fg` du REPL prendrait le terminal)
+    if let Ok([cmd, args @ ..]) = parser::tokenize(line).and_then(parser::words).as_deref() {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        if let Some(out) = jobs.builtin(cmd, &args) {
+            for out in out {
+                term.push_output(out);
             }
-            return;
+            return None;
         }
-
-        use std::process::Command;
-        match Command::new(cmd).args(&args).output() {
-            Ok(out) => {
-                if !out.stdout.is_empty() {
-                    term.push_output(String::from_utf8_lossy(&out.stdout).to_string());
-                }
-                if !out.stderr.is_empty() {
-                    term.push_output(String::from_utf8_lossy(&out.stderr).to_string());
-                }
+    }
+    match shell.dispatch(line, &mut PaneOutput { terminal, term, logs, jobs }) {
+        // `exit` : la boucle du TUI voit la demande (`exit_requested`) et quitte
+        Dispatch::Done(_) | Dispatch::Exit(_) => {
+            if shell.take_tui() {
+                term.push_output("tui: déjà dans le TUI (:repl pour revenir au REPL)");
             }
+            // `history -c` a pu vider l'historique, `theme` changer le thème
+            term.reload_history();
+            status.set_theme(shell.prompt().lock().unwrap().theme().clone());
+        }
+        // Commande simple : le job tourne pendant que le TUI continue
+        Dispatch::External(external) => {
+            run_external(&external, &shell.registry(), term, logs, jobs);
+        }
+    }
+    let moved = shell.sync_cwd();
+    if let Some(dir) = &moved {
+        logs.add(format!("cd -> {}", dir.display()));
+    }
+    moved
+}
+
+/// Start an external program of the Shell screen: a PTY for interactive programs,
+/// otherwise a job whose stdout/stderr stream into the Terminal pane (a trailing `&`
+/// keeps it in the background) unless redirected to a file. Logs failed execution,
+/// suggesting a close command name when the program does not exist.
+///
+/// Returns None while the program runs in the foreground, else its status: 0 for a
+/// background job, 1 when a redirection file cannot be opened, 127 when the program
+/// does not exist.
+fn run_external(external: &External, registry: &CommandRegistry, term: &mut TerminalPane, logs: &mut LogPanel, jobs: &mut JobManager) -> Option<i32> {
+    let (cmd, args) = (external.words[0].as_str(), &external.words[1..]);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let line = external.line.as_str();
+    let background = external.background;
+
+    // Un programme interactif redirigé (`vim < f`) n'a pas besoin du pseudo-terminal
+    #[cfg(unix)]
+    if !background && external.redirections.is_empty() && components::pty::INTERACTIVE.contains(&cmd) {
+        return match term.start_pty(line, cmd, &args) {
+            Ok(()) => None,
             Err(e) => {
-                term.push_output(format!("command not found: {} ({})", cmd, e));
-                logs.add(format!("exec error: {} {:?}", cmd, e));
+                term.push_output(format!("pty: {}: {}", cmd, e));
+                Some(127)
+            }
+        };
+    }
+
+    match jobs.spawn(line, cmd, &args, &external.redirections, background) {
+        Ok(id) => {
+            if !background {
+                return None;
+            }
+            term.push_output(format!("[{}] {}", id, line));
+            logs.add(format!("job [{}] started: {}", id, line));
+            Some(0)
+        }
+        // Fichier de redirection impossible à ouvrir
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => {
+            term.push_output(format!("❌ {}", e));
+            logs.error(format!("exec error: {} {}", cmd, e));
+            Some(1)
+        }
+        Err(e) => {
+            term.push_output(format!("command not found: {} ({})", cmd, e));
+            let close = registry.suggest_command(cmd);
+            if !close.is_empty() {
+                term.push_output(format!("   Did you mean: {} ?", close.join(", ")));
+            }
+            logs.error(format!("exec error: {} {:?}", cmd, e));
+            Some(127)
+        }
+    }
+}
+
+/// Wait for the foreground program of a compound line (`make; ./app`, `for`,
+/// functions): the Terminal pane takes the screen and streams its output, keys go to
+/// the PTY and Ctrl+C interrupts the job. Returns its exit status (130 once killed).
+fn wait_external(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, term: &mut TerminalPane, jobs: &mut JobManager) -> i32 {
+    loop {
+        jobs.poll();
+        let (lines, finished) = jobs.drain_foreground();
+        for l in lines {
+            term.push_output(l);
+        }
+        if let Some(st) = finished {
+            if st != JobStatus::Exited(0) {
+                term.push_output(format!("[{}]", st.label()));
+            }
+            return match st {
+                JobStatus::Exited(code) => code,
+                _ => 130,
+            };
+        }
+        if let Some(code) = term.poll_pty() {
+            if code != 0 {
+                term.push_output(format!("[exit {}]", code));
+            }
+            return code;
+        }
+        // Plus rien au premier plan (job oublié) : la ligne continue
+        if jobs.foreground().is_none() && !term.pty_active() {
+            return 0;
+        }
+        let _ = terminal.draw(|f| term.render(f, f.area()));
+        if !crossterm::event::poll(Duration::from_millis(20)).unwrap_or(false) {
+            continue;
+        }
+        match event::read() {
+            Ok(Event::Key(key)) if term.pty_active() => term.pty_key(key),
+            Ok(Event::Paste(text)) if term.pty_active() => term.pty_paste(&text),
+            Ok(Event::Key(key)) if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') => {
+                if let Some(id) = jobs.foreground() && let Err(e) = jobs.interrupt(id) {
+                    term.push_output(format!("[{}] interrupt: {}", id, e));
+                }
             }
+            _ => {}
         }
     }
 }
@@ -0,0 +1,95 @@
+//! Snippets (`[[snippets]]` in config/editor.toml).
+//!
+//! Typing a trigger then Tab in Insert mode (or in the workspace editor) replaces it
+//! with the body of its snippet, each line indented like the cursor line. `$1`,
+//! `${1:placeholder}`… mark tab stops: Tab and Shift+Tab move between them, the
+//! first typed char replaces the placeholder, and `$0` (or the end of the body) is
+//! where the cursor ends up. `\$` inserts a dollar sign.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use crate::shell::config::SnippetConfig;
+
+/// Text of an expanded snippet and its tab stops (char ranges in the text), in the
+/// order Tab visits them.
+pub struct Expansion {
+    pub text: String,
+    pub stops: Vec<Range<usize>>,
+}
+
+/// Snippet whose trigger ends `before` (the line up to the cursor) as a whole word;
+/// the longest trigger wins.
+pub fn find<'a>(snippets: &'a [SnippetConfig], before: &str) -> Option<&'a SnippetConfig> {
+    snippets
+        .iter()
+        .filter(|s| !s.trigger.is_empty() && before.ends_with(&s.trigger))
+        .filter(|s| {
+            let head = &before[..before.len() - s.trigger.len()];
+            head.chars().next_back().is_none_or(|c| !(c.is_alphanumeric() || c == '_'))
+        })
+        .max_by_key(|s| s.trigger.len())
+}
+
+/// Expand a snippet body: lines after the first get `indent`, tab characters
+/// become one indentation `unit`.
+pub fn expand(body: &str, indent: &str, unit: &str) -> Expansion {
+    let chars: Vec<char> = body.chars().collect();
+    let mut text = String::new();
+    let mut stops: BTreeMap<usize, Range<usize>> = BTreeMap::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match c {
+            '\\' if matches!(chars.get(i), Some('$' | '}' | '\\')) => {
+                text.push(chars[i]);
+                i += 1;
+            }
+            '\n' => {
+                text.push('\n');
+                text.push_str(indent);
+            }
+            '\t' => text.push_str(unit),
+            '$' => {
+                let braced = chars.get(i) == Some(&'{');
+                let digits_at = if braced { i + 1 } else { i };
+                let digits: String = chars[digits_at.min(chars.len())..].iter().take_while(|c| c.is_ascii_digit()).collect();
+                let Ok(n) = digits.parse::<usize>() else {
+                    text.push('$');
+                    continue;
+                };
+                let mut j = digits_at + digits.len();
+                let mut placeholder = String::new();
+                if braced {
+                    if chars.get(j) == Some(&':') {
+                        j += 1;
+                        while j < chars.len() && chars[j] != '}' {
+                            if chars[j] == '\\' && chars.get(j + 1) == Some(&'}') {
+                                j += 1;
+                            }
+                            placeholder.push(chars[j]);
+                            j += 1;
+                        }
+                    }
+                    if chars.get(j) != Some(&'}') {
+                        // Pas de `}` fermant : texte littéral
+                        text.push('$');
+                        continue;
+                    }
+                    j += 1;
+                }
+                let start = text.chars().count();
+                text.push_str(&placeholder);
+                stops.entry(n).or_insert(start..start + placeholder.chars().count());
+                i = j;
+            }
+            c => text.push(c),
+        }
+    }
+    let len = text.chars().count();
+    let last = stops.remove(&0).unwrap_or(len..len);
+    let mut ordered: Vec<Range<usize>> = stops.into_values().collect();
+    ordered.push(last);
+    Expansion { text, stops: ordered }
+}
@@ -5,47 +5,188 @@
 //! The goal is to keep UI rendering functions stateless and pure, while
 //! this module represents the mutable state manipulated by input handlers.
 
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
+use ratatui::text::Line;
+use regex::Regex;
 use ropey::Rope;
+use serde::{Deserialize, Serialize};
+
+use crate::shell::config::{CommentSyntax, EditorConfig, IconConfig, Indent, OpenWithProgram, SaveCleanup, SnippetConfig};
+use crate::shell::git::{BlameLine, GitFileStatus};
+use crate::shell::http::{HttpRequest, HttpResponse};
+use crate::shell::tui::encoding::{LineEnding, TextEncoding};
+use crate::shell::tui::{gutter, marks, undo};
+use crate::shell::vfs::VirtualFs;
 
 /// Current main screen displayed by the TUI.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
 pub enum Screen {
+    #[default]
     Home,
     Shell,
     Explorer,
     Editor,
     Workspace, // si tu l'utilises pour le split Explorer | Editor
+    Dashboard,
+    Git,
+    Containers,
+    Http,
+    Data,
+    Table,
+    Hex,
+    Diff,
+    Tasks,
+    Search,
 }
 
-impl Default for Screen {
-    fn default() -> Self {
-        Screen::Home // ou Screen::Workspace si tu veux démarrer en IDE
+impl Screen {
+    /// Parse a config value (`home`, `shell`, `workspace`, `dashboard`, `git`,
+    /// `containers`, `http`).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "home" => Some(Screen::Home),
+            "shell" => Some(Screen::Shell),
+            "workspace" | "explorer" => Some(Screen::Workspace),
+            "dashboard" => Some(Screen::Dashboard),
+            "git" => Some(Screen::Git),
+            "containers" => Some(Screen::Containers),
+            "http" => Some(Screen::Http),
+            _ => None,
+        }
     }
 }
 
 /// Overlays displayed above the current screen.
 /// Help is ephemeral (closes on next key). Input carries a small stateful prompt.
-#[derive(Clone, Copy, PartialEq, Eq)]
+/// Jobs lists commands started from the TUI shell.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
 pub enum Overlay {
+    #[default]
     None,
     Help,
     Input,
-}
-
-impl Default for Overlay {
-    fn default() -> Self { Overlay::None }
+    Jobs,
+    Finder,
+    Paste,
+    OpenWith,
+    Permissions,
+    Roots,
+    Compress,
+    Rename,
+    Replace,
+    Tabs,
+    Loading,
+    Unsaved,
+    Recover,
+    Conflict,
+    Symbols,
 }
 
 /// Which pane currently has keyboard focus (used in Workspace split view)
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
 pub enum Focus {
     Explorer,
+    #[default]
     Editor,
 }
 
-impl Default for Focus {
-    fn default() -> Self { Focus::Editor }
+/// Markdown preview layout for `.md` buffers
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarkdownPreview {
+    #[default]
+    Off,
+    /// Editor and preview side by side
+    Split,
+    /// Preview only
+    Full,
+}
+
+impl MarkdownPreview {
+    /// Next mode in the Off → Split → Full cycle.
+    pub fn next(self) -> Self {
+        match self {
+            MarkdownPreview::Off => MarkdownPreview::Split,
+            MarkdownPreview::Split => MarkdownPreview::Full,
+            MarkdownPreview::Full => MarkdownPreview::Off,
+        }
+    }
+}
+
+/// Explorer sort key (directories always come first)
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExplorerSort {
+    #[default]
+    Name,
+    Size,
+    Modified,
+    Extension,
+}
+
+impl ExplorerSort {
+    /// Cycle to the next sort key.
+    pub fn next(self) -> Self {
+        match self {
+            ExplorerSort::Name => ExplorerSort::Size,
+            ExplorerSort::Size => ExplorerSort::Modified,
+            ExplorerSort::Modified => ExplorerSort::Extension,
+            ExplorerSort::Extension => ExplorerSort::Name,
+        }
+    }
+
+    /// Parse a config value (`name`, `size`, `modified`/`mtime`, `extension`/`ext`).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "name" => Some(ExplorerSort::Name),
+            "size" => Some(ExplorerSort::Size),
+            "modified" | "mtime" => Some(ExplorerSort::Modified),
+            "extension" | "ext" => Some(ExplorerSort::Extension),
+            _ => None,
+        }
+    }
+
+    /// Label shown in the explorer title.
+    pub fn label(self) -> &'static str {
+        match self {
+            ExplorerSort::Name => "nom",
+            ExplorerSort::Size => "taille",
+            ExplorerSort::Modified => "date",
+            ExplorerSort::Extension => "extension",
+        }
+    }
+}
+
+/// Built-in explorer icon set
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum IconStyle {
+    #[default]
+    Emoji,
+    Nerd,
+    Ascii,
+}
+
+impl IconStyle {
+    /// Parse a config value (`emoji`, `nerd`/`nerdfont`, `ascii`).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "emoji" => Some(IconStyle::Emoji),
+            "nerd" | "nerdfont" | "nerd-font" => Some(IconStyle::Nerd),
+            "ascii" => Some(IconStyle::Ascii),
+            _ => None,
+        }
+    }
+}
+
+/// Icons used by the explorer: a built-in style plus the overrides from `config/theme.toml`
+#[derive(Default)]
+pub struct IconTheme {
+    pub style: IconStyle,
+    pub custom: IconConfig,
 }
 
 /// File explorer state (root, cwd, entries, selection, hidden toggle)
@@ -53,15 +194,585 @@ impl Default for Focus {
 pub struct FileExplorerState {
     pub cwd: PathBuf,
     pub root: PathBuf,
+    /// Configured roots (`--root` or config), switched with `r`
+    pub roots: Vec<PathBuf>,
+    /// Selection in the root switcher
+    pub roots_selected: usize,
+    /// Visible entries (`all_entries` narrowed by `filter`)
     pub entries: Vec<DirEntryView>,
+    /// Every loaded entry of `cwd`, sorted
+    pub all_entries: Vec<DirEntryView>,
+    /// Filter-as-you-type query (`/`), matched as a case-insensitive subsequence
+    pub filter: String,
+    pub filter_editing: bool,
+    /// Breadcrumb segment being chosen (`g`), None when the bar is not focused
+    pub crumb: Option<usize>,
     pub selected: usize,
     pub show_hidden: bool,
+    /// Hide entries matched by .gitignore / git excludes
+    pub hide_ignored: bool,
+    /// Follow `cd` typed in the Shell screen (`follow_cwd` in the config)
+    pub follow_cwd: bool,
+    /// Git status badge per entry name (files and directories containing changes)
+    pub git_badges: HashMap<String, char>,
+    /// File marked as the left side of a diff (`D` on a second file opens the diff)
+    pub diff_mark: Option<PathBuf>,
+    /// Background listing of `cwd` in progress (entries arrive in batches)
+    pub loading: Option<Receiver<DirLoad>>,
+    /// Spinner frame shown while loading
+    pub spinner: usize,
+    pub sort: ExplorerSort,
+    pub sort_desc: bool,
+    /// Paths yanked (`y`) or cut (`x`), pasted with `p`
+    pub clipboard: Option<FileClipboard>,
+    /// Entries marked with Space (absolute paths, kept across directories)
+    pub marked: HashSet<PathBuf>,
+    /// Pinned directories (see `shell::bookmarks`)
+    pub pinned: Vec<PathBuf>,
+    /// Last entries moved to the trash, restorable with `u`
+    pub trashed: Option<TrashedBatch>,
+    /// Watcher on `cwd` for external changes (None if the platform watcher failed)
+    pub watch: Option<DirWatch>,
+    /// Last external change not yet reloaded (reloads are debounced)
+    pub changed_at: Option<Instant>,
+    /// Entry to select again once the reload brings it back
+    pub reselect: Option<String>,
+    /// Directories visited before the current one (`H`), most recent last
+    pub back: Vec<PathBuf>,
+    /// Directories left with `H`, to return to with `L`
+    pub forward: Vec<PathBuf>,
+    /// Archive or remote host being browsed; `cwd` is then a path inside it
+    pub vfs: Option<VfsMount>,
+    /// `sftp://` connection in progress (`:e sftp://…`)
+    pub connecting: Option<Receiver<Result<RemoteTarget, String>>>,
+    /// Background extraction (`E` in an archive): summary or error
+    pub extracting: Option<Receiver<Result<String, String>>>,
+    /// Disk usage mode (`U`); None when off
+    pub du: Option<DiskUsage>,
+    pub icons: IconTheme,
+}
+
+/// Recursive sizes of the entries of one directory, filled by a background worker
+pub struct DiskUsage {
+    /// Size on disk per entry name
+    pub sizes: HashMap<String, u64>,
+    pub rx: Option<Receiver<(String, u64)>>,
+    /// Set to stop the worker (directory change or mode off)
+    pub cancel: Arc<AtomicBool>,
+}
+
+/// Virtual filesystem entered from the explorer
+pub struct VfsMount {
+    /// Archive file on disk, or `sftp://host` for a remote host; `cwd` is this
+    /// path joined with the directory inside it
+    pub source: PathBuf,
+    pub fs: Arc<dyn VirtualFs>,
+    /// Local directory `E` extracts to, and `..` returns to from the top
+    pub local: PathBuf,
+    /// Explorer root to restore when leaving (remote hosts replace the root)
+    pub prev_root: Option<PathBuf>,
+}
+
+/// Established `sftp://` connection and the remote path it was asked for
+pub struct RemoteTarget {
+    /// `sftp://host`, the explorer root while browsing it
+    pub source: PathBuf,
+    pub fs: Arc<dyn VirtualFs>,
+    /// Path inside the filesystem
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+/// File opened in the editor from a virtual filesystem
+pub struct RemoteFile {
+    pub fs: Arc<dyn VirtualFs>,
+    /// Path inside the filesystem
+    pub path: PathBuf,
+}
+
+/// Filesystem watcher on one directory (non-recursive)
+pub struct DirWatch {
+    pub dir: PathBuf,
+    /// Kept alive for as long as the directory is watched
+    pub _watcher: notify::RecommendedWatcher,
+    pub events: Receiver<notify::Result<notify::Event>>,
+}
+
+/// Message from the background directory loader
+pub enum DirLoad {
+    Entries(Vec<DirEntryView>),
+    Badges(HashMap<String, char>),
+}
+
+/// Explorer clipboard: paths to copy, or to move when `cut`
+pub struct FileClipboard {
+    pub paths: Vec<PathBuf>,
+    pub cut: bool,
+}
+
+/// Read-only preview of the file selected in the Workspace explorer
+#[derive(Default)]
+pub struct PreviewState {
+    pub path: Option<PathBuf>,
+    /// Highlighted first lines of `path`
+    pub lines: Vec<Line<'static>>,
+    pub pending: Option<Receiver<Vec<Line<'static>>>>,
+}
+
+/// "Open with" menu: configured programs and the choices for `path`
+#[derive(Default)]
+pub struct OpenWithState {
+    pub programs: Vec<OpenWithProgram>,
+    pub path: PathBuf,
+    /// Index into `programs`, None for the `open` built-in
+    pub choices: Vec<Option<usize>>,
+    pub selected: usize,
+}
+
+/// Permissions editor: mode being edited for `path` and the grid cursor
+#[derive(Default)]
+pub struct PermissionsState {
+    pub path: PathBuf,
+    /// Mode bits (including setuid/setgid/sticky), as edited
+    pub mode: u32,
+    pub original: u32,
+    /// Toggle under the cursor, 0..9 (user r/w/x, group r/w/x, other r/w/x)
+    pub cursor: usize,
+    pub owner: String,
+    pub group: String,
+}
+
+/// Entries sent to the trash by one delete
+pub struct TrashedBatch {
+    pub paths: Vec<PathBuf>,
+}
+
+/// Short-lived notification shown above the status bar
+pub struct Toast {
+    pub message: String,
+    pub error: bool,
+    pub until: Instant,
+}
+
+/// One source → destination step of a paste
+pub struct PasteItem {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    /// Replace an existing destination
+    pub overwrite: bool,
+}
+
+/// Message from the background paste worker
+pub enum PasteEvent {
+    Progress { done: u64, total: u64, current: String },
+    Finished(Vec<String>),
+}
+
+/// Message from the background reader of a large file
+pub enum LoadEvent {
+    Progress(u64),
+    Finished(Result<(Rope, TextEncoding, LineEnding), String>),
+}
+
+/// Large file being read into the editor
+#[derive(Default)]
+pub struct LoadState {
+    pub path: PathBuf,
+    pub running: Option<Receiver<LoadEvent>>,
+    pub done: u64,
+    pub total: u64,
+}
+
+/// Paste in progress: conflicts waiting for a decision, then the copy/move worker
+#[derive(Default)]
+pub struct PasteState {
+    pub cut: bool,
+    pub plan: Vec<PasteItem>,
+    pub conflicts: VecDeque<PasteItem>,
+    pub running: Option<Receiver<PasteEvent>>,
+    pub done: u64,
+    pub total: u64,
+    pub current: String,
+    pub errors: Vec<String>,
+    pub finished: bool,
+}
+
+/// Batch rename of the marked entries (`R` with several marks)
+#[derive(Default)]
+pub struct RenameState {
+    pub targets: Vec<PathBuf>,
+    pub pattern: String,
+}
+
+/// Editor search-and-replace run waiting for a y / n / a / q answer (`c` flag)
+pub struct ReplaceState {
+    pub regex: Regex,
+    /// Replacement, with `$1` / `${name}` group references
+    pub replacement: String,
+    /// Every match of a line (`g`), not only the first one
+    pub global: bool,
+    /// Where the next match is searched: row and char column
+    pub row: usize,
+    pub col: usize,
+    /// Last row searched (inclusive)
+    pub last_row: usize,
+    /// Current match: row and char range within it
+    pub hit: Option<(usize, Range<usize>)>,
+    pub replaced: usize,
+}
+
+/// Archive being written from the explorer selection (`Z`)
+#[derive(Default)]
+pub struct CompressState {
+    pub dest: PathBuf,
+    pub running: Option<Receiver<PasteEvent>>,
+    pub done: u64,
+    pub total: u64,
+    pub current: String,
 }
 
 /// A single displayed entry in the explorer list
+#[derive(Clone, Default)]
 pub struct DirEntryView {
     pub name: String,
+    /// Directory, or symlink to a directory
     pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    pub is_symlink: bool,
+    /// Directory holding a `.git`
+    pub is_repo: bool,
+}
+
+/// Git screen state: repository, status entries, selected diff and commit message buffer
+#[derive(Default)]
+pub struct GitState {
+    pub root: Option<PathBuf>,
+    pub branch: Option<String>,
+    pub entries: Vec<GitFileStatus>,
+    pub selected: usize,
+    pub diff: Vec<String>,
+    pub diff_scroll: usize,
+    /// Commit message being written (Some while in commit mode)
+    pub message: Option<EditorState>,
+    /// Last result or error shown in the title
+    pub message_line: Option<String>,
+}
+
+/// Which list the container panel shows
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContainerTab {
+    #[default]
+    Containers,
+    Images,
+}
+
+/// A container as listed by `docker ps -a`
+#[derive(Clone)]
+pub struct ContainerInfo {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub status: String,
+}
+
+/// An image as listed by `docker images`
+#[derive(Clone)]
+pub struct ImageInfo {
+    pub name: String,
+    pub id: String,
+    pub size: String,
+}
+
+/// Container panel state: detected runtime, lists, selection and followed logs
+#[derive(Default)]
+pub struct ContainerState {
+    /// Container CLI in use ("docker" or "podman"), detected on first refresh
+    pub runtime: Option<String>,
+    pub tab: ContainerTab,
+    pub containers: Vec<ContainerInfo>,
+    pub images: Vec<ImageInfo>,
+    pub selected: usize,
+    /// Job streaming `<runtime> logs -f` for the followed container
+    pub logs_job: Option<usize>,
+    pub logs_title: String,
+    /// Lines scrolled up from the bottom of the logs pane
+    pub logs_scroll: usize,
+    /// Last result or error shown in the title
+    pub message_line: Option<String>,
+}
+
+/// Focusable parts of the HTTP screen, in Tab order
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum HttpField {
+    Collection,
+    Method,
+    #[default]
+    Url,
+    Headers,
+    Body,
+    Response,
+}
+
+/// HTTP screen state: request being built, saved collection and last response
+pub struct HttpState {
+    pub field: HttpField,
+    /// Index into `http::METHODS`
+    pub method: usize,
+    pub url: String,
+    /// One `Key: Value` header per line
+    pub headers: EditorState,
+    pub body: EditorState,
+    /// Saved requests (name, request), sorted by name
+    pub saved: Vec<(String, HttpRequest)>,
+    pub saved_selected: usize,
+    /// In-flight request, answered by a worker thread
+    pub pending: Option<Receiver<Result<HttpResponse, String>>>,
+    pub response: Option<HttpResponse>,
+    /// Parsed response body when it is JSON
+    pub json: Option<serde_json::Value>,
+    /// Paths of collapsed JSON objects/arrays
+    pub folded: HashSet<String>,
+    pub response_selected: usize,
+    /// Last result or error shown in the title
+    pub message_line: Option<String>,
+}
+
+impl Default for HttpState {
+    fn default() -> Self {
+        Self {
+            field: HttpField::default(),
+            method: 0,
+            url: String::new(),
+            headers: EditorState::new_empty(),
+            body: EditorState::new_empty(),
+            saved: Vec::new(),
+            saved_selected: 0,
+            pending: None,
+            response: None,
+            json: None,
+            folded: HashSet::new(),
+            response_selected: 0,
+            message_line: None,
+        }
+    }
+}
+
+/// Focusable parts of the SQLite data screen
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum DataFocus {
+    #[default]
+    Tables,
+    Grid,
+    Sql,
+}
+
+/// SQLite data screen state: open database, table list, current page or query result
+#[derive(Default)]
+pub struct SqliteState {
+    pub path: Option<PathBuf>,
+    pub conn: Option<rusqlite::Connection>,
+    pub tables: Vec<String>,
+    pub table_selected: usize,
+    /// Table being browsed (None while showing an ad-hoc query result)
+    pub table: Option<String>,
+    /// Current page (0-based) and row count of the browsed table
+    pub page: usize,
+    pub total_rows: usize,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub row_selected: usize,
+    /// First visible column (horizontal scroll)
+    pub col_offset: usize,
+    /// Ad-hoc SQL being typed
+    pub sql: String,
+    pub focus: DataFocus,
+    /// Screen to return to when leaving
+    pub return_to: Screen,
+    /// Last result or error shown in the title
+    pub message_line: Option<String>,
+}
+
+/// CSV/TSV viewer state: parsed rows, visible (filtered/sorted) rows and selection
+#[derive(Default)]
+pub struct CsvState {
+    pub path: Option<PathBuf>,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Indices into `rows` after filtering and sorting
+    pub view: Vec<usize>,
+    /// Sort column and direction (true = ascending)
+    pub sort: Option<(usize, bool)>,
+    pub filter: String,
+    pub filter_editing: bool,
+    pub selected_row: usize,
+    pub selected_col: usize,
+    /// First visible column (horizontal scroll)
+    pub col_offset: usize,
+    /// Screen to return to when leaving
+    pub return_to: Screen,
+    pub message_line: Option<String>,
+}
+
+/// Hex viewer state for binary files
+#[derive(Default)]
+pub struct HexState {
+    pub path: Option<PathBuf>,
+    pub bytes: Vec<u8>,
+    /// Offset of the selected byte
+    pub cursor: usize,
+    /// First visible row (16 bytes per row)
+    pub scroll_row: usize,
+    /// Byte editing enabled (hex digits overwrite)
+    pub editing: bool,
+    /// Next hex digit goes to the low nibble of the cursor byte
+    pub low_nibble: bool,
+    pub dirty: bool,
+    /// Screen to return to when leaving
+    pub return_to: Screen,
+    pub message_line: Option<String>,
+}
+
+/// Kind of a diff line
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// One line of a diff, split into segments flagged when they changed within the line
+#[derive(Clone)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub old_no: Option<usize>,
+    pub new_no: Option<usize>,
+    pub segments: Vec<(bool, String)>,
+}
+
+/// Diff viewer state: hunks of the comparison and display mode
+#[derive(Default)]
+pub struct DiffState {
+    pub left_name: String,
+    pub right_name: String,
+    /// Hunk header and its lines
+    pub hunks: Vec<(String, Vec<DiffLine>)>,
+    pub side_by_side: bool,
+    pub scroll: usize,
+    /// Screen to return to when leaving
+    pub return_to: Screen,
+    pub message_line: Option<String>,
+}
+
+/// A runnable project task (make target, cargo command, npm script, just recipe)
+#[derive(Clone)]
+pub struct TaskEntry {
+    pub source: String,
+    pub name: String,
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+/// Task runner panel: tasks of the detected project directory
+#[derive(Default)]
+pub struct TaskState {
+    pub root: PathBuf,
+    pub tasks: Vec<TaskEntry>,
+    pub selected: usize,
+    pub message_line: Option<String>,
+}
+
+/// Fuzzy file finder: filename index of the explorer root and current matches
+#[derive(Default)]
+pub struct FinderState {
+    /// Root the index was built for
+    pub root: Option<PathBuf>,
+    /// Indexed files, relative to `root`
+    pub files: Vec<String>,
+    /// Background indexing in progress (batches of relative paths)
+    pub indexing: Option<Receiver<Vec<String>>>,
+    pub spinner: usize,
+    pub query: String,
+    /// Index into `files` and matched character positions, best first
+    pub results: Vec<(usize, Vec<usize>)>,
+    pub selected: usize,
+}
+
+/// Definition found in a source file by its syntax grammar
+#[derive(Clone)]
+pub struct Symbol {
+    pub name: String,
+    /// Kind named by the grammar (`function`, `struct`, `class`, `section`…)
+    pub kind: String,
+    /// File, then 0-based line and character column of the name
+    pub path: PathBuf,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Go-to-symbol overlay (Ctrl+K): symbols of the current buffer or of the workspace
+#[derive(Default)]
+pub struct SymbolsState {
+    /// Listing the workspace index rather than the current buffer (Tab)
+    pub workspace: bool,
+    /// Symbols of the buffer the overlay was opened on
+    pub buffer: Vec<Symbol>,
+    /// Root the workspace index was built for, and its symbols
+    pub root: Option<PathBuf>,
+    pub index: Vec<Symbol>,
+    /// Background indexing in progress (symbols of a batch of files)
+    pub indexing: Option<Receiver<Vec<Symbol>>>,
+    pub spinner: usize,
+    pub query: String,
+    /// Index into the listed symbols and matched character positions, best first
+    pub results: Vec<(usize, Vec<usize>)>,
+    pub selected: usize,
+    /// Word whose definition is looked up once the workspace index is built
+    pub pending_definition: Option<String>,
+}
+
+/// One matching line of a content search
+#[derive(Clone)]
+pub struct GrepHit {
+    /// Path relative to the search root
+    pub path: PathBuf,
+    /// 0-based line and character column of the match
+    pub line: usize,
+    pub col: usize,
+    pub matched: String,
+    pub text: String,
+}
+
+/// Content search panel: query, streamed hits and the running search
+#[derive(Default)]
+pub struct SearchState {
+    pub root: PathBuf,
+    pub query: String,
+    /// Query field has focus (otherwise the result list)
+    pub editing: bool,
+    pub hits: Vec<GrepHit>,
+    pub selected: usize,
+    pub pending: Option<Receiver<GrepHit>>,
+    /// Set to stop the running search
+    pub cancel: Option<Arc<AtomicBool>>,
+    pub spinner: usize,
+    pub return_to: Screen,
+    pub message_line: Option<String>,
+}
+
+/// Quick-notes pane shown over any screen
+pub struct ScratchpadState {
+    pub open: bool,
+    /// Notes file read once, on first opening
+    pub loaded: bool,
+    pub editor: EditorState,
+    pub message_line: Option<String>,
+}
+
+impl Default for ScratchpadState {
+    fn default() -> Self {
+        Self { open: false, loaded: false, editor: EditorState::new_empty(), message_line: None }
+    }
 }
 
 /// Editor modes (simple Vim-like)
@@ -70,6 +781,188 @@ pub enum EditorMode {
     Normal,
     Insert,
     Command,
+    /// Character-wise selection from `visual_anchor` to the cursor (`v`)
+    Visual,
+    /// Line-wise selection from `visual_anchor` to the cursor (`V`)
+    VisualLine,
+    /// Rectangle between the columns of `visual_anchor` and the cursor (Ctrl+V)
+    VisualBlock,
+}
+
+/// Text yanked or deleted from a selection, pasted with `p` / `P`
+#[derive(Clone, Default)]
+pub struct EditorRegister {
+    pub text: String,
+    /// Whole lines: pasted below / above the cursor line
+    pub linewise: bool,
+    /// Block of a rectangular selection: its lines go at the cursor column of the
+    /// following lines
+    pub block: bool,
+}
+
+/// Text typed on the first line of a block (`I` / `A` in block selection), copied
+/// to the other lines as it changes
+pub struct BlockInsert {
+    pub first_row: usize,
+    pub last_row: usize,
+    pub col: usize,
+    /// Length of the first line before typing
+    pub base_len: usize,
+    /// Text already copied to the other lines
+    pub typed: String,
+    /// Pad shorter lines with spaces up to `col` (`A`); `I` skips them
+    pub pad: bool,
+}
+
+/// Yanks kept in the numbered history (`"0` is the latest).
+pub const REGISTER_HISTORY: usize = 10;
+
+/// Editor registers shared by every tab: the unnamed one (mirrored on the system
+/// clipboard), the named ones `"a`–`"z` and the numbered history of yanks
+#[derive(Default)]
+pub struct Registers {
+    pub unnamed: EditorRegister,
+    pub named: HashMap<char, EditorRegister>,
+    /// Latest yanks and deletions first
+    pub history: VecDeque<EditorRegister>,
+    /// Register chosen with `"x` for the next yank, delete or paste
+    pub selected: Option<char>,
+}
+
+/// Jump points kept in the jump list.
+pub const JUMP_HISTORY: usize = 100;
+
+/// Position of the cursor in a file before a jump
+#[derive(Clone, PartialEq, Eq)]
+pub struct Jump {
+    pub path: PathBuf,
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Jump {
+    /// Cursor position of a buffer backed by a file.
+    pub fn of(ed: &EditorState) -> Option<Jump> {
+        Some(Jump { path: ed.path.clone()?, row: ed.cursor_row, col: ed.cursor_col })
+    }
+}
+
+/// Jump list shared by every tab: where the cursor was before a search, a goto-line,
+/// a go to symbol or mark, or the opening of a file (Ctrl+O back, Ctrl+I forward)
+#[derive(Default)]
+pub struct JumpList {
+    pub entries: Vec<Jump>,
+    /// Entry walked to last; `entries.len()` when not walking
+    pub index: usize,
+}
+
+impl JumpList {
+    /// Record the position left by a jump. Entries ahead of the walked one are
+    /// dropped and an older entry on the same line is replaced.
+    pub fn record(&mut self, jump: Option<Jump>) {
+        let Some(jump) = jump else { return; };
+        self.entries.truncate(self.index);
+        self.entries.retain(|j| j.path != jump.path || j.row != jump.row);
+        self.entries.push(jump);
+        if self.entries.len() > JUMP_HISTORY {
+            self.entries.remove(0);
+        }
+        self.index = self.entries.len();
+    }
+
+    /// Previous jump point. Leaving the end of the list records `current`, so that
+    /// walking forward comes back to it.
+    pub fn back(&mut self, current: Option<Jump>) -> Option<Jump> {
+        if self.index >= self.entries.len() {
+            if let Some(here) = current.filter(|c| self.entries.last() != Some(c)) {
+                self.entries.push(here);
+            }
+            self.index = self.entries.len().saturating_sub(1);
+        }
+        if self.index == 0 {
+            return None;
+        }
+        self.index -= 1;
+        self.entries.get(self.index).cloned()
+    }
+
+    /// Next jump point, after walking back.
+    pub fn forward(&mut self) -> Option<Jump> {
+        if self.index + 1 >= self.entries.len() {
+            return None;
+        }
+        self.index += 1;
+        self.entries.get(self.index).cloned()
+    }
+}
+
+/// Snippet being filled in: its tab stops as buffer char ranges, in the order Tab
+/// visits them (`$0` last)
+pub struct SnippetSession {
+    pub stops: Vec<Range<usize>>,
+    pub current: usize,
+    /// The current stop still holds its placeholder, replaced by the next typed char
+    pub fresh: bool,
+}
+
+impl SnippetSession {
+    /// Follow `len` chars inserted at `at`: text typed at the edge of the current
+    /// stop extends it, other stops move after it.
+    fn inserted(&mut self, at: usize, len: usize) {
+        for (i, stop) in self.stops.iter_mut().enumerate() {
+            let current = i == self.current;
+            if stop.start > at || (stop.start == at && !current) {
+                stop.start += len;
+                stop.end += len;
+            } else if stop.end > at || (stop.end == at && current) {
+                stop.end += len;
+            }
+        }
+    }
+
+    /// Follow the removal of `range`.
+    fn removed(&mut self, range: &Range<usize>) {
+        let moved = |p: usize| if p <= range.start { p } else if p >= range.end { p - range.len() } else { range.start };
+        for stop in &mut self.stops {
+            *stop = moved(stop.start)..moved(stop.end);
+        }
+    }
+}
+
+/// Change of a buffer line against the HEAD version of its file
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineChange {
+    #[default]
+    Unchanged,
+    Added,
+    Modified,
+    /// Lines of HEAD were deleted after this line
+    DeletedBelow,
+    /// Lines of HEAD were deleted before the first line
+    DeletedAbove,
+}
+
+/// Blame of a buffer: the revision blamed and the line entries, or git's error
+pub type BlameResult = (u64, Result<Vec<BlameLine>, String>);
+
+/// Git markers of an editor buffer (see `tui::gutter`)
+#[derive(Default)]
+pub struct GitGutter {
+    /// Repository root and path of the file in it
+    pub repo: Option<(PathBuf, String)>,
+    /// Text of the file at HEAD, None when it is not committed
+    pub head: Option<String>,
+    /// Change of each buffer line, computed for `revision`
+    pub marks: Vec<LineChange>,
+    pub revision: Option<u64>,
+    /// Inline blame of the cursor line shown
+    pub blame: bool,
+    /// Blame of each buffer line, computed for `blame_revision`
+    pub blame_lines: Vec<BlameLine>,
+    pub blame_revision: Option<u64>,
+    /// Blame running in the background: revision blamed and result
+    pub blame_running: Option<Receiver<BlameResult>>,
+    pub blame_started: Option<Instant>,
 }
 
 /// Text editor state backed by ropey for efficient edits
@@ -78,17 +971,78 @@ pub struct EditorState {
     pub buffer: Rope,
     pub cursor_row: usize,
     pub cursor_col: usize,
+    /// First buffer line shown
     pub scroll_row: usize,
+    /// Text rows of the pane the buffer was last drawn in
+    pub view_height: usize,
+    /// Lines kept visible above and below the cursor (`scrolloff` in config/editor.toml)
+    pub scrolloff: usize,
     pub mode: EditorMode,
     pub cmdline: String,
     pub dirty: bool,
-    /// Last search query entered (for Ctrl+F prefill)
+    /// Last search query entered (a regex; for Ctrl+F prefill)
     pub last_search: Option<String>,
-    pub search_positions: Vec<(usize, usize)>, // (row, col in chars)
+    pub search_ignore_case: bool,
+    pub search_whole_word: bool,
+    pub search_positions: Vec<(usize, Range<usize>)>, // (row, chars of the match)
     pub search_index: Option<usize>,
-    /// Undo/redo stacks (bounded)
-    pub undo_stack: Vec<EditorSnapshot>,
-    pub redo_stack: Vec<EditorSnapshot>,
+    /// Cursor when Ctrl+F opened: the live search starts there, Esc goes back to it
+    pub search_origin: (usize, usize),
+    /// Undo/redo stacks (bounded to `UNDO_LIMIT` steps)
+    pub undo_stack: Vec<UndoStep>,
+    pub redo_stack: Vec<UndoStep>,
+    /// Typed characters (or Backspaces) still extending the last undo step
+    pub typing: Option<TypingRun>,
+    /// Undo history saved with the file and restored when it is reopened unchanged
+    /// (`persistent_undo` in config/editor.toml)
+    pub persist_undo: bool,
+    /// Other end of the selection in Visual modes (row, col)
+    pub visual_anchor: (usize, usize),
+    /// First key of a two-key Normal command (`d` of `dd`, `y` of `yy`)
+    pub pending: Option<char>,
+    /// Indentation of this file type (Tab, `>` / `<`, width of tab characters)
+    pub indent: Indent,
+    /// Folded regions: header row and last hidden row, sorted and disjoint
+    pub folds: Vec<(usize, usize)>,
+    /// Marks set with `m` (row, char column); `'` holds the position before the last
+    /// jump to a mark
+    pub marks: BTreeMap<char, (usize, usize)>,
+    /// Typing an opening bracket or quote also inserts the closing one
+    /// (`auto_pairs` in config/editor.toml)
+    pub auto_pairs: bool,
+    /// Clean-up applied when saving (trailing whitespace, final line break)
+    pub cleanup: SaveCleanup,
+    /// Comment markers of this file type, None when it has none
+    pub comment: Option<CommentSyntax>,
+    /// Snippets offered for this file type (see `tui::snippets`)
+    pub snippets: Vec<SnippetConfig>,
+    /// Snippet whose tab stops Tab / Shift+Tab walk through
+    pub snippet: Option<SnippetSession>,
+    /// Insertion on every line of a block selection in progress
+    pub block_insert: Option<BlockInsert>,
+    /// Tabs, non-breaking and trailing spaces drawn as visible marks (`:set list`, Alt+i)
+    pub show_invisibles: bool,
+    /// Soft wrap: long lines continue on the next screen rows (`:set wrap`, Alt+z)
+    pub wrap: bool,
+    /// Edits and saves are refused (very large files open this way)
+    pub read_only: bool,
+    /// How the file is stored; the buffer itself always holds LF-only text
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+    /// Bumped by every change of the buffer
+    pub revision: u64,
+    /// Revision copied to the swap file, None while there is no swap file of ours
+    pub swap_revision: Option<u64>,
+    /// Whether a swap file left by a previous session was looked for
+    pub swap_checked: bool,
+    /// Modification time of the file when it was read or saved, to notice changes
+    /// made by other programs (local files only)
+    pub disk_mtime: Option<SystemTime>,
+    /// Change markers against HEAD and inline blame
+    pub git: GitGutter,
+    /// Set when the file comes from a virtual filesystem (archive, SFTP): read and
+    /// saved through it, `path` being only shown
+    pub remote: Option<RemoteFile>,
 }
 
 impl EditorState {
@@ -100,14 +1054,43 @@ impl EditorState {
             cursor_row: 0,
             cursor_col: 0,
             scroll_row: 0,
+            view_height: 20,
+            scrolloff: 0,
             mode: EditorMode::Normal,
             cmdline: String::new(),
             dirty: false,
             last_search: None,
+            search_ignore_case: false,
+            search_whole_word: false,
             search_positions: Vec::new(),
             search_index: None,
+            search_origin: (0, 0),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            typing: None,
+            persist_undo: false,
+            visual_anchor: (0, 0),
+            pending: None,
+            indent: Indent::default(),
+            folds: Vec::new(),
+            marks: BTreeMap::new(),
+            auto_pairs: false,
+            cleanup: SaveCleanup::default(),
+            comment: None,
+            snippets: Vec::new(),
+            snippet: None,
+            block_insert: None,
+            show_invisibles: false,
+            wrap: false,
+            read_only: false,
+            encoding: TextEncoding::default(),
+            line_ending: LineEnding::default(),
+            revision: 0,
+            swap_revision: None,
+            swap_checked: false,
+            disk_mtime: None,
+            git: GitGutter::default(),
+            remote: None,
         }
     }
 }
@@ -122,9 +1105,47 @@ pub struct TuiState {
     // Input overlay is handled via this optional state when overlay == Input
     pub overlay_input: Option<InputOverlay>,
     pub explorer: FileExplorerState,
-    pub editor: Option<EditorState>,
     /// Multiple editor tabs; current determines which one is shown.
     pub tabs: EditorTabs,
+    /// Row selected in the tab picker (Ctrl+T)
+    pub tabs_selected: usize,
+    /// Close or quit waiting on the unsaved-changes confirmation
+    pub unsaved: Option<UnsavedState>,
+    /// Time of the swap file offered for recovery in the current tab
+    pub recover: Option<SystemTime>,
+    /// Tab whose file changed on disk while it has unsaved changes
+    pub conflict: Option<usize>,
+    /// Preview layout for Markdown tabs (Ctrl+P cycles)
+    pub md_preview: MarkdownPreview,
+    /// Selected row in the jobs overlay
+    pub jobs_selected: usize,
+    /// Whether the jobs overlay shows the output of the selected job
+    pub jobs_show_output: bool,
+    pub git: GitState,
+    pub containers: ContainerState,
+    pub http: HttpState,
+    pub sqlite: SqliteState,
+    pub csv: CsvState,
+    pub hex: HexState,
+    pub diff: DiffState,
+    pub scratchpad: ScratchpadState,
+    pub tasks: TaskState,
+    pub finder: FinderState,
+    pub symbols: SymbolsState,
+    pub paste: PasteState,
+    pub compress: CompressState,
+    pub load: LoadState,
+    pub rename: RenameState,
+    pub replace: Option<ReplaceState>,
+    /// Editor registers shared by every tab (yank / delete, paste)
+    pub registers: Registers,
+    /// Cursor positions left by jumps, across tabs (Ctrl+O / Ctrl+I)
+    pub jumps: JumpList,
+    pub toast: Option<Toast>,
+    pub preview: PreviewState,
+    pub open_with: OpenWithState,
+    pub permissions: PermissionsState,
+    pub search: SearchState,
 }
 
 impl Default for TuiState {
@@ -137,59 +1158,310 @@ impl Default for TuiState {
             overlay: Overlay::None,
             overlay_input: None,
             explorer: FileExplorerState::default(),
-            editor: None,
             tabs: EditorTabs::default(),
+            tabs_selected: 0,
+            unsaved: None,
+            recover: None,
+            conflict: None,
+            md_preview: MarkdownPreview::default(),
+            jobs_selected: 0,
+            jobs_show_output: false,
+            git: GitState::default(),
+            containers: ContainerState::default(),
+            http: HttpState::default(),
+            sqlite: SqliteState::default(),
+            csv: CsvState::default(),
+            hex: HexState::default(),
+            diff: DiffState::default(),
+            scratchpad: ScratchpadState::default(),
+            tasks: TaskState::default(),
+            finder: FinderState::default(),
+            symbols: SymbolsState::default(),
+            paste: PasteState::default(),
+            compress: CompressState::default(),
+            load: LoadState::default(),
+            rename: RenameState::default(),
+            replace: None,
+            registers: Registers::default(),
+            jumps: JumpList::default(),
+            toast: None,
+            preview: PreviewState::default(),
+            open_with: OpenWithState::default(),
+            permissions: PermissionsState::default(),
+            search: SearchState::default(),
         }
     }
 }
 
-impl TuiState {
-    /// Convenience constructor equal to Default
-    pub fn new() -> Self { Self::default() }
-}
-
 pub struct EditorTab {
     pub state: EditorState,
 }
 
+/// Cursor and scroll position of an editor pane
+#[derive(Clone, Copy, Default)]
+pub struct PaneView {
+    pub cursor_row: usize,
+    pub cursor_col: usize,
+    pub scroll_row: usize,
+}
+
+impl PaneView {
+    pub fn of(ed: &EditorState) -> Self {
+        Self { cursor_row: ed.cursor_row, cursor_col: ed.cursor_col, scroll_row: ed.scroll_row }
+    }
+
+    /// Move `ed` to this position, kept inside its buffer.
+    pub fn apply(self, ed: &mut EditorState) {
+        ed.cursor_row = self.cursor_row.min(ed.buffer.len_lines().saturating_sub(1));
+        ed.cursor_col = self.cursor_col.min(ed.buffer.line(ed.cursor_row).len_chars());
+        ed.scroll_row = self.scroll_row.min(ed.cursor_row);
+    }
+}
+
+/// Vertical split of the editor area. The focused pane shows the current tab at the
+/// tab's own position; the other pane shows `tab` at `view`, possibly the same tab.
+pub struct EditorSplit {
+    pub tab: usize,
+    pub view: PaneView,
+    /// The focused pane is the right one
+    pub right_focused: bool,
+}
+
+#[derive(Default)]
 pub struct EditorTabs {
     pub tabs: Vec<EditorTab>,
     pub current: usize,
+    /// Second pane beside the current tab
+    pub split: Option<EditorSplit>,
+    /// Preferences from `config/editor.toml`, applied to every buffer opened in a tab
+    pub config: EditorConfig,
 }
 
-/// Snapshot for undo/redo
-pub struct EditorSnapshot {
-    pub buffer: Rope,
+/// What the unsaved-changes confirmation guards
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PendingClose {
+    /// Closing the tab at this index
+    Tab(usize),
+    /// Quitting the TUI with modified tabs
+    Quit,
+}
+
+/// Unsaved-changes confirmation and the overlay to go back to once answered
+pub struct UnsavedState {
+    pub action: PendingClose,
+    pub back_to: Overlay,
+}
+
+/// Most undo steps kept per buffer
+pub const UNDO_LIMIT: usize = 1000;
+
+/// One change of a buffer, positions in chars
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum EditOp {
+    Insert { at: usize, text: String },
+    Remove { at: usize, text: String },
+}
+
+impl EditOp {
+    /// Apply the op; returns the lines it touched and the change in line count (see
+    /// `EditorState::lines_moved`).
+    fn apply(&self, buffer: &mut Rope) -> (usize, usize, isize) {
+        match self {
+            EditOp::Insert { at, text } => Self::insert(buffer, *at, text),
+            EditOp::Remove { at, text } => Self::remove(buffer, *at, text),
+        }
+    }
+
+    fn revert(&self, buffer: &mut Rope) -> (usize, usize, isize) {
+        match self {
+            EditOp::Insert { at, text } => Self::remove(buffer, *at, text),
+            EditOp::Remove { at, text } => Self::insert(buffer, *at, text),
+        }
+    }
+
+    fn insert(buffer: &mut Rope, at: usize, text: &str) -> (usize, usize, isize) {
+        let row = buffer.char_to_line(at);
+        buffer.insert(at, text);
+        (row, row, text.matches('\n').count() as isize)
+    }
+
+    fn remove(buffer: &mut Rope, at: usize, text: &str) -> (usize, usize, isize) {
+        let end = at + text.chars().count();
+        let (first, last) = (buffer.char_to_line(at), buffer.char_to_line(end));
+        buffer.remove(at..end);
+        (first, last, -((last - first) as isize))
+    }
+}
+
+/// Undo step: the changes it made, and the cursor, scroll and modified flag to
+/// restore when it is undone (on the redo stack: when it is redone)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UndoStep {
+    pub ops: Vec<EditOp>,
     pub cursor_row: usize,
     pub cursor_col: usize,
     pub scroll_row: usize,
     pub dirty: bool,
 }
 
+impl UndoStep {
+    /// Swap the saved cursor, scroll and modified flag with the editor's.
+    fn exchange(&mut self, ed: &mut EditorState) {
+        std::mem::swap(&mut self.cursor_row, &mut ed.cursor_row);
+        std::mem::swap(&mut self.cursor_col, &mut ed.cursor_col);
+        std::mem::swap(&mut self.scroll_row, &mut ed.scroll_row);
+        std::mem::swap(&mut self.dirty, &mut ed.dirty);
+    }
+}
+
+/// Characters typed (or erased with Backspace) in a row, merged into one undo step
+pub struct TypingRun {
+    pub deleting: bool,
+    /// Cursor after the last key: typing elsewhere starts a new step
+    pub at: (usize, usize),
+    pub last: Instant,
+    /// The last key typed a whitespace: the next word starts a new step
+    pub space: bool,
+}
+
 impl EditorState {
-    /// Push current state to undo stack, clear redo; keep at most 50 entries
+    /// Open a new undo step at the current state and clear redo; keep at most `UNDO_LIMIT` steps.
     pub fn push_undo(&mut self) {
-        let snap = EditorSnapshot {
-            buffer: self.buffer.clone(),
+        self.undo_stack.push(UndoStep {
+            ops: Vec::new(),
             cursor_row: self.cursor_row,
             cursor_col: self.cursor_col,
             scroll_row: self.scroll_row,
             dirty: self.dirty,
-        };
-        self.undo_stack.push(snap);
-        if self.undo_stack.len() > 50 { let overflow = self.undo_stack.len() - 50; self.undo_stack.drain(0..overflow); }
+        });
+        if self.undo_stack.len() > UNDO_LIMIT { let overflow = self.undo_stack.len() - UNDO_LIMIT; self.undo_stack.drain(0..overflow); }
         self.redo_stack.clear();
+        self.typing = None;
+    }
+
+    /// Insert `text` at char `at`, recorded in the current undo step.
+    pub fn insert_at(&mut self, at: usize, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let (first, last, delta) = EditOp::insert(&mut self.buffer, at, text);
+        self.revision += 1;
+        self.lines_moved(first, last, delta);
+        if let Some(snippet) = self.snippet.as_mut() {
+            snippet.inserted(at, text.chars().count());
+        }
+        let Some(step) = self.undo_stack.last_mut() else { return; };
+        // Frappe continue : prolonger l'insertion précédente
+        if let Some(EditOp::Insert { at: prev, text: prev_text }) = step.ops.last_mut()
+            && *prev + prev_text.chars().count() == at
+        {
+            prev_text.push_str(text);
+            return;
+        }
+        step.ops.push(EditOp::Insert { at, text: text.to_string() });
+    }
+
+    /// Remove the chars of `range`, recorded in the current undo step.
+    pub fn remove_range(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        let text = self.buffer.slice(range.clone()).to_string();
+        let (first, last, delta) = EditOp::remove(&mut self.buffer, range.start, &text);
+        self.revision += 1;
+        self.lines_moved(first, last, delta);
+        if let Some(snippet) = self.snippet.as_mut() {
+            snippet.removed(&range);
+        }
+        let Some(step) = self.undo_stack.last_mut() else { return; };
+        // Retours arrière (ou suppressions) successifs : un seul texte supprimé
+        if let Some(EditOp::Remove { at: prev, text: prev_text }) = step.ops.last_mut() {
+            if *prev == range.end {
+                *prev = range.start;
+                prev_text.insert_str(0, &text);
+                return;
+            }
+            if *prev == range.start {
+                prev_text.push_str(&text);
+                return;
+            }
+        }
+        step.ops.push(EditOp::Remove { at: range.start, text });
+    }
+
+    /// Keep the folds after an edit of rows `first..=last` that added `delta` lines:
+    /// the folds below move, the ones touched by the edit open.
+    /// Follow an edit of lines `first..=last` that changed the line count by `delta`:
+    /// folds touched by it open, the others and the marks move with their lines (a
+    /// mark on a removed line goes to the line that took its place).
+    fn lines_moved(&mut self, first: usize, last: usize, delta: isize) {
+        self.shift_folds(first, last, delta);
+        for (row, _) in self.marks.values_mut() {
+            if *row > last {
+                *row = row.saturating_add_signed(delta);
+            } else if *row > first {
+                *row = first;
+            }
+        }
+    }
+
+    fn shift_folds(&mut self, first: usize, last: usize, delta: isize) {
+        self.folds.retain_mut(|(header, end)| {
+            if *end < first {
+                true
+            } else if *header > last {
+                *header = header.saturating_add_signed(delta);
+                *end = end.saturating_add_signed(delta);
+                true
+            } else {
+                false
+            }
+        });
+    }
+
+    /// Revert the last undo step; it moves to the redo stack.
+    pub fn undo(&mut self) -> bool {
+        let Some(mut step) = self.undo_stack.pop() else { return false; };
+        self.folds.clear();
+        for op in step.ops.iter().rev() {
+            let (first, last, delta) = op.revert(&mut self.buffer);
+            self.lines_moved(first, last, delta);
+        }
+        step.exchange(self);
+        self.redo_stack.push(step);
+        self.revision += 1;
+        self.typing = None;
+        true
+    }
+
+    /// Apply again the last undone step; it moves back to the undo stack.
+    pub fn redo(&mut self) -> bool {
+        let Some(mut step) = self.redo_stack.pop() else { return false; };
+        self.folds.clear();
+        for op in &step.ops {
+            let (first, last, delta) = op.apply(&mut self.buffer);
+            self.lines_moved(first, last, delta);
+        }
+        step.exchange(self);
+        self.undo_stack.push(step);
+        self.revision += 1;
+        self.typing = None;
+        true
     }
 }
 
 /// Kind of input requested by an input overlay
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum InputKind {
-    NewEntry,       // create file or folder (folder if name ends with '/')
+    NewEntry,       // create file or folder (folder if name ends with '/', or '\' on Windows)
     RenameEntry,    // rename selected entry
-    DeleteConfirm,  // confirm deletion of selected entry (type 'y' to confirm)
+    DeleteConfirm,  // confirm permanent deletion of selected entry (type 'y' to confirm)
+    QuitConfirm,    // confirm leaving the TUI (`confirm_exit`, type 'y' to confirm)
     SearchText,     // search text within current editor buffer
+    ReplaceText,    // substitute command for the current editor buffer ([%]s/pattern/replacement/[gci])
     GotoLine,       // go to a specific line number
+    HttpSaveName,   // name under which the current HTTP request is saved
+    Compress,       // archive name for the selected entries (.zip, .tar.gz, .tgz or .tar)
 }
 
 /// State for a minimal input overlay (prompt at bottom or centered popup)
@@ -198,15 +1470,6 @@ pub struct InputOverlay {
     pub buffer: String,
 }
 
-impl Default for EditorTabs {
-    fn default() -> Self {
-        Self {
-            tabs: Vec::new(),
-            current: 0,
-        }
-    }
-}
-
 impl EditorTabs {
     /// Return true if no tabs are open.
     pub fn is_empty(&self) -> bool { self.tabs.is_empty() }
@@ -227,10 +1490,16 @@ impl EditorTabs {
         if !self.tabs.is_empty() { self.current = (self.current + self.tabs.len() - 1) % self.tabs.len(); }
     }
 
-    /// Close the current tab and adjust the index. Does nothing if no tabs.
-    pub fn close_current(&mut self) {
-        if self.tabs.is_empty() { return; }
-        self.tabs.remove(self.current);
+    /// Close the tab at `idx`; the current tab stays the same buffer when it can.
+    pub fn close(&mut self, idx: usize) {
+        if idx >= self.tabs.len() { return; }
+        self.tabs.remove(idx);
+        if idx < self.current { self.current -= 1; }
+        match self.split.as_mut() {
+            Some(split) if split.tab == idx => self.split = None,
+            Some(split) if split.tab > idx => split.tab -= 1,
+            _ => {}
+        }
         if self.current >= self.tabs.len() { self.current = self.tabs.len().saturating_sub(1); }
     }
 
@@ -241,10 +1510,71 @@ impl EditorTabs {
         }
     }
 
-    /// For now: keep a single tab. Replace existing buffer with new state.
-    pub fn open_or_focus(&mut self, ed: EditorState) {
-        self.tabs.clear();
-        self.tabs.push(EditorTab { state: ed });
-        self.current = 0;
+    /// Split the editor area (the current tab in both panes, the right one focused),
+    /// or go back to a single pane.
+    pub fn toggle_split(&mut self) {
+        if self.split.is_some() {
+            self.split = None;
+        } else if let Some(ed) = self.current() {
+            self.split = Some(EditorSplit { tab: self.current, view: PaneView::of(ed), right_focused: true });
+        }
+    }
+
+    /// Give the focus to the other pane: its tab becomes the current one.
+    pub fn switch_pane(&mut self) {
+        let Some(split) = self.split.as_mut() else { return; };
+        let Some(focused) = self.tabs.get(self.current).map(|t| PaneView::of(&t.state)) else { return; };
+        let other = std::mem::replace(&mut split.tab, self.current);
+        let view = std::mem::replace(&mut split.view, focused);
+        split.right_focused = !split.right_focused;
+        self.current = other;
+        if let Some(tab) = self.tabs.get_mut(other) {
+            view.apply(&mut tab.state);
+        }
+    }
+
+    /// Run `render` on the tab of the unfocused pane, moved to that pane's position.
+    pub fn with_other_pane<R>(&mut self, render: impl FnOnce(&EditorState) -> R) -> Option<R> {
+        let split = self.split.as_mut()?;
+        let ed = &mut self.tabs.get_mut(split.tab)?.state;
+        let own = PaneView::of(ed);
+        split.view.apply(ed);
+        // Position bornée au buffer, qui a pu rétrécir depuis
+        split.view = PaneView::of(ed);
+        let out = render(ed);
+        own.apply(ed);
+        Some(out)
+    }
+
+    /// Index of the tab editing `path`.
+    pub fn position_of(&self, path: &Path) -> Option<usize> {
+        let key = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+        let wanted = key(path);
+        self.tabs.iter().position(|t| t.state.path.as_deref().is_some_and(|p| key(p) == wanted))
+    }
+
+    /// Focus the tab already editing `ed`'s file, or open `ed` in a new tab right
+    /// after the current one.
+    pub fn open_or_focus(&mut self, mut ed: EditorState) {
+        if let Some(idx) = ed.path.as_deref().and_then(|p| self.position_of(p)) {
+            self.current = idx;
+            return;
+        }
+        ed.indent = self.config.indent_for(ed.path.as_deref());
+        ed.cleanup = self.config.cleanup_for(ed.path.as_deref());
+        ed.comment = self.config.comment_for(ed.path.as_deref());
+        ed.snippets = self.config.snippets_for(ed.path.as_deref());
+        ed.persist_undo = self.config.persistent_undo.unwrap_or(false);
+        ed.auto_pairs = self.config.auto_pairs.unwrap_or(true);
+        ed.scrolloff = self.config.scrolloff.unwrap_or(0);
+        undo::load(&mut ed);
+        marks::load(&mut ed);
+        gutter::load_head(&mut ed);
+        let at = if self.tabs.is_empty() { 0 } else { self.current + 1 };
+        self.tabs.insert(at, EditorTab { state: ed });
+        self.current = at;
+        if let Some(split) = self.split.as_mut().filter(|s| s.tab >= at) {
+            split.tab += 1;
+        }
     }
 }
\ No newline at end of file
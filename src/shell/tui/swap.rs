@@ -0,0 +1,125 @@
+//! Crash-recovery swap files of the editor tabs.
+//!
+//! Modified buffers are copied every `SWAP_INTERVAL` to `~/.local/state/paschek/swap/`
+//! (`$XDG_STATE_HOME` when set), one file per edited path, named after the path with
+//! `/` replaced by `%`. The copy is removed once the file is saved or its tab closed,
+//! so a swap file newer than its file means the shell stopped with unsaved changes:
+//! reopening the file offers to recover them.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::shell::config::paths;
+use crate::shell::tui::state::EditorState;
+
+/// Delay between two swap writes of the modified buffers.
+pub const SWAP_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Directory of the per-file state of one kind (`swap`, `undo`) under `~/.local/state/paschek/`.
+pub fn state_dir(kind: &str) -> PathBuf {
+    paths::state_dir().join(kind)
+}
+
+/// File of `state_dir(kind)` holding the state of `file`: its absolute path with
+/// separators (and, on Windows, the `:` / `?` of `\\?\C:\…`) replaced by `%`, then `.ext`.
+pub fn state_file(kind: &str, file: &Path, ext: &str) -> PathBuf {
+    let absolute = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    let mut name = absolute.to_string_lossy().replace(|c| std::path::is_separator(c) || (cfg!(windows) && ":?".contains(c)), "%");
+    // Noms de fichier limités à 255 octets : tronquer et distinguer par un hachage
+    if name.len() > 200 {
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+        let tail: String = name.chars().rev().take(100).collect::<Vec<_>>().into_iter().rev().collect();
+        name = format!("{:016x}{}", hasher.finish(), tail);
+    }
+    state_dir(kind).join(format!("{}.{}", name, ext))
+}
+
+/// Swap file of `file`.
+pub fn swap_path(file: &Path) -> PathBuf {
+    state_file("swap", file, "swp")
+}
+
+/// Write the buffer text of `file` to its swap file.
+pub fn write(file: &Path, text: &str) -> io::Result<()> {
+    fs::create_dir_all(state_dir("swap"))?;
+    fs::write(swap_path(file), text)
+}
+
+/// Delete the swap file of `file`, if any.
+pub fn remove(file: &Path) {
+    let _ = fs::remove_file(swap_path(file));
+}
+
+/// Modification time of the swap file of `file` when it is newer than the file
+/// (or the file is gone): there are changes to recover.
+pub fn newer_copy(file: &Path) -> Option<SystemTime> {
+    let swapped = fs::metadata(swap_path(file)).and_then(|m| m.modified()).ok()?;
+    match fs::metadata(file).and_then(|m| m.modified()) {
+        Ok(saved) if saved >= swapped => None,
+        _ => Some(swapped),
+    }
+}
+
+/// Text of the swap file of `file`.
+pub fn read(file: &Path) -> io::Result<String> {
+    fs::read_to_string(swap_path(file))
+}
+
+/// Local file of a buffer: files of a virtual filesystem get no swap file.
+fn local_path(ed: &EditorState) -> Option<&Path> {
+    ed.path.as_deref().filter(|_| ed.remote.is_none())
+}
+
+/// Copy a modified buffer to its swap file when it changed since the last copy, and
+/// drop the copy once the buffer is back to its saved state.
+pub fn autosave(ed: &mut EditorState) {
+    let Some(path) = local_path(ed) else { return; };
+    match ed.swap_revision {
+        Some(_) if !ed.dirty => {
+            remove(path);
+            ed.swap_revision = None;
+        }
+        rev if ed.dirty && rev != Some(ed.revision) && write(path, &ed.buffer.to_string()).is_ok() => {
+            ed.swap_revision = Some(ed.revision);
+        }
+        _ => {}
+    }
+}
+
+/// Delete the swap file written for a buffer (after a save, or when its changes are
+/// dropped). A copy left by a previous session is kept.
+pub fn discard(ed: &mut EditorState) {
+    if ed.swap_revision.take().is_some() && let Some(path) = local_path(ed) {
+        remove(path);
+    }
+}
+
+/// Time of a swap file left by a previous session for the file of a buffer.
+pub fn pending_recovery(ed: &EditorState) -> Option<SystemTime> {
+    if ed.swap_revision.is_some() {
+        return None;
+    }
+    newer_copy(local_path(ed)?)
+}
+
+/// Replace the buffer with its swap copy (one undo step), the file staying unsaved.
+pub fn recover(ed: &mut EditorState) -> io::Result<()> {
+    let path = local_path(ed).ok_or_else(|| io::Error::other("No file path"))?;
+    let text = read(path)?;
+    ed.push_undo();
+    ed.remove_range(0..ed.buffer.len_chars());
+    ed.insert_at(0, &text);
+    ed.dirty = true;
+    // La copie est désormais celle du buffer : supprimée à l'enregistrement
+    ed.swap_revision = Some(ed.revision);
+    let last = ed.buffer.len_lines().saturating_sub(1);
+    ed.cursor_row = ed.cursor_row.min(last);
+    ed.cursor_col = ed.cursor_col.min(ed.buffer.line(ed.cursor_row).len_chars());
+    ed.scroll_row = ed.scroll_row.min(ed.cursor_row);
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! Undo history kept across sessions (`persistent_undo = true` in config/editor.toml).
+//!
+//! Saving a file also writes its undo and redo steps to
+//! `~/.local/state/paschek/undo/` (named like the swap files), with a fingerprint of
+//! the saved text. Reopening the file restores them when its content still matches,
+//! so changes made by another program in between drop the history.
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::shell::tui::state::{EditorState, UndoStep};
+use crate::shell::tui::swap;
+
+/// History file content.
+#[derive(Serialize, Deserialize)]
+struct History {
+    /// Chars and FNV-1a hash of the text the steps apply to
+    chars: usize,
+    hash: u64,
+    undo: Vec<UndoStep>,
+    redo: Vec<UndoStep>,
+}
+
+/// FNV-1a hash of the buffer text (stable across runs, unlike `DefaultHasher`).
+fn fingerprint(ed: &EditorState) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for chunk in ed.buffer.chunks() {
+        for b in chunk.bytes() {
+            hash ^= u64::from(b);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// Local file of a buffer keeping its history.
+fn history_path(ed: &EditorState) -> Option<&Path> {
+    ed.path.as_deref().filter(|_| ed.persist_undo && ed.remote.is_none())
+}
+
+/// Write the history of a buffer that was just saved; a buffer without history
+/// drops the stale file.
+pub fn save(ed: &EditorState) {
+    let Some(path) = history_path(ed) else { return; };
+    let target = swap::state_file("undo", path, "json");
+    if ed.undo_stack.is_empty() && ed.redo_stack.is_empty() {
+        let _ = fs::remove_file(target);
+        return;
+    }
+    let history = History {
+        chars: ed.buffer.len_chars(),
+        hash: fingerprint(ed),
+        undo: ed.undo_stack.clone(),
+        redo: ed.redo_stack.clone(),
+    };
+    if let Ok(json) = serde_json::to_string(&history) {
+        let _ = fs::create_dir_all(swap::state_dir("undo")).and_then(|_| fs::write(target, json));
+    }
+}
+
+/// Restore the saved history of a freshly opened buffer whose text is the one it was
+/// saved with.
+pub fn load(ed: &mut EditorState) {
+    let Some(path) = history_path(ed) else { return; };
+    let Ok(json) = fs::read_to_string(swap::state_file("undo", path, "json")) else { return; };
+    let Ok(history) = serde_json::from_str::<History>(&json) else { return; };
+    if history.chars == ed.buffer.len_chars() && history.hash == fingerprint(ed) {
+        ed.undo_stack = history.undo;
+        ed.redo_stack = history.redo;
+    }
+}
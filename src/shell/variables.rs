@@ -0,0 +1,100 @@
+//! Environment variables of the shell.
+//!
+//! `export NAME=value` sets a variable in the process environment, so every system
+//! command spawned afterwards (from the REPL or the TUI) sees it. The names exported
+//! during the session are remembered for `export` without arguments. `$NAME` and
+//! `${NAME}` references are expanded by the tokenizer ([`crate::shell::parser`]).
+//!
+//! Inside a function, `$1`…`$9` (`${10}`…), `$#` and `$@` / `$*` (the arguments
+//! joined by spaces, as one word) refer to the arguments of the call; `$0` is the
+//! function name.
+
+use std::collections::BTreeSet;
+use std::env;
+use std::sync::Mutex;
+
+/// Names exported during this session.
+static EXPORTED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Name and arguments of the running function calls, innermost last.
+static POSITIONAL: Mutex<Vec<Vec<String>>> = Mutex::new(Vec::new());
+
+/// Make `params` (function name, then arguments) the positional parameters until
+/// the matching [`pop_positional`].
+pub fn push_positional(params: Vec<String>) {
+    if let Ok(mut stack) = POSITIONAL.lock() {
+        stack.push(params);
+    }
+}
+
+/// Go back to the positional parameters of the enclosing call.
+pub fn pop_positional() {
+    if let Ok(mut stack) = POSITIONAL.lock() {
+        stack.pop();
+    }
+}
+
+/// Value of the positional parameter `name` (`0`, `1`…, `#`, `@`, `*`).
+fn positional(name: &str) -> String {
+    let stack = POSITIONAL.lock().map(|s| s.last().cloned().unwrap_or_default()).unwrap_or_default();
+    let args = stack.get(1..).unwrap_or_default();
+    match name {
+        "#" => args.len().to_string(),
+        "@" | "*" => args.join(" "),
+        n => n.parse::<usize>().ok().and_then(|i| stack.get(i).cloned()).unwrap_or_default(),
+    }
+}
+
+/// Whether `name` can be used as a variable name.
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Set `name` in the process environment (`PWD`, `OLDPWD`…) without listing it
+/// among the exported variables.
+pub fn set(name: &str, value: &str) {
+    // SAFETY: l'environnement n'est modifié que depuis le thread principal (REPL / boucle
+    // du TUI) ; les threads de lecture des jobs n'y accèdent pas.
+    unsafe { env::set_var(name, value) };
+}
+
+/// Set `name` in the process environment and remember it as shell-managed.
+pub fn export(name: &str, value: &str) {
+    set(name, value);
+    if let Ok(mut names) = EXPORTED.lock() {
+        names.insert(name.to_string());
+    }
+}
+
+/// Shell-managed variables and their current value, sorted by name.
+pub fn exported() -> Vec<(String, String)> {
+    let names = EXPORTED.lock().map(|n| n.clone()).unwrap_or_default();
+    names.into_iter().filter_map(|name| env::var(&name).ok().map(|value| (name, value))).collect()
+}
+
+/// Value of the variable referenced at `at` in `chars` (just past a `$`: `NAME` or
+/// `{NAME}`) and the index following the reference; `None` when no name follows.
+/// Unset variables expand to an empty string.
+pub fn lookup(chars: &[char], at: usize) -> Option<(String, usize)> {
+    // `$1`, `$#`, `$@` : un seul caractère (`$10` vaut `${1}0`)
+    if let Some(&c) = chars.get(at).filter(|c| c.is_ascii_digit() || matches!(c, '#' | '@' | '*')) {
+        return Some((positional(&c.to_string()), at + 1));
+    }
+    let braced = chars.get(at) == Some(&'{');
+    let start = if braced { at + 1 } else { at };
+    let len = chars[start.min(chars.len())..]
+        .iter()
+        .take_while(|c| c.is_ascii_alphanumeric() || **c == '_')
+        .count();
+    let end = start + len;
+    if len == 0 || (braced && chars.get(end) != Some(&'}')) {
+        // `$` seul ou `${` non fermé : texte littéral
+        return None;
+    }
+    let name: String = chars[start..end].iter().collect();
+    if name.chars().all(|c| c.is_ascii_digit()) {
+        return Some((positional(&name), end + 1));
+    }
+    Some((env::var(&name).unwrap_or_default(), if braced { end + 1 } else { end }))
+}
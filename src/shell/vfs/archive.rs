@@ -0,0 +1,334 @@
+//! Archives (`.zip`, `.tar`, `.tar.gz` / `.tgz`) as virtual filesystems, and
+//! archive creation from local files.
+//!
+//! The member list is read once when the archive is opened. Extraction and
+//! reading a member (to view it in the editor) read the archive again; members whose path would leave the destination (absolute
+//! paths, `..`) are never listed nor written, and tar links are skipped.
+//! [`create`] packs files and directory trees, storing symlinks as links.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::{VfsEntry, VirtualFs};
+
+/// Supported archive formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveKind {
+    /// Format from the file name, if it is a supported archive.
+    pub fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveKind::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Archive member as read from the archive index.
+struct Member {
+    path: PathBuf,
+    is_dir: bool,
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+/// An archive on disk with its member tree.
+pub struct Archive {
+    path: PathBuf,
+    kind: ArchiveKind,
+    /// Children of every directory, keyed by directory ("" is the top)
+    tree: HashMap<PathBuf, Vec<VfsEntry>>,
+}
+
+/// Member path with only normal components, or None if it could escape.
+fn clean(path: &Path) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+    for c in path.components() {
+        match c {
+            Component::Normal(p) => out.push(p),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!out.as_os_str().is_empty()).then_some(out)
+}
+
+fn tar_reader(path: &Path, kind: ArchiveKind) -> io::Result<tar::Archive<Box<dyn Read>>> {
+    let file = BufReader::new(File::open(path)?);
+    let reader: Box<dyn Read> = if kind == ArchiveKind::TarGz { Box::new(GzDecoder::new(file)) } else { Box::new(file) };
+    Ok(tar::Archive::new(reader))
+}
+
+fn zip_reader(path: &Path) -> io::Result<ZipArchive<BufReader<File>>> {
+    ZipArchive::new(BufReader::new(File::open(path)?)).map_err(io::Error::other)
+}
+
+fn read_members(path: &Path, kind: ArchiveKind) -> io::Result<Vec<Member>> {
+    let mut members = Vec::new();
+    if kind == ArchiveKind::Zip {
+        let mut zip = zip_reader(path)?;
+        for i in 0..zip.len() {
+            let file = zip.by_index_raw(i).map_err(io::Error::other)?;
+            if let Some(p) = file.enclosed_name().and_then(|p| clean(&p)) {
+                members.push(Member { path: p, is_dir: file.is_dir(), size: file.size(), modified: None });
+            }
+        }
+    } else {
+        let mut archive = tar_reader(path, kind)?;
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let Some(p) = entry.path().ok().and_then(|p| clean(&p)) else { continue; };
+            let header = entry.header();
+            members.push(Member {
+                path: p,
+                is_dir: header.entry_type().is_dir(),
+                size: header.size().unwrap_or(0),
+                modified: header.mtime().ok().map(|s| UNIX_EPOCH + Duration::from_secs(s)),
+            });
+        }
+    }
+    Ok(members)
+}
+
+/// Group members by parent directory, adding the directories archives often omit.
+fn index(members: Vec<Member>) -> HashMap<PathBuf, Vec<VfsEntry>> {
+    let mut dirs: HashMap<PathBuf, BTreeMap<String, VfsEntry>> = HashMap::new();
+    dirs.entry(PathBuf::new()).or_default();
+    for m in members {
+        let names: Vec<String> = m.path.iter().map(|c| c.to_string_lossy().to_string()).collect();
+        let mut parent = PathBuf::new();
+        for (i, name) in names.iter().enumerate() {
+            let children = dirs.entry(parent.clone()).or_default();
+            if i + 1 == names.len() {
+                children.insert(name.clone(), VfsEntry { name: name.clone(), is_dir: m.is_dir, size: m.size, modified: m.modified });
+            } else {
+                children
+                    .entry(name.clone())
+                    .or_insert_with(|| VfsEntry { name: name.clone(), is_dir: true, size: 0, modified: None });
+            }
+            parent.push(name);
+        }
+        if m.is_dir {
+            dirs.entry(m.path).or_default();
+        }
+    }
+    dirs.into_iter().map(|(dir, children)| (dir, children.into_values().collect())).collect()
+}
+
+impl Archive {
+    /// Read the member list of `path`.
+    pub fn open(path: &Path, kind: ArchiveKind) -> io::Result<Self> {
+        let tree = index(read_members(path, kind)?);
+        Ok(Archive { path: path.to_path_buf(), kind, tree })
+    }
+}
+
+impl VirtualFs for Archive {
+    fn list(&self, dir: &Path) -> io::Result<Vec<VfsEntry>> {
+        self.tree
+            .get(dir)
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{} : absent de l’archive", dir.display())))
+    }
+
+    fn extract(&self, items: &[(PathBuf, PathBuf)]) -> io::Result<usize> {
+        // Destination d'un membre s'il fait partie (ou est sous) un élément demandé
+        let target = |p: &Path| {
+            items.iter().find_map(|(member, dest)| {
+                let rest = p.strip_prefix(member).ok()?;
+                Some(if rest.as_os_str().is_empty() { dest.clone() } else { dest.join(rest) })
+            })
+        };
+        let write = |reader: &mut dyn Read, out: &Path| -> io::Result<()> {
+            if let Some(dir) = out.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            io::copy(reader, &mut File::create(out)?).map(|_| ())
+        };
+
+        let mut written = 0;
+        if self.kind == ArchiveKind::Zip {
+            let mut zip = zip_reader(&self.path)?;
+            for i in 0..zip.len() {
+                let mut file = zip.by_index(i).map_err(io::Error::other)?;
+                let Some(out) = file.enclosed_name().and_then(|p| clean(&p)).and_then(|p| target(&p)) else { continue; };
+                if file.is_dir() {
+                    fs::create_dir_all(&out)?;
+                } else {
+                    write(&mut file, &out)?;
+                    written += 1;
+                }
+            }
+        } else {
+            let mut archive = tar_reader(&self.path, self.kind)?;
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+                let Some(out) = entry.path().ok().and_then(|p| clean(&p)).and_then(|p| target(&p)) else { continue; };
+                let kind = entry.header().entry_type();
+                if kind.is_dir() {
+                    fs::create_dir_all(&out)?;
+                } else if kind.is_file() {
+                    write(&mut entry, &out)?;
+                    written += 1;
+                }
+            }
+        }
+        // Dossiers sans entrée propre dans l'archive
+        for (member, dest) in items {
+            if self.tree.contains_key(member) {
+                fs::create_dir_all(dest)?;
+            }
+        }
+        Ok(written)
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        if self.kind == ArchiveKind::Zip {
+            let mut zip = zip_reader(&self.path)?;
+            for i in 0..zip.len() {
+                let mut file = zip.by_index(i).map_err(io::Error::other)?;
+                if !file.is_dir() && file.enclosed_name().and_then(|p| clean(&p)).as_deref() == Some(path) {
+                    file.read_to_end(&mut bytes)?;
+                    return Ok(bytes);
+                }
+            }
+        } else {
+            let mut archive = tar_reader(&self.path, self.kind)?;
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+                if entry.header().entry_type().is_file() && entry.path().ok().and_then(|p| clean(&p)).as_deref() == Some(path) {
+                    entry.read_to_end(&mut bytes)?;
+                    return Ok(bytes);
+                }
+            }
+        }
+        Err(io::Error::new(io::ErrorKind::NotFound, format!("{} : absent de l’archive", path.display())))
+    }
+}
+
+/// Progress callback of [`create`]: bytes just read and the file they come from.
+/// Returning an error stops the archive.
+pub type Progress<'a> = &'a mut dyn FnMut(u64, &Path) -> io::Result<()>;
+
+/// Reader reporting every chunk to the progress callback.
+struct Counted<'a, 'b, R> {
+    inner: R,
+    path: &'a Path,
+    progress: &'a mut Progress<'b>,
+}
+
+impl<R: Read> Read for Counted<'_, '_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        (self.progress)(n as u64, self.path)?;
+        Ok(n)
+    }
+}
+
+/// `src` and everything below it, paired with their names in the archive.
+fn walk(src: &Path, name: PathBuf, out: &mut Vec<(PathBuf, PathBuf)>) -> io::Result<()> {
+    let is_dir = fs::symlink_metadata(src)?.is_dir();
+    out.push((src.to_path_buf(), name.clone()));
+    if is_dir {
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            walk(&entry.path(), name.join(entry.file_name()), out)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_zip<W: Write + io::Seek>(zip: &mut ZipWriter<W>, items: &[(PathBuf, PathBuf)], progress: &mut Progress) -> io::Result<()> {
+    for (src, name) in items {
+        let meta = fs::symlink_metadata(src)?;
+        let name = name.to_string_lossy().replace('\\', "/");
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        #[cfg(unix)]
+        let options = {
+            use std::os::unix::fs::PermissionsExt;
+            options.unix_permissions(meta.permissions().mode())
+        };
+        if meta.file_type().is_symlink() {
+            let target = fs::read_link(src)?;
+            zip.add_symlink(name, target.to_string_lossy(), options).map_err(io::Error::other)?;
+        } else if meta.is_dir() {
+            zip.add_directory(name, options).map_err(io::Error::other)?;
+        } else {
+            zip.start_file(name, options.large_file(meta.len() >= u32::MAX as u64)).map_err(io::Error::other)?;
+            let mut reader = Counted { inner: File::open(src)?, path: src, progress };
+            io::copy(&mut reader, zip)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_tar<W: Write>(tar: &mut tar::Builder<W>, items: &[(PathBuf, PathBuf)], progress: &mut Progress) -> io::Result<()> {
+    tar.follow_symlinks(false);
+    for (src, name) in items {
+        let meta = fs::symlink_metadata(src)?;
+        if meta.is_file() {
+            let mut header = tar::Header::new_gnu();
+            header.set_metadata(&meta);
+            let reader = Counted { inner: File::open(src)?, path: src, progress };
+            tar.append_data(&mut header, name, reader)?;
+        } else {
+            tar.append_path_with_name(src, name)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write an archive of `kind` at `dest` holding each source (directories with their
+/// content) under its own name. The partial file is removed on error.
+pub fn create(dest: &Path, kind: ArchiveKind, sources: &[PathBuf], mut progress: Progress) -> io::Result<()> {
+    let mut items = Vec::new();
+    for src in sources {
+        let name = src.file_name().map(PathBuf::from).unwrap_or_default();
+        walk(src, name, &mut items)?;
+    }
+    let result = (|| {
+        let file = BufWriter::new(File::create(dest)?);
+        match kind {
+            ArchiveKind::Zip => {
+                let mut zip = ZipWriter::new(file);
+                write_zip(&mut zip, &items, &mut progress)?;
+                zip.finish().map_err(io::Error::other)?.flush()
+            }
+            ArchiveKind::Tar => {
+                let mut tar = tar::Builder::new(file);
+                write_tar(&mut tar, &items, &mut progress)?;
+                tar.into_inner()?.flush()
+            }
+            ArchiveKind::TarGz => {
+                let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+                write_tar(&mut tar, &items, &mut progress)?;
+                tar.into_inner()?.finish()?.flush()
+            }
+        }
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(dest);
+    }
+    result
+}
@@ -0,0 +1,51 @@
+//! Virtual filesystems browsed by the TUI explorer.
+//!
+//! A [`VirtualFs`] exposes a directory tree that does not live on the local disk:
+//! the members of an archive (read-only, see [`archive`]) or a remote host over
+//! SFTP (see [`sftp`]). Paths inside it are relative, `""` being its top level.
+
+pub mod archive;
+pub mod sftp;
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// One entry of a virtual directory.
+#[derive(Debug, Clone)]
+pub struct VfsEntry {
+    pub name: String,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// Filesystem the explorer can enter like a directory and the editor can open files from.
+pub trait VirtualFs: Send + Sync {
+    /// Entries directly under `dir`.
+    fn list(&self, dir: &Path) -> io::Result<Vec<VfsEntry>>;
+
+    /// Copy each `(member, destination)` pair to the local disk; a directory
+    /// member is copied with everything below it. Returns the number of files written.
+    fn extract(&self, items: &[(PathBuf, PathBuf)]) -> io::Result<usize>;
+
+    /// Content of the file at `path`.
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    /// Replace the content of the file at `path` (read-only filesystems refuse).
+    fn write(&self, _path: &Path, _data: &[u8]) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::PermissionDenied, "système de fichiers en lecture seule"))
+    }
+
+    /// Whether `write` is supported.
+    fn writable(&self) -> bool {
+        false
+    }
+}
+
+/// Open `path` as a virtual filesystem, or None if it is not a supported archive.
+pub fn open(path: &Path) -> Option<io::Result<Arc<dyn VirtualFs>>> {
+    let kind = archive::ArchiveKind::detect(path)?;
+    Some(archive::Archive::open(path, kind).map(|a| Arc::new(a) as Arc<dyn VirtualFs>))
+}
@@ -0,0 +1,278 @@
+//! Remote directories over SFTP (`sftp://[user@]host[:port]/path`).
+//!
+//! The host part may be an alias from `~/.ssh/config`: its `HostName`, `User`,
+//! `Port` and `IdentityFile` apply, so saved SSH host profiles work unchanged.
+//! Authentication tries the SSH agent, then the profile's identity files and the
+//! default keys (`~/.ssh/id_ed25519`, `id_ecdsa`, `id_rsa`). The server key must
+//! already be in `~/.ssh/known_hosts`: unknown or changed keys are refused.
+//! Paths inside the filesystem are relative to the remote `/`.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use ssh2::{CheckResult, KnownHostFileKind, OpenFlags, OpenType, Session, Sftp};
+
+use super::{VfsEntry, VirtualFs};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Timeout of every blocking SSH call, in milliseconds
+const SESSION_TIMEOUT_MS: u32 = 15_000;
+
+/// Parsed `sftp://` location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SftpUrl {
+    pub user: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+    /// Absolute remote path ("/" when the URL has none)
+    pub path: PathBuf,
+}
+
+impl SftpUrl {
+    /// Parse `sftp://[user@]host[:port][/path]`; None if `s` is not an sftp URL.
+    pub fn parse(s: &str) -> Option<Self> {
+        let rest = s.strip_prefix("sftp://")?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (user, hostport) = match authority.rsplit_once('@') {
+            Some((u, h)) => (Some(u.to_string()), h),
+            None => (None, authority),
+        };
+        let (host, port) = match hostport.rsplit_once(':') {
+            Some((h, p)) => (h, Some(p.parse().ok()?)),
+            None => (hostport, None),
+        };
+        if host.is_empty() {
+            return None;
+        }
+        Some(SftpUrl { user, host: host.to_string(), port, path: PathBuf::from(path) })
+    }
+
+    /// `sftp://authority` without the path: the explorer shows remote paths under it.
+    pub fn base(&self) -> PathBuf {
+        let user = self.user.as_deref().map(|u| format!("{}@", u)).unwrap_or_default();
+        let port = self.port.map(|p| format!(":{}", p)).unwrap_or_default();
+        PathBuf::from(format!("sftp://{}{}{}", user, self.host, port))
+    }
+}
+
+/// Settings of one host from `~/.ssh/config`.
+#[derive(Debug, Default)]
+struct HostProfile {
+    hostname: Option<String>,
+    user: Option<String>,
+    port: Option<u16>,
+    identity_files: Vec<PathBuf>,
+}
+
+/// `*` / `?` pattern of a `Host` line.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    fn inner(p: &[char], t: &[char]) -> bool {
+        match p.split_first() {
+            None => t.is_empty(),
+            Some(('*', rest)) => (0..=t.len()).any(|i| inner(rest, &t[i..])),
+            Some(('?', rest)) => !t.is_empty() && inner(rest, &t[1..]),
+            Some((c, rest)) => t.first() == Some(c) && inner(rest, &t[1..]),
+        }
+    }
+    let (p, t): (Vec<char>, Vec<char>) = (pattern.chars().collect(), text.chars().collect());
+    inner(&p, &t)
+}
+
+/// Profile of `alias` in an ssh config file; like ssh, the first value found wins.
+fn parse_profile(config: &str, alias: &str) -> HostProfile {
+    let mut profile = HostProfile::default();
+    let mut active = true; // options before the first `Host` apply to every host
+    for line in config.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = match line.split_once(|c: char| c.is_whitespace() || c == '=') {
+            Some((k, v)) => (k.to_ascii_lowercase(), v.trim_start_matches(|c: char| c.is_whitespace() || c == '=').trim()),
+            None => continue,
+        };
+        match key.as_str() {
+            "host" => {
+                let patterns: Vec<&str> = value.split_whitespace().collect();
+                let negated = patterns.iter().any(|p| p.strip_prefix('!').is_some_and(|p| glob_match(p, alias)));
+                active = !negated && patterns.iter().any(|p| !p.starts_with('!') && glob_match(p, alias));
+            }
+            "match" => active = false,
+            _ if !active => {}
+            "hostname" => {
+                profile.hostname.get_or_insert_with(|| value.replace("%h", alias));
+            }
+            "user" => {
+                profile.user.get_or_insert_with(|| value.to_string());
+            }
+            "port" if profile.port.is_none() => profile.port = value.parse().ok(),
+            "identityfile" => profile.identity_files.push(crate::shell::config::expand_home(value.trim_matches('"'))),
+            _ => {}
+        }
+    }
+    profile
+}
+
+fn ssh_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".ssh"))
+}
+
+fn ssh_error(e: ssh2::Error) -> io::Error {
+    io::Error::other(e.message().to_string())
+}
+
+/// Connected SFTP session.
+pub struct SftpFs {
+    /// Kept alive for as long as the channel is used
+    _session: Session,
+    sftp: Sftp,
+}
+
+impl SftpFs {
+    /// Connect and authenticate to the host of `url`.
+    pub fn connect(url: &SftpUrl) -> io::Result<Self> {
+        let config = ssh_dir().and_then(|d| fs::read_to_string(d.join("config")).ok()).unwrap_or_default();
+        let profile = parse_profile(&config, &url.host);
+        let host = profile.hostname.clone().unwrap_or_else(|| url.host.clone());
+        let port = url.port.or(profile.port).unwrap_or(22);
+        let user = url
+            .user
+            .clone()
+            .or(profile.user.clone())
+            .or_else(|| std::env::var("USER").or_else(|_| std::env::var("LOGNAME")).ok())
+            .ok_or_else(|| io::Error::other("utilisateur SSH inconnu"))?;
+
+        let addr = (host.as_str(), port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{} : hôte introuvable", host)))?;
+        let tcp = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)?;
+        let mut session = Session::new().map_err(ssh_error)?;
+        session.set_timeout(SESSION_TIMEOUT_MS);
+        session.set_tcp_stream(tcp);
+        session.handshake().map_err(ssh_error)?;
+        check_host_key(&session, &host, port)?;
+        authenticate(&session, &user, &profile)?;
+        let sftp = session.sftp().map_err(ssh_error)?;
+        Ok(SftpFs { _session: session, sftp })
+    }
+
+    /// Whether `path` (relative to the remote "/") is a directory.
+    pub fn is_dir(&self, path: &Path) -> io::Result<bool> {
+        self.sftp.stat(&remote(path)).map(|s| s.is_dir()).map_err(ssh_error)
+    }
+
+    /// Copy the remote `from` (with everything below it) to the local `to`.
+    fn download(&self, from: &Path, to: &Path) -> io::Result<usize> {
+        if self.is_dir(from)? {
+            fs::create_dir_all(to)?;
+            let mut written = 0;
+            for entry in self.list(from)? {
+                written += self.download(&from.join(&entry.name), &to.join(&entry.name))?;
+            }
+            Ok(written)
+        } else {
+            let mut file = self.sftp.open(remote(from)).map_err(ssh_error)?;
+            io::copy(&mut file, &mut fs::File::create(to)?)?;
+            Ok(1)
+        }
+    }
+}
+
+/// Remote absolute path of a path relative to the remote "/".
+fn remote(path: &Path) -> PathBuf {
+    Path::new("/").join(path)
+}
+
+/// Refuse servers whose key is not (or no longer) in `~/.ssh/known_hosts`.
+fn check_host_key(session: &Session, host: &str, port: u16) -> io::Result<()> {
+    let (key, _) = session.host_key().ok_or_else(|| io::Error::other("clé du serveur absente"))?;
+    let mut known = session.known_hosts().map_err(ssh_error)?;
+    if let Some(file) = ssh_dir().map(|d| d.join("known_hosts")).filter(|f| f.exists()) {
+        known.read_file(&file, KnownHostFileKind::OpenSSH).map_err(ssh_error)?;
+    }
+    match known.check_port(host, port, key) {
+        CheckResult::Match => Ok(()),
+        CheckResult::Mismatch => Err(io::Error::other(format!("{} : la clé du serveur a changé (known_hosts)", host))),
+        CheckResult::NotFound => Err(io::Error::other(format!(
+            "{} : hôte inconnu, connectez-vous une fois avec ssh pour l'ajouter à known_hosts",
+            host
+        ))),
+        CheckResult::Failure => Err(io::Error::other("vérification de la clé du serveur impossible")),
+    }
+}
+
+fn authenticate(session: &Session, user: &str, profile: &HostProfile) -> io::Result<()> {
+    if session.userauth_agent(user).is_ok() && session.authenticated() {
+        return Ok(());
+    }
+    let defaults: Vec<PathBuf> = ssh_dir()
+        .map(|d| ["id_ed25519", "id_ecdsa", "id_rsa"].iter().map(|k| d.join(k)).collect())
+        .unwrap_or_default();
+    for key in profile.identity_files.iter().chain(&defaults).filter(|k| k.exists()) {
+        if session.userauth_pubkey_file(user, None, key, None).is_ok() && session.authenticated() {
+            return Ok(());
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!("{} : authentification refusée (agent SSH et clés sans phrase de passe)", user),
+    ))
+}
+
+impl VirtualFs for SftpFs {
+    fn list(&self, dir: &Path) -> io::Result<Vec<VfsEntry>> {
+        let listed = self.sftp.readdir(remote(dir)).map_err(ssh_error)?;
+        Ok(listed
+            .into_iter()
+            .filter_map(|(path, stat)| {
+                let name = path.file_name()?.to_string_lossy().to_string();
+                // Lien : suivre pour savoir s'il mène à un dossier
+                let is_dir = if stat.file_type().is_symlink() {
+                    self.sftp.stat(&path).is_ok_and(|s| s.is_dir())
+                } else {
+                    stat.is_dir()
+                };
+                Some(VfsEntry {
+                    name,
+                    is_dir,
+                    size: stat.size.unwrap_or(0),
+                    modified: stat.mtime.map(|s| UNIX_EPOCH + Duration::from_secs(s)),
+                })
+            })
+            .collect())
+    }
+
+    fn extract(&self, items: &[(PathBuf, PathBuf)]) -> io::Result<usize> {
+        let mut written = 0;
+        for (member, dest) in items {
+            written += self.download(member, dest)?;
+        }
+        Ok(written)
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let mut file = self.sftp.open(remote(path)).map_err(ssh_error)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        // Le mode ne sert qu'à la création : un fichier existant garde ses permissions
+        let flags = OpenFlags::WRITE | OpenFlags::TRUNCATE | OpenFlags::CREATE;
+        let mut file = self.sftp.open_mode(remote(path), flags, 0o644, OpenType::File).map_err(ssh_error)?;
+        file.write_all(data)?;
+        file.flush()
+    }
+
+    fn writable(&self) -> bool {
+        true
+    }
+}